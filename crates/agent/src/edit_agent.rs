@@ -18,7 +18,10 @@ use futures::{
     stream::BoxStream,
 };
 use gpui::{AppContext, AsyncApp, Entity, Task};
-use language::{Anchor, Buffer, BufferSnapshot, LineIndent, Point, TextBufferSnapshot};
+use language::{
+    Anchor, Buffer, BufferSnapshot, LineIndent, Point, TextBufferSnapshot,
+    language_settings::ResolvedWhitespaceOptions,
+};
 use language_model::{
     CompletionIntent, LanguageModel, LanguageModelCompletionError, LanguageModelRequest,
     LanguageModelRequestMessage, LanguageModelToolChoice, MessageContent, Role,
@@ -206,26 +209,42 @@ impl EditAgent {
                 })
             }
         };
+        let write_chunk = |chunk: String, first_chunk: &mut bool, cx: &mut AsyncApp| {
+            cx.update(|cx| {
+                buffer.update(cx, |buffer, cx| {
+                    if mem::take(first_chunk) {
+                        buffer.set_text(chunk, cx)
+                    } else {
+                        buffer.append(chunk, cx)
+                    }
+                });
+                self.action_log
+                    .update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+                set_agent_location(cx);
+            });
+            send_edit_event();
+        };
+        // The model often indents with other whitespace than the buffer, so its text is written
+        // in the buffer's style.
+        let whitespace = buffer.read_with(cx, |buffer, cx| {
+            ResolvedWhitespaceOptions::for_buffer(buffer, cx)
+        });
+        let mut reindenter = Reindenter::new(IndentDelta::Spaces(0)).normalizing(whitespace);
         let mut first_chunk = true;
         while let Some(event) = parse_rx.next().await {
             match event? {
                 CreateFileParserEvent::NewTextChunk { chunk } => {
-                    cx.update(|cx| {
-                        buffer.update(cx, |buffer, cx| {
-                            if mem::take(&mut first_chunk) {
-                                buffer.set_text(chunk, cx)
-                            } else {
-                                buffer.append(chunk, cx)
-                            }
-                        });
-                        self.action_log
-                            .update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
-                        set_agent_location(cx);
-                    });
-                    send_edit_event();
+                    let chunk = reindenter.push(&chunk);
+                    if !chunk.is_empty() {
+                        write_chunk(chunk, &mut first_chunk, cx);
+                    }
                 }
             }
         }
+        let rest = reindenter.finish();
+        if !rest.is_empty() {
+            write_chunk(rest, &mut first_chunk, cx);
+        }
 
         if first_chunk {
             cx.update(|cx| {
@@ -298,7 +317,12 @@ impl EditAgent {
                 continue;
             };
 
-            let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+            let (snapshot, whitespace) = buffer.read_with(cx, |buffer, cx| {
+                (
+                    buffer.snapshot(),
+                    ResolvedWhitespaceOptions::for_buffer(buffer, cx),
+                )
+            });
 
             // Resolve the old text in the background, updating the agent
             // location as we keep refining which range it corresponds to.
@@ -359,7 +383,7 @@ impl EditAgent {
             // Compute edits in the background and apply them as they become
             // available.
             let (compute_edits, edits) =
-                Self::compute_edits(snapshot, resolved_old_text, edit_events, cx);
+                Self::compute_edits(snapshot, whitespace, resolved_old_text, edit_events, cx);
             let mut edits = edits.ready_chunks(32);
             while let Some(edits) = edits.next().await {
                 if edits.is_empty() {
@@ -551,6 +575,7 @@ impl EditAgent {
 
     fn compute_edits<T>(
         snapshot: BufferSnapshot,
+        whitespace: ResolvedWhitespaceOptions,
         resolved_old_text: ResolvedOldText,
         mut edit_events: T,
         cx: &mut AsyncApp,
@@ -565,16 +590,21 @@ impl EditAgent {
         let compute_edits = cx.background_spawn(async move {
             let buffer_start_indent = snapshot
                 .line_indent_for_row(snapshot.offset_to_point(resolved_old_text.range.start).row);
-            let indent_delta =
-                reindent::compute_indent_delta(buffer_start_indent, resolved_old_text.indent);
+            // The model often indents with other whitespace than the buffer, so its text is
+            // first rewritten in the buffer's style.
+            let query_indent =
+                reindent::normalize_line_indent(resolved_old_text.indent, &whitespace);
+            let indent_delta = reindent::compute_indent_delta(buffer_start_indent, query_indent);
 
             let old_text = snapshot
                 .text_for_range(resolved_old_text.range.clone())
                 .collect::<String>();
             let mut diff = StreamingDiff::new(old_text);
             let mut edit_start = resolved_old_text.range.start;
-            let mut new_text_chunks =
-                Self::reindent_new_text_chunks(indent_delta, &mut edit_events);
+            let mut new_text_chunks = Self::reindent_new_text_chunks(
+                Reindenter::new(indent_delta).normalizing(whitespace),
+                &mut edit_events,
+            );
             let mut done = false;
             while !done {
                 let char_operations = if let Some(new_text_chunk) = new_text_chunks.next().await {
@@ -610,10 +640,9 @@ impl EditAgent {
     }
 
     fn reindent_new_text_chunks(
-        delta: IndentDelta,
+        mut reindenter: Reindenter,
         mut stream: impl Unpin + Stream<Item = Result<EditParserEvent>>,
     ) -> impl Stream<Item = Result<String>> {
-        let mut reindenter = Reindenter::new(delta);
         let mut done = false;
         futures::stream::poll_fn(move |cx| {
             while !done {
@@ -732,6 +761,7 @@ mod tests {
     use project::{AgentLocation, Project};
     use rand::prelude::*;
     use rand::rngs::StdRng;
+    use reindent::IndentDelta;
     use std::cmp;
 
     #[gpui::test(iterations = 100)]
@@ -1273,10 +1303,12 @@ mod tests {
                 done: index == chunks.len() - 1,
             })
         }));
-        let indented_chunks =
-            EditAgent::reindent_new_text_chunks(IndentDelta::Spaces(2), new_text_chunks)
-                .collect::<Vec<_>>()
-                .await;
+        let indented_chunks = EditAgent::reindent_new_text_chunks(
+            Reindenter::new(IndentDelta::Spaces(2)),
+            new_text_chunks,
+        )
+        .collect::<Vec<_>>()
+        .await;
         let new_text = indented_chunks
             .into_iter()
             .collect::<Result<String>>()
@@ -1293,10 +1325,12 @@ mod tests {
                 done: index == chunks.len() - 1,
             })
         }));
-        let indented_chunks =
-            EditAgent::reindent_new_text_chunks(IndentDelta::Tabs(-2), new_text_chunks)
-                .collect::<Vec<_>>()
-                .await;
+        let indented_chunks = EditAgent::reindent_new_text_chunks(
+            Reindenter::new(IndentDelta::Tabs(-2)),
+            new_text_chunks,
+        )
+        .collect::<Vec<_>>()
+        .await;
         let new_text = indented_chunks
             .into_iter()
             .collect::<Result<String>>()
@@ -1325,9 +1359,10 @@ mod tests {
                 done: index == chunks.len() - 1,
             })
         }));
-        let reindented_chunks = EditAgent::reindent_new_text_chunks(delta, new_text_chunks)
-            .collect::<Vec<_>>()
-            .await;
+        let reindented_chunks =
+            EditAgent::reindent_new_text_chunks(Reindenter::new(delta), new_text_chunks)
+                .collect::<Vec<_>>()
+                .await;
         let actual_reindented_text = reindented_chunks
             .into_iter()
             .collect::<Result<String>>()
@@ -1,4 +1,4 @@
-use language::LineIndent;
+use language::{LineIndent, language_settings::ResolvedWhitespaceOptions};
 use std::{cmp, iter};
 
 #[derive(Copy, Clone, Debug)]
@@ -31,10 +31,27 @@ pub fn compute_indent_delta(buffer_indent: LineIndent, query_indent: LineIndent)
     }
 }
 
+/// Rewrites `indent`, such as that of the old text the model quoted, in the
+/// style of the buffer `whitespace` was resolved for.
+pub fn normalize_line_indent(
+    indent: LineIndent,
+    whitespace: &ResolvedWhitespaceOptions,
+) -> LineIndent {
+    let indent_str = "\t".repeat(indent.tabs as usize) + &" ".repeat(indent.spaces as usize);
+    let normalized = whitespace.normalize_indent(&indent_str, whitespace.indent_size.get());
+    LineIndent {
+        line_blank: indent.line_blank,
+        ..LineIndent::from(normalized.as_str())
+    }
+}
+
 /// Synchronous re-indentation adapter. Buffers incomplete lines and applies
 /// an `IndentDelta` to each line's leading whitespace before emitting it.
 pub struct Reindenter {
     delta: IndentDelta,
+    /// When set, each line's leading whitespace is first rewritten in the
+    /// style of the buffer these were resolved for.
+    whitespace: Option<ResolvedWhitespaceOptions>,
     buffer: String,
     in_leading_whitespace: bool,
 }
@@ -43,11 +60,20 @@ impl Reindenter {
     pub fn new(delta: IndentDelta) -> Self {
         Self {
             delta,
+            whitespace: None,
             buffer: String::new(),
             in_leading_whitespace: true,
         }
     }
 
+    /// Rewrites the leading whitespace of each line in the buffer's style,
+    /// such as spaces the model generated for a buffer indented with tabs,
+    /// before applying the delta.
+    pub fn normalizing(mut self, whitespace: ResolvedWhitespaceOptions) -> Self {
+        self.whitespace = Some(whitespace);
+        self
+    }
+
     /// Feed a chunk of text and return the re-indented portion that is
     /// ready to emit. Incomplete trailing lines are buffered internally.
     pub fn push(&mut self, chunk: &str) -> String {
@@ -71,6 +97,24 @@ impl Reindenter {
             };
             let line = &self.buffer[start_ix..line_end];
 
+            let normalized_line;
+            let line = match &self.whitespace {
+                Some(whitespace) if self.in_leading_whitespace => {
+                    match line.find(|c| c != ' ' && c != '\t') {
+                        Some(ix) => {
+                            normalized_line = whitespace
+                                .normalize_indent(&line[..ix], whitespace.indent_size.get())
+                                + &line[ix..];
+                            normalized_line.as_str()
+                        }
+                        // Wait for the rest of the leading whitespace.
+                        None if is_pending_line && !is_final => break,
+                        None => line,
+                    }
+                }
+                _ => line,
+            };
+
             if self.in_leading_whitespace {
                 if let Some(non_whitespace_ix) = line.find(|c| self.delta.character() != c) {
                     // We found a non-whitespace character, adjust indentation
@@ -113,6 +157,7 @@ impl Reindenter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::num::NonZeroU32;
 
     #[test]
     fn test_indent_single_chunk() {
@@ -178,6 +223,40 @@ mod tests {
         assert_eq!(out, "");
     }
 
+    #[test]
+    fn test_normalizing() {
+        let whitespace = |hard_tabs| ResolvedWhitespaceOptions {
+            indent_size: NonZeroU32::new(4).unwrap(),
+            tab_width: NonZeroU32::new(4).unwrap(),
+            hard_tabs,
+            trim_trailing_whitespace: true,
+            insert_final_newline: true,
+        };
+
+        let mut r = Reindenter::new(IndentDelta::Tabs(1)).normalizing(whitespace(true));
+        // The leading whitespace is only normalized once it's complete.
+        assert_eq!(r.push("    abc\n  "), "\t\tabc\n");
+        assert_eq!(r.push("      def\n"), "\t\t\tdef\n");
+        assert_eq!(r.finish(), "");
+
+        let mut r = Reindenter::new(IndentDelta::Spaces(0)).normalizing(whitespace(false));
+        assert_eq!(r.push("\tabc\n\t\tdef"), "    abc\n        def");
+        assert_eq!(r.finish(), "");
+    }
+
+    #[test]
+    fn test_normalize_line_indent() {
+        let whitespace = ResolvedWhitespaceOptions {
+            indent_size: NonZeroU32::new(4).unwrap(),
+            tab_width: NonZeroU32::new(4).unwrap(),
+            hard_tabs: true,
+            trim_trailing_whitespace: true,
+            insert_final_newline: true,
+        };
+        let indent = normalize_line_indent(LineIndent::spaces(8), &whitespace);
+        assert_eq!((indent.tabs, indent.spaces), (2, 0));
+    }
+
     #[test]
     fn test_compute_indent_delta_spaces() {
         let buffer = LineIndent {
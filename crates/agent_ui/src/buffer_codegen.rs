@@ -11,7 +11,10 @@ use futures::{
     stream::BoxStream,
 };
 use gpui::{App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Subscription, Task};
-use language::{Buffer, IndentKind, LanguageName, Point, TransactionId, line_diff};
+use language::{
+    Buffer, IndentKind, LanguageName, Point, TransactionId,
+    language_settings::ResolvedWhitespaceOptions, line_diff,
+};
 use language_model::{
     CompletionIntent, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage,
@@ -681,14 +684,17 @@ impl CodegenAlternative {
             }
         }
 
-        let language_name = {
+        let (language_name, whitespace) = {
             let multibuffer = self.buffer.read(cx);
             let snapshot = multibuffer.snapshot(cx);
             let ranges = snapshot.range_to_buffer_ranges(self.range.start..self.range.end);
-            ranges
-                .first()
-                .and_then(|(buffer, _, _)| buffer.language())
-                .map(|language| language.name())
+            let buffer = ranges.first().map(|(buffer, _, _)| buffer);
+            (
+                buffer
+                    .and_then(|buffer| buffer.language())
+                    .map(|language| language.name()),
+                buffer.map(|buffer| ResolvedWhitespaceOptions::for_buffer_snapshot(buffer, cx)),
+            )
         };
 
         self.diff = Diff::default();
@@ -751,9 +757,19 @@ impl CodegenAlternative {
                                 while let Some(line) = lines.next() {
                                     new_text.push_str(line);
                                     if line_indent.is_none()
-                                        && let Some(non_whitespace_ch_ix) =
+                                        && let Some(mut non_whitespace_ch_ix) =
                                             new_text.find(|ch: char| !ch.is_whitespace())
                                     {
+                                        // Measure the generated indentation in the style of the
+                                        // buffer, whatever the model wrote it with.
+                                        if let Some(whitespace) = &whitespace {
+                                            let indent = whitespace.normalize_indent(
+                                                &new_text[..non_whitespace_ch_ix],
+                                                whitespace.indent_size.get(),
+                                            );
+                                            new_text.replace_range(..non_whitespace_ch_ix, &indent);
+                                            non_whitespace_ch_ix = indent.len();
+                                        }
                                         line_indent = Some(non_whitespace_ch_ix);
                                         base_indent = base_indent.or(line_indent);
 
@@ -1497,7 +1513,7 @@ mod tests {
     };
     use gpui::TestAppContext;
     use indoc::indoc;
-    use language::{Buffer, Point};
+    use language::{Buffer, Point, language_settings::IndentationOverride};
     use language_model::fake_provider::FakeLanguageModel;
     use language_model::{
         LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelRegistry,
@@ -1506,7 +1522,7 @@ mod tests {
     use languages::rust_lang;
     use rand::prelude::*;
     use settings::SettingsStore;
-    use std::{future, sync::Arc};
+    use std::{future, num::NonZeroU32, sync::Arc};
 
     #[gpui::test(iterations = 10)]
     async fn test_transform_autoindent(cx: &mut TestAppContext, mut rng: StdRng) {
@@ -1753,6 +1769,71 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_generated_spaces_are_normalized_to_tabs(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let text = indoc! {"
+            func main() {
+            \tx := 0
+            }
+        "};
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local(text, cx);
+            buffer.set_indentation_override(
+                Some(IndentationOverride {
+                    tab_size: NonZeroU32::new(4).unwrap(),
+                    tab_width: None,
+                    hard_tabs: true,
+                }),
+                cx,
+            );
+            buffer
+        });
+        let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+        let range = buffer.read_with(cx, |buffer, cx| {
+            let snapshot = buffer.snapshot(cx);
+            snapshot.anchor_before(Point::new(0, 0))..snapshot.anchor_after(Point::new(2, 1))
+        });
+        let prompt_builder = Arc::new(PromptBuilder::new(None).unwrap());
+        let codegen = cx.new(|cx| {
+            CodegenAlternative::new(
+                buffer.clone(),
+                range.clone(),
+                true,
+                prompt_builder,
+                Uuid::new_v4(),
+                cx,
+            )
+        });
+
+        // The model indents by 4 spaces, whatever the buffer uses.
+        let chunks_tx = simulate_response_stream(&codegen, cx);
+        let new_text = concat!(
+            "func main() {\n",
+            "    x := 0\n",
+            "    for x < 10 {\n",
+            "        x++\n",
+            "    }\n",
+            "}", //
+        );
+        chunks_tx.unbounded_send(new_text.to_string()).unwrap();
+        drop(chunks_tx);
+        cx.background_executor.run_until_parked();
+
+        assert_eq!(
+            buffer.read_with(cx, |buffer, cx| buffer.snapshot(cx).text()),
+            indoc! {"
+                func main() {
+                \tx := 0
+                \tfor x < 10 {
+                \t\tx++
+                \t}
+                }
+            "}
+        );
+    }
+
     #[gpui::test]
     async fn test_inactive_codegen_alternative(cx: &mut TestAppContext) {
         init_test(cx);
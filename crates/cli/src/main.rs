@@ -123,6 +123,10 @@ struct Args {
     /// Will attempt to give the correct command to run
     #[arg(long)]
     system_specs: bool,
+    /// Check the indentation of the given paths against the project's settings and
+    /// `.editorconfig` files without opening Zed, exiting with a nonzero status on violations
+    #[arg(long)]
+    check_indentation: bool,
     /// Open the project in a dev container.
     ///
     /// Automatically triggers "Reopen in Dev Container" if a `.devcontainer/`
@@ -514,6 +518,15 @@ fn main() -> Result<()> {
         anyhow::bail!(msg.join("\n"));
     }
 
+    if args.check_indentation {
+        let status = std::process::Command::new(app.path())
+            .arg("--check-indentation")
+            .args(&args.paths_with_position)
+            .status()
+            .context("running the indentation check")?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
     #[cfg(all(
         any(target_os = "linux", target_os = "macos"),
         not(feature = "no-bundled-uninstall")
@@ -37,7 +37,7 @@ use rpc::RECEIVE_TIMEOUT;
 use serde_json::json;
 use settings::{
     DocumentFoldingRanges, DocumentSymbols, InlayHintSettingsContent, InlineBlameSettings,
-    SemanticTokens, SettingsStore,
+    LocalSettingsKind, LocalSettingsPath, SemanticTokens, SettingsStore,
 };
 use std::{
     collections::BTreeSet,
@@ -4098,6 +4098,93 @@ async fn test_collaborating_with_external_editorconfig(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_collaborating_with_editorconfig_overrides(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a.language_registry().add(rust_lang());
+    client_b.language_registry().add(rust_lang());
+    client_a
+        .fs()
+        .insert_tree(
+            path!("/a"),
+            json!({
+                "src": {
+                    "main.rs": "fn main() {}",
+                },
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project(path!("/a"), cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| {
+            p.open_buffer((worktree_id, rel_path("src/main.rs")), cx)
+        })
+        .await
+        .unwrap();
+    cx_b.run_until_parked();
+    let default_tab_size =
+        cx_b.read(|cx| LanguageSettings::for_buffer(&buffer_b.read(cx), cx).tab_size);
+
+    // Client A overrides the indentation of the file, as the indent size selector does.
+    let override_path = LocalSettingsPath::InWorktree(rel_path("src/main.rs").into());
+    cx_a.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.set_local_settings(
+                worktree_id,
+                override_path.clone(),
+                LocalSettingsKind::Editorconfig,
+                Some("[*.rs]\nindent_size = 7\n"),
+                cx,
+            )
+        })
+    })
+    .unwrap();
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+
+    cx_b.read(|cx| {
+        let settings = LanguageSettings::for_buffer(&buffer_b.read(cx), cx);
+        assert_eq!(Some(settings.tab_size), NonZeroU32::new(7));
+    });
+
+    // Client A clears the override.
+    cx_a.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.set_local_settings(
+                worktree_id,
+                override_path,
+                LocalSettingsKind::Editorconfig,
+                None,
+                cx,
+            )
+        })
+    })
+    .unwrap();
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+
+    cx_b.read(|cx| {
+        let settings = LanguageSettings::for_buffer(&buffer_b.read(cx), cx);
+        assert_eq!(settings.tab_size, default_tab_size);
+    });
+}
+
 #[gpui::test]
 async fn test_add_breakpoints(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
     let executor = cx_a.executor();
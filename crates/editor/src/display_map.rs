@@ -215,6 +215,8 @@ pub struct DisplayMap {
     fold_map: FoldMap,
     /// Keeps track of hard tabs in a buffer.
     tab_map: TabMap,
+    /// The width tabs are displayed with instead of the buffer's tab size.
+    tab_size_override: Option<NonZeroU32>,
     /// Handles soft wrapping.
     wrap_map: Entity<WrapMap>,
     /// Tracks custom blocks such as diagnostics that should be displayed within buffer.
@@ -400,7 +402,7 @@ impl DisplayMap {
         diagnostics_max_severity: DiagnosticSeverity,
         cx: &mut Context<Self>,
     ) -> Self {
-        let tab_size = Self::tab_size(&buffer, cx);
+        let tab_size = Self::buffer_tab_size(&buffer, cx);
         // Important: obtain the snapshot BEFORE creating the subscription.
         // snapshot() may call sync() which publishes edits. If we subscribe first,
         // those edits would be captured but the InlayMap would already be at the
@@ -423,6 +425,7 @@ impl DisplayMap {
             fold_map,
             inlay_map,
             tab_map,
+            tab_size_override: None,
             wrap_map,
             block_map,
             crease_map,
@@ -478,7 +481,7 @@ impl DisplayMap {
         let snapshot = {
             let edits = self.buffer_subscription.consume();
             let snapshot = self.buffer.read(cx).snapshot(cx);
-            let tab_size = Self::tab_size(&self.buffer, cx);
+            let tab_size = self.tab_size(cx);
             let (snapshot, edits) = self.inlay_map.sync(snapshot, edits.into_inner());
             let (mut writer, snapshot, edits) = self.fold_map.write(snapshot, edits);
             let (snapshot, edits) = self.tab_map.sync(snapshot, edits, tab_size);
@@ -578,7 +581,7 @@ impl DisplayMap {
     }
 
     fn sync_through_wrap(&mut self, cx: &mut App) -> (WrapSnapshot, WrapPatch) {
-        let tab_size = Self::tab_size(&self.buffer, cx);
+        let tab_size = self.tab_size(cx);
         let buffer_snapshot = self.buffer.read(cx).snapshot(cx);
         let edits = self.buffer_subscription.consume().into_inner();
 
@@ -741,7 +744,7 @@ impl DisplayMap {
 
         let buffer_snapshot = self.buffer.read(cx).snapshot(cx);
         let edits = self.buffer_subscription.consume().into_inner();
-        let tab_size = Self::tab_size(&self.buffer, cx);
+        let tab_size = self.tab_size(cx);
 
         let (snapshot, edits) = self.inlay_map.sync(buffer_snapshot.clone(), edits);
         let (mut fold_map, snapshot, edits) = self.fold_map.write(snapshot, edits);
@@ -816,7 +819,7 @@ impl DisplayMap {
     ) {
         let snapshot = self.buffer.read(cx).snapshot(cx);
         let edits = self.buffer_subscription.consume().into_inner();
-        let tab_size = Self::tab_size(&self.buffer, cx);
+        let tab_size = self.tab_size(cx);
 
         let (snapshot, edits) = self.inlay_map.sync(snapshot, edits);
         let (mut fold_map, snapshot, edits) = self.fold_map.write(snapshot, edits);
@@ -850,7 +853,7 @@ impl DisplayMap {
             .map(|range| range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot))
             .collect::<Vec<_>>();
         let edits = self.buffer_subscription.consume().into_inner();
-        let tab_size = Self::tab_size(&self.buffer, cx);
+        let tab_size = self.tab_size(cx);
 
         let (snapshot, edits) = self.inlay_map.sync(snapshot, edits);
         let (mut fold_map, snapshot, edits) = self.fold_map.write(snapshot, edits);
@@ -1266,7 +1269,7 @@ impl DisplayMap {
     ) -> bool {
         let snapshot = self.buffer.read(cx).snapshot(cx);
         let edits = self.buffer_subscription.consume().into_inner();
-        let tab_size = Self::tab_size(&self.buffer, cx);
+        let tab_size = self.tab_size(cx);
 
         let (snapshot, edits) = self.inlay_map.sync(snapshot, edits);
         let (mut fold_map, snapshot, edits) = self.fold_map.write(snapshot, edits);
@@ -1289,6 +1292,55 @@ impl DisplayMap {
         widths_changed
     }
 
+    /// Switches the tabs of the buffer between expanding to the next multiple of the tab size and
+    /// elastic tabstops, which line up the cells that tabs separate on consecutive lines.
+    #[instrument(skip_all)]
+    pub fn set_elastic_tabstops(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = self.buffer_subscription.consume().into_inner();
+        let tab_size = self.tab_size(cx);
+
+        let (snapshot, edits) = self.inlay_map.sync(snapshot, edits);
+        let (snapshot, edits) = self.fold_map.read(snapshot, edits);
+        let (snapshot, edits) = self.tab_map.sync(snapshot, edits, tab_size);
+        let (snapshot, edits) = self
+            .wrap_map
+            .update(cx, |map, cx| map.sync(snapshot, edits, cx));
+        self.block_map.read(snapshot, edits, None);
+
+        let (snapshot, edits) = self.tab_map.set_elastic_tabstops(enabled);
+        let (snapshot, edits) = self
+            .wrap_map
+            .update(cx, |map, cx| map.sync(snapshot, edits, cx));
+        self.block_map.read(snapshot, edits, None);
+    }
+
+    pub fn elastic_tabstops(&self) -> bool {
+        self.tab_map.elastic_tabstops()
+    }
+
+    /// Displays tabs `tab_size` columns wide rather than with the tab size the settings of the
+    /// buffer prescribe, or with that again when it's `None`, leaving the buffer as it is.
+    #[instrument(skip_all)]
+    pub fn set_tab_size_override(&mut self, tab_size: Option<NonZeroU32>, cx: &mut Context<Self>) {
+        self.tab_size_override = tab_size;
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = self.buffer_subscription.consume().into_inner();
+        let tab_size = self.tab_size(cx);
+
+        let (snapshot, edits) = self.inlay_map.sync(snapshot, edits);
+        let (snapshot, edits) = self.fold_map.read(snapshot, edits);
+        let (snapshot, edits) = self.tab_map.sync(snapshot, edits, tab_size);
+        let (snapshot, edits) = self
+            .wrap_map
+            .update(cx, |map, cx| map.sync(snapshot, edits, cx));
+        self.block_map.read(snapshot, edits, None);
+    }
+
+    pub fn tab_size_override(&self) -> Option<NonZeroU32> {
+        self.tab_size_override
+    }
+
     pub(crate) fn current_inlays(&self) -> impl Iterator<Item = &Inlay> + Default {
         self.inlay_map.current_inlays()
     }
@@ -1305,7 +1357,7 @@ impl DisplayMap {
         }
         let buffer_snapshot = self.buffer.read(cx).snapshot(cx);
         let edits = self.buffer_subscription.consume().into_inner();
-        let tab_size = Self::tab_size(&self.buffer, cx);
+        let tab_size = self.tab_size(cx);
 
         let companion_wrap_data = self.companion.as_ref().and_then(|(companion_dm, _)| {
             companion_dm
@@ -1372,10 +1424,18 @@ impl DisplayMap {
         }
     }
 
+    fn tab_size(&self, cx: &App) -> NonZeroU32 {
+        self.tab_size_override
+            .unwrap_or_else(|| Self::buffer_tab_size(&self.buffer, cx))
+    }
+
     #[instrument(skip_all)]
-    fn tab_size(buffer: &Entity<MultiBuffer>, cx: &App) -> NonZeroU32 {
+    fn buffer_tab_size(buffer: &Entity<MultiBuffer>, cx: &App) -> NonZeroU32 {
         if let Some(buffer) = buffer.read(cx).as_singleton().map(|buffer| buffer.read(cx)) {
-            LanguageSettings::for_buffer(buffer, cx).tab_size
+            let settings = LanguageSettings::for_buffer(buffer, cx);
+            // A tab width apart from the indent size, as in `vim: ts=8 sw=4`, is the one tabs
+            // are shown at.
+            settings.tab_width.unwrap_or(settings.tab_size)
         } else {
             AllLanguageSettings::get_global(cx).defaults.tab_size
         }
@@ -3918,6 +3978,74 @@ pub mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_tab_size_override(cx: &mut gpui::App) {
+        init_test(cx, &|_| {});
+
+        let buffer = MultiBuffer::build_simple("\ta\n\t\tb", cx);
+        let map = cx.new(|cx| {
+            DisplayMap::new(
+                buffer.clone(),
+                font("Helvetica"),
+                px(14.0),
+                None,
+                1,
+                1,
+                FoldPlaceholder::test(),
+                DiagnosticSeverity::Warning,
+                cx,
+            )
+        });
+        assert_eq!(
+            map.update(cx, |map, cx| map.snapshot(cx)).text(),
+            "    a\n        b"
+        );
+
+        map.update(cx, |map, cx| {
+            map.set_tab_size_override(NonZeroU32::new(2), cx)
+        });
+        assert_eq!(
+            map.update(cx, |map, cx| map.snapshot(cx)).text(),
+            "  a\n    b"
+        );
+
+        map.update(cx, |map, cx| map.set_tab_size_override(None, cx));
+        assert_eq!(
+            map.update(cx, |map, cx| map.snapshot(cx)).text(),
+            "    a\n        b"
+        );
+    }
+
+    #[gpui::test]
+    fn test_modeline_tab_width(cx: &mut gpui::App) {
+        init_test(cx, &|_| {});
+
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local("\ta\n# vim: ts=8 sw=2 et", cx);
+            buffer.set_modeline(language::parse_modeline(&["# vim: ts=8 sw=2 et"], &[]));
+            buffer
+        });
+        let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+        let map = cx.new(|cx| {
+            DisplayMap::new(
+                buffer.clone(),
+                font("Helvetica"),
+                px(14.0),
+                None,
+                1,
+                1,
+                FoldPlaceholder::test(),
+                DiagnosticSeverity::Warning,
+                cx,
+            )
+        });
+        // Tabs are shown at the modeline's tab stop, not at its indent width.
+        assert_eq!(
+            map.update(cx, |map, cx| map.snapshot(cx)).text(),
+            "        a\n# vim: ts=8 sw=2 et"
+        );
+    }
+
     #[gpui::test]
     fn test_max_point(cx: &mut gpui::App) {
         init_test(cx, &|_| {});
@@ -5,7 +5,7 @@ use super::{
 
 use language::{LanguageAwareStyling, Point};
 use multi_buffer::MultiBufferSnapshot;
-use std::{cmp, num::NonZeroU32, ops::Range};
+use std::{cmp, num::NonZeroU32, ops::Range, sync::Arc};
 use sum_tree::Bias;
 
 const MAX_EXPANSION_COLUMN: u32 = 256;
@@ -26,11 +26,127 @@ impl TabMap {
             fold_snapshot,
             tab_size: tab_size.min(MAX_TABS),
             max_expansion_column: MAX_EXPANSION_COLUMN,
+            elastic_stops: None,
             version: 0,
         };
         (Self(snapshot.clone()), snapshot)
     }
 
+    pub fn elastic_tabstops(&self) -> bool {
+        self.0.elastic_stops.is_some()
+    }
+
+    /// Switches between tabs expanding to the next multiple of the tab size and elastic tabs,
+    /// which line up the cells of consecutive lines that tabs separate.
+    pub fn set_elastic_tabstops(&mut self, enabled: bool) -> (TabSnapshot, Vec<TabEdit>) {
+        if self.0.elastic_stops.is_some() == enabled {
+            return (self.0.clone(), Vec::new());
+        }
+        let old_max_point = self.0.max_point();
+        self.0.version += 1;
+        self.0.elastic_stops =
+            enabled.then(|| Arc::new(elastic_stops(&self.0.fold_snapshot, self.0.tab_size)));
+        (
+            self.0.clone(),
+            vec![TabEdit {
+                old: TabPoint::zero()..old_max_point,
+                new: TabPoint::zero()..self.0.max_point(),
+            }],
+        )
+    }
+
+    /// Applies `fold_edits` while tabs are elastic. An elastic tab's width depends on the cells
+    /// of the lines around it, but only of those in the same block of consecutive lines with
+    /// tabs, so the stops of the blocks around the edited lines are recomputed, and only those
+    /// lines are reported as edited.
+    fn sync_elastic(
+        &mut self,
+        fold_snapshot: FoldSnapshot,
+        fold_edits: &[FoldEdit],
+        version: usize,
+    ) -> (TabSnapshot, Vec<TabEdit>) {
+        let old_snapshot = &self.0;
+        let old_fold_snapshot = &old_snapshot.fold_snapshot;
+        let max_row = fold_snapshot.max_point().row();
+        let has_tab = |row: u32| {
+            row_cells(&fold_snapshot, row..row + 1)
+                .first()
+                .is_some_and(|cells| !cells.is_empty())
+        };
+
+        // The rows each edit touches, before and after it, merged where they meet.
+        let mut row_edits: Vec<(Range<u32>, Range<u32>)> = Vec::new();
+        for fold_edit in fold_edits {
+            let old_rows = fold_edit.old.start.to_point(old_fold_snapshot).row()
+                ..fold_edit.old.end.to_point(old_fold_snapshot).row() + 1;
+            let new_rows = fold_edit.new.start.to_point(&fold_snapshot).row()
+                ..fold_edit.new.end.to_point(&fold_snapshot).row() + 1;
+            push_row_edit(&mut row_edits, old_rows, new_rows);
+        }
+        // Extended through the blocks of rows with tabs around them, up to the neighboring
+        // edits, so that the rows they're extended by are untouched and map one to one.
+        let mut extended: Vec<(Range<u32>, Range<u32>)> = Vec::with_capacity(row_edits.len());
+        for ix in 0..row_edits.len() {
+            let (mut old_rows, mut new_rows) = row_edits[ix].clone();
+            let floor = ix.checked_sub(1).map_or(0, |ix| row_edits[ix].1.end);
+            let ceiling = row_edits
+                .get(ix + 1)
+                .map_or(max_row + 1, |edit| edit.1.start);
+            while new_rows.start > floor && has_tab(new_rows.start - 1) {
+                new_rows.start -= 1;
+                old_rows.start -= 1;
+            }
+            while new_rows.end < ceiling && has_tab(new_rows.end) {
+                new_rows.end += 1;
+                old_rows.end += 1;
+            }
+            push_row_edit(&mut extended, old_rows, new_rows);
+        }
+
+        let old_stops = old_snapshot.elastic_stops.clone().unwrap_or_default();
+        let unchanged = |rows: Range<u32>| {
+            let end = (rows.end as usize).min(old_stops.len());
+            old_stops[(rows.start as usize).min(end)..end]
+                .iter()
+                .cloned()
+        };
+        let mut stops = Vec::with_capacity(max_row as usize + 1);
+        let mut old_row = 0;
+        for (old_rows, new_rows) in &extended {
+            stops.extend(unchanged(old_row..old_rows.start));
+            let mut cells = row_cells(&fold_snapshot, new_rows.clone());
+            cells.resize(new_rows.len(), Vec::new());
+            stops.extend(stops_for_cells(&cells, self.0.tab_size));
+            old_row = old_rows.end;
+        }
+        stops.extend(unchanged(old_row..u32::MAX));
+
+        let new_snapshot = TabSnapshot {
+            fold_snapshot,
+            tab_size: old_snapshot.tab_size,
+            max_expansion_column: old_snapshot.max_expansion_column,
+            elastic_stops: Some(Arc::new(stops)),
+            version,
+        };
+        let rows_range = |snapshot: &TabSnapshot, rows: &Range<u32>| {
+            let last_row = rows.end - 1;
+            snapshot.fold_point_to_tab_point(FoldPoint::new(rows.start, 0))
+                ..snapshot.fold_point_to_tab_point(FoldPoint::new(
+                    last_row,
+                    snapshot.fold_snapshot.line_len(last_row),
+                ))
+        };
+        let tab_edits = extended
+            .iter()
+            .map(|(old_rows, new_rows)| TabEdit {
+                old: rows_range(old_snapshot, old_rows),
+                new: rows_range(&new_snapshot, new_rows),
+            })
+            .collect();
+        self.0 = new_snapshot;
+        (self.0.clone(), tab_edits)
+    }
+
     #[cfg(test)]
     pub fn set_max_expansion_column(&mut self, column: u32) -> TabSnapshot {
         self.0.max_expansion_column = column;
@@ -46,11 +162,16 @@ impl TabMap {
     ) -> (TabSnapshot, Vec<TabEdit>) {
         let tab_size = tab_size.min(MAX_TABS);
 
+        let elastic = self.0.elastic_stops.is_some();
         if self.0.tab_size != tab_size {
             let old_max_point = self.0.max_point();
             self.0.version += 1;
             self.0.fold_snapshot = fold_snapshot;
             self.0.tab_size = tab_size;
+            if elastic {
+                self.0.elastic_stops =
+                    Some(Arc::new(elastic_stops(&self.0.fold_snapshot, tab_size)));
+            }
             return (
                 self.0.clone(),
                 vec![TabEdit {
@@ -72,6 +193,9 @@ impl TabMap {
             old_snapshot.tab_size = tab_size;
             return (old_snapshot.clone(), vec![]);
         }
+        if elastic {
+            return self.sync_elastic(fold_snapshot, &fold_edits, new_version);
+        }
         // Expand each edit to include the next tab on the same line as the edit,
         // and any subsequent tabs on that line that moved across the tab expansion
         // boundary.
@@ -146,6 +270,7 @@ impl TabMap {
             fold_snapshot,
             tab_size,
             max_expansion_column: old_snapshot.max_expansion_column,
+            elastic_stops: old_snapshot.elastic_stops.clone(),
             version: new_version,
         };
 
@@ -199,6 +324,8 @@ pub struct TabSnapshot {
     /// The maximum column up to which a tab can expand.
     /// Any tab after this column will not expand.
     pub max_expansion_column: u32,
+    /// The columns at which the tabs of each row end, when tabs are elastic.
+    elastic_stops: Option<Arc<Vec<Vec<u32>>>>,
     pub version: usize,
 }
 
@@ -316,7 +443,6 @@ impl TabSnapshot {
             max_expansion_column: self.max_expansion_column,
             output_position: range.start.0,
             max_output_position: range.end.0,
-            tab_size: self.tab_size,
             chunk: Chunk {
                 text: unsafe { std::str::from_utf8_unchecked(&SPACES[..to_next_stop as usize]) },
                 is_tab: true,
@@ -364,7 +490,7 @@ impl TabSnapshot {
     pub fn fold_point_to_tab_point(&self, input: FoldPoint) -> TabPoint {
         let chunks = self.fold_snapshot.chunks_at(FoldPoint::new(input.row(), 0));
         let tab_cursor = TabStopCursor::new(chunks);
-        let expanded = self.expand_tabs(tab_cursor, input.column());
+        let expanded = self.expand_tabs(tab_cursor, input.row(), input.column());
         TabPoint::new(input.row(), expanded)
     }
 
@@ -382,7 +508,7 @@ impl TabSnapshot {
         let tab_cursor = TabStopCursor::new(chunks);
         let expanded = output.column();
         let (collapsed, expanded_char_column, to_next_stop) =
-            self.collapse_tabs(tab_cursor, expanded, bias);
+            self.collapse_tabs(tab_cursor, output.row(), expanded, bias);
 
         (
             FoldPoint::new(output.row(), collapsed),
@@ -408,13 +534,11 @@ impl TabSnapshot {
     }
 
     #[ztracing::instrument(skip_all)]
-    fn expand_tabs<'a>(&self, mut cursor: TabStopCursor<'a>, column: u32) -> u32 {
+    fn expand_tabs<'a>(&self, mut cursor: TabStopCursor<'a>, row: u32, column: u32) -> u32 {
         // we only ever act on a single row at a time
         // so the main difference is that other layers build a transform sumtree, and can then just run through that
         // we cant quite do this here, as we need to work with the previous layer chunk to understand the tabs of the corresponding row
         // we can still do forward searches for this though, we search for a row, then traverse the column up to where we need to be
-        let tab_size = self.tab_size.get();
-
         let end_column = column.min(self.max_expansion_column);
         let mut seek_target = end_column;
         let mut tab_count = 0;
@@ -422,7 +546,7 @@ impl TabSnapshot {
 
         while let Some(tab_stop) = cursor.seek_forward(seek_target) {
             let expanded_chars_old = tab_stop.char_offset + expanded_tab_len - tab_count;
-            let tab_len = tab_size - ((expanded_chars_old - 1) % tab_size);
+            let tab_len = self.tab_len(row, expanded_chars_old - 1);
             tab_count += 1;
             expanded_tab_len += tab_len;
 
@@ -446,10 +570,10 @@ impl TabSnapshot {
     fn collapse_tabs<'a>(
         &self,
         mut cursor: TabStopCursor<'a>,
+        row: u32,
         column: u32,
         bias: Bias,
     ) -> (u32, u32, u32) {
-        let tab_size = self.tab_size.get();
         let mut collapsed_column = column;
         let mut seek_target = column.min(self.max_expansion_column);
         let mut tab_count = 0;
@@ -458,7 +582,7 @@ impl TabSnapshot {
         while let Some(tab_stop) = cursor.seek_forward(seek_target) {
             // Calculate how much we want to expand this tab stop (into spaces)
             let expanded_chars_old = tab_stop.char_offset + expanded_tab_len - tab_count;
-            let tab_len = tab_size - ((expanded_chars_old - 1) % tab_size);
+            let tab_len = self.tab_len(row, expanded_chars_old - 1);
             // Increment tab count
             tab_count += 1;
             // The count of how many spaces we've added to this line in place of tab bytes
@@ -497,6 +621,115 @@ impl TabSnapshot {
             0,
         )
     }
+
+    /// The number of columns that a tab at `column` of `row` expands to: up to the next
+    /// multiple of the tab size, or to the next elastic tab stop of the row.
+    fn tab_len(&self, row: u32, column: u32) -> u32 {
+        if let Some(stop) = self
+            .elastic_stops
+            .as_ref()
+            .and_then(|stops| stops.get(row as usize))
+            .and_then(|stops| stops.iter().find(|stop| **stop > column))
+        {
+            return (stop - column).min(MAX_TABS.get());
+        }
+        let tab_size = self.tab_size.get();
+        tab_size - column % tab_size
+    }
+}
+
+/// The columns at which the tabs of each row of `fold_snapshot` end when tabs are elastic. The
+/// tabs ending the same cell of consecutive rows line up, a column past the widest of those
+/// cells, and at least a tab size past the previous tab on each row, so that indentation keeps
+/// its width.
+fn elastic_stops(fold_snapshot: &FoldSnapshot, tab_size: NonZeroU32) -> Vec<Vec<u32>> {
+    let cells = row_cells(fold_snapshot, 0..fold_snapshot.max_point().row() + 1);
+    stops_for_cells(&cells, tab_size)
+}
+
+/// Adds the rows an edit touches before and after it to `row_edits`, which are in order,
+/// merging it into the last one when their rows meet.
+fn push_row_edit(
+    row_edits: &mut Vec<(Range<u32>, Range<u32>)>,
+    old_rows: Range<u32>,
+    new_rows: Range<u32>,
+) {
+    if let Some((last_old, last_new)) = row_edits.last_mut()
+        && new_rows.start <= last_new.end
+    {
+        last_old.end = last_old.end.max(old_rows.end);
+        last_new.end = last_new.end.max(new_rows.end);
+    } else {
+        row_edits.push((old_rows, new_rows));
+    }
+}
+
+/// The width of each cell that a tab ends, on each of `rows` of `fold_snapshot`.
+fn row_cells(fold_snapshot: &FoldSnapshot, rows: Range<u32>) -> Vec<Vec<u32>> {
+    let start = FoldPoint::new(rows.start, 0).to_offset(fold_snapshot);
+    let end =
+        cmp::min(FoldPoint::new(rows.end, 0), fold_snapshot.max_point()).to_offset(fold_snapshot);
+    let mut cells = vec![Vec::new()];
+    let mut width = 0;
+    for chunk in fold_snapshot.chunks(
+        start..end,
+        LanguageAwareStyling {
+            tree_sitter: false,
+            diagnostics: false,
+        },
+        Highlights::default(),
+    ) {
+        for c in chunk.text.chars() {
+            match c {
+                '\n' => {
+                    cells.push(Vec::new());
+                    width = 0;
+                }
+                '\t' => {
+                    if let Some(row) = cells.last_mut() {
+                        row.push(width);
+                    }
+                    width = 0;
+                }
+                _ => width += 1,
+            }
+        }
+    }
+    cells.truncate(rows.len());
+    cells
+}
+
+/// The columns at which the tabs of each row end, from the width of the `cells` they end.
+fn stops_for_cells(cells: &[Vec<u32>], tab_size: NonZeroU32) -> Vec<Vec<u32>> {
+    let mut stops = cells
+        .iter()
+        .map(|row| Vec::with_capacity(row.len()))
+        .collect::<Vec<Vec<u32>>>();
+    let max_cells = cells.iter().map(Vec::len).max().unwrap_or(0);
+    for cell in 0..max_cells {
+        let mut start = 0;
+        while start < cells.len() {
+            if cells[start].len() <= cell {
+                start += 1;
+                continue;
+            }
+            let end = (start..cells.len())
+                .find(|row| cells[*row].len() <= cell)
+                .unwrap_or(cells.len());
+            let width = cells[start..end]
+                .iter()
+                .map(|row| row[cell] + 1)
+                .max()
+                .unwrap_or(0)
+                .clamp(tab_size.get(), MAX_TABS.get());
+            for row in start..end {
+                let previous = stops[row].last().copied().unwrap_or(0);
+                stops[row].push(previous + width);
+            }
+            start = end;
+        }
+    }
+    stops
 }
 
 // todo(lw): Implement TabPointCursor properly
@@ -595,7 +828,6 @@ pub struct TabChunks<'a> {
     snapshot: &'a TabSnapshot,
     max_expansion_column: u32,
     max_output_position: Point,
-    tab_size: NonZeroU32,
     // region: iteration state
     fold_chunks: FoldChunks<'a>,
     chunk: Chunk<'a>,
@@ -664,12 +896,11 @@ impl<'a> Iterator for TabChunks<'a> {
             self.chunk.chars >>= 1;
             self.chunk.newlines >>= 1;
 
-            let tab_size = if self.input_column < self.max_expansion_column {
-                self.tab_size.get()
+            let mut len = if self.input_column < self.max_expansion_column {
+                self.snapshot.tab_len(self.output_position.row, self.column)
             } else {
                 1
             };
-            let mut len = tab_size - self.column % tab_size;
             let next_output_position = cmp::min(
                 self.output_position + Point::new(0, len),
                 self.max_output_position,
@@ -1045,6 +1276,86 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    fn test_elastic_tabstops(cx: &mut gpui::App) {
+        let input = "a\tb\nlong\tc\n\nx\ty\tz";
+
+        let buffer = MultiBuffer::build_simple(input, cx);
+        let buffer_snapshot = buffer.read(cx).snapshot(cx);
+        let (_, inlay_snapshot) = InlayMap::new(buffer_snapshot);
+        let (_, fold_snapshot) = FoldMap::new(inlay_snapshot);
+        let (mut tab_map, _) = TabMap::new(fold_snapshot, 2.try_into().unwrap());
+
+        let (tab_snapshot, edits) = tab_map.set_elastic_tabstops(true);
+        assert_eq!(edits.len(), 1);
+        // The first two lines form a block, the last one another.
+        assert_eq!(tab_snapshot.text(), "a    b\nlong c\n\nx y z");
+        assert_eq!(
+            tab_snapshot.fold_point_to_tab_point(FoldPoint::new(0, 2)),
+            TabPoint::new(0, 5)
+        );
+        assert_eq!(
+            tab_snapshot
+                .tab_point_to_fold_point(TabPoint::new(1, 5), Bias::Left)
+                .0,
+            FoldPoint::new(1, 5)
+        );
+
+        let (tab_snapshot, _) = tab_map.set_elastic_tabstops(false);
+        assert_eq!(tab_snapshot.text(), "a b\nlong  c\n\nx y z");
+    }
+
+    #[gpui::test]
+    fn test_elastic_tabstops_edits(cx: &mut gpui::App) {
+        let buffer = MultiBuffer::build_simple("a\tb\nlong\tc\n\nx\ty\tz\nq", cx);
+        let subscription = buffer.update(cx, |buffer, _| buffer.subscribe());
+        let buffer_snapshot = buffer.read(cx).snapshot(cx);
+        let (mut inlay_map, inlay_snapshot) = InlayMap::new(buffer_snapshot);
+        let (mut fold_map, fold_snapshot) = FoldMap::new(inlay_snapshot);
+        let tab_size = NonZeroU32::new(2).unwrap();
+        let (mut tab_map, _) = TabMap::new(fold_snapshot, tab_size);
+        tab_map.set_elastic_tabstops(true);
+
+        let buffer_snapshot = buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(0, 1)..Point::new(0, 1), "aaaaa")], None, cx);
+            buffer.snapshot(cx)
+        });
+        let (inlay_snapshot, inlay_edits) =
+            inlay_map.sync(buffer_snapshot, subscription.consume().into_inner());
+        let (fold_snapshot, fold_edits) = fold_map.read(inlay_snapshot, inlay_edits);
+        let (tab_snapshot, tab_edits) = tab_map.sync(fold_snapshot.clone(), fold_edits, tab_size);
+
+        // Only the block of lines with tabs around the edit moves, and is reported as edited.
+        assert_eq!(tab_snapshot.text(), "aaaaaa b\nlong   c\n\nx y z\nq");
+        assert_eq!(
+            tab_edits,
+            [TabEdit {
+                old: TabPoint::new(0, 0)..TabPoint::new(1, 6),
+                new: TabPoint::new(0, 0)..TabPoint::new(1, 8),
+            }]
+        );
+        let (mut fresh_map, _) = TabMap::new(fold_snapshot, tab_size);
+        let (fresh_snapshot, _) = fresh_map.set_elastic_tabstops(true);
+        assert_eq!(tab_snapshot.text(), fresh_snapshot.text());
+
+        // Removing the line between two blocks joins them.
+        let buffer_snapshot = buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(2, 0)..Point::new(3, 0), "")], None, cx);
+            buffer.snapshot(cx)
+        });
+        let (inlay_snapshot, inlay_edits) =
+            inlay_map.sync(buffer_snapshot, subscription.consume().into_inner());
+        let (fold_snapshot, fold_edits) = fold_map.read(inlay_snapshot, inlay_edits);
+        let (tab_snapshot, tab_edits) = tab_map.sync(fold_snapshot.clone(), fold_edits, tab_size);
+        let (mut fresh_map, _) = TabMap::new(fold_snapshot, tab_size);
+        let (fresh_snapshot, _) = fresh_map.set_elastic_tabstops(true);
+        assert_eq!(tab_snapshot.text(), fresh_snapshot.text());
+        assert_eq!(tab_snapshot.text(), "aaaaaa b\nlong   c\nx      y z\nq");
+        assert_eq!(tab_edits.len(), 1);
+        assert_eq!(tab_edits[0].new.start, TabPoint::new(0, 0));
+        assert_eq!(tab_edits[0].new.end.row(), 2);
+    }
+
     #[gpui::test]
     fn test_collapse_tabs(cx: &mut gpui::App) {
         let input = "A\tBC\tDEF\tG\tHI\tJ\tK\tL\tM";
@@ -181,8 +181,8 @@ use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, SharedScrol
 use selections_collection::{MutableSelectionsCollection, SelectionsCollection};
 use serde::{Deserialize, Serialize};
 use settings::{
-    GitGutterSetting, RelativeLineNumbers, Settings, SettingsLocation, SettingsStore,
-    update_settings_file,
+    GitGutterSetting, IndentGuideColoring, RelativeLineNumbers, Settings, SettingsLocation,
+    SettingsStore, update_settings_file,
 };
 use smallvec::{SmallVec, smallvec};
 use snippet::Snippet;
@@ -1190,6 +1190,8 @@ pub struct Editor {
     show_diff_review_button: bool,
     show_wrap_guides: Option<bool>,
     show_indent_guides: Option<bool>,
+    /// How to color the indent guides, instead of as the language settings prescribe.
+    indent_guide_coloring: Option<IndentGuideColoring>,
     buffers_with_disabled_indent_guides: HashSet<BufferId>,
     highlight_order: usize,
     highlighted_rows: HashMap<TypeId, Vec<RowHighlight>>,
@@ -2426,6 +2428,7 @@ impl Editor {
             show_diff_review_button: false,
             show_wrap_guides: None,
             show_indent_guides,
+            indent_guide_coloring: None,
             buffers_with_disabled_indent_guides: HashSet::default(),
             highlight_order: 0,
             highlighted_rows: HashMap::default(),
@@ -12537,7 +12540,7 @@ impl Editor {
         cx: &mut Context<Self>,
         mut manipulate: M,
     ) where
-        M: FnMut(&str) -> LineManipulationResult,
+        M: FnMut(&str, MultiBufferRow) -> LineManipulationResult,
     {
         self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
 
@@ -12574,7 +12577,7 @@ impl Editor {
                 new_text,
                 line_count_before,
                 line_count_after,
-            } = manipulate(&text);
+            } = manipulate(&text, start_row);
 
             edits.push((start_point..end_point, new_text));
 
@@ -12635,7 +12638,7 @@ impl Editor {
     ) where
         Fn: FnMut(&mut Vec<&str>),
     {
-        self.manipulate_lines(window, cx, |text| {
+        self.manipulate_lines(window, cx, |text, _| {
             let mut lines: Vec<&str> = text.split('\n').collect();
             let line_count_before = lines.len();
 
@@ -12655,13 +12658,13 @@ impl Editor {
         cx: &mut Context<Self>,
         mut callback: Fn,
     ) where
-        Fn: FnMut(&mut Vec<Cow<'_, str>>),
+        Fn: FnMut(&mut Vec<Cow<'_, str>>, MultiBufferRow),
     {
-        self.manipulate_lines(window, cx, |text| {
+        self.manipulate_lines(window, cx, |text, start_row| {
             let mut lines: Vec<Cow<str>> = text.split('\n').map(Cow::from).collect();
             let line_count_before = lines.len();
 
-            callback(&mut lines);
+            callback(&mut lines, start_row);
 
             LineManipulationResult {
                 new_text: lines.join("\n"),
@@ -12677,18 +12680,21 @@ impl Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let settings = self.buffer.read(cx).language_settings(cx);
-        let tab_size = settings.tab_size.get() as usize;
+        let tab_sizes = TabSizes::new(&self.buffer, cx);
 
-        self.manipulate_mutable_lines(window, cx, |lines| {
+        self.manipulate_mutable_lines(window, cx, |lines, start_row| {
             // Allocates a reasonably sized scratch buffer once for the whole loop
             let mut reindented_line = String::with_capacity(MAX_LINE_LEN);
             // Avoids recomputing spaces that could be inserted many times
-            let space_cache: Vec<Vec<char>> = (1..=tab_size)
+            let space_cache: Vec<Vec<char>> = (1..=tab_sizes.max())
                 .map(|n| IndentSize::spaces(n as u32).chars().collect())
                 .collect();
 
-            for line in lines.iter_mut().filter(|line| !line.is_empty()) {
+            for (row, line) in (start_row.0..)
+                .zip(lines.iter_mut())
+                .filter(|(_, line)| !line.is_empty())
+            {
+                let tab_size = tab_sizes.for_row(MultiBufferRow(row));
                 let mut chars = line.as_ref().chars();
                 let mut col = 0;
                 let mut changed = false;
@@ -12732,18 +12738,21 @@ impl Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let settings = self.buffer.read(cx).language_settings(cx);
-        let tab_size = settings.tab_size.get() as usize;
+        let tab_sizes = TabSizes::new(&self.buffer, cx);
 
-        self.manipulate_mutable_lines(window, cx, |lines| {
+        self.manipulate_mutable_lines(window, cx, |lines, start_row| {
             // Allocates a reasonably sized buffer once for the whole loop
             let mut reindented_line = String::with_capacity(MAX_LINE_LEN);
             // Avoids recomputing spaces that could be inserted many times
-            let space_cache: Vec<Vec<char>> = (1..=tab_size)
+            let space_cache: Vec<Vec<char>> = (1..=tab_sizes.max())
                 .map(|n| IndentSize::spaces(n as u32).chars().collect())
                 .collect();
 
-            for line in lines.iter_mut().filter(|line| !line.is_empty()) {
+            for (row, line) in (start_row.0..)
+                .zip(lines.iter_mut())
+                .filter(|(_, line)| !line.is_empty())
+            {
+                let tab_size = tab_sizes.for_row(MultiBufferRow(row));
                 let mut chars = line.chars();
                 let mut spaces_count = 0;
                 let mut first_non_indent_char = None;
@@ -22966,6 +22975,61 @@ impl Editor {
         cx.notify();
     }
 
+    /// Colors the indent guides with `coloring` rather than as the language settings prescribe,
+    /// or as they prescribe again when it's `None`.
+    pub fn set_indent_guide_coloring(
+        &mut self,
+        coloring: Option<IndentGuideColoring>,
+        cx: &mut Context<Self>,
+    ) {
+        self.indent_guide_coloring = coloring;
+        cx.notify();
+    }
+
+    /// How the indent guides are colored: as set for the editor, or else as the language
+    /// settings of its buffer prescribe.
+    pub fn indent_guide_coloring(&self, cx: &App) -> IndentGuideColoring {
+        self.indent_guide_coloring
+            .unwrap_or_else(|| match self.buffer().read(cx).as_singleton() {
+                Some(buffer) => {
+                    LanguageSettings::for_buffer(buffer.read(cx), cx)
+                        .indent_guides
+                        .coloring
+                }
+                None => {
+                    AllLanguageSettings::get_global(cx)
+                        .defaults
+                        .indent_guides
+                        .coloring
+                }
+            })
+    }
+
+    /// Lines up the cells that tabs separate on consecutive lines, as elastic tabstops do,
+    /// rather than expanding each tab to the next multiple of the tab size.
+    pub fn set_elastic_tabstops(&mut self, elastic_tabstops: bool, cx: &mut Context<Self>) {
+        self.display_map
+            .update(cx, |map, cx| map.set_elastic_tabstops(elastic_tabstops, cx));
+        cx.notify();
+    }
+
+    pub fn elastic_tabstops(&self, cx: &App) -> bool {
+        self.display_map.read(cx).elastic_tabstops()
+    }
+
+    /// Displays tabs `tab_size` columns wide in this editor alone, rather than with the tab size
+    /// the settings of its buffer prescribe, or with that again when it's `None`. Other editors
+    /// of the buffer, such as those of a split, and the buffer's indentation are left as they are.
+    pub fn set_tab_size_override(&mut self, tab_size: Option<NonZeroU32>, cx: &mut Context<Self>) {
+        self.display_map
+            .update(cx, |map, cx| map.set_tab_size_override(tab_size, cx));
+        cx.notify();
+    }
+
+    pub fn tab_size_override(&self, cx: &App) -> Option<NonZeroU32> {
+        self.display_map.read(cx).tab_size_override()
+    }
+
     pub fn working_directory(&self, cx: &App) -> Option<PathBuf> {
         if let Some(buffer) = self.buffer().read(cx).as_singleton() {
             if let Some(file) = buffer.read(cx).file().and_then(|f| f.as_local())
@@ -29376,6 +29440,48 @@ struct LineManipulationResult {
     pub line_count_after: usize,
 }
 
+/// The tab size in effect for each file with an excerpt in a multibuffer, so that converting
+/// indentation across excerpts follows each file's own settings rather than the first one's.
+struct TabSizes {
+    snapshot: MultiBufferSnapshot,
+    by_buffer: HashMap<BufferId, usize>,
+    default: usize,
+}
+
+impl TabSizes {
+    fn new(multi_buffer: &Entity<MultiBuffer>, cx: &App) -> Self {
+        let multi_buffer = multi_buffer.read(cx);
+        let by_buffer = multi_buffer
+            .all_buffers_iter()
+            .map(|buffer| {
+                let buffer = buffer.read(cx);
+                let tab_size = LanguageSettings::for_buffer(buffer, cx).tab_size.get() as usize;
+                (buffer.remote_id(), tab_size)
+            })
+            .collect();
+        Self {
+            snapshot: multi_buffer.snapshot(cx),
+            by_buffer,
+            default: multi_buffer.language_settings(cx).tab_size.get() as usize,
+        }
+    }
+
+    fn for_row(&self, row: MultiBufferRow) -> usize {
+        self.snapshot
+            .point_to_buffer_point(Point::new(row.0, 0))
+            .and_then(|(buffer, _)| self.by_buffer.get(&buffer.remote_id()))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    fn max(&self) -> usize {
+        self.by_buffer
+            .values()
+            .copied()
+            .fold(self.default, usize::max)
+    }
+}
+
 fn render_diff_hunk_controls(
     row: u32,
     status: &DiffHunkStatus,
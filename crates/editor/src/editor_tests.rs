@@ -6462,6 +6462,62 @@ async fn test_convert_indentation_to_tabs(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+fn test_convert_indentation_to_spaces_per_excerpt_file(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.tab_size = NonZeroU32::new(2);
+        settings.languages.0.insert(
+            "Python".into(),
+            LanguageSettingsContent {
+                tab_size: NonZeroU32::new(4),
+                ..Default::default()
+            },
+        );
+    });
+
+    let python = Arc::new(Language::new(
+        LanguageConfig {
+            name: "Python".into(),
+            ..Default::default()
+        },
+        None,
+    ));
+    let buffer_1 = cx.new(|cx| Buffer::local("a\n\tb", cx));
+    let buffer_2 = cx.new(|cx| {
+        let mut buffer = Buffer::local("c\n\td", cx);
+        buffer.set_language(Some(python), cx);
+        buffer
+    });
+    let multibuffer = cx.new(|cx| {
+        let mut multibuffer = MultiBuffer::new(ReadWrite);
+        multibuffer.set_excerpts_for_path(
+            PathKey::sorted(0),
+            buffer_1.clone(),
+            [Point::new(0, 0)..Point::new(1, 2)],
+            0,
+            cx,
+        );
+        multibuffer.set_excerpts_for_path(
+            PathKey::sorted(1),
+            buffer_2.clone(),
+            [Point::new(0, 0)..Point::new(1, 2)],
+            0,
+            cx,
+        );
+        multibuffer
+    });
+
+    cx.add_window(|window, cx| {
+        let mut editor = build_editor(multibuffer, window, cx);
+        editor.select_all(&SelectAll, window, cx);
+        editor.convert_indentation_to_spaces(&ConvertIndentationToSpaces, window, cx);
+        editor
+    });
+
+    buffer_1.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "a\n  b"));
+    buffer_2.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "c\n    d"));
+}
+
 #[gpui::test]
 async fn test_toggle_case(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -58,6 +58,13 @@ impl Editor {
         ))
     }
 
+    /// Recomputes the indent guides when the editor next renders, such as after the indentation
+    /// of its buffer changes without an edit.
+    pub fn refresh_indent_guides(&mut self, cx: &mut Context<Editor>) {
+        self.active_indent_guides_state.dirty = true;
+        cx.notify();
+    }
+
     pub fn find_active_indent_guide_indices(
         &mut self,
         indent_guides: &[IndentGuide],
@@ -159,6 +166,8 @@ pub fn indent_guides_in_range(
     let start_anchor = snapshot.buffer_snapshot().anchor_before(start_offset);
     let end_anchor = snapshot.buffer_snapshot().anchor_after(end_offset);
 
+    let tab_size_override = editor.tab_size_override(cx);
+
     let mut fold_ranges = Vec::<Range<Point>>::new();
     let folds = snapshot.folds_in_range(start_offset..end_offset).peekable();
     for fold in folds {
@@ -199,6 +208,22 @@ pub fn indent_guides_in_range(
 
             !has_containing_fold
         })
+        .map(|mut indent_guide| {
+            if let Some(coloring) = editor.indent_guide_coloring {
+                indent_guide.settings.coloring = coloring;
+            }
+            // Tabs displayed with another width move the levels they indent along with them.
+            if let Some(tab_size) = tab_size_override
+                && snapshot
+                    .buffer_snapshot()
+                    .line_indent_for_row(indent_guide.start_row)
+                    .tabs
+                    > 0
+            {
+                indent_guide.tab_size = tab_size.get();
+            }
+            indent_guide
+        })
         .collect()
 }
 
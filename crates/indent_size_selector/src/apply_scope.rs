@@ -0,0 +1,1390 @@
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    Action as _, AnyElement, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, WeakEntity,
+};
+use language::{Buffer, LanguageName, language_settings::IndentationOverride};
+use paths::EDITORCONFIG_NAME;
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use settings::{
+    IndentSizeSelectorOverrideScope, IndentationContent, IndentationProfileContent,
+    LanguageSettingsContent, LocalSettingsPath, Settings as _, SettingsContent, SettingsStore,
+    WorktreeId, update_settings_file,
+};
+use std::{collections::BTreeSet, num::NonZeroU32, ops::Range, sync::Arc};
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, prelude::*};
+use util::{ResultExt, paths::PathStyle, rel_path::RelPath};
+use workspace::{ModalView, Workspace};
+
+use crate::{
+    ApplyTo, IndentSizeSelectorSettings, apply_to_path,
+    bulk_results::BulkApplyResults,
+    editorconfig_override, file_buffer, file_type_glob, fix_on_save,
+    glob_selector::GlobSelector,
+    governing_editorconfig::{
+        EditorconfigTarget, offer_project_editorconfig, set_section_properties,
+        write_editorconfig_section,
+    },
+    history, indentation_events, indentation_properties, is_compact,
+    metrics::{self, Span},
+    modal_width,
+    override_batch::OverrideBatch,
+    persistence, profiles, set_buffer_override,
+    unsupported_files::{self, UnsupportedFile, overridable_file},
+};
+
+/// Precedes the section this selector appends to a directory's `.editorconfig`, so that choosing
+/// again replaces it instead of stacking another one.
+pub(crate) const OVERRIDE_MARKER: &str = "# Set from the indent size selector\n";
+
+/// Where the indentation chosen in the selector is applied.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ApplyScope {
+    /// The open buffer only, until it's reloaded from disk.
+    Buffer,
+    /// The file's path, as an `.editorconfig` override.
+    File,
+    /// Every file under the file's directory, as an `.editorconfig` override.
+    Directory(Arc<RelPath>),
+    /// Every open buffer of the language, as an `.editorconfig` override for each one's file.
+    OpenBuffers(LanguageName),
+    /// Every file of the language, in the user settings.
+    Language(LanguageName),
+    /// Every file in the worktree, as an `.editorconfig` override.
+    Worktree(SharedString),
+    /// The files in the worktree matching a glob, which is asked for next, as an
+    /// `.editorconfig` override.
+    Glob,
+    /// Every file in the worktree, in its `.zed/settings.json`, which is created when it doesn't
+    /// exist yet, so that the indentation is committed and shared along with the project.
+    Workspace(WorktreeId),
+    /// Every file, in the user settings.
+    Global,
+    /// New untitled buffers, in the user settings.
+    UntitledBuffers,
+}
+
+impl ApplyScope {
+    /// Whether this is the scope `apply_to` picks.
+    fn is(&self, apply_to: ApplyTo) -> bool {
+        matches!(
+            (self, apply_to),
+            (Self::Buffer, ApplyTo::Buffer)
+                | (Self::Language(_), ApplyTo::Language)
+                | (Self::Worktree(_), ApplyTo::Worktree)
+                | (Self::Workspace(_), ApplyTo::Workspace)
+        )
+    }
+
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Self::Buffer => "This buffer only".to_string(),
+            Self::File => "This file".to_string(),
+            Self::Directory(directory) => {
+                format!("Files in {}", directory.display(PathStyle::local()))
+            }
+            Self::OpenBuffers(language_name) => format!("All open {language_name} buffers"),
+            Self::Language(language_name) => format!("All {language_name} files"),
+            Self::Worktree(worktree_name) => format!("All files in {worktree_name}"),
+            Self::Glob => "Files matching a glob…".to_string(),
+            Self::Workspace(_) => "This workspace".to_string(),
+            Self::Global => "All files".to_string(),
+            Self::UntitledBuffers => "New untitled buffers".to_string(),
+        }
+    }
+
+    /// The files the scope covers when written to an `.editorconfig` on disk, if it can be.
+    pub(crate) fn editorconfig_target(&self, buffer: &Buffer) -> Option<EditorconfigTarget> {
+        match self {
+            Self::File => Some(EditorconfigTarget::File(buffer.file()?.path().clone())),
+            Self::Directory(directory) => Some(EditorconfigTarget::Directory(directory.clone())),
+            Self::Worktree(_) => Some(EditorconfigTarget::Directory(RelPath::empty().into())),
+            Self::Buffer
+            | Self::OpenBuffers(_)
+            | Self::Language(_)
+            | Self::Glob
+            | Self::Workspace(_)
+            | Self::Global
+            | Self::UntitledBuffers => None,
+        }
+    }
+
+    fn breadth(&self) -> IndentSizeSelectorOverrideScope {
+        match self {
+            Self::Buffer => IndentSizeSelectorOverrideScope::Buffer,
+            Self::File | Self::OpenBuffers(_) => IndentSizeSelectorOverrideScope::File,
+            Self::Directory(_) => IndentSizeSelectorOverrideScope::Directory,
+            Self::Language(_) => IndentSizeSelectorOverrideScope::Language,
+            Self::Worktree(_) | Self::Glob | Self::Workspace(_) => {
+                IndentSizeSelectorOverrideScope::Worktree
+            }
+            Self::Global | Self::UntitledBuffers => IndentSizeSelectorOverrideScope::Global,
+        }
+    }
+
+    /// Whether the scope is applied as an `.editorconfig` override, which the host of a shared
+    /// project sends to its guests.
+    pub(crate) fn is_override(&self) -> bool {
+        matches!(
+            self,
+            Self::File | Self::OpenBuffers(_) | Self::Directory(_) | Self::Worktree(_) | Self::Glob
+        )
+    }
+
+    pub(crate) fn detail(&self) -> &'static str {
+        match self {
+            Self::Buffer => "until closed",
+            Self::File
+            | Self::OpenBuffers(_)
+            | Self::Directory(_)
+            | Self::Worktree(_)
+            | Self::Glob => "override",
+            Self::Workspace(_) => "project settings",
+            Self::Language(_) | Self::Global | Self::UntitledBuffers => "user settings",
+        }
+    }
+}
+
+/// Asks where the indentation chosen in the selector should apply, then applies it there.
+pub(crate) struct ApplyScopeSelector {
+    picker: Entity<Picker<ApplyScopeSelectorDelegate>>,
+}
+
+impl ApplyScopeSelector {
+    /// Opens the scope chooser for applying `size` (as tabs when `hard_tabs` is set, rendered
+    /// `tab_width` wide when it's given) to the file open in `editor`, along with the rest of
+    /// `profile` when one was chosen, converting its existing indentation too when `convert` is
+    /// set: the selected lines when some are selected, or else all of them.
+    pub(crate) fn toggle(
+        editor: &Entity<Editor>,
+        size: NonZeroU32,
+        hard_tabs: bool,
+        tab_width: Option<NonZeroU32>,
+        profile: Option<IndentationProfileContent>,
+        convert: bool,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let selected_rows = if convert {
+            editor.update(cx, |editor, cx| fix_on_save::selected_rows(editor, cx))
+        } else {
+            Vec::new()
+        };
+        let editor = editor.read(cx);
+        let (Some(workspace), Some(project), Some(buffer)) = (
+            editor.workspace(),
+            editor.project().cloned(),
+            file_buffer(editor, cx),
+        ) else {
+            return;
+        };
+        let workspace_handle = workspace.downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                let delegate = ApplyScopeSelectorDelegate::new(
+                    cx.entity().downgrade(),
+                    workspace_handle,
+                    project,
+                    buffer,
+                    size,
+                    hard_tabs,
+                    tab_width,
+                    profile,
+                    convert,
+                    selected_rows,
+                    cx,
+                );
+                Self {
+                    picker: cx.new(|cx| Picker::uniform_list(delegate, window, cx)),
+                }
+            });
+        });
+    }
+
+    /// Applies `size` to the file open in `editor` in the scope `apply_to` picks, without asking,
+    /// as confirming that scope in the chooser would. Returns how broadly it applies, or `None`
+    /// when that scope isn't available for the file.
+    pub(crate) fn apply_preselected(
+        editor: &Entity<Editor>,
+        apply_to: ApplyTo,
+        size: NonZeroU32,
+        hard_tabs: bool,
+        tab_width: Option<NonZeroU32>,
+        profile: Option<IndentationProfileContent>,
+        convert: bool,
+        cx: &mut App,
+    ) -> Option<IndentSizeSelectorOverrideScope> {
+        let selected_rows = if convert {
+            editor.update(cx, |editor, cx| fix_on_save::selected_rows(editor, cx))
+        } else {
+            Vec::new()
+        };
+        let editor = editor.read(cx);
+        let (Some(workspace), Some(project), Some(buffer)) = (
+            editor.workspace(),
+            editor.project().cloned(),
+            file_buffer(editor, cx),
+        ) else {
+            return None;
+        };
+        let delegate = ApplyScopeSelectorDelegate::new(
+            WeakEntity::new_invalid(),
+            workspace.downgrade(),
+            project,
+            buffer,
+            size,
+            hard_tabs,
+            tab_width,
+            profile,
+            convert,
+            selected_rows,
+            cx,
+        );
+        let scope = delegate
+            .scopes
+            .iter()
+            .find(|scope| scope.is(apply_to))
+            .cloned()?;
+        delegate.apply_and_convert(&scope, cx);
+        history::describe(workspace.downgrade(), delegate.summary(&scope, cx), cx);
+        Some(scope.breadth())
+    }
+}
+
+impl Render for ApplyScopeSelector {
+    fn render(&mut self, window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("ApplyScopeSelector")
+            .w(modal_width(window))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for ApplyScopeSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for ApplyScopeSelector {}
+impl ModalView for ApplyScopeSelector {}
+
+struct ApplyScopeSelectorDelegate {
+    apply_scope_selector: WeakEntity<ApplyScopeSelector>,
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    buffer: Entity<Buffer>,
+    size: NonZeroU32,
+    hard_tabs: bool,
+    /// How wide a tab is rendered, when it differs from `size`.
+    tab_width: Option<NonZeroU32>,
+    /// The profile the indentation comes from, whose other settings are applied along with it.
+    profile: Option<IndentationProfileContent>,
+    /// Whether the existing indentation of the files it's applied to is converted too.
+    convert: bool,
+    /// The rows of the buffer that converting is limited to, unless it's empty.
+    selected_rows: Vec<Range<u32>>,
+    scopes: Vec<ApplyScope>,
+    candidates: Arc<[StringMatchCandidate]>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl ApplyScopeSelectorDelegate {
+    fn new(
+        apply_scope_selector: WeakEntity<ApplyScopeSelector>,
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        buffer: Entity<Buffer>,
+        size: NonZeroU32,
+        hard_tabs: bool,
+        tab_width: Option<NonZeroU32>,
+        profile: Option<IndentationProfileContent>,
+        convert: bool,
+        selected_rows: Vec<Range<u32>>,
+        cx: &App,
+    ) -> Self {
+        let tab_width = tab_width.filter(|tab_width| *tab_width != size);
+        let mut scopes = vec![ApplyScope::Buffer];
+        if overridable_file(buffer.read(cx)).is_some() {
+            scopes.push(ApplyScope::File);
+        }
+        if let Some(file) = buffer.read(cx).file()
+            && let Some(directory) = file.path().parent()
+            && !directory.is_empty()
+        {
+            scopes.push(ApplyScope::Directory(directory.into()));
+        }
+        if let Some(language) = buffer.read(cx).language() {
+            scopes.push(ApplyScope::OpenBuffers(language.name()));
+            scopes.push(ApplyScope::Language(language.name()));
+        }
+        // A file opened on its own is the only file in its worktree.
+        if UnsupportedFile::for_buffer(buffer.read(cx)) != Some(UnsupportedFile::SingleFile)
+            && let Some(worktree) = buffer
+                .read(cx)
+                .file()
+                .and_then(|file| project.read(cx).worktree_for_id(file.worktree_id(cx), cx))
+        {
+            scopes.push(ApplyScope::Worktree(
+                worktree.read(cx).root_name_str().to_string().into(),
+            ));
+            scopes.push(ApplyScope::Glob);
+        }
+        if let Some(file) = buffer.read(cx).file()
+            && unsupported_files::project_settings_unavailable(&project, buffer.read(cx), cx)
+                .is_none()
+        {
+            scopes.push(ApplyScope::Workspace(file.worktree_id(cx)));
+        }
+        scopes.push(ApplyScope::Global);
+        scopes.push(ApplyScope::UntitledBuffers);
+        if project.read(cx).is_via_collab() {
+            // A guest's overrides wouldn't reach the host or the other guests, so guests only
+            // get the scopes that are theirs alone.
+            scopes.retain(|scope| !scope.is_override());
+        }
+        if tab_width.is_some() {
+            // Settings files have a single `tab_size` for both.
+            scopes.retain(|scope| scope.is_override() || *scope == ApplyScope::Buffer);
+        }
+        if profile
+            .as_ref()
+            .is_some_and(|profile| !profiles::editorconfig_properties(profile).is_empty())
+        {
+            // Overriding a buffer alone, or new untitled buffers, only takes an indentation,
+            // which would drop the rest of the profile.
+            scopes
+                .retain(|scope| !matches!(scope, ApplyScope::Buffer | ApplyScope::UntitledBuffers));
+        }
+        let settings = IndentSizeSelectorSettings::get_global(cx);
+        let language_name = buffer.read(cx).language().map(|language| language.name());
+        scopes.retain(|scope| settings.allows_scope(language_name.as_ref(), scope.breadth()));
+        let candidates = scopes
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, scope)| StringMatchCandidate::new(candidate_id, &scope.label()))
+            .collect();
+
+        Self {
+            apply_scope_selector,
+            workspace,
+            project,
+            buffer,
+            size,
+            hard_tabs,
+            tab_width,
+            profile,
+            convert,
+            selected_rows,
+            scopes,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    /// Whether the language of the file allows applying its indentation to `scope`.
+    fn allows(&self, scope: &ApplyScope, cx: &App) -> bool {
+        let language_name = self
+            .buffer
+            .read(cx)
+            .language()
+            .map(|language| language.name());
+        IndentSizeSelectorSettings::get_global(cx)
+            .allows_scope(language_name.as_ref(), scope.breadth())
+    }
+
+    /// Describes applying the indentation to `scope`, for the notification offering to undo it,
+    /// such as "4 spaces via .editorconfig override for worktree 'api'".
+    fn summary(&self, scope: &ApplyScope, cx: &App) -> String {
+        let indentation = match (self.hard_tabs, self.tab_width) {
+            (true, Some(tab_width)) => format!("Tabs {tab_width} wide indenting by {}", self.size),
+            (true, None) => "Tabs".to_string(),
+            (false, _) => format!("{} spaces", self.size),
+        };
+        let via = format!("via {EDITORCONFIG_NAME} override");
+        let destination = match scope {
+            ApplyScope::Buffer => "for this buffer until it's closed".to_string(),
+            ApplyScope::File => {
+                let path = self
+                    .buffer
+                    .read(cx)
+                    .file()
+                    .map(|file| file.path().display(PathStyle::local()).to_string())
+                    .unwrap_or_default();
+                format!("{via} for {path}")
+            }
+            ApplyScope::Directory(directory) => format!(
+                "{via} for files in {}",
+                directory.display(PathStyle::local())
+            ),
+            ApplyScope::OpenBuffers(language_name) => {
+                format!("via {EDITORCONFIG_NAME} overrides for open {language_name} buffers")
+            }
+            ApplyScope::Language(language_name) => {
+                format!("in your user settings for {language_name}")
+            }
+            ApplyScope::Worktree(worktree_name) => format!("{via} for worktree '{worktree_name}'"),
+            ApplyScope::Glob => format!("{via} for files matching a glob"),
+            ApplyScope::Workspace(worktree_id) => {
+                match self.project.read(cx).worktree_for_id(*worktree_id, cx) {
+                    Some(worktree) => format!(
+                        "in the settings of worktree '{}'",
+                        worktree.read(cx).root_name_str()
+                    ),
+                    None => "in the workspace settings".to_string(),
+                }
+            }
+            ApplyScope::Global => "in your user settings for all files".to_string(),
+            ApplyScope::UntitledBuffers => {
+                "in your user settings for new untitled buffers".to_string()
+            }
+        };
+        format!("{indentation} {destination}")
+    }
+
+    /// Applies the indentation to `scope`, converting the file's existing lines too when asked
+    /// to.
+    fn apply_and_convert(&self, scope: &ApplyScope, cx: &mut App) {
+        self.apply(scope, cx);
+        // The open buffers are converted as the indentation is applied to each of them.
+        if !matches!(scope, ApplyScope::OpenBuffers(_)) {
+            self.convert(scope, cx);
+        }
+    }
+
+    fn convert(&self, scope: &ApplyScope, cx: &mut App) {
+        if !self.convert || !self.allows(scope, cx) {
+            return;
+        }
+        if self.buffer.read(cx).read_only() {
+            unsupported_files::show_not_applied(&self.workspace, unsupported_files::READ_ONLY, cx);
+        } else {
+            fix_on_save::convert_existing_indentation(
+                &self.buffer,
+                &self.selected_rows,
+                self.size.get(),
+                self.hard_tabs,
+                cx,
+            );
+        }
+    }
+
+    fn apply(&self, scope: &ApplyScope, cx: &mut App) {
+        if !self.allows(scope, cx) {
+            return;
+        }
+        let size = self.size;
+        let hard_tabs = self.hard_tabs;
+        let tab_width = self.tab_width;
+        if !matches!(scope, ApplyScope::Buffer | ApplyScope::UntitledBuffers) {
+            self.clear_buffer_override(cx);
+        }
+        match scope {
+            ApplyScope::Buffer => set_buffer_override(
+                &self.buffer,
+                IndentationOverride {
+                    tab_size: size,
+                    tab_width,
+                    hard_tabs,
+                },
+                cx,
+            ),
+            ApplyScope::File => {
+                let profile = self.profile.as_ref();
+                if !apply_to_path(&self.buffer, size.get(), hard_tabs, tab_width, profile, cx) {
+                    self.show_not_applied(scope, cx);
+                }
+            }
+            ApplyScope::OpenBuffers(language_name) => {
+                self.apply_to_open_buffers(language_name, cx);
+            }
+            ApplyScope::Directory(directory) => {
+                self.apply_to_directory(scope, directory.clone(), cx);
+            }
+            ApplyScope::Worktree(_) => self.apply_to_directory(scope, RelPath::empty().into(), cx),
+            // The glob is asked for once the scope chooser is dismissed.
+            ApplyScope::Glob => {}
+            ApplyScope::Language(language_name) => write_language_override(
+                &self.project,
+                LanguageOverrideFile::User,
+                vec![language_name.to_string()],
+                size,
+                hard_tabs,
+                self.profile.clone(),
+                cx,
+            ),
+            ApplyScope::Workspace(worktree_id) => {
+                let profile = self.profile.clone();
+                update_settings(
+                    &self.project,
+                    LanguageOverrideFile::Project(*worktree_id),
+                    move |settings| {
+                        let defaults = &mut settings.project.all_languages.defaults;
+                        defaults.tab_size = Some(size);
+                        defaults.hard_tabs = Some(hard_tabs);
+                        if let Some(profile) = &profile {
+                            profiles::set_language_settings(profile, defaults);
+                        }
+                    },
+                    cx,
+                );
+            }
+            ApplyScope::Global => {
+                let profile = self.profile.clone();
+                update_settings(
+                    &self.project,
+                    LanguageOverrideFile::User,
+                    move |settings| {
+                        let defaults = &mut settings.project.all_languages.defaults;
+                        defaults.tab_size = Some(size);
+                        defaults.hard_tabs = Some(hard_tabs);
+                        if let Some(profile) = &profile {
+                            profiles::set_language_settings(profile, defaults);
+                        }
+                    },
+                    cx,
+                );
+            }
+            ApplyScope::UntitledBuffers => {
+                update_settings(
+                    &self.project,
+                    LanguageOverrideFile::User,
+                    move |settings| {
+                        settings
+                            .indent_size_selector
+                            .get_or_insert_default()
+                            .untitled_indentation = Some(IndentationContent {
+                            tab_size: size,
+                            hard_tabs: Some(hard_tabs),
+                        });
+                    },
+                    cx,
+                );
+                // The buffer is one of them when it's untitled itself.
+                if self.buffer.read(cx).file().is_none() {
+                    set_buffer_override(
+                        &self.buffer,
+                        IndentationOverride {
+                            tab_size: size,
+                            tab_width,
+                            hard_tabs,
+                        },
+                        cx,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Clears the override for the buffer alone, which would otherwise keep shadowing the
+    /// indentation applied more broadly.
+    fn clear_buffer_override(&self, cx: &mut App) {
+        history::set_indentation_override(&self.buffer, None, cx);
+    }
+
+    /// Writes the indentation into the `.editorconfig` on disk that governs `scope`, so that it
+    /// persists and is shared with everyone working in the project.
+    fn write_to_editorconfig(&self, scope: &ApplyScope, cx: &mut App) {
+        if !self.allows(scope, cx) {
+            return;
+        }
+        self.clear_buffer_override(cx);
+        let buffer = self.buffer.read(cx);
+        let (Some(file), Some(target)) = (buffer.file(), scope.editorconfig_target(buffer)) else {
+            return;
+        };
+        let worktree_id = file.worktree_id(cx);
+        if let EditorconfigTarget::File(path) = &target {
+            // A file override from the selector would otherwise take precedence over the file.
+            let mut batch = OverrideBatch::default();
+            batch.set(
+                worktree_id,
+                LocalSettingsPath::InWorktree(path.clone()),
+                None,
+            );
+            batch.commit(cx).log_err();
+            persistence::forget_choice(&self.buffer, cx);
+        }
+        let mut properties =
+            indentation_properties(self.size.get(), self.hard_tabs, self.tab_width);
+        if let Some(profile) = &self.profile {
+            properties.extend(profiles::editorconfig_properties(profile));
+        }
+        write_editorconfig_section(&self.project, worktree_id, target, properties, cx);
+    }
+
+    /// Overrides the indentation of every buffer of `language_name` open in the workspace, in
+    /// any pane or excerpt, then shows how each one turned out.
+    fn apply_to_open_buffers(&self, language_name: &LanguageName, cx: &mut App) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let mut targets = Vec::<Entity<Buffer>>::new();
+        for editor in workspace.read(cx).items_of_type::<Editor>(cx) {
+            for buffer in editor.read(cx).buffer().read(cx).all_buffers() {
+                let is_match = buffer
+                    .read(cx)
+                    .language()
+                    .is_some_and(|language| language.name() == *language_name);
+                if is_match && !targets.contains(&buffer) {
+                    targets.push(buffer);
+                }
+            }
+        }
+        BulkApplyResults::apply_and_show(
+            &workspace,
+            &targets,
+            self.size.get(),
+            self.hard_tabs,
+            self.tab_width,
+            self.profile.clone(),
+            self.convert,
+            cx,
+        );
+    }
+
+    /// Overrides the indentation of everything under `directory` in the file's worktree, along
+    /// with the rest of the chosen profile, explaining when that fails.
+    fn apply_to_directory(&self, scope: &ApplyScope, directory: Arc<RelPath>, cx: &mut App) {
+        let Some(worktree_id) = self.buffer.read(cx).file().map(|file| file.worktree_id(cx)) else {
+            return;
+        };
+        let mut properties =
+            indentation_properties(self.size.get(), self.hard_tabs, self.tab_width);
+        if let Some(profile) = &self.profile {
+            properties.extend(profiles::editorconfig_properties(profile));
+        }
+        if !set_directory_override(worktree_id, directory, &properties, cx) {
+            self.show_not_applied(scope, cx);
+        }
+    }
+
+    fn show_not_applied(&self, scope: &ApplyScope, cx: &mut App) {
+        unsupported_files::show_not_applied(
+            &self.workspace,
+            format!("{}: the indentation couldn't be applied", scope.label()),
+            cx,
+        );
+    }
+}
+
+/// Sets `properties` in the `.editorconfig` override covering `scope` for the file of `buffer`,
+/// keeping the indentation and every other property already set there. Returns whether it was
+/// written, which it can't be for scopes that aren't overrides.
+pub(crate) fn set_override_properties(
+    buffer: &Entity<Buffer>,
+    scope: &ApplyScope,
+    properties: &[(&str, String)],
+    cx: &mut App,
+) -> bool {
+    let buffer = buffer.read(cx);
+    let (Some(file), Some(target)) = (overridable_file(buffer), scope.editorconfig_target(buffer))
+    else {
+        return false;
+    };
+    let worktree_id = file.worktree_id(cx);
+    let (override_path, content) = match target {
+        EditorconfigTarget::File(path) => {
+            let override_path = LocalSettingsPath::InWorktree(path.clone());
+            let existing =
+                editorconfig_override(worktree_id, &override_path, cx).unwrap_or_default();
+            let content = set_section_properties(&existing, &file_type_glob(&path), properties);
+            (override_path, content)
+        }
+        EditorconfigTarget::Directory(directory) => {
+            let override_path = LocalSettingsPath::InWorktree(directory);
+            let existing =
+                editorconfig_override(worktree_id, &override_path, cx).unwrap_or_default();
+            let content = set_override_section(&existing, "/**", properties);
+            (override_path, content)
+        }
+    };
+    let mut batch = OverrideBatch::default();
+    batch.set(worktree_id, override_path, Some(content));
+    batch.commit(cx).log_err().is_some()
+}
+
+/// Appends an `.editorconfig` section setting `properties` for everything under `directory`,
+/// keeping any `.editorconfig` already in effect there. Returns whether it was applied.
+fn set_directory_override(
+    worktree_id: WorktreeId,
+    directory: Arc<RelPath>,
+    properties: &[(&str, String)],
+    cx: &mut App,
+) -> bool {
+    let override_path = LocalSettingsPath::InWorktree(directory);
+    let config = directory_override_content(worktree_id, &override_path, properties, cx);
+    let mut batch = OverrideBatch::default();
+    batch.set(worktree_id, override_path, Some(config));
+    batch.commit(cx).log_err().is_some()
+}
+
+/// The `.editorconfig` override at `override_path` with a section setting `properties` for
+/// everything under its directory, updating the one the selector appended before, if any.
+pub(crate) fn directory_override_content(
+    worktree_id: WorktreeId,
+    override_path: &LocalSettingsPath,
+    properties: &[(&str, String)],
+    cx: &App,
+) -> String {
+    let existing = editorconfig_override(worktree_id, override_path, cx).unwrap_or_default();
+    set_override_section(&existing, "/**", properties)
+}
+
+/// Sets `properties` in the `[header]` section of the `existing` override, among the sections
+/// after the marker, which are the only ones the selector changes. Every other property and
+/// section is kept, whether it came from the directory's own `.editorconfig` or was added
+/// alongside the selector's.
+pub(crate) fn set_override_section(
+    existing: &str,
+    header: &str,
+    properties: &[(&str, String)],
+) -> String {
+    let (config, sections) = match existing.split_once(OVERRIDE_MARKER) {
+        Some((config, sections)) => (config.trim_end(), sections),
+        None => (existing.trim_end(), ""),
+    };
+    let mut content = String::new();
+    if !config.is_empty() {
+        content.push_str(config);
+        content.push_str("\n\n");
+    }
+    content.push_str(OVERRIDE_MARKER);
+    content.push_str(&set_section_properties(sections, header, properties));
+    content
+}
+
+/// The settings file a language override is written to.
+#[derive(Clone, Copy)]
+pub(crate) enum LanguageOverrideFile {
+    User,
+    /// The `.zed/settings.json` at the root of the worktree.
+    Project(WorktreeId),
+}
+
+/// Writes the indentation as an override for each of `language_names` in `file`, along with the
+/// rest of `profile` when it comes from one.
+pub(crate) fn write_language_override(
+    project: &Entity<Project>,
+    file: LanguageOverrideFile,
+    language_names: Vec<String>,
+    tab_size: NonZeroU32,
+    hard_tabs: bool,
+    profile: Option<IndentationProfileContent>,
+    cx: &mut App,
+) {
+    update_language_settings(
+        project,
+        file,
+        language_names,
+        move |language_settings| {
+            language_settings.tab_size = Some(tab_size);
+            language_settings.hard_tabs = Some(hard_tabs);
+            if let Some(profile) = &profile {
+                profiles::set_language_settings(profile, language_settings);
+            }
+        },
+        cx,
+    );
+}
+
+/// Applies `update` to the settings of each of `language_names` in `file`.
+pub(crate) fn update_language_settings(
+    project: &Entity<Project>,
+    file: LanguageOverrideFile,
+    language_names: Vec<String>,
+    update: impl Fn(&mut LanguageSettingsContent),
+    cx: &mut App,
+) {
+    update_settings(
+        project,
+        file,
+        move |settings| {
+            for language_name in language_names {
+                update(
+                    settings
+                        .project
+                        .all_languages
+                        .languages
+                        .0
+                        .entry(language_name)
+                        .or_default(),
+                );
+            }
+        },
+        cx,
+    );
+}
+
+/// Applies `update` to the settings in `file`, in a single write, recording the change in the
+/// indentation history so that it can be reverted.
+pub(crate) fn update_settings(
+    project: &Entity<Project>,
+    file: LanguageOverrideFile,
+    update: impl FnOnce(&mut SettingsContent),
+    cx: &mut App,
+) {
+    let before = settings_in_file(file, cx);
+    let mut after = before.clone();
+    update(&mut after);
+    let (Some(before), Some(after)) = (
+        serde_json::to_value(&before).log_err(),
+        serde_json::to_value(&after).log_err(),
+    ) else {
+        return;
+    };
+    if before == after {
+        return;
+    }
+    history::record_settings(project, file, before.clone(), after.clone(), cx);
+    write_settings_changes(project, file, before, after, cx);
+}
+
+/// The settings in `file`, as the settings store last loaded them.
+fn settings_in_file(file: LanguageOverrideFile, cx: &App) -> SettingsContent {
+    let store = cx.global::<SettingsStore>();
+    match file {
+        LanguageOverrideFile::User => store
+            .raw_user_settings()
+            .map(|settings| settings.content.as_ref().clone())
+            .unwrap_or_default(),
+        LanguageOverrideFile::Project(worktree_id) => SettingsContent {
+            project: store
+                .local_settings(worktree_id)
+                .find(|(path, _)| path.is_empty())
+                .map(|(_, content)| content.clone())
+                .unwrap_or_default(),
+            ..Default::default()
+        },
+    }
+}
+
+/// Writes the settings that differ between `from` and `to`, both serialized settings of
+/// `file`, as they are in `to`, leaving the rest of the file as it is. Swapping the two reverts
+/// the change.
+pub(crate) fn write_settings_changes(
+    project: &Entity<Project>,
+    file: LanguageOverrideFile,
+    from: serde_json::Value,
+    to: serde_json::Value,
+    cx: &App,
+) {
+    let update = move |settings: &mut SettingsContent| {
+        let Some(mut current) = serde_json::to_value(&*settings).log_err() else {
+            return;
+        };
+        copy_changes(&mut current, &from, &to);
+        if let Some(changed) = serde_json::from_value(current).log_err() {
+            *settings = changed;
+        }
+    };
+    let project = project.read(cx);
+    let fs = project.fs().clone();
+    let worktree_id = match file {
+        LanguageOverrideFile::User => {
+            update_settings_file(fs, cx, move |settings, _| update(settings));
+            return;
+        }
+        LanguageOverrideFile::Project(worktree_id) => worktree_id,
+    };
+    let Some(worktree) = project.worktree_for_id(worktree_id, cx) else {
+        return;
+    };
+    let path = worktree
+        .read(cx)
+        .abs_path()
+        .join(paths::local_settings_file_relative_path().as_std_path());
+
+    cx.spawn(async move |cx| {
+        let old_text = if fs.is_file(&path).await {
+            fs.load(&path).await?
+        } else {
+            settings::initial_project_settings_content().to_string()
+        };
+        let new_text =
+            cx.read_global(|store: &SettingsStore, _| store.new_text_for_update(old_text, update))?;
+        if let Some(directory) = path.parent() {
+            fs.create_dir(directory).await?;
+        }
+        fs.atomic_write(path, new_text).await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Sets the values of `current` that differ between `from` and `to` to those in `to`, removing
+/// the ones `to` leaves out.
+fn copy_changes(current: &mut serde_json::Value, from: &serde_json::Value, to: &serde_json::Value) {
+    if let (serde_json::Value::Object(from), serde_json::Value::Object(to)) = (from, to)
+        && let serde_json::Value::Object(current) = current
+    {
+        let keys = from
+            .keys()
+            .chain(to.keys())
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        for key in keys {
+            match (from.get(&key), to.get(&key)) {
+                (from_value, to_value) if from_value == to_value => {}
+                (_, None) => {
+                    current.remove(&key);
+                }
+                (from_value, Some(to_value)) => copy_changes(
+                    current.entry(key).or_insert(serde_json::Value::Null),
+                    from_value.unwrap_or(&serde_json::Value::Null),
+                    to_value,
+                ),
+            }
+        }
+        return;
+    }
+    *current = to.clone();
+}
+
+impl PickerDelegate for ApplyScopeSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        if self.hard_tabs {
+            "Apply tabs to…".into()
+        } else {
+            format!("Apply {} spaces to…", self.size).into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        let scope = &self.scopes[self.matches.get(self.selected_index)?.candidate_id];
+        scope.editorconfig_target(self.buffer.read(cx))?;
+
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_0p5()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("write-editorconfig", "Write to .editorconfig")
+                        .key_binding(
+                            KeyBinding::for_action(&menu::SecondaryConfirm, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("apply", "Apply")
+                        .key_binding(
+                            KeyBinding::for_action(&menu::Confirm, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                )
+                .into_any(),
+        )
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let scope = self.scopes[mat.candidate_id].clone();
+            if scope == ApplyScope::Glob {
+                if self.allows(&scope, cx)
+                    && let Some(workspace) = self.workspace.upgrade()
+                {
+                    let buffer = self.buffer.clone();
+                    let (size, hard_tabs, tab_width) = (self.size, self.hard_tabs, self.tab_width);
+                    let convert = self.convert;
+                    let selected_rows = self.selected_rows.clone();
+                    window.defer(cx, move |window, cx| {
+                        GlobSelector::toggle(
+                            &workspace,
+                            buffer,
+                            size,
+                            hard_tabs,
+                            tab_width,
+                            convert,
+                            selected_rows,
+                            window,
+                            cx,
+                        );
+                    });
+                }
+                self.dismissed(window, cx);
+                return;
+            }
+            let old = indentation_events::resolved_indent_size(&self.buffer, cx);
+            metrics::measure(Span::Apply, cx, |cx| {
+                history::transact(cx, |cx| {
+                    if secondary && scope.editorconfig_target(self.buffer.read(cx)).is_some() {
+                        // The `.editorconfig` on disk is left out of the history, so there's
+                        // nothing to offer undoing.
+                        self.write_to_editorconfig(&scope, cx);
+                        self.convert(&scope, cx);
+                    } else {
+                        self.apply_and_convert(&scope, cx);
+                        history::describe(self.workspace.clone(), self.summary(&scope, cx), cx);
+                    }
+                })
+            });
+            if self.allows(&scope, cx) {
+                indentation_events::emit_applied(
+                    vec![(self.buffer.clone(), Some(old))],
+                    self.size.get(),
+                    self.hard_tabs,
+                    scope.breadth(),
+                    cx,
+                );
+            }
+            if let &ApplyScope::Workspace(worktree_id) = &scope
+                && !secondary
+                && self.allows(&scope, cx)
+                && let Some(workspace) = self.workspace.upgrade()
+            {
+                let mut properties =
+                    indentation_properties(self.size.get(), self.hard_tabs, self.tab_width);
+                if let Some(profile) = &self.profile {
+                    properties.extend(profiles::editorconfig_properties(profile));
+                }
+                offer_project_editorconfig(&workspace, worktree_id, properties, window, cx);
+            }
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.apply_scope_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string.clone(),
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let scope = &self.scopes[mat.candidate_id];
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .when(!is_compact(window), |list_item| {
+                    list_item.end_slot(
+                        Label::new(scope.detail())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::language_settings::LanguageSettings;
+    use project::FakeFs;
+    use serde_json::json;
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace};
+
+    #[gpui::test]
+    async fn test_apply_tab_width_to_scopes(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn f() {\n\tg();\n}\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+
+        let delegate = cx.update(|cx| {
+            ApplyScopeSelectorDelegate::new(
+                WeakEntity::new_invalid(),
+                WeakEntity::new_invalid(),
+                project.clone(),
+                buffer.clone(),
+                NonZeroU32::new(4).unwrap(),
+                true,
+                NonZeroU32::new(8),
+                None,
+                false,
+                Vec::new(),
+                cx,
+            )
+        });
+        // Settings files can't keep the width of a tab apart from the indent size.
+        assert!(delegate.scopes.iter().all(|scope| {
+            !matches!(
+                scope,
+                ApplyScope::Language(_)
+                    | ApplyScope::Workspace(_)
+                    | ApplyScope::Global
+                    | ApplyScope::UntitledBuffers
+            )
+        }));
+
+        let worktree = delegate
+            .scopes
+            .iter()
+            .find(|scope| matches!(scope, ApplyScope::Worktree(_)))
+            .cloned()
+            .unwrap();
+        cx.update(|cx| delegate.apply(&worktree, cx));
+        cx.run_until_parked();
+        cx.update(|cx| {
+            let settings = LanguageSettings::for_buffer(buffer.read(cx), cx);
+            assert_eq!((settings.tab_size.get(), settings.hard_tabs), (4, true));
+            assert_eq!(settings.tab_width, NonZeroU32::new(8));
+        });
+
+        cx.update(|cx| delegate.apply(&ApplyScope::Buffer, cx));
+        assert_eq!(
+            cx.update(|cx| buffer.read(cx).indentation_override().copied()),
+            Some(IndentationOverride {
+                tab_size: NonZeroU32::new(4).unwrap(),
+                tab_width: NonZeroU32::new(8),
+                hard_tabs: true,
+            })
+        );
+    }
+
+    #[gpui::test]
+    async fn test_apply_profile_to_scopes(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn f() {\n\tg();\n}\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace =
+            multi_workspace.read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone());
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.rs")), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        let buffer = cx.update(|_, cx| {
+            let editor = item.act_as::<Editor>(cx).unwrap();
+            editor.read(cx).buffer().read(cx).as_singleton().unwrap()
+        });
+        cx.run_until_parked();
+
+        let profile = IndentationProfileContent {
+            name: "web".to_string(),
+            tab_size: NonZeroU32::new(2).unwrap(),
+            hard_tabs: Some(false),
+            max_line_length: Some(100),
+            trim_trailing_whitespace: Some(true),
+            insert_final_newline: None,
+        };
+        let delegate = cx.update(|_, cx| {
+            ApplyScopeSelectorDelegate::new(
+                WeakEntity::new_invalid(),
+                workspace.downgrade(),
+                project.clone(),
+                buffer.clone(),
+                profile.tab_size,
+                false,
+                None,
+                Some(profile.clone()),
+                false,
+                Vec::new(),
+                cx,
+            )
+        });
+        // Overriding the buffer alone can't keep the rest of the profile.
+        assert!(!delegate.scopes.contains(&ApplyScope::Buffer));
+        assert!(!delegate.scopes.contains(&ApplyScope::UntitledBuffers));
+
+        // The open buffers of the language take the whole profile.
+        let open_buffers = ApplyScope::OpenBuffers(language::rust_lang().name());
+        assert!(delegate.scopes.contains(&open_buffers));
+        cx.update(|_, cx| delegate.apply(&open_buffers, cx));
+        cx.run_until_parked();
+        let content = cx.update(|_, cx| {
+            let path = LocalSettingsPath::InWorktree(rel_path("a.rs").into());
+            editorconfig_override(worktree_id, &path, cx).unwrap()
+        });
+        assert_eq!(
+            content,
+            "[*.rs]\nindent_style = space\nindent_size = 2\nmax_line_length = 100\n\
+             trim_trailing_whitespace = true\n"
+        );
+    }
+
+    #[test]
+    fn test_set_override_section() {
+        let properties = indentation_properties(2, false, None);
+        assert_eq!(
+            set_override_section("", "/**", &properties),
+            "# Set from the indent size selector\n\
+             [/**]\n\
+             indent_style = space\n\
+             indent_size = 2\n"
+        );
+
+        // The directory's own `.editorconfig`, and the properties and sections set alongside the
+        // selector's, are kept. So is the width of a tab, which indenting with spaces leaves
+        // alone.
+        let existing = "root = true\n\n\
+                        # Set from the indent size selector\n\
+                        [/**]\n\
+                        indent_style = tab\n\
+                        indent_size = tab\n\
+                        tab_width = 8\n\
+                        end_of_line = lf\n\
+                        max_line_length = 100\n\n\
+                        [**/*.md]\n\
+                        indent_size = 4\n";
+        assert_eq!(
+            set_override_section(existing, "/**", &properties),
+            "root = true\n\n\
+             # Set from the indent size selector\n\
+             [/**]\n\
+             indent_style = space\n\
+             indent_size = 2\n\
+             tab_width = 8\n\
+             end_of_line = lf\n\
+             max_line_length = 100\n\n\
+             [**/*.md]\n\
+             indent_size = 4\n"
+        );
+    }
+
+    #[test]
+    fn test_copy_changes() {
+        let from = serde_json::json!({
+            "tab_size": 4,
+            "languages": { "Rust": { "hard_tabs": false }, "Go": { "hard_tabs": true } },
+        });
+        let to = serde_json::json!({
+            "tab_size": 2,
+            "languages": { "Rust": { "hard_tabs": true } },
+            "indent_size_selector": { "untitled_indentation": { "tab_size": 2 } },
+        });
+        let mut current = serde_json::json!({
+            "tab_size": 4,
+            "theme": "One Dark",
+            "languages": { "Rust": { "hard_tabs": false, "tab_size": 8 }, "Go": { "hard_tabs": true } },
+        });
+        copy_changes(&mut current, &from, &to);
+        assert_eq!(
+            current,
+            serde_json::json!({
+                "tab_size": 2,
+                "theme": "One Dark",
+                "languages": { "Rust": { "hard_tabs": true, "tab_size": 8 } },
+                "indent_size_selector": { "untitled_indentation": { "tab_size": 2 } },
+            })
+        );
+
+        copy_changes(&mut current, &to, &from);
+        assert_eq!(
+            current,
+            serde_json::json!({
+                "tab_size": 4,
+                "theme": "One Dark",
+                "languages": { "Rust": { "hard_tabs": false, "tab_size": 8 }, "Go": { "hard_tabs": true } },
+            })
+        );
+    }
+}
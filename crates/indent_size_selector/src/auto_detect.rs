@@ -0,0 +1,259 @@
+use collections::HashMap;
+use editor::{Editor, EditorEvent};
+use gpui::{App, Context, Entity, Global, Window};
+use language::{
+    Buffer, BufferId, IndentKind, IndentSize, Point, language_settings::IndentationOverride,
+};
+use settings::Settings as _;
+use std::num::NonZeroU32;
+use workspace::{Toast, notifications::NotificationId};
+
+use crate::{
+    Detect, IndentSizeSelectorSettings,
+    detection::{
+        LARGE_BUFFER_ROWS, buffer_detection_in_background, detected_indentation, to_indent_size,
+    },
+    file_buffer,
+    indent_source::IndentSource,
+    read_indent_size,
+};
+
+/// The number of indented lines a single edit has to add for the indentation to be detected
+/// again.
+const INDENTED_LINES_BEFORE_REDETECTING: usize = 20;
+
+/// The overrides installed from what was detected in each buffer.
+#[derive(Default)]
+struct DetectedOverrides(HashMap<BufferId, IndentationOverride>);
+
+impl Global for DetectedOverrides {}
+
+/// Switches the file opened in `editor` to the indentation its content uses, when
+/// `auto_detect_indentation` is enabled and only its language's defaults or the user's settings
+/// determine its indentation. Detection runs again after an edit adds many indented lines at
+/// once, such as pasting code into a new scratch buffer.
+pub(crate) fn watch(editor: &mut Editor, window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let Some(window) = window else {
+        return;
+    };
+    if !editor.mode().is_full() {
+        return;
+    }
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+
+    if IndentSizeSelectorSettings::get_global(cx).auto_detect_indentation {
+        // Restoring an earlier choice for the file happens as the editor is created, and should
+        // win.
+        cx.defer_in(window, |editor, _, cx| {
+            if !matches!(
+                IndentSource::for_editor(editor, cx),
+                IndentSource::Default | IndentSource::UserSettings { .. }
+            ) {
+                return;
+            }
+            let Some(buffer) = file_buffer(editor, cx) else {
+                return;
+            };
+            if buffer.read(cx).max_point().row < LARGE_BUFFER_ROWS {
+                apply_detected(editor, cx);
+                return;
+            }
+            // Scanning a large file would stall opening it.
+            let detection = buffer_detection_in_background(&buffer, cx);
+            cx.spawn(async move |editor, cx| {
+                let Some(detected) = to_indent_size(detection.await) else {
+                    return;
+                };
+                editor
+                    .update(cx, |editor, cx| {
+                        if matches!(
+                            IndentSource::for_editor(editor, cx),
+                            IndentSource::Default | IndentSource::UserSettings { .. }
+                        ) {
+                            apply_indentation(editor, &buffer, detected, cx);
+                        }
+                    })
+                    .ok();
+            })
+            .detach();
+        });
+    }
+
+    let mut version = buffer.read(cx).version();
+    cx.subscribe_self(move |editor, event: &EditorEvent, cx| {
+        if !matches!(event, EditorEvent::Edited { .. }) {
+            return;
+        }
+        let snapshot = buffer.read(cx).snapshot();
+        let previous = std::mem::replace(&mut version, snapshot.version().clone());
+        if !IndentSizeSelectorSettings::get_global(cx).auto_detect_indentation {
+            return;
+        }
+        let added_indented_lines = snapshot
+            .edits_since::<Point>(&previous)
+            .flat_map(|edit| edit.new.start.row..=edit.new.end.row)
+            .filter(|row| {
+                !snapshot.is_line_blank(*row) && snapshot.indent_size_for_line(*row).len > 0
+            })
+            .count();
+        if added_indented_lines >= INDENTED_LINES_BEFORE_REDETECTING
+            && matches!(
+                IndentSource::for_editor(editor, cx),
+                IndentSource::Default | IndentSource::UserSettings { .. } | IndentSource::Detected
+            )
+        {
+            apply_detected(editor, cx);
+        }
+    })
+    .detach();
+}
+
+/// Registers the action switching the file open in `editor` to the indentation its content uses,
+/// whatever determines its indentation otherwise.
+pub(crate) fn register(editor: &mut Editor, _: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action(move |_: &Detect, _, cx| {
+            editor_handle.update(cx, detect).ok();
+        })
+        .detach();
+}
+
+/// Applies the indentation detected in the file open in `editor`, explaining why nothing
+/// changed when it didn't.
+fn detect(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let message = match apply_detected(editor, cx) {
+        Some(true) => return,
+        Some(false) => "This file already uses the indentation detected in it",
+        None => "No indentation could be detected in this file",
+    };
+    let Some(workspace) = editor.workspace() else {
+        return;
+    };
+    workspace.update(cx, |workspace, cx| {
+        struct DetectIndentation;
+        workspace.show_toast(
+            Toast::new(NotificationId::unique::<DetectIndentation>(), message).autohide(),
+            cx,
+        );
+    });
+}
+
+/// Overrides the indentation of the file open in `editor` with the one detected in its content
+/// until it's closed. Returns whether it was changed, or `None` when nothing could be detected.
+fn apply_detected(editor: &mut Editor, cx: &mut Context<Editor>) -> Option<bool> {
+    let buffer = file_buffer(editor, cx)?;
+    let detected = detected_indentation(&buffer, cx)?;
+    apply_indentation(editor, &buffer, detected, cx)
+}
+
+/// Overrides the indentation of `buffer`, open in `editor`, with the `detected` one, like
+/// [`apply_detected`].
+pub(crate) fn apply_indentation(
+    editor: &mut Editor,
+    buffer: &Entity<Buffer>,
+    detected: IndentSize,
+    cx: &mut Context<Editor>,
+) -> Option<bool> {
+    let configured = read_indent_size(editor, cx)?.indent_size;
+    let Some(indentation_override) = detected_override(detected, configured) else {
+        return Some(false);
+    };
+    cx.default_global::<DetectedOverrides>()
+        .0
+        .insert(buffer.read(cx).remote_id(), indentation_override);
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_indentation_override(Some(indentation_override), cx)
+    });
+    // The status item re-reads the indentation whenever the editor notifies.
+    cx.notify();
+    Some(true)
+}
+
+/// The override switching a buffer from its `configured` indentation to the `detected` one its
+/// content uses, if they differ. Tabs keep the configured width.
+pub(crate) fn detected_override(
+    detected: IndentSize,
+    configured: IndentSize,
+) -> Option<IndentationOverride> {
+    let matches_configured = match detected.kind {
+        IndentKind::Tab => configured.kind == IndentKind::Tab,
+        IndentKind::Space => detected == configured,
+    };
+    if matches_configured {
+        return None;
+    }
+    Some(match detected.kind {
+        IndentKind::Tab => IndentationOverride {
+            tab_size: NonZeroU32::new(configured.len)?,
+            tab_width: None,
+            hard_tabs: true,
+        },
+        IndentKind::Space => IndentationOverride {
+            tab_size: NonZeroU32::new(detected.len)?,
+            tab_width: None,
+            hard_tabs: false,
+        },
+    })
+}
+
+/// Whether the override of `buffer` is the one installed from what was detected in it, rather
+/// than one picked with the selector.
+pub(crate) fn has_detected_override(buffer: &Entity<Buffer>, cx: &App) -> bool {
+    let buffer = buffer.read(cx);
+    buffer
+        .indentation_override()
+        .is_some_and(|indentation_override| {
+            cx.try_global::<DetectedOverrides>()
+                .and_then(|overrides| overrides.0.get(&buffer.remote_id()))
+                == Some(indentation_override)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+
+    #[gpui::test]
+    fn test_detected_override(cx: &mut TestAppContext) {
+        let detect = |text: &str, configured: IndentSize, cx: &mut TestAppContext| {
+            let buffer = cx.new(|cx| Buffer::local(text, cx));
+            cx.update(|cx| detected_override(detected_indentation(&buffer, cx)?, configured))
+        };
+
+        assert_eq!(
+            detect("a:\n  b:\n    c: 1\n", IndentSize::spaces(4), cx),
+            Some(IndentationOverride {
+                tab_size: NonZeroU32::new(2).unwrap(),
+                tab_width: None,
+                hard_tabs: false,
+            })
+        );
+        assert_eq!(
+            detect("fn a() {\n\tb();\n}\n", IndentSize::spaces(8), cx),
+            Some(IndentationOverride {
+                tab_size: NonZeroU32::new(8).unwrap(),
+                tab_width: None,
+                hard_tabs: true,
+            })
+        );
+        // Files already using their configured indentation, or with nothing indented, are left
+        // alone.
+        assert_eq!(detect("a:\n    b: 1\n", IndentSize::spaces(4), cx), None);
+        assert_eq!(
+            detect(
+                "fn a() {\n\tb();\n}\n",
+                IndentSize {
+                    len: 4,
+                    kind: IndentKind::Tab
+                },
+                cx
+            ),
+            None
+        );
+        assert_eq!(detect("a = 1\n", IndentSize::spaces(4), cx), None);
+    }
+}
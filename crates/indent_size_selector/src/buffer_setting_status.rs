@@ -0,0 +1,148 @@
+use editor::Editor;
+use gpui::{Entity, Subscription, WeakEntity};
+use language::Buffer;
+use settings::SettingsStore;
+use std::sync::Arc;
+use ui::{Tooltip, prelude::*};
+use workspace::{StatusItemView, item::ItemHandle};
+
+use crate::{
+    buffer_settings::{BufferSetting, BufferSettingSelector},
+    file_buffer,
+    line_ending::LineEndingSetting,
+};
+
+/// Shows the value a [`BufferSetting`] has for the active file in the status bar, warning when
+/// it differs from the one its `.editorconfig` sets, and opens the setting's selector when
+/// clicked.
+pub struct BufferSettingStatus {
+    setting: Arc<dyn BufferSetting>,
+    value: Option<SharedString>,
+    /// The value the `.editorconfig` files governing the active file set, if any.
+    configured: Option<SharedString>,
+    active_editor: Option<WeakEntity<Editor>>,
+    _observe_active_editor: Option<Subscription>,
+    /// The active file's buffer, observed since converting it, such as its line endings, doesn't
+    /// notify the editor.
+    observed_buffer: Option<(WeakEntity<Buffer>, Subscription)>,
+    _observe_settings: Subscription,
+}
+
+impl BufferSettingStatus {
+    pub fn new(setting: Arc<dyn BufferSetting>, cx: &mut Context<Self>) -> Self {
+        Self {
+            setting,
+            value: None,
+            configured: None,
+            active_editor: None,
+            _observe_active_editor: None,
+            observed_buffer: None,
+            // Editing an `.editorconfig` changes the configured value.
+            _observe_settings: cx.observe_global::<SettingsStore>(Self::refresh),
+        }
+    }
+
+    /// Shows the line ending of the active file.
+    pub fn line_ending(cx: &mut Context<Self>) -> Self {
+        Self::new(Arc::new(LineEndingSetting), cx)
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let buffer = self
+            .observed_buffer
+            .as_ref()
+            .and_then(|(buffer, _)| buffer.upgrade());
+        self.read_buffer(buffer.as_ref(), cx);
+    }
+
+    /// Reads the file open in `editor` when it's another one than before, such as after
+    /// navigating to another excerpt.
+    fn update(&mut self, editor: Entity<Editor>, cx: &mut Context<Self>) {
+        self.active_editor = Some(editor.downgrade());
+        let buffer = file_buffer(editor.read(cx), cx);
+        let observed = self.observed_buffer.as_ref().map(|(buffer, _)| buffer);
+        if observed == buffer.as_ref().map(|buffer| buffer.downgrade()).as_ref() {
+            return;
+        }
+        self.observed_buffer = buffer.as_ref().map(|buffer| {
+            let subscription = cx.observe(buffer, |this, buffer, cx| {
+                this.read_buffer(Some(&buffer), cx)
+            });
+            (buffer.downgrade(), subscription)
+        });
+        self.read_buffer(buffer.as_ref(), cx);
+    }
+
+    fn read_buffer(&mut self, buffer: Option<&Entity<Buffer>>, cx: &mut Context<Self>) {
+        let buffer = buffer.map(|buffer| buffer.read(cx));
+        let value = buffer.and_then(|buffer| self.setting.current_value(buffer, cx));
+        let configured = buffer.and_then(|buffer| self.setting.configured_value(buffer, cx));
+        if (&value, &configured) != (&self.value, &self.configured) {
+            self.value = value;
+            self.configured = configured;
+            cx.notify();
+        }
+    }
+}
+
+impl Render for BufferSettingStatus {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if !self.setting.shows_status(cx) {
+            return div();
+        }
+
+        let setting = self.setting.clone();
+        let title: SharedString = format!("Select {}", setting.name()).into();
+        let mismatch = self
+            .configured
+            .clone()
+            .filter(|configured| Some(configured) != self.value.as_ref());
+        let action = setting.action();
+        div().when_some(self.value.clone(), |el, value| {
+            el.child(
+                Button::new(
+                    SharedString::from(format!("change-{}", setting.editorconfig_property())),
+                    setting.label(&value),
+                )
+                .label_size(LabelSize::Small)
+                .when(mismatch.is_some(), |button| button.color(Color::Warning))
+                .on_click(cx.listener(|this, _, window, cx| {
+                    if let Some(editor) = this.active_editor.as_ref().and_then(|e| e.upgrade()) {
+                        BufferSettingSelector::toggle(&editor, this.setting.clone(), window, cx);
+                    }
+                }))
+                .tooltip(move |_window, cx| match (&mismatch, action.as_deref()) {
+                    (Some(configured), action) => Tooltip::with_meta(
+                        title.clone(),
+                        action,
+                        format!("The .editorconfig sets {}", setting.label(configured)),
+                        cx,
+                    ),
+                    (None, Some(action)) => Tooltip::for_action(title.clone(), action, cx),
+                    (None, None) => Tooltip::simple(title.clone(), cx),
+                }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for BufferSettingStatus {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.act_as::<Editor>(cx)) {
+            self._observe_active_editor = Some(cx.observe(&editor, Self::update));
+            self.update(editor, cx);
+        } else {
+            self.value = None;
+            self.configured = None;
+            self.active_editor = None;
+            self._observe_active_editor = None;
+            self.observed_buffer = None;
+        }
+        cx.notify();
+    }
+}
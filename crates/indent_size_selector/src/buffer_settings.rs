@@ -0,0 +1,575 @@
+use anyhow::Result;
+use editor::Editor;
+use gpui::{
+    Action, AnyElement, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Global, Task,
+    WeakEntity,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use settings::{EditorconfigProperties, LanguageSettingsContent, SettingsStore};
+use std::sync::Arc;
+use ui::{KeyBinding, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::ModalView;
+
+use crate::{
+    SelectCharset, SetLineLength, ToggleEndOfLine,
+    charset::CharsetSetting,
+    file_buffer,
+    line_ending::LineEndingSetting,
+    line_length::LineLengthSetting,
+    modal_width,
+    setting_scope_selector::{SettingScopeSelector, SettingValue},
+    whitespace_on_save::WhitespaceOnSave,
+};
+
+/// A setting of the file open in an editor besides its indentation, such as its line ending or
+/// its line length, which the buffer setting selector picks a value of and applies at the same
+/// scopes as the indentation. Values are in the `.editorconfig` syntax that overrides store them
+/// in, such as `crlf`. Registered with [`register_buffer_setting`].
+pub trait BufferSetting: Send + Sync + 'static {
+    /// Names the setting in the selectors, such as "Line Ending".
+    fn name(&self) -> SharedString;
+
+    /// The `.editorconfig` property that sets it, such as `end_of_line`.
+    fn editorconfig_property(&self) -> &'static str;
+
+    /// The values the selector lists for the file of `buffer`.
+    fn candidates(&self, buffer: &Buffer, cx: &App) -> Vec<SharedString>;
+
+    /// The value the file of `buffer` has now, if it's one the setting can have.
+    fn current_value(&self, buffer: &Buffer, cx: &App) -> Option<SharedString>;
+
+    /// The value that the `.editorconfig` files governing the file of `buffer` set, if any.
+    fn configured_value(&self, _buffer: &Buffer, _cx: &App) -> Option<SharedString> {
+        None
+    }
+
+    /// Describes `value` in the selectors, such as "100 columns" for `100`.
+    fn label(&self, value: &str) -> SharedString {
+        value.to_string().into()
+    }
+
+    /// The value typed into the selector as `query`, for settings that take values besides the
+    /// ones listed, such as any column.
+    fn parse(&self, _query: &str) -> Option<SharedString> {
+        None
+    }
+
+    /// Whether the user and project settings can hold it, so that it can be applied to a
+    /// language, the workspace or every file, and not only as an `.editorconfig` override.
+    fn has_user_setting(&self) -> bool {
+        false
+    }
+
+    /// Sets `value` in the user or project settings of a language or of every file, for
+    /// settings that [have one](Self::has_user_setting).
+    fn set_user_setting(&self, _value: &str, _settings: &mut LanguageSettingsContent) {}
+
+    /// Whether the content of a buffer can be converted to a value, such as its lines to a line
+    /// ending, which the selector offers along with applying it.
+    fn converts(&self) -> bool {
+        false
+    }
+
+    /// Brings `buffer` in line with `value` once it's applied, converting its content when
+    /// `convert` is set.
+    fn apply_to_buffer(
+        &self,
+        _value: &str,
+        _buffer: &Entity<Buffer>,
+        _project: &Entity<Project>,
+        _convert: bool,
+        _cx: &mut App,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// The action that opens the selector for it, if it has one.
+    fn action(&self) -> Option<Box<dyn Action>> {
+        None
+    }
+
+    /// Whether a [`BufferSettingStatus`](crate::BufferSettingStatus) for it is shown in the
+    /// status bar.
+    fn shows_status(&self, _cx: &App) -> bool {
+        true
+    }
+}
+
+#[derive(Default)]
+struct BufferSettings(Vec<Arc<dyn BufferSetting>>);
+
+impl Global for BufferSettings {}
+
+/// Registers `setting` to be listed in the indent size selector, after the ones registered
+/// before it.
+pub fn register_buffer_setting(setting: Arc<dyn BufferSetting>, cx: &mut App) {
+    cx.default_global::<BufferSettings>().0.push(setting);
+}
+
+/// Registers the settings built into the crate.
+pub(crate) fn register_builtin_settings(cx: &mut App) {
+    register_buffer_setting(Arc::new(LineEndingSetting), cx);
+    register_buffer_setting(Arc::new(LineLengthSetting), cx);
+    register_buffer_setting(Arc::new(WhitespaceOnSave::TrimTrailingWhitespace), cx);
+    register_buffer_setting(Arc::new(WhitespaceOnSave::EnsureFinalNewline), cx);
+    register_buffer_setting(Arc::new(CharsetSetting), cx);
+}
+
+/// The registered settings, in the order they were registered.
+pub(crate) fn registered_settings(cx: &App) -> Vec<Arc<dyn BufferSetting>> {
+    cx.try_global::<BufferSettings>()
+        .map(|settings| settings.0.clone())
+        .unwrap_or_default()
+}
+
+/// The properties that the `.editorconfig` files governing the file of `buffer` set.
+pub(crate) fn governing_properties(buffer: &Buffer, cx: &App) -> Option<EditorconfigProperties> {
+    let file = buffer.file()?;
+    cx.global::<SettingsStore>()
+        .editorconfig_store
+        .read(cx)
+        .properties(file.worktree_id(cx), file.path())
+}
+
+/// Handles the actions opening the selector for one of the built-in settings.
+pub(crate) fn register(
+    editor: &mut Editor,
+    _window: Option<&mut Window>,
+    cx: &mut Context<Editor>,
+) {
+    register_toggle::<ToggleEndOfLine>(editor, Arc::new(LineEndingSetting), cx);
+    register_toggle::<SetLineLength>(editor, Arc::new(LineLengthSetting), cx);
+    register_toggle::<SelectCharset>(editor, Arc::new(CharsetSetting), cx);
+}
+
+fn register_toggle<A: Action>(
+    editor: &mut Editor,
+    setting: Arc<dyn BufferSetting>,
+    cx: &mut Context<Editor>,
+) {
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action(move |_: &A, window, cx| {
+            if let Some(editor) = editor_handle.upgrade() {
+                BufferSettingSelector::toggle(&editor, setting.clone(), window, cx);
+            }
+        })
+        .detach();
+}
+
+/// Picks a value of a [`BufferSetting`] for the file open in an editor, then asks where to apply
+/// it.
+pub(crate) struct BufferSettingSelector {
+    picker: Entity<Picker<BufferSettingSelectorDelegate>>,
+}
+
+impl BufferSettingSelector {
+    pub(crate) fn toggle(
+        editor: &Entity<Editor>,
+        setting: Arc<dyn BufferSetting>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let (Some(workspace), Some(buffer)) = (
+            editor.read(cx).workspace(),
+            file_buffer(editor.read(cx), cx),
+        ) else {
+            return;
+        };
+        let editor = editor.downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                let delegate = BufferSettingSelectorDelegate::new(
+                    cx.entity().downgrade(),
+                    editor,
+                    setting,
+                    buffer.read(cx),
+                    cx,
+                );
+                Self {
+                    picker: cx.new(|cx| Picker::uniform_list(delegate, window, cx)),
+                }
+            });
+        });
+    }
+}
+
+impl Render for BufferSettingSelector {
+    fn render(&mut self, window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("BufferSettingSelector")
+            .w(modal_width(window))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for BufferSettingSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BufferSettingSelector {}
+impl ModalView for BufferSettingSelector {}
+
+struct BufferSettingSelectorDelegate {
+    buffer_setting_selector: WeakEntity<BufferSettingSelector>,
+    editor: WeakEntity<Editor>,
+    setting: Arc<dyn BufferSetting>,
+    current: Option<SharedString>,
+    configured: Option<SharedString>,
+    candidates: Vec<SharedString>,
+    /// The candidates matching the query, preceded by the value typed when it's another one.
+    values: Vec<SharedString>,
+    selected_index: usize,
+}
+
+impl BufferSettingSelectorDelegate {
+    fn new(
+        buffer_setting_selector: WeakEntity<BufferSettingSelector>,
+        editor: WeakEntity<Editor>,
+        setting: Arc<dyn BufferSetting>,
+        buffer: &Buffer,
+        cx: &App,
+    ) -> Self {
+        let current = setting.current_value(buffer, cx);
+        let configured = setting.configured_value(buffer, cx);
+        let candidates = setting.candidates(buffer, cx);
+        let selected = configured.as_ref().or(current.as_ref());
+        let selected_index = candidates
+            .iter()
+            .position(|value| Some(value) == selected)
+            .unwrap_or(0);
+        Self {
+            buffer_setting_selector,
+            editor,
+            setting,
+            current,
+            configured,
+            values: candidates.clone(),
+            candidates,
+            selected_index,
+        }
+    }
+}
+
+impl PickerDelegate for BufferSettingSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        format!("Select {}…", self.setting.name()).into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.values.len()
+    }
+
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        if !self.setting.converts() {
+            return None;
+        }
+
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_0p5()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("apply-and-convert", "Apply and Convert")
+                        .key_binding(
+                            KeyBinding::for_action(&menu::SecondaryConfirm, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("apply", "Apply")
+                        .key_binding(
+                            KeyBinding::for_action(&menu::Confirm, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                )
+                .into_any(),
+        )
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(value) = self.values.get(self.selected_index).cloned()
+            && let Some(editor) = self.editor.upgrade()
+        {
+            let value = SettingValue {
+                setting: self.setting.clone(),
+                value,
+                convert: secondary && self.setting.converts(),
+            };
+            window.defer(cx, move |window, cx| {
+                SettingScopeSelector::toggle(&editor, value, window, cx);
+            });
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.buffer_setting_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let query = query.trim();
+        let mut values = if query.is_empty() {
+            self.candidates.clone()
+        } else {
+            let query = query.to_lowercase();
+            self.candidates
+                .iter()
+                .filter(|value| {
+                    self.setting
+                        .label(value)
+                        .to_lowercase()
+                        .contains(query.as_str())
+                })
+                .cloned()
+                .collect()
+        };
+        if let Some(typed) = self.setting.parse(query) {
+            values.retain(|value| *value != typed);
+            values.insert(0, typed);
+        }
+        self.values = values;
+        if !query.is_empty() {
+            self.selected_index = 0;
+        }
+        self.selected_index = self.selected_index.min(self.values.len().saturating_sub(1));
+        cx.notify();
+        Task::ready(())
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let value = self.values.get(ix)?;
+        let detail = match (
+            self.current.as_ref() == Some(value),
+            self.configured.as_ref() == Some(value),
+        ) {
+            (true, true) => Some("current, .editorconfig"),
+            (true, false) => Some("current"),
+            (false, true) => Some(".editorconfig"),
+            (false, false) => None,
+        };
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(Label::new(self.setting.label(value)))
+                .when_some(detail, |item, detail| {
+                    item.end_slot(Label::new(detail).color(Color::Muted))
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apply_scope::{self, ApplyScope};
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use util::{path, rel_path::RelPath};
+    use workspace::AppState;
+
+    struct TestSetting;
+
+    impl BufferSetting for TestSetting {
+        fn name(&self) -> SharedString {
+            "Test".into()
+        }
+
+        fn editorconfig_property(&self) -> &'static str {
+            "test"
+        }
+
+        fn candidates(&self, _: &Buffer, _: &App) -> Vec<SharedString> {
+            Vec::new()
+        }
+
+        fn current_value(&self, _: &Buffer, _: &App) -> Option<SharedString> {
+            None
+        }
+    }
+
+    #[gpui::test]
+    fn test_register_buffer_setting(cx: &mut App) {
+        assert!(registered_settings(cx).is_empty());
+
+        register_builtin_settings(cx);
+        register_buffer_setting(Arc::new(TestSetting), cx);
+        assert_eq!(
+            registered_settings(cx)
+                .iter()
+                .map(|setting| setting.editorconfig_property())
+                .collect::<Vec<_>>(),
+            vec![
+                "end_of_line",
+                "max_line_length",
+                "trim_trailing_whitespace",
+                "insert_final_newline",
+                "charset",
+                "test",
+            ]
+        );
+    }
+
+    async fn open_buffer(cx: &mut TestAppContext) -> Entity<Buffer> {
+        cx.update(|cx| {
+            AppState::test(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "src": { "a.rs": "fn f() {}\n" } }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/src/a.rs"), cx)
+            })
+            .await
+            .unwrap()
+    }
+
+    #[gpui::test]
+    async fn test_candidates_and_parse(cx: &mut TestAppContext) {
+        let buffer = open_buffer(cx).await;
+        cx.update(|cx| {
+            let buffer = buffer.read(cx);
+            assert_eq!(
+                LineLengthSetting.candidates(buffer, cx),
+                ["80", "100", "120"].map(SharedString::from)
+            );
+            assert_eq!(
+                LineLengthSetting.current_value(buffer, cx),
+                Some("80".into())
+            );
+            assert_eq!(LineLengthSetting.parse("72"), Some("72".into()));
+            assert_eq!(LineLengthSetting.parse("0"), None);
+            assert_eq!(LineLengthSetting.parse("wide"), None);
+
+            assert_eq!(
+                LineEndingSetting.candidates(buffer, cx),
+                ["lf", "crlf"].map(SharedString::from)
+            );
+            assert_eq!(
+                LineEndingSetting.current_value(buffer, cx),
+                Some("lf".into())
+            );
+            assert_eq!(LineEndingSetting.parse("crlf"), None);
+        });
+
+        // The current line length is listed first when it isn't a suggested one.
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings(cx, |content| {
+                    content.project.all_languages.defaults.preferred_line_length = Some(90);
+                });
+            });
+            assert_eq!(
+                LineLengthSetting.candidates(buffer.read(cx), cx),
+                ["90", "80", "100", "120"].map(SharedString::from)
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_apply_paths(cx: &mut TestAppContext) {
+        let buffer = open_buffer(cx).await;
+
+        // Settings without a user setting are only applied as `.editorconfig` overrides.
+        cx.update(|cx| {
+            assert!(!LineEndingSetting.has_user_setting());
+            let mut content = LanguageSettingsContent::default();
+            LineEndingSetting.set_user_setting("crlf", &mut content);
+            assert_eq!(content, LanguageSettingsContent::default());
+
+            assert_eq!(
+                LineEndingSetting.configured_value(buffer.read(cx), cx),
+                None
+            );
+            let properties = [(LineEndingSetting.editorconfig_property(), "crlf".into())];
+            assert!(apply_scope::set_override_properties(
+                &buffer,
+                &ApplyScope::File,
+                &properties,
+                cx
+            ));
+            assert_eq!(
+                LineEndingSetting.configured_value(buffer.read(cx), cx),
+                Some("crlf".into())
+            );
+        });
+
+        // Others are applied either way.
+        cx.update(|cx| {
+            assert!(LineLengthSetting.has_user_setting());
+            let directory = ApplyScope::Directory(RelPath::unix("src").unwrap().into());
+            let properties = [(LineLengthSetting.editorconfig_property(), "100".into())];
+            assert!(apply_scope::set_override_properties(
+                &buffer,
+                &directory,
+                &properties,
+                cx
+            ));
+            assert_eq!(
+                LineLengthSetting.configured_value(buffer.read(cx), cx),
+                Some("100".into())
+            );
+            // The line ending set for the file is kept.
+            assert_eq!(
+                LineEndingSetting.configured_value(buffer.read(cx), cx),
+                Some("crlf".into())
+            );
+
+            let mut content = LanguageSettingsContent::default();
+            LineLengthSetting.set_user_setting("120", &mut content);
+            assert_eq!(content.preferred_line_length, Some(120));
+            assert_eq!(content.wrap_guides, Some(vec![120]));
+            LineLengthSetting.set_user_setting("wide", &mut content);
+            assert_eq!(content.preferred_line_length, Some(120));
+        });
+    }
+}
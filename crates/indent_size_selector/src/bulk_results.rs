@@ -0,0 +1,360 @@
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, WeakEntity};
+use language::Buffer;
+use project::ProjectPath;
+use settings::{IndentSizeSelectorOverrideScope, IndentationProfileContent, Settings as _};
+use std::num::NonZeroU32;
+use ui::prelude::*;
+use util::paths::PathStyle;
+use workspace::{
+    Workspace,
+    notifications::{Notification, NotificationFrame, NotificationId, SuppressEvent},
+};
+
+use crate::{
+    IndentSizeSelectorSettings, apply_to_path, apply_to_paths, fix_on_save, history,
+    unsupported_files::UnsupportedFile,
+};
+
+/// How applying the indentation to one of the files of a bulk apply turned out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome {
+    Applied,
+    /// No override can be written for the buffer's file, so the buffer alone was overridden.
+    AppliedUntilClosed,
+    Failed,
+    /// The buffer's language doesn't allow overriding its file.
+    Skipped,
+}
+
+impl Outcome {
+    fn heading(&self) -> &'static str {
+        match self {
+            Self::Applied => "Applied",
+            Self::AppliedUntilClosed => "Applied until closed",
+            Self::Failed => "Failed",
+            Self::Skipped => "Skipped",
+        }
+    }
+}
+
+struct BulkResult {
+    buffer: Entity<Buffer>,
+    label: SharedString,
+    outcome: Outcome,
+}
+
+/// Lists the outcome of applying an indentation to many files at once, with a retry for each
+/// failure, until it's dismissed.
+pub(crate) struct BulkApplyResults {
+    focus_handle: FocusHandle,
+    workspace: WeakEntity<Workspace>,
+    size: u32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+    /// The profile the indentation comes from, whose other settings are applied along with it.
+    profile: Option<IndentationProfileContent>,
+    /// Whether the existing indentation of the files is converted too.
+    convert: bool,
+    results: Vec<BulkResult>,
+}
+
+impl BulkApplyResults {
+    /// Applies the indentation to each of `targets`, along with the rest of `profile` when one
+    /// was chosen, converting their existing indentation too when `convert` is set, then shows
+    /// how each one turned out.
+    pub(crate) fn apply_and_show(
+        workspace: &Entity<Workspace>,
+        targets: &[Entity<Buffer>],
+        size: u32,
+        hard_tabs: bool,
+        tab_width: Option<NonZeroU32>,
+        profile: Option<IndentationProfileContent>,
+        convert: bool,
+        cx: &mut App,
+    ) {
+        let results = apply(
+            targets,
+            size,
+            hard_tabs,
+            tab_width,
+            profile.as_ref(),
+            convert,
+            cx,
+        );
+        let weak_workspace = workspace.downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_notification(NotificationId::unique::<Self>(), cx, |cx| {
+                cx.new(|cx| Self {
+                    focus_handle: cx.focus_handle(),
+                    workspace: weak_workspace,
+                    size,
+                    hard_tabs,
+                    tab_width,
+                    profile,
+                    convert,
+                    results,
+                })
+            });
+        });
+    }
+
+    fn retry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let (size, hard_tabs, tab_width) = (self.size, self.hard_tabs, self.tab_width);
+        let profile = self.profile.as_ref();
+        let result = &mut self.results[ix];
+        if history::transact(cx, |cx| {
+            apply_to_path(&result.buffer, size, hard_tabs, tab_width, profile, cx)
+        }) {
+            result.outcome = if UnsupportedFile::for_buffer(result.buffer.read(cx)).is_some() {
+                Outcome::AppliedUntilClosed
+            } else {
+                Outcome::Applied
+            };
+            if self.convert {
+                fix_on_save::convert_existing_indentation(&result.buffer, &[], size, hard_tabs, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    fn open(&self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(file) = self.results[ix].buffer.read(cx).file().cloned() else {
+            return;
+        };
+        let project_path = ProjectPath::from((file.worktree_id(cx), file.path().clone()));
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, window, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn render_group(&self, outcome: Outcome, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let rows = self
+            .results
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| result.outcome == outcome)
+            .map(|(ix, result)| {
+                h_flex()
+                    .w_full()
+                    .gap_1()
+                    .justify_between()
+                    .child(Label::new(result.label.clone()).size(LabelSize::Small))
+                    .child(
+                        h_flex()
+                            .gap_0p5()
+                            .when(outcome == Outcome::Failed, |this| {
+                                this.child(
+                                    Button::new(("retry", ix), "Retry")
+                                        .label_size(LabelSize::Small)
+                                        .on_click(
+                                            cx.listener(move |this, _, _, cx| this.retry(ix, cx)),
+                                        ),
+                                )
+                            })
+                            .when(outcome != Outcome::Skipped, |this| {
+                                this.child(
+                                    Button::new(("open", ix), "Open")
+                                        .label_size(LabelSize::Small)
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            this.open(ix, window, cx)
+                                        })),
+                                )
+                            }),
+                    )
+            })
+            .collect::<Vec<_>>();
+        if rows.is_empty() {
+            return None;
+        }
+
+        Some(
+            v_flex()
+                .gap_0p5()
+                .child(
+                    Label::new(format!("{} ({})", outcome.heading(), rows.len()))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .children(rows)
+                .into_any(),
+        )
+    }
+}
+
+/// Applies the indentation to each of `targets` as [`BulkApplyResults::apply_and_show`] does,
+/// returning how each one turned out.
+fn apply(
+    targets: &[Entity<Buffer>],
+    size: u32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+    profile: Option<&IndentationProfileContent>,
+    convert: bool,
+    cx: &mut App,
+) -> Vec<BulkResult> {
+    let settings = IndentSizeSelectorSettings::get_global(cx);
+    let allowed = targets
+        .iter()
+        .map(|buffer| {
+            let language_name = buffer.read(cx).language().map(|language| language.name());
+            settings.allows_scope(
+                language_name.as_ref(),
+                IndentSizeSelectorOverrideScope::File,
+            )
+        })
+        .collect::<Vec<_>>();
+    let allowed_targets = targets
+        .iter()
+        .zip(&allowed)
+        .filter(|(_, allowed)| **allowed)
+        .map(|(buffer, _)| buffer.clone())
+        .collect::<Vec<_>>();
+    // The files are applied together, so that a failure leaves none of them half done.
+    let applied = apply_to_paths(&allowed_targets, size, hard_tabs, tab_width, profile, cx);
+    if applied && convert {
+        for buffer in &allowed_targets {
+            fix_on_save::convert_existing_indentation(buffer, &[], size, hard_tabs, cx);
+        }
+    }
+
+    targets
+        .iter()
+        .zip(allowed)
+        .map(|(buffer, allowed)| {
+            let file = buffer.read(cx).file().cloned();
+            let outcome = if !allowed {
+                Outcome::Skipped
+            } else if applied && UnsupportedFile::for_buffer(buffer.read(cx)).is_some() {
+                Outcome::AppliedUntilClosed
+            } else if applied {
+                Outcome::Applied
+            } else {
+                Outcome::Failed
+            };
+            let label = match &file {
+                Some(file) => file.path().display(PathStyle::local()).to_string().into(),
+                None => "untitled".into(),
+            };
+            BulkResult {
+                buffer: buffer.clone(),
+                label,
+                outcome,
+            }
+        })
+        .collect()
+}
+
+impl Focusable for BulkApplyResults {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<DismissEvent> for BulkApplyResults {}
+impl EventEmitter<SuppressEvent> for BulkApplyResults {}
+impl Notification for BulkApplyResults {}
+
+impl Render for BulkApplyResults {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = if self.hard_tabs {
+            "Set indentation to tabs".to_string()
+        } else {
+            format!("Set indentation to {} spaces", self.size)
+        };
+
+        NotificationFrame::new()
+            .with_title(Some(title))
+            .show_suppress_button(false)
+            .on_close(cx.listener(|_, _, _, cx| cx.emit(DismissEvent)))
+            .with_content(
+                v_flex()
+                    .gap_2()
+                    .children(self.render_group(Outcome::Failed, cx))
+                    .children(self.render_group(Outcome::Skipped, cx))
+                    .children(self.render_group(Outcome::AppliedUntilClosed, cx))
+                    .children(self.render_group(Outcome::Applied, cx)),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Fs as _, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_outcomes(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings(cx, |content| {
+                    content
+                        .indent_size_selector
+                        .get_or_insert_default()
+                        .max_override_scope = Some(
+                        [("Rust".to_string(), IndentSizeSelectorOverrideScope::Buffer)]
+                            .into_iter()
+                            .collect(),
+                    );
+                });
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "a.rs": "fn f() {}\n",
+                "notes.txt": "a\n",
+                "gone.txt": "b\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let mut targets = Vec::new();
+        for path in [
+            path!("/dir/a.rs"),
+            path!("/dir/notes.txt"),
+            path!("/dir/gone.txt"),
+        ] {
+            let buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(path, cx))
+                .await
+                .unwrap();
+            targets.push(buffer);
+        }
+        fs.remove_file(Path::new(path!("/dir/gone.txt")), Default::default())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let results = cx.update(|cx| apply(&targets, 2, false, None, None, false, cx));
+        let outcomes = results
+            .iter()
+            .map(|result| (result.label.to_string(), result.outcome))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            [
+                // Rust files may only have their buffers overridden.
+                ("a.rs".to_string(), Outcome::Skipped),
+                ("notes.txt".to_string(), Outcome::Applied),
+                // A deleted file has no path to write an override for.
+                ("gone.txt".to_string(), Outcome::AppliedUntilClosed),
+            ]
+        );
+    }
+}
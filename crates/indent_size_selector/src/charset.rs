@@ -0,0 +1,298 @@
+use anyhow::Result;
+use ec4rs::property::Charset as EditorconfigCharset;
+use encoding_rs::Encoding;
+use gpui::{Action, Entity};
+use language::Buffer;
+use project::Project;
+use ui::prelude::*;
+
+use crate::{
+    SelectCharset,
+    buffer_settings::{BufferSetting, governing_properties},
+};
+
+/// The `.editorconfig` charsets that Zed can both read and write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Charset {
+    Utf8,
+    Utf8Bom,
+    Latin1,
+}
+
+const CHARSETS: [Charset; 3] = [Charset::Utf8, Charset::Utf8Bom, Charset::Latin1];
+
+impl Charset {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf8Bom => "UTF-8 with BOM",
+            Self::Latin1 => "Latin-1",
+        }
+    }
+
+    /// The `.editorconfig` value of `charset` for it.
+    fn editorconfig_value(self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::Utf8Bom => "utf-8-bom",
+            Self::Latin1 => "latin1",
+        }
+    }
+
+    fn from_editorconfig_value(value: &str) -> Option<Self> {
+        CHARSETS
+            .into_iter()
+            .find(|charset| charset.editorconfig_value() == value)
+    }
+
+    /// The encoding files in it are read and written with, and whether they start with a BOM.
+    fn encoding(self) -> (&'static Encoding, bool) {
+        match self {
+            Self::Utf8 => (encoding_rs::UTF_8, false),
+            Self::Utf8Bom => (encoding_rs::UTF_8, true),
+            // The WHATWG encoding that browsers and `encoding_rs` decode Latin-1 labels as.
+            Self::Latin1 => (encoding_rs::WINDOWS_1252, false),
+        }
+    }
+
+    /// The charset `buffer` is saved in, if it's one of these.
+    fn of_buffer(buffer: &Buffer) -> Option<Self> {
+        CHARSETS
+            .into_iter()
+            .find(|charset| charset.encoding() == (buffer.encoding(), buffer.has_bom()))
+    }
+
+    /// The charset that the `.editorconfig` files governing the file of `buffer` set for it, if
+    /// they set one of these.
+    fn configured(buffer: &Buffer, cx: &App) -> Option<Self> {
+        let properties = governing_properties(buffer, cx)?;
+        match properties.get::<EditorconfigCharset>().ok()? {
+            EditorconfigCharset::Utf8 => Some(Self::Utf8),
+            EditorconfigCharset::Utf8Bom => Some(Self::Utf8Bom),
+            EditorconfigCharset::Latin1 => Some(Self::Latin1),
+            EditorconfigCharset::Utf16Le | EditorconfigCharset::Utf16Be => None,
+        }
+    }
+}
+
+/// How the buffer of a file is brought in line with the charset set for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CharsetConversion {
+    /// Reads the file again in the charset, for a file that's already in it.
+    Redecode,
+    /// Saves the buffer in the charset, rewriting the file's bytes.
+    Resave,
+}
+
+impl CharsetConversion {
+    /// Why `buffer` can't be converted this way in `project`, if it can't.
+    fn unsupported_reason(self, buffer: &Buffer, project: &Project) -> Option<&'static str> {
+        if project.is_shared() {
+            Some("Cannot change encoding during collaboration")
+        } else if project.is_via_remote_server() {
+            Some("Cannot change encoding of remote server file")
+        } else if self == Self::Redecode && buffer.is_dirty() {
+            // Reading the file again would discard the unsaved changes.
+            Some("Save file to change encoding")
+        } else {
+            None
+        }
+    }
+}
+
+/// The charset of a file, which only `.editorconfig` overrides set. Applying it reads the file
+/// again in it, since the file is described as being in it, while converting saves the file in
+/// it.
+pub(crate) struct CharsetSetting;
+
+impl BufferSetting for CharsetSetting {
+    fn name(&self) -> SharedString {
+        "Charset".into()
+    }
+
+    fn editorconfig_property(&self) -> &'static str {
+        "charset"
+    }
+
+    fn candidates(&self, _buffer: &Buffer, _cx: &App) -> Vec<SharedString> {
+        CHARSETS
+            .into_iter()
+            .map(|charset| charset.editorconfig_value().into())
+            .collect()
+    }
+
+    fn current_value(&self, buffer: &Buffer, _cx: &App) -> Option<SharedString> {
+        Some(Charset::of_buffer(buffer)?.editorconfig_value().into())
+    }
+
+    fn configured_value(&self, buffer: &Buffer, cx: &App) -> Option<SharedString> {
+        Some(Charset::configured(buffer, cx)?.editorconfig_value().into())
+    }
+
+    fn label(&self, value: &str) -> SharedString {
+        Charset::from_editorconfig_value(value)
+            .map(|charset| charset.label().into())
+            .unwrap_or_else(|| value.to_string().into())
+    }
+
+    fn action(&self) -> Option<Box<dyn Action>> {
+        Some(Box::new(SelectCharset))
+    }
+
+    fn converts(&self) -> bool {
+        true
+    }
+
+    fn apply_to_buffer(
+        &self,
+        value: &str,
+        buffer: &Entity<Buffer>,
+        project: &Entity<Project>,
+        convert: bool,
+        cx: &mut App,
+    ) -> Result<()> {
+        let Some(charset) = Charset::from_editorconfig_value(value) else {
+            anyhow::bail!("{value} is not a charset Zed can read and write");
+        };
+        if Charset::of_buffer(buffer.read(cx)) == Some(charset) {
+            return Ok(());
+        }
+        let conversion = if convert {
+            CharsetConversion::Resave
+        } else {
+            CharsetConversion::Redecode
+        };
+        if let Some(reason) = conversion.unsupported_reason(buffer.read(cx), project.read(cx)) {
+            anyhow::bail!(reason);
+        }
+        let (encoding, has_bom) = charset.encoding();
+        match conversion {
+            CharsetConversion::Redecode => buffer.update(cx, |buffer, cx| {
+                let _ = buffer.reload_with_encoding(encoding, cx);
+            }),
+            CharsetConversion::Resave => {
+                buffer.update(cx, |buffer, _| {
+                    buffer.set_encoding(encoding);
+                    buffer.set_has_bom(has_bom);
+                });
+                let buffer = buffer.clone();
+                project.update(cx, |project, cx| {
+                    project.save_buffer(buffer, cx).detach_and_log_err(cx);
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apply_scope::{self, ApplyScope};
+    use gpui::{AppContext as _, TestAppContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+    use workspace::AppState;
+
+    #[test]
+    fn test_charset_values() {
+        for charset in CHARSETS {
+            assert_eq!(
+                Charset::from_editorconfig_value(charset.editorconfig_value()),
+                Some(charset)
+            );
+        }
+        assert_eq!(Charset::from_editorconfig_value("utf-16le"), None);
+        assert_eq!(CharsetSetting.label("utf-8-bom").as_ref(), "UTF-8 with BOM");
+        assert_eq!(CharsetSetting.label("utf-16le").as_ref(), "utf-16le");
+    }
+
+    #[gpui::test]
+    fn test_charset_candidates_and_current_value(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+        });
+        let buffer = cx.new(|cx| Buffer::local("a\n", cx));
+        cx.update(|cx| {
+            assert_eq!(
+                CharsetSetting.candidates(buffer.read(cx), cx),
+                ["utf-8", "utf-8-bom", "latin1"].map(SharedString::from)
+            );
+            assert_eq!(
+                CharsetSetting.current_value(buffer.read(cx), cx),
+                Some("utf-8".into())
+            );
+        });
+
+        buffer.update(cx, |buffer, _| buffer.set_has_bom(true));
+        cx.update(|cx| {
+            assert_eq!(
+                CharsetSetting.current_value(buffer.read(cx), cx),
+                Some("utf-8-bom".into())
+            );
+        });
+
+        buffer.update(cx, |buffer, _| {
+            buffer.set_encoding(encoding_rs::WINDOWS_1252);
+            buffer.set_has_bom(false);
+        });
+        cx.update(|cx| {
+            assert_eq!(
+                CharsetSetting.current_value(buffer.read(cx), cx),
+                Some("latin1".into())
+            );
+        });
+
+        // Zed can't write the other charsets, so they're no value of the setting.
+        buffer.update(cx, |buffer, _| buffer.set_encoding(encoding_rs::UTF_16LE));
+        cx.update(|cx| assert_eq!(CharsetSetting.current_value(buffer.read(cx), cx), None));
+    }
+
+    #[gpui::test]
+    async fn test_charset_editorconfig_round_trip(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                ".editorconfig": "[*]\ncharset = latin1\n\n[*.md]\ncharset = utf-16le\n",
+                "a.txt": "a\n",
+                "b.md": "b\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let open = |path: &'static str, cx: &mut TestAppContext| {
+            project.update(cx, |project, cx| project.open_local_buffer(path, cx))
+        };
+        let text = open(path!("/dir/a.txt"), cx).await.unwrap();
+        let markdown = open(path!("/dir/b.md"), cx).await.unwrap();
+        cx.run_until_parked();
+
+        cx.update(|cx| {
+            assert_eq!(
+                CharsetSetting.configured_value(text.read(cx), cx),
+                Some("latin1".into())
+            );
+            assert_eq!(CharsetSetting.configured_value(markdown.read(cx), cx), None);
+
+            // A value written as an override is read back as the configured one.
+            let properties = [(CharsetSetting.editorconfig_property(), "utf-8-bom".into())];
+            assert!(apply_scope::set_override_properties(
+                &text,
+                &ApplyScope::File,
+                &properties,
+                cx
+            ));
+            assert_eq!(
+                CharsetSetting.configured_value(text.read(cx), cx),
+                Some("utf-8-bom".into())
+            );
+        });
+    }
+}
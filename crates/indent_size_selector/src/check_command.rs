@@ -0,0 +1,244 @@
+use anyhow::Result;
+use gpui::{App, AppContext as _, Entity, Task};
+use paths::{EDITORCONFIG_NAME, local_settings_file_relative_path};
+use project::Project;
+use settings::{LocalSettingsKind, LocalSettingsPath, SettingsStore, WorktreeId};
+use std::{fmt::Write as _, sync::Arc};
+use util::{ResultExt as _, rel_path::RelPath};
+
+use crate::{
+    consistency_report::{ExportedReport, report_path},
+    fix_on_save,
+};
+
+/// The outcome of checking the indentation of a project, as `zed --check-indentation` reports
+/// it.
+pub struct IndentationCheck {
+    /// Each violation, and each file that couldn't be read, one per line.
+    pub output: String,
+    /// How many files were checked, and how many of them failed.
+    pub summary: String,
+    failing_files: usize,
+    unreadable_files: usize,
+}
+
+impl IndentationCheck {
+    /// 0 when every file uses its configured indentation, 1 when some don't, and 2 when some
+    /// couldn't be read.
+    pub fn exit_code(&self) -> i32 {
+        if self.unreadable_files > 0 {
+            2
+        } else if self.failing_files > 0 {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+/// Checks the indentation of the files of `project`'s visible worktrees with the scan behind
+/// [`ShowIndentationConsistency`](crate::ShowIndentationConsistency), so that CI and the editor
+/// agree on which files break their configured indentation.
+///
+/// Meant to run once the worktrees are scanned. The project's settings and `.editorconfig`
+/// files are loaded first, as the project would otherwise only load them in the background.
+pub fn check_indentation(
+    project: &Entity<Project>,
+    cx: &mut App,
+) -> Task<Result<IndentationCheck>> {
+    let settings_files = load_settings_files(project.read(cx), cx);
+    let project = project.clone();
+    cx.spawn(async move |cx| {
+        let settings_files = settings_files.await;
+        let scan = cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                for (worktree_id, directory, kind, content) in settings_files {
+                    store
+                        .set_local_settings(
+                            worktree_id,
+                            LocalSettingsPath::InWorktree(directory),
+                            kind,
+                            Some(&content),
+                            cx,
+                        )
+                        .log_err();
+                }
+            });
+            fix_on_save::scan_files(project.read(cx), cx)
+        });
+        let files = cx.background_spawn(scan).await;
+
+        Ok(project.read_with(cx, |project, cx| {
+            let mut scanned = Vec::new();
+            let mut unreadable = Vec::new();
+            for file in files {
+                match file {
+                    Ok(file) => scanned.push(file),
+                    Err(file) => unreadable.push(file),
+                }
+            }
+            let report = ExportedReport::new(project, scanned, cx);
+
+            let mut output = String::new();
+            let mut failing_files = 0;
+            for (path, violations) in report.mismatches() {
+                failing_files += 1;
+                for violation in violations {
+                    writeln!(output, "{path}: {violation}").ok();
+                }
+            }
+            for file in &unreadable {
+                writeln!(
+                    output,
+                    "{}: couldn't be read: {:#}",
+                    report_path(project, &file.path, cx),
+                    file.error
+                )
+                .ok();
+            }
+            let mut summary = format!(
+                "Checked {} files, {failing_files} with indentation violations",
+                report.file_count()
+            );
+            if !unreadable.is_empty() {
+                write!(summary, ", {} unreadable", unreadable.len()).ok();
+            }
+
+            IndentationCheck {
+                output,
+                summary,
+                failing_files,
+                unreadable_files: unreadable.len(),
+            }
+        }))
+    })
+}
+
+/// Reads the `.zed/settings.json` and `.editorconfig` files of `project`'s visible worktrees,
+/// along with the worktree and directory each one applies to.
+fn load_settings_files(
+    project: &Project,
+    cx: &App,
+) -> Task<Vec<(WorktreeId, Arc<RelPath>, LocalSettingsKind, String)>> {
+    let fs = project.fs().clone();
+    let settings_file = local_settings_file_relative_path();
+    let mut candidates = Vec::new();
+    for worktree in project.visible_worktrees(cx) {
+        let worktree = worktree.read(cx);
+        for entry in worktree.files(false, 0) {
+            let (directory, kind) = if entry.path.ends_with(settings_file) {
+                let Some(directory) = entry
+                    .path
+                    .ancestors()
+                    .nth(settings_file.components().count())
+                else {
+                    continue;
+                };
+                (directory.into(), LocalSettingsKind::Settings)
+            } else if entry.path.file_name() == Some(EDITORCONFIG_NAME) {
+                let Some(directory) = entry.path.parent() else {
+                    continue;
+                };
+                (directory.into(), LocalSettingsKind::Editorconfig)
+            } else {
+                continue;
+            };
+            candidates.push((
+                worktree.id(),
+                directory,
+                kind,
+                worktree.absolutize(&entry.path),
+            ));
+        }
+    }
+
+    cx.background_spawn(async move {
+        let mut files = Vec::new();
+        for (worktree_id, directory, kind, abs_path) in candidates {
+            if let Some(content) = fs.load(&abs_path).await.log_err() {
+                files.push((worktree_id, directory, kind, content));
+            }
+        }
+        files
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::RealFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use std::path::Path;
+    use util::test::TempTree;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            release_channel::init(semver::Version::new(0, 0, 0), cx);
+        });
+    }
+
+    async fn check(root: &Path, cx: &mut TestAppContext) -> IndentationCheck {
+        let project = Project::test(Arc::new(RealFs::new(None, cx.executor())), [root], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let scan = project.update(cx, |project, cx| project.wait_for_initial_scan(cx));
+        scan.await;
+        let check = cx.update(|cx| check_indentation(&project, cx));
+        check.await.unwrap()
+    }
+
+    #[gpui::test]
+    async fn test_check_indentation(cx: &mut TestAppContext) {
+        init_test(cx);
+        let editorconfig = "root = true\n\n[*.rs]\nindent_style = space\nindent_size = 4\n";
+        let clean = "fn f() {\n    g();\n}\n";
+        let violating = "fn f() {\n  g();\n}\n";
+
+        let dir = TempTree::new(json!({
+            ".editorconfig": editorconfig,
+            "clean.rs": clean,
+        }));
+        let result = check(dir.path(), cx).await;
+        assert_eq!(result.exit_code(), 0);
+        assert_eq!(result.output, "");
+        assert_eq!(
+            result.summary,
+            "Checked 1 files, 0 with indentation violations"
+        );
+
+        let dir = TempTree::new(json!({
+            ".editorconfig": editorconfig,
+            "clean.rs": clean,
+            "violating.rs": violating,
+        }));
+        let result = check(dir.path(), cx).await;
+        assert_eq!(result.exit_code(), 1);
+        assert_eq!(
+            result.output,
+            "violating.rs: indented with 2 spaces instead of 4 spaces\n"
+        );
+
+        // The project's own settings apply along with `.editorconfig`.
+        let dir = TempTree::new(json!({
+            ".zed": { "settings.json": r#"{ "tab_size": 2 }"# },
+            "violating.rs": violating,
+        }));
+        assert_eq!(check(dir.path(), cx).await.exit_code(), 0);
+
+        let dir = TempTree::new(json!({
+            ".editorconfig": editorconfig,
+            "clean.rs": clean,
+            "violating.rs": violating,
+        }));
+        std::fs::write(dir.path().join("unreadable.rs"), b"fn f() {\n    \xff\n}\n").unwrap();
+        let result = check(dir.path(), cx).await;
+        assert_eq!(result.exit_code(), 2);
+        assert!(result.output.contains("unreadable.rs: couldn't be read"));
+        assert!(result.summary.ends_with(", 1 unreadable"));
+    }
+}
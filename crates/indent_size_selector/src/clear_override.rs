@@ -0,0 +1,90 @@
+use editor::Editor;
+use gpui::{App, Entity};
+use language::Buffer;
+use settings::{LocalSettingsPath, WorktreeId};
+use util::ResultExt as _;
+
+use crate::{
+    apply_scope::OVERRIDE_MARKER, editorconfig_override, file_buffer, history,
+    override_batch::OverrideBatch, persistence,
+};
+
+/// An `.editorconfig` override that the selector installed, along with the content it leaves
+/// behind once cleared.
+struct InstalledOverride {
+    path: LocalSettingsPath,
+    remaining: Option<String>,
+}
+
+/// The `.editorconfig` overrides the selector installed for the file of `buffer` or one of its
+/// directories, up to the root of its worktree.
+fn installed_overrides(
+    buffer: &Entity<Buffer>,
+    cx: &App,
+) -> Option<(WorktreeId, Vec<InstalledOverride>)> {
+    let file = buffer.read(cx).file()?;
+    let worktree_id = file.worktree_id(cx);
+    let mut overrides = Vec::new();
+
+    let file_path = LocalSettingsPath::InWorktree(file.path().clone());
+    if editorconfig_override(worktree_id, &file_path, cx).is_some() {
+        overrides.push(InstalledOverride {
+            path: file_path,
+            remaining: None,
+        });
+    }
+    for directory in file.path().ancestors().skip(1) {
+        let path = LocalSettingsPath::InWorktree(directory.into());
+        let Some(config) = editorconfig_override(worktree_id, &path, cx) else {
+            continue;
+        };
+        // Only the section after the marker is the selector's. Anything before it came from
+        // the directory's own `.editorconfig`.
+        let Some((existing, _)) = config.split_once(OVERRIDE_MARKER) else {
+            continue;
+        };
+        let existing = existing.trim_end();
+        overrides.push(InstalledOverride {
+            path,
+            remaining: (!existing.is_empty()).then(|| format!("{existing}\n")),
+        });
+    }
+    Some((worktree_id, overrides))
+}
+
+/// Whether the selector installed an override that applies to the file of `buffer`.
+pub(crate) fn has_override(buffer: &Entity<Buffer>, cx: &App) -> bool {
+    buffer.read(cx).indentation_override().is_some()
+        || installed_overrides(buffer, cx).is_some_and(|(_, overrides)| !overrides.is_empty())
+}
+
+/// Removes every override the selector installed for the file open in `editor`: the override
+/// for its buffer, the `.editorconfig` overrides for it and its directories, and the choice
+/// remembered for later sessions. Returns whether there was one to remove.
+pub(crate) fn clear_override(editor: &Entity<Editor>, cx: &mut App) -> bool {
+    let Some(buffer) = file_buffer(editor.read(cx), cx) else {
+        return false;
+    };
+    let had_override = has_override(&buffer, cx);
+
+    // The overrides a guest sees are the host's, and clearing them would only hide them here.
+    let is_guest = editor
+        .read(cx)
+        .project()
+        .is_some_and(|project| project.read(cx).is_via_collab());
+    history::transact(cx, |cx| {
+        history::set_indentation_override(&buffer, None, cx);
+        if !is_guest && let Some((worktree_id, overrides)) = installed_overrides(&buffer, cx) {
+            let mut batch = OverrideBatch::default();
+            for installed in overrides {
+                batch.set(worktree_id, installed.path, installed.remaining);
+            }
+            batch.commit(cx).log_err();
+        }
+    });
+    persistence::forget_choice(&buffer, cx);
+
+    // The status item re-reads the indentation whenever the editor notifies.
+    editor.update(cx, |_, cx| cx.notify());
+    had_override
+}
@@ -0,0 +1,599 @@
+use anyhow::Result;
+use gpui::{Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity};
+use language::{DetectedIndent, LanguageName};
+use project::{DirectoryLister, Project, ProjectPath};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Write as _};
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::{ResultExt as _, paths::PathStyle};
+use workspace::{
+    Toast, Workspace,
+    item::{Item, ItemEvent},
+    notifications::NotificationId,
+};
+
+use crate::{
+    ConsistencyReportFormat, ConvertProjectIndentation, ExportIndentationConsistency,
+    ShowIndentationConsistency,
+    detection::expected_indent,
+    fix_on_save::{self, FileNeedingReindent, ScannedFile},
+    project_conversion,
+};
+
+/// Lists the files of the project whose indentation disagrees with their settings, grouped by
+/// language, as found by scanning the project in the background.
+pub(crate) struct IndentationConsistencyReport {
+    focus_handle: FocusHandle,
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    /// `None` while the project is being scanned.
+    groups: Option<Vec<(LanguageName, Vec<FileNeedingReindent>)>>,
+    _scan: Task<()>,
+}
+
+/// Whether the files of the workspace's project can be checked, telling why not otherwise.
+fn is_supported(workspace: &mut Workspace, cx: &mut Context<Workspace>) -> bool {
+    // The files are read from the local file system without opening them.
+    if workspace.project().read(cx).is_local() {
+        return true;
+    }
+    struct IndentationConsistency;
+
+    workspace.show_toast(
+        Toast::new(
+            NotificationId::unique::<IndentationConsistency>(),
+            "Checking the indentation of a project is only supported for local projects",
+        )
+        .autohide(),
+        cx,
+    );
+    false
+}
+
+/// Groups `files` by language, listing each group's files by path.
+fn group_by_language(
+    files: Vec<FileNeedingReindent>,
+) -> Vec<(LanguageName, Vec<FileNeedingReindent>)> {
+    let mut files_by_language = BTreeMap::<LanguageName, Vec<FileNeedingReindent>>::new();
+    for file in files {
+        files_by_language
+            .entry(file.language.clone())
+            .or_default()
+            .push(file);
+    }
+    files_by_language
+        .into_iter()
+        .map(|(language, mut files)| {
+            files.sort_by(|a, b| a.path.cmp(&b.path));
+            (language, files)
+        })
+        .collect()
+}
+
+impl IndentationConsistencyReport {
+    /// Opens the report for the project, or scans it again when it's already open.
+    pub(crate) fn deploy(
+        workspace: &mut Workspace,
+        _: &ShowIndentationConsistency,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        if !is_supported(workspace, cx) {
+            return;
+        }
+
+        if let Some(existing) = workspace.item_of_type::<Self>(cx) {
+            existing.update(cx, |report, cx| report.scan(cx));
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+        let workspace_handle = cx.weak_entity();
+        let report = cx.new(|cx| {
+            let mut report = Self {
+                focus_handle: cx.focus_handle(),
+                workspace: workspace_handle,
+                project,
+                groups: None,
+                _scan: Task::ready(()),
+            };
+            report.scan(cx);
+            report
+        });
+        workspace.add_item_to_active_pane(Box::new(report), None, true, window, cx);
+    }
+
+    fn scan(&mut self, cx: &mut Context<Self>) {
+        self.groups = None;
+        let files_needing_reindent = fix_on_save::files_needing_reindent(self.project.read(cx), cx);
+        self._scan = cx.spawn(async move |report, cx| {
+            let files = cx.background_spawn(files_needing_reindent).await;
+            report
+                .update(cx, |report, cx| {
+                    report.groups = Some(group_by_language(files));
+                    cx.notify();
+                })
+                .ok();
+        });
+        cx.notify();
+    }
+
+    fn open(
+        &self,
+        language_ix: usize,
+        file_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((_, files)) = self
+            .groups
+            .as_ref()
+            .and_then(|groups| groups.get(language_ix))
+        else {
+            return;
+        };
+        let Some(file) = files.get(file_ix) else {
+            return;
+        };
+        let project_path = file.path.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, window, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    /// Previews converting every listed file to its configured indentation, as
+    /// [`ConvertProjectIndentation`] does.
+    fn fix_all(&self, window: &mut Window, cx: &mut Context<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                project_conversion::convert_project_indentation(
+                    workspace,
+                    &ConvertProjectIndentation,
+                    window,
+                    cx,
+                );
+            })
+            .ok();
+    }
+
+    fn export(&self, window: &mut Window, cx: &mut Context<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                export_indentation_consistency(
+                    workspace,
+                    &ExportIndentationConsistency::default(),
+                    window,
+                    cx,
+                );
+            })
+            .ok();
+    }
+
+    fn render_group(
+        &self,
+        language_ix: usize,
+        language: &LanguageName,
+        files: &[FileNeedingReindent],
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .id(("language", language_ix))
+            .gap_0p5()
+            .child(
+                Label::new(format!("{language} ({})", files.len()))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .children(files.iter().enumerate().map(|(file_ix, file)| {
+                let violations = file
+                    .violations
+                    .iter()
+                    .map(|violation| violation.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ListItem::new(file_ix)
+                    .spacing(ListItemSpacing::Sparse)
+                    .child(Label::new(
+                        file.path.path.display(PathStyle::local()).to_string(),
+                    ))
+                    .end_slot(
+                        Label::new(violations)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.open(language_ix, file_ix, window, cx)
+                    }))
+            }))
+    }
+}
+
+impl Render for IndentationConsistencyReport {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let file_count = self
+            .groups
+            .as_ref()
+            .map(|groups| groups.iter().map(|(_, files)| files.len()).sum::<usize>());
+        let summary = match file_count {
+            None => "Scanning the project…".to_string(),
+            Some(0) => "Every file in this project uses its configured indentation".to_string(),
+            Some(1) => "1 file doesn't use its configured indentation".to_string(),
+            Some(count) => format!("{count} files don't use their configured indentation"),
+        };
+        let groups = self
+            .groups
+            .as_ref()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(|(language_ix, (language, files))| {
+                self.render_group(language_ix, language, files, cx)
+                    .into_any_element()
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .key_context("IndentationConsistencyReport")
+            .track_focus(&self.focus_handle)
+            .id("indentation-consistency-report")
+            .size_full()
+            .p_4()
+            .gap_3()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .child(
+                        v_flex()
+                            .child(Label::new("Indentation Consistency").size(LabelSize::Large))
+                            .child(Label::new(summary).color(Color::Muted)),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("rescan", "Rescan")
+                                    .disabled(file_count.is_none())
+                                    .on_click(cx.listener(|this, _, _, cx| this.scan(cx))),
+                            )
+                            .child(
+                                Button::new("export", "Export")
+                                    .disabled(file_count.is_none())
+                                    .on_click(
+                                        cx.listener(|this, _, window, cx| this.export(window, cx)),
+                                    ),
+                            )
+                            .child(
+                                Button::new("fix-all", "Fix All")
+                                    .style(ButtonStyle::Filled)
+                                    .disabled(file_count.is_none_or(|count| count == 0))
+                                    .on_click(
+                                        cx.listener(|this, _, window, cx| this.fix_all(window, cx)),
+                                    ),
+                            ),
+                    ),
+            )
+            .children(groups)
+    }
+}
+
+/// An indentation as exported, in the terms of `.editorconfig`'s `indent_style` and
+/// `indent_size`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(tag = "indent_style", rename_all = "snake_case")]
+enum ExportedIndent {
+    Tab,
+    Space { indent_size: u32 },
+}
+
+impl From<DetectedIndent> for ExportedIndent {
+    fn from(indent: DetectedIndent) -> Self {
+        match indent {
+            DetectedIndent::Tabs => Self::Tab,
+            DetectedIndent::Spaces(indent_size) => Self::Space { indent_size },
+        }
+    }
+}
+
+impl std::fmt::Display for ExportedIndent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tab => write!(f, "tabs"),
+            Self::Space { indent_size: 1 } => write!(f, "1 space"),
+            Self::Space { indent_size } => write!(f, "{indent_size} spaces"),
+        }
+    }
+}
+
+/// A file of an exported report.
+#[derive(Debug, PartialEq, Serialize)]
+struct ExportedFile {
+    /// The file's path in its worktree, prefixed with the worktree's name when the project has
+    /// several.
+    path: String,
+    language: String,
+    /// `None` when none of the file's lines is indented.
+    detected: Option<ExportedIndent>,
+    configured: ExportedIndent,
+    mismatch: bool,
+    /// How the file breaks its configured indentation, as the report lists it.
+    violations: Vec<String>,
+}
+
+/// The path of a file of `project` as reports show it: its path in its worktree, prefixed with
+/// the worktree's name when the project has several.
+pub(crate) fn report_path(project: &Project, path: &ProjectPath, cx: &App) -> String {
+    let prefix_worktree = project.visible_worktrees(cx).nth(1).is_some();
+    let mut report_path = path.path.as_unix_str().to_string();
+    if prefix_worktree && let Some(worktree) = project.worktree_for_id(path.worktree_id, cx) {
+        report_path = format!("{}/{report_path}", worktree.read(cx).root_name_str());
+    }
+    report_path
+}
+
+/// The indentation of every file of a project, as [`ExportIndentationConsistency`] writes it.
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct ExportedReport {
+    files: Vec<ExportedFile>,
+}
+
+impl ExportedReport {
+    pub(crate) fn new(project: &Project, files: Vec<ScannedFile>, cx: &App) -> Self {
+        let mut files = files
+            .into_iter()
+            .map(|file| ExportedFile {
+                path: report_path(project, &file.path, cx),
+                language: file.language.to_string(),
+                detected: file.detected.map(ExportedIndent::from),
+                configured: expected_indent(file.configured).into(),
+                mismatch: !file.violations.is_empty(),
+                violations: file
+                    .violations
+                    .iter()
+                    .map(|violation| violation.to_string())
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { files }
+    }
+
+    /// The files of the report that don't use their configured indentation.
+    pub(crate) fn mismatches(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.files
+            .iter()
+            .filter(|file| file.mismatch)
+            .map(|file| (file.path.as_str(), file.violations.as_slice()))
+    }
+
+    pub(crate) fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    fn contents(&self, format: ConsistencyReportFormat) -> Result<String> {
+        Ok(match format {
+            ConsistencyReportFormat::Json => serde_json::to_string_pretty(self)? + "\n",
+            ConsistencyReportFormat::Markdown => self.markdown(),
+        })
+    }
+
+    fn markdown(&self) -> String {
+        let mismatches = self.files.iter().filter(|file| file.mismatch).count();
+        let mut out = format!(
+            "# Indentation consistency\n\n{mismatches} of {} files don't use their configured \
+             indentation.\n",
+            self.files.len()
+        );
+        if self.files.is_empty() {
+            return out;
+        }
+        out.push_str("\n| File | Language | Detected | Configured | Mismatch |\n");
+        out.push_str("| --- | --- | --- | --- | --- |\n");
+        for file in &self.files {
+            let detected = file
+                .detected
+                .map_or("none".to_string(), |indent| indent.to_string());
+            let mismatch = if file.mismatch {
+                format!("yes: {}", file.violations.join(", "))
+            } else {
+                "no".to_string()
+            };
+            writeln!(
+                out,
+                "| `{}` | {} | {detected} | {} | {mismatch} |",
+                file.path, file.language, file.configured,
+            )
+            .ok();
+        }
+        out
+    }
+}
+
+impl ConsistencyReportFormat {
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Json => "indentation-report.json",
+            Self::Markdown => "indentation-report.md",
+        }
+    }
+}
+
+/// Scans the project and writes the indentation of each of its files to the file picked.
+pub(crate) fn export_indentation_consistency(
+    workspace: &mut Workspace,
+    action: &ExportIndentationConsistency,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if !is_supported(workspace, cx) {
+        return;
+    }
+    let format = action.format;
+    let project = workspace.project().clone();
+    let fs = project.read(cx).fs().clone();
+    let scan = fix_on_save::scan_indentation(project.read(cx), cx);
+    let path = workspace.prompt_for_new_path(
+        DirectoryLister::Local(project.clone(), fs.clone()),
+        Some(format.file_name().to_string()),
+        window,
+        cx,
+    );
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(path) = path
+            .await
+            .log_err()
+            .flatten()
+            .and_then(|paths| paths.into_iter().next())
+        else {
+            return Ok(());
+        };
+        let files = cx.background_spawn(scan).await;
+        let contents = project
+            .read_with(cx, |project, cx| ExportedReport::new(project, files, cx))
+            .contents(format)?;
+        fs.atomic_write(path.clone(), contents).await?;
+        workspace.update(cx, |workspace, cx| {
+            struct ExportedIndentationConsistency;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<ExportedIndentationConsistency>(),
+                    format!("Exported the indentation report to {}", path.display()),
+                )
+                .autohide(),
+                cx,
+            );
+        })?;
+        Result::<()>::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+impl Focusable for IndentationConsistencyReport {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<ItemEvent> for IndentationConsistencyReport {}
+
+impl Item for IndentationConsistencyReport {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Indentation Consistency".into()
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language::{DetectedIndent, IndentSize, IndentViolation};
+    use project::ProjectPath;
+    use settings::WorktreeId;
+    use util::rel_path::RelPath;
+
+    #[test]
+    fn test_group_by_language() {
+        let file = |path: &str, language: &'static str| FileNeedingReindent {
+            path: ProjectPath {
+                worktree_id: WorktreeId::from_usize(1),
+                path: RelPath::unix(path).unwrap().into(),
+            },
+            language: LanguageName::new_static(language),
+            configured: IndentSize::spaces(4),
+            violations: vec![IndentViolation::Mismatch {
+                detected: DetectedIndent::Spaces(2),
+                expected: DetectedIndent::Spaces(4),
+            }],
+        };
+        let groups = group_by_language(vec![
+            file("src/b.rs", "Rust"),
+            file("main.go", "Go"),
+            file("src/a.rs", "Rust"),
+        ]);
+        assert_eq!(
+            groups
+                .iter()
+                .map(|(language, files)| (
+                    language.to_string(),
+                    files
+                        .iter()
+                        .map(|file| file.path.path.as_unix_str().to_string())
+                        .collect::<Vec<_>>()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Go".to_string(), vec!["main.go".to_string()]),
+                (
+                    "Rust".to_string(),
+                    vec!["src/a.rs".to_string(), "src/b.rs".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exported_report_contents() {
+        let report = ExportedReport {
+            files: vec![
+                ExportedFile {
+                    path: "main.go".into(),
+                    language: "Go".into(),
+                    detected: Some(ExportedIndent::Tab),
+                    configured: ExportedIndent::Tab,
+                    mismatch: false,
+                    violations: Vec::new(),
+                },
+                ExportedFile {
+                    path: "src/lib.rs".into(),
+                    language: "Rust".into(),
+                    detected: Some(ExportedIndent::Space { indent_size: 2 }),
+                    configured: ExportedIndent::Space { indent_size: 4 },
+                    mismatch: true,
+                    violations: vec!["indented with 2 spaces instead of 4 spaces".into()],
+                },
+            ],
+        };
+
+        let json: serde_json::Value =
+            serde_json::from_str(&report.contents(ConsistencyReportFormat::Json).unwrap()).unwrap();
+        assert_eq!(
+            json["files"][1],
+            serde_json::json!({
+                "path": "src/lib.rs",
+                "language": "Rust",
+                "detected": { "indent_style": "space", "indent_size": 2 },
+                "configured": { "indent_style": "space", "indent_size": 4 },
+                "mismatch": true,
+                "violations": ["indented with 2 spaces instead of 4 spaces"],
+            })
+        );
+        assert_eq!(
+            json["files"][0]["configured"],
+            serde_json::json!({ "indent_style": "tab" })
+        );
+
+        let markdown = report.contents(ConsistencyReportFormat::Markdown).unwrap();
+        assert!(markdown.contains("1 of 2 files don't use their configured indentation."));
+        assert!(markdown.contains("| `main.go` | Go | tabs | tabs | no |"));
+        assert!(markdown.contains(
+            "| `src/lib.rs` | Rust | 2 spaces | 4 spaces | yes: indented with 2 spaces instead \
+             of 4 spaces |"
+        ));
+    }
+}
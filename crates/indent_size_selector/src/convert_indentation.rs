@@ -0,0 +1,119 @@
+use editor::Editor;
+use gpui::{Context, Window};
+use language::IndentSize;
+use std::num::NonZeroU32;
+
+use crate::{
+    ConvertIndentationToSpaces, ConvertIndentationToTabs, file_buffer, fix_on_save,
+    read_indent_size,
+};
+
+/// Registers the actions rewriting the leading whitespace of every line of the file open in
+/// `editor`, like Vim's `:retab`.
+pub(crate) fn register(editor: &mut Editor, _: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |action: &ConvertIndentationToSpaces, _, cx| {
+                editor_handle
+                    .update(cx, |editor, cx| {
+                        convert(editor, cx, |detected, configured| {
+                            to_spaces(detected, configured, action.indent_size)
+                        })
+                    })
+                    .ok();
+            }
+        })
+        .detach();
+    editor
+        .register_action(move |action: &ConvertIndentationToTabs, _, cx| {
+            editor_handle
+                .update(cx, |editor, cx| {
+                    convert(editor, cx, |detected, configured| {
+                        to_tabs(detected, configured, action.indent_size)
+                    })
+                })
+                .ok();
+        })
+        .detach();
+}
+
+/// Re-indents the file at the cursor from and to the indentation `conversion` picks, given the
+/// indentation detected in it and the one its settings prescribe. When lines are selected, only
+/// those are re-indented, from the indentation detected in them.
+fn convert(
+    editor: &mut Editor,
+    cx: &mut Context<Editor>,
+    conversion: impl FnOnce(Option<IndentSize>, IndentSize) -> (IndentSize, IndentSize),
+) {
+    if editor.read_only(cx) {
+        return;
+    }
+    let (Some(buffer), Some(configured)) = (file_buffer(editor, cx), read_indent_size(editor, cx))
+    else {
+        return;
+    };
+    let selected_rows = fix_on_save::selected_rows(editor, cx);
+    let detected = fix_on_save::detected_in_rows(&buffer, &selected_rows, cx);
+    let (from, to) = conversion(detected, configured.indent_size);
+    fix_on_save::convert_buffer(&buffer, &selected_rows, from, to, cx);
+}
+
+/// Converts from the detected indentation, or the configured one when none is detected, to
+/// `indent_size` spaces per level, or as many as the configured indentation is wide.
+fn to_spaces(
+    detected: Option<IndentSize>,
+    configured: IndentSize,
+    indent_size: Option<NonZeroU32>,
+) -> (IndentSize, IndentSize) {
+    let len = indent_size.map_or(configured.len, NonZeroU32::get);
+    (detected.unwrap_or(configured), IndentSize::spaces(len))
+}
+
+/// Converts to a tab per level, from `indent_size` spaces per level when it's given, or else
+/// from the detected indentation, or the configured one when none is detected.
+fn to_tabs(
+    detected: Option<IndentSize>,
+    configured: IndentSize,
+    indent_size: Option<NonZeroU32>,
+) -> (IndentSize, IndentSize) {
+    let from = match indent_size {
+        Some(indent_size) => IndentSize::spaces(indent_size.get()),
+        None => detected.unwrap_or(configured),
+    };
+    (from, IndentSize::tab())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let size = |len| NonZeroU32::new(len);
+
+        assert_eq!(
+            to_spaces(Some(IndentSize::tab()), IndentSize::spaces(4), None),
+            (IndentSize::tab(), IndentSize::spaces(4))
+        );
+        assert_eq!(
+            to_spaces(None, IndentSize::spaces(2), size(8)),
+            (IndentSize::spaces(2), IndentSize::spaces(8))
+        );
+
+        assert_eq!(
+            to_tabs(Some(IndentSize::spaces(2)), IndentSize::spaces(4), None),
+            (IndentSize::spaces(2), IndentSize::tab())
+        );
+        // The given size overrides what's detected, such as for files mixing widths.
+        assert_eq!(
+            to_tabs(Some(IndentSize::spaces(2)), IndentSize::spaces(4), size(4)),
+            (IndentSize::spaces(4), IndentSize::tab())
+        );
+        assert_eq!(
+            to_tabs(None, IndentSize::spaces(3), None),
+            (IndentSize::spaces(3), IndentSize::tab())
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use editor::Editor;
+use gpui::{
+    ClipboardItem, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity,
+};
+use language::{IndentKind, IndentSize, Point};
+use picker::{Picker, PickerDelegate};
+use settings::Settings as _;
+use std::{num::NonZeroU32, sync::Arc};
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::ModalView;
+
+use crate::{
+    CopyWithConvertedIndentation, IndentSizeSelectorSettings,
+    detection::{detect_indentation_in_text, detected_indentation, to_indent_size},
+    file_buffer, modal_width, read_indent_size,
+    reindent_on_paste::reindent_text,
+};
+
+/// Handles copying the selections of `editor` with their indentation converted, asking for the
+/// indentation when the action doesn't give one.
+pub(crate) fn register(editor: &mut Editor, _: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action(move |action: &CopyWithConvertedIndentation, window, cx| {
+            let Some(editor) = editor_handle.upgrade() else {
+                return;
+            };
+            let to = if action.hard_tabs {
+                IndentSize::tab()
+            } else if let Some(size) = action.size {
+                IndentSize::spaces(size.get())
+            } else {
+                CopyIndentationSelector::toggle(&editor, window, cx);
+                return;
+            };
+            copy_converted(&editor, to, cx);
+        })
+        .detach();
+}
+
+/// Copies the selected text of `editor`, or the line at each empty selection, re-indented from
+/// the indentation it uses to `to`, without changing the file.
+fn copy_converted(editor: &Entity<Editor>, to: IndentSize, cx: &mut App) {
+    let text = editor.update(cx, |editor, cx| selected_text(editor, cx));
+    let from = to_indent_size(detect_indentation_in_text(&text))
+        .or_else(|| {
+            let buffer = file_buffer(editor.read(cx), cx)?;
+            detected_indentation(&buffer, cx)
+        })
+        .or_else(|| Some(read_indent_size(editor.read(cx), cx)?.indent_size));
+    let text = match from {
+        Some(from) if from != to => reindent_text(&text, from, to),
+        _ => text,
+    };
+    cx.write_to_clipboard(ClipboardItem::new_string(text));
+}
+
+/// The text of the selections of `editor`, one per line, with the whole line at each empty one.
+fn selected_text(editor: &Editor, cx: &mut App) -> String {
+    let display_snapshot = editor.display_snapshot(cx);
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let mut text = String::new();
+    for selection in editor.selections.all_adjusted(&display_snapshot) {
+        let range = if selection.is_empty() {
+            Point::new(selection.start.row, 0)
+                ..Point::new(selection.start.row + 1, 0).min(snapshot.max_point())
+        } else {
+            selection.start..selection.end
+        };
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.extend(snapshot.text_for_range(range));
+    }
+    text
+}
+
+/// Picks the indentation to copy the selections of an editor with.
+struct CopyIndentationSelector {
+    picker: Entity<Picker<CopyIndentationSelectorDelegate>>,
+}
+
+impl CopyIndentationSelector {
+    fn toggle(editor: &Entity<Editor>, window: &mut Window, cx: &mut App) {
+        let Some(workspace) = editor.read(cx).workspace() else {
+            return;
+        };
+        let settings = IndentSizeSelectorSettings::get_global(cx);
+        let mut candidates = settings
+            .sizes
+            .iter()
+            .map(|size| IndentSize::spaces(*size))
+            .collect::<Vec<_>>();
+        if settings.show_hard_tabs {
+            candidates.push(IndentSize::tab());
+        }
+        let editor = editor.downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                let delegate = CopyIndentationSelectorDelegate {
+                    copy_indentation_selector: cx.entity().downgrade(),
+                    editor,
+                    matches: candidates.clone(),
+                    candidates,
+                    selected_index: 0,
+                };
+                Self {
+                    picker: cx.new(|cx| Picker::uniform_list(delegate, window, cx)),
+                }
+            });
+        });
+    }
+}
+
+impl Render for CopyIndentationSelector {
+    fn render(&mut self, window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("CopyIndentationSelector")
+            .w(modal_width(window))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for CopyIndentationSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for CopyIndentationSelector {}
+impl ModalView for CopyIndentationSelector {}
+
+fn label(indent_size: IndentSize) -> String {
+    match indent_size.kind {
+        IndentKind::Tab => "Tabs".to_string(),
+        IndentKind::Space => format!("{} spaces", indent_size.len),
+    }
+}
+
+struct CopyIndentationSelectorDelegate {
+    copy_indentation_selector: WeakEntity<CopyIndentationSelector>,
+    editor: WeakEntity<Editor>,
+    candidates: Vec<IndentSize>,
+    /// The candidates matching the query, preceded by the number of spaces typed when it's
+    /// another one.
+    matches: Vec<IndentSize>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for CopyIndentationSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Copy the selection indented with…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(to) = self.matches.get(self.selected_index).copied()
+            && let Some(editor) = self.editor.upgrade()
+        {
+            copy_converted(&editor, to, cx);
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.copy_indentation_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let query = query.trim().to_lowercase();
+        let mut matches = self
+            .candidates
+            .iter()
+            .copied()
+            .filter(|indent_size| label(*indent_size).to_lowercase().contains(&query))
+            .collect::<Vec<_>>();
+        if let Ok(size) = query.parse::<NonZeroU32>() {
+            let typed = IndentSize::spaces(size.get());
+            matches.retain(|indent_size| *indent_size != typed);
+            matches.insert(0, typed);
+        }
+        self.matches = matches;
+        self.selected_index = 0;
+        cx.notify();
+        Task::ready(())
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let indent_size = self.matches.get(ix)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(Label::new(label(*indent_size))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::SelectionEffects;
+    use gpui::{TestAppContext, VisualContext as _, VisualTestContext};
+    use language::Buffer;
+    use std::ops::Range;
+    use workspace::AppState;
+
+    /// Copies the text of `editor` in `range` converted to `to`, returning what was copied.
+    fn copy(
+        editor: &Entity<Editor>,
+        range: Range<Point>,
+        to: IndentSize,
+        cx: &mut VisualTestContext,
+    ) -> String {
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |selections| {
+                selections.select_ranges([range])
+            });
+        });
+        cx.update(|_, cx| copy_converted(editor, to, cx));
+        cx.read_from_clipboard()
+            .and_then(|item| item.text())
+            .unwrap()
+    }
+
+    #[gpui::test]
+    fn test_copy_converted(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let tabs = "fn f() {\n\tif x {\n\t\tg();\n\t}\n}\n";
+        let spaces = "fn f() {\n    if x {\n        g();\n    }\n}\n";
+
+        let buffer = cx.new(|cx| Buffer::local(tabs, cx));
+        let (editor, cx) =
+            cx.add_window_view(|window, cx| Editor::for_buffer(buffer, None, window, cx));
+        assert_eq!(
+            copy(
+                &editor,
+                Point::new(1, 0)..Point::new(4, 0),
+                IndentSize::spaces(4),
+                cx
+            ),
+            "    if x {\n        g();\n    }\n"
+        );
+        // A first line selected past its indentation is copied as it is, and the others are
+        // still converted.
+        assert_eq!(
+            copy(
+                &editor,
+                Point::new(1, 4)..Point::new(3, 2),
+                IndentSize::spaces(2),
+                cx
+            ),
+            "x {\n    g();\n  }"
+        );
+        // The buffer keeps its indentation.
+        editor.update(cx, |editor, cx| {
+            assert_eq!(editor.text(cx), tabs);
+        });
+
+        let buffer = cx.new(|cx| Buffer::local(spaces, cx));
+        let editor =
+            cx.new_window_entity(|window, cx| Editor::for_buffer(buffer, None, window, cx));
+        assert_eq!(
+            copy(
+                &editor,
+                Point::new(1, 0)..Point::new(4, 0),
+                IndentSize::tab(),
+                cx
+            ),
+            "\tif x {\n\t\tg();\n\t}\n"
+        );
+        assert_eq!(
+            copy(
+                &editor,
+                Point::new(1, 7)..Point::new(3, 5),
+                IndentSize::tab(),
+                cx
+            ),
+            "x {\n\t\tg();\n\t}"
+        );
+    }
+}
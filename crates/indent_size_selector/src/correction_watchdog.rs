@@ -0,0 +1,180 @@
+use collections::HashMap;
+use editor::{Editor, EditorEvent};
+use gpui::{Context, Window};
+use language::{IndentKind, Point};
+use settings::Settings as _;
+use workspace::{Toast, notifications::NotificationId};
+
+use crate::{IndentSizeSelectorSettings, apply_to_path, history, read_indent_size};
+
+/// The number of lines manually re-indented to the same width before suggesting that width.
+const CORRECTIONS_BEFORE_SUGGESTING: usize = 5;
+
+/// Tracks lines whose indentation the user fixed by hand to a width other than the configured
+/// one.
+#[derive(Default)]
+struct CorrectionWatchdog {
+    version: Option<clock::Global>,
+    /// The width each manually re-indented row was last corrected to.
+    corrected_rows: HashMap<u32, u32>,
+    suggested: bool,
+}
+
+/// Watches the edits in `editor`, suggesting a different indent size once the user keeps
+/// re-indenting lines to it by hand.
+pub(crate) fn watch(editor: &mut Editor, _: Option<&mut Window>, cx: &mut Context<Editor>) {
+    if !editor.buffer().read(cx).is_singleton() {
+        return;
+    }
+    let mut watchdog = CorrectionWatchdog::default();
+    cx.subscribe_self(move |editor, event: &EditorEvent, cx| {
+        if !matches!(event, EditorEvent::Edited { .. }) || watchdog.suggested {
+            return;
+        }
+        let settings = IndentSizeSelectorSettings::get_global(cx);
+        if !settings.suggest_from_corrections {
+            watchdog.version = None;
+            return;
+        }
+        let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let Some(configured) = read_indent_size(editor, cx) else {
+            return;
+        };
+        let configured = configured.indent_size;
+        let snapshot = buffer.read(cx).snapshot();
+        let Some(version) = watchdog.version.replace(snapshot.version().clone()) else {
+            return;
+        };
+
+        for edit in snapshot.edits_since::<Point>(&version) {
+            let row = edit.new.start.row;
+            if edit.new.end.row != row || edit.old.end.row != edit.old.start.row {
+                continue;
+            }
+            let indent = snapshot.indent_size_for_line(row);
+            if indent.kind != IndentKind::Space
+                || indent.len == 0
+                || edit.new.end.column > indent.len
+                || snapshot.is_line_blank(row)
+            {
+                continue;
+            }
+            if indent.len % configured.len == 0 {
+                watchdog.corrected_rows.remove(&row);
+                continue;
+            }
+            if let Some(width) = settings
+                .sizes
+                .iter()
+                .copied()
+                .find(|width| *width > 0 && *width != configured.len && indent.len % width == 0)
+            {
+                watchdog.corrected_rows.insert(row, width);
+            }
+        }
+
+        let Some(width) = settings.sizes.iter().copied().find(|width| {
+            watchdog
+                .corrected_rows
+                .values()
+                .filter(|corrected| *corrected == width)
+                .count()
+                >= CORRECTIONS_BEFORE_SUGGESTING
+        }) else {
+            return;
+        };
+        let Some(workspace) = editor.workspace() else {
+            return;
+        };
+        watchdog.suggested = true;
+        workspace.update(cx, |workspace, cx| {
+            struct IndentationCorrectionSuggestion;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<IndentationCorrectionSuggestion>(),
+                    format!("Switch this file to {width} spaces?"),
+                )
+                .on_click("Switch", move |_, cx| {
+                    history::transact(cx, |cx| {
+                        apply_to_path(&buffer, width, false, None, None, cx)
+                    });
+                }),
+                cx,
+            );
+        });
+    })
+    .detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace};
+
+    #[gpui::test]
+    async fn test_suggests_after_repeated_corrections(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings(cx, |content| {
+                    content
+                        .indent_size_selector
+                        .get_or_insert_default()
+                        .suggest_from_corrections = Some(true);
+                });
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.txt": "    a\n".repeat(8) }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace =
+            multi_workspace.read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone());
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.txt")), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        let editor = cx.update(|_, cx| item.act_as::<Editor>(cx).unwrap());
+        editor.update(cx, |editor, cx| watch(editor, None, cx));
+        let notification_count = |cx: &mut gpui::VisualTestContext| {
+            workspace.read_with(cx, |workspace, _| workspace.notification_ids().len())
+        };
+        let reindent_to_2 = |row: u32, cx: &mut gpui::VisualTestContext| {
+            editor.update(cx, |editor, cx| {
+                editor.edit([(Point::new(row, 0)..Point::new(row, 2), "")], cx)
+            });
+            cx.run_until_parked();
+        };
+
+        // The watchdog starts counting from the first edit it sees.
+        editor.update(cx, |editor, cx| {
+            editor.edit([(Point::new(7, 5)..Point::new(7, 5), "b")], cx)
+        });
+        cx.run_until_parked();
+        let shown_before = notification_count(cx);
+
+        for row in 0..CORRECTIONS_BEFORE_SUGGESTING as u32 - 1 {
+            reindent_to_2(row, cx);
+        }
+        assert_eq!(notification_count(cx), shown_before);
+
+        // Another line re-indented to 2 spaces rather than the configured 4 suggests switching.
+        reindent_to_2(CORRECTIONS_BEFORE_SUGGESTING as u32 - 1, cx);
+        assert_eq!(notification_count(cx), shown_before + 1);
+    }
+}
@@ -0,0 +1,263 @@
+use collections::HashMap;
+use gpui::{App, AppContext as _, Entity, Global, Task};
+use language::{
+    Buffer, BufferId, BufferSnapshot, DetectOptions, DetectedIndent, Detection,
+    DetectionConfidence, IndentKind, IndentSize, IndentationDetector, Point, Rope,
+    detect_indentation,
+};
+use smol::future::yield_now;
+use std::ops::Range;
+
+/// The number of rows past which a buffer counts as large: detection leaves off once it's
+/// confident, and runs in the background when the buffer is opened.
+pub(crate) const LARGE_BUFFER_ROWS: u32 = 50_000;
+
+/// The number of rows inspected between checks of whether a scan can leave off, and between
+/// yields of a scan running in the background.
+const CHUNK_ROWS: u32 = 4096;
+
+/// What was detected in each buffer, along with the version it was detected at, until the
+/// buffer is released.
+#[derive(Default)]
+struct DetectionCache(HashMap<BufferId, (clock::Global, Detection)>);
+
+impl Global for DetectionCache {}
+
+/// Returns the indentation `buffer` actually uses, reusing the previous scan while the buffer
+/// is unchanged.
+pub fn detected_indentation(buffer: &Entity<Buffer>, cx: &mut App) -> Option<IndentSize> {
+    to_indent_size(buffer_detection(buffer, cx))
+}
+
+/// Returns the number of lines of `buffer` indented with [`language::ExoticWhitespace`].
+pub(crate) fn exotic_whitespace_lines(buffer: &Entity<Buffer>, cx: &mut App) -> usize {
+    buffer_detection(buffer, cx).exotic_whitespace_lines
+}
+
+/// Returns what detecting the indentation of `buffer` found, reusing the previous scan while the
+/// buffer is unchanged.
+pub(crate) fn buffer_detection(buffer: &Entity<Buffer>, cx: &mut App) -> Detection {
+    let buffer_id = buffer.read(cx).remote_id();
+    let version = buffer.read(cx).version();
+    if let Some((cached_version, detection)) = cx
+        .try_global::<DetectionCache>()
+        .and_then(|cache| cache.0.get(&buffer_id))
+        && *cached_version == version
+    {
+        return *detection;
+    }
+
+    let detection = detect_buffer_indentation(&buffer.read(cx).snapshot());
+    cache_detection(buffer, version, detection, cx);
+    detection
+}
+
+/// Like [`buffer_detection`], but scanning the buffer on the background executor when it
+/// changed since the previous scan, so that detecting the indentation of a large file doesn't
+/// block the UI.
+pub(crate) fn buffer_detection_in_background(
+    buffer: &Entity<Buffer>,
+    cx: &mut App,
+) -> Task<Detection> {
+    let snapshot = buffer.read(cx).snapshot();
+    let buffer_id = snapshot.remote_id();
+    let version = snapshot.version().clone();
+    if let Some((cached_version, detection)) = cx
+        .try_global::<DetectionCache>()
+        .and_then(|cache| cache.0.get(&buffer_id))
+        && *cached_version == version
+    {
+        return Task::ready(*detection);
+    }
+
+    let buffer = buffer.downgrade();
+    cx.spawn(async move |cx| {
+        let detection = cx
+            .background_spawn(async move { detect_in_chunks(&snapshot).await })
+            .await;
+        cx.update(|cx| {
+            if let Some(buffer) = buffer.upgrade() {
+                cache_detection(&buffer, version, detection, cx);
+            }
+        });
+        detection
+    })
+}
+
+/// Caches what was detected in `buffer` at `version`, until the buffer is released.
+fn cache_detection(
+    buffer: &Entity<Buffer>,
+    version: clock::Global,
+    detection: Detection,
+    cx: &mut App,
+) {
+    let buffer_id = buffer.read(cx).remote_id();
+    let cache = cx.default_global::<DetectionCache>();
+    if cache.0.insert(buffer_id, (version, detection)).is_none() {
+        cx.observe_release(buffer, move |_, cx| {
+            if cx.has_global::<DetectionCache>() {
+                cx.global_mut::<DetectionCache>().0.remove(&buffer_id);
+            }
+        })
+        .detach();
+    }
+}
+
+/// Infers the indentation of a buffer from its content: the kind and width in
+/// [`Detection::indent`], and how much to trust them in [`Detection::confidence`].
+///
+/// The rows of a buffer past [`LARGE_BUFFER_ROWS`] are inspected a chunk at a time, leaving off
+/// as soon as the detection is highly confident, so the counts in what's found only cover the
+/// rows inspected by then.
+pub fn detect_buffer_indentation(snapshot: &BufferSnapshot) -> Detection {
+    let mut detector = IndentationDetector::new(&DetectOptions::default());
+    for rows in row_chunks(snapshot) {
+        if !detect_in_rows(&mut detector, snapshot, rows) {
+            break;
+        }
+    }
+    detector.detection()
+}
+
+/// Like [`detect_buffer_indentation`], but yielding between chunks of rows, so that a scan
+/// running in the background stops soon after it's dropped.
+pub(crate) async fn detect_in_chunks(snapshot: &BufferSnapshot) -> Detection {
+    let mut detector = IndentationDetector::new(&DetectOptions::default());
+    for rows in row_chunks(snapshot) {
+        if !detect_in_rows(&mut detector, snapshot, rows) {
+            break;
+        }
+        yield_now().await;
+    }
+    detector.detection()
+}
+
+/// The rows of `snapshot`, [`CHUNK_ROWS`] at a time.
+pub(crate) fn row_chunks(snapshot: &BufferSnapshot) -> impl Iterator<Item = Range<u32>> {
+    let row_count = snapshot.max_point().row + 1;
+    (0..row_count)
+        .step_by(CHUNK_ROWS as usize)
+        .map(move |start| start..(start + CHUNK_ROWS).min(row_count))
+}
+
+/// Feeds `rows` of `snapshot` to `detector`, returning whether the rows after them are still
+/// worth inspecting.
+fn detect_in_rows(
+    detector: &mut IndentationDetector,
+    snapshot: &BufferSnapshot,
+    rows: Range<u32>,
+) -> bool {
+    let start = snapshot.point_to_offset(Point::new(rows.start, 0));
+    let end = snapshot.point_to_offset(Point::new(rows.end, 0).min(snapshot.max_point()));
+    let mut lines = snapshot.as_rope().chunks_in_range(start..end).lines();
+    while let Some(line) = lines.next() {
+        detector.push_line(line);
+    }
+    let confident = snapshot.max_point().row >= LARGE_BUFFER_ROWS
+        && detector.detection().confidence() == Some(DetectionConfidence::High);
+    !detector.is_done() && !confident
+}
+
+/// Inspects the indentation of `text`, such as the content of a file that isn't open.
+pub(crate) fn detect_indentation_in_text(text: &str) -> Detection {
+    detect_indentation(&Rope::from(text), &DetectOptions::default())
+}
+
+/// The unit of indentation that checks expect of a file configured to use `indent_size`. Tabs
+/// are a single unit whatever their width.
+pub(crate) fn expected_indent(indent_size: IndentSize) -> DetectedIndent {
+    match indent_size.kind {
+        IndentKind::Tab => DetectedIndent::Tabs,
+        IndentKind::Space => DetectedIndent::Spaces(indent_size.len),
+    }
+}
+
+pub(crate) fn to_indent_size(detection: Detection) -> Option<IndentSize> {
+    detection.indent.map(|indent| match indent {
+        DetectedIndent::Tabs => IndentSize::tab(),
+        DetectedIndent::Spaces(len) => IndentSize::spaces(len),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    fn test_detect_indentation(cx: &mut TestAppContext) {
+        let detect = |text: &str, cx: &mut TestAppContext| {
+            let buffer = cx.new(|cx| Buffer::local(text, cx));
+            cx.update(|cx| detected_indentation(&buffer, cx))
+        };
+
+        assert_eq!(detect("fn a() {}\n", cx), None);
+        assert_eq!(
+            detect("a:\n  b:\n    c: 1\n  d: 2\n", cx),
+            Some(IndentSize::spaces(2))
+        );
+        assert_eq!(
+            detect("func a() {\n\tif b {\n\t\treturn\n\t}\n}\n", cx),
+            Some(IndentSize::tab())
+        );
+
+        let buffer = cx.new(|cx| Buffer::local("a:\n    b:\n        c: 1\n    d: 2\n", cx));
+        let detection = buffer.read_with(cx, |buffer, _| {
+            detect_buffer_indentation(&buffer.snapshot())
+        });
+        assert_eq!(detection.indent, Some(DetectedIndent::Spaces(4)));
+        assert_eq!(detection.confidence(), Some(DetectionConfidence::Low));
+
+        let buffer = cx.new(|cx| Buffer::local("a:\n\u{00A0} b:\n  c:\n", cx));
+        assert_eq!(cx.update(|cx| exotic_whitespace_lines(&buffer, cx)), 1);
+        assert_eq!(
+            to_indent_size(detect_indentation_in_text("a:\n  b:\n\n    c: 1\n  d: 2\n")),
+            Some(IndentSize::spaces(2))
+        );
+    }
+
+    #[gpui::test]
+    fn test_detection_cache_evicts_released_buffers(cx: &mut TestAppContext) {
+        let cached = |buffer_id, cx: &mut TestAppContext| {
+            cx.update(|cx| cx.global::<DetectionCache>().0.contains_key(&buffer_id))
+        };
+        let buffer = cx.new(|cx| Buffer::local("a:\n  b: 1\n", cx));
+        let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+        cx.update(|cx| buffer_detection(&buffer, cx));
+        assert!(cached(buffer_id, cx));
+
+        // Detecting again once it's edited replaces the entry.
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "c:\n")], None, cx));
+        cx.update(|cx| buffer_detection(&buffer, cx));
+        assert!(cached(buffer_id, cx));
+
+        drop(buffer);
+        cx.run_until_parked();
+        assert!(!cached(buffer_id, cx));
+    }
+
+    #[gpui::test]
+    async fn test_detect_indentation_of_large_buffer(cx: &mut TestAppContext) {
+        // Confidently indented by 2 spaces well before the tab-indented line at the end.
+        let text = format!(
+            "{}{}\tc\n",
+            "a:\n  b: 1\n".repeat(20),
+            "x\n".repeat(LARGE_BUFFER_ROWS as usize)
+        );
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let detection = cx
+            .update(|cx| buffer_detection_in_background(&buffer, cx))
+            .await;
+        assert_eq!(detection.indent, Some(DetectedIndent::Spaces(2)));
+        assert_eq!(detection.confidence(), Some(DetectionConfidence::High));
+        assert_eq!(detection.tab_indented_lines, 0);
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| detect_buffer_indentation(
+                &buffer.snapshot()
+            )),
+            detection
+        );
+        // The background scan is reused.
+        assert_eq!(cx.update(|cx| buffer_detection(&buffer, cx)), detection);
+    }
+}
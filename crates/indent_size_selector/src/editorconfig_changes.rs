@@ -0,0 +1,196 @@
+use gpui::{App, AppContext as _, Context, Entity, WeakEntity, Window};
+use paths::EDITORCONFIG_NAME;
+use project::{PathChange, Project, ProjectEntryId};
+use settings::{LocalSettingsPath, SettingsStore, WorktreeId};
+use std::sync::Arc;
+use util::{ResultExt as _, rel_path::RelPath};
+use workspace::{
+    Toast, Workspace,
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
+};
+
+use crate::{history, override_batch::OverrideBatch, persistence};
+
+/// Offers to drop the overrides the selector installed for files under a directory whose
+/// `.editorconfig` changes on disk, since they would otherwise keep winning over it. Overrides
+/// for the directory itself share its `.editorconfig`'s slot, so reloading it already replaces
+/// them.
+pub(crate) fn watch(
+    workspace: &mut Workspace,
+    _: Option<&mut Window>,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    cx.subscribe(&project, |workspace, project, event, cx| {
+        let project::Event::WorktreeUpdatedEntries(worktree_id, changes) = event else {
+            return;
+        };
+        // The overrides a guest sees are the host's.
+        if project.read(cx).is_via_collab() {
+            return;
+        }
+        let directories = changed_editorconfig_directories(changes);
+        if directories.is_empty() {
+            return;
+        }
+        let overridden_files = overridden_files_under(&project, *worktree_id, &directories, cx);
+        if !overridden_files.is_empty() {
+            offer_to_drop(workspace, project, *worktree_id, overridden_files, cx);
+        }
+    })
+    .detach();
+}
+
+/// The directories whose `.editorconfig` was added, changed or removed, leaving out those
+/// loaded as the worktree is first scanned.
+fn changed_editorconfig_directories(
+    changes: &[(Arc<RelPath>, ProjectEntryId, PathChange)],
+) -> Vec<Arc<RelPath>> {
+    changes
+        .iter()
+        .filter(|(path, _, change)| {
+            *change != PathChange::Loaded && path.file_name() == Some(EDITORCONFIG_NAME)
+        })
+        .filter_map(|(path, _, _)| path.parent().map(Arc::from))
+        .collect()
+}
+
+/// The files under any of `directories` that have an override from the selector.
+fn overridden_files_under(
+    project: &Entity<Project>,
+    worktree_id: WorktreeId,
+    directories: &[Arc<RelPath>],
+    cx: &App,
+) -> Vec<Arc<RelPath>> {
+    let Some(worktree) = project.read(cx).worktree_for_id(worktree_id, cx) else {
+        return Vec::new();
+    };
+    let worktree = worktree.read(cx);
+    cx.global::<SettingsStore>()
+        .editorconfig_store
+        .read(cx)
+        .local_editorconfig_settings(worktree_id)
+        .filter_map(|(path, _, _)| match path {
+            LocalSettingsPath::InWorktree(path) => Some(path),
+            LocalSettingsPath::OutsideWorktree(_) => None,
+        })
+        .filter(|path| {
+            directories
+                .iter()
+                .any(|directory| path.starts_with(directory))
+                && worktree
+                    .entry_for_path(path)
+                    .is_some_and(|entry| entry.is_file())
+        })
+        .collect()
+}
+
+fn offer_to_drop(
+    workspace: &mut Workspace,
+    project: Entity<Project>,
+    worktree_id: WorktreeId,
+    overridden_files: Vec<Arc<RelPath>>,
+    cx: &mut Context<Workspace>,
+) {
+    struct EditorconfigChanged;
+
+    let message = match overridden_files.as_slice() {
+        [_] => format!(
+            "An {EDITORCONFIG_NAME} changed, but the indentation picked for a file under it \
+             still takes precedence."
+        ),
+        _ => format!(
+            "An {EDITORCONFIG_NAME} changed, but the indentation picked for {} files under it \
+             still takes precedence.",
+            overridden_files.len()
+        ),
+    };
+    let workspace_handle = cx.weak_entity();
+    workspace.show_notification(NotificationId::unique::<EditorconfigChanged>(), cx, |cx| {
+        cx.new(|cx| {
+            MessageNotification::new(message, cx)
+                .with_title("Indentation Overrides")
+                .show_suppress_button(false)
+                .primary_message(format!("Use {EDITORCONFIG_NAME}"))
+                .primary_on_click(move |_, cx| {
+                    drop_overrides(
+                        &project,
+                        worktree_id,
+                        &overridden_files,
+                        &workspace_handle,
+                        cx,
+                    );
+                })
+                .secondary_message("Keep Overrides")
+        })
+    });
+}
+
+/// Removes the overrides for `overridden_files`, along with the choices remembered for them.
+fn drop_overrides(
+    project: &Entity<Project>,
+    worktree_id: WorktreeId,
+    overridden_files: &[Arc<RelPath>],
+    workspace: &WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    let mut batch = OverrideBatch::default();
+    for path in overridden_files {
+        batch.set(
+            worktree_id,
+            LocalSettingsPath::InWorktree(path.clone()),
+            None,
+        );
+    }
+    if !history::transact(cx, |cx| batch.commit(cx).log_err().is_some()) {
+        return;
+    }
+    if let Some(worktree) = project.read(cx).worktree_for_id(worktree_id, cx) {
+        for path in overridden_files {
+            persistence::forget_choice_at(worktree.read(cx).absolutize(path), cx);
+        }
+    }
+    workspace
+        .update(cx, |workspace, cx| {
+            struct EditorconfigApplied;
+
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<EditorconfigApplied>(),
+                    format!("The {EDITORCONFIG_NAME} now applies to those files"),
+                )
+                .autohide(),
+                cx,
+            );
+        })
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_editorconfig_directories() {
+        let change = |path: &str, change: PathChange| {
+            (
+                RelPath::unix(path).unwrap().into_arc(),
+                ProjectEntryId::from_proto(1),
+                change,
+            )
+        };
+        assert_eq!(
+            changed_editorconfig_directories(&[
+                change(".editorconfig", PathChange::Updated),
+                change("src/.editorconfig", PathChange::Removed),
+                // Loaded by the initial scan rather than edited.
+                change("docs/.editorconfig", PathChange::Loaded),
+                change("src/main.rs", PathChange::Updated),
+            ]),
+            vec![
+                RelPath::empty().into_arc(),
+                RelPath::unix("src").unwrap().into_arc()
+            ]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use editor::Editor;
+use gpui::{Context, Entity, PromptLevel, SharedString, Window};
+use language::{
+    Buffer, IndentSize,
+    language_settings::{LanguageSettings, ResolvedWhitespaceOptions},
+};
+use settings::Settings as _;
+use workspace::{Save, SaveIntent};
+
+use crate::{
+    IndentSizeSelectorSettings, file_buffer, fix_on_save, inconsistent_lines::inconsistent_rows,
+    indent_source::IndentSource, indentation_fixes::line_fix_edits, read_indent_size,
+};
+
+/// The lines of a file about to be saved whose indentation conflicts with the `.editorconfig`
+/// governing it.
+struct EditorconfigConflict {
+    buffer: Entity<Buffer>,
+    rows: Vec<u32>,
+    configured: IndentSize,
+    tab_width: u32,
+    /// Names the `.editorconfig`, such as "src/.editorconfig".
+    editorconfig: SharedString,
+}
+
+/// Asks before saving the file open in `editor` whether to fix the lines whose indentation
+/// conflicts with the `.editorconfig` governing it, when `check_editorconfig_on_save` is
+/// enabled, so that they don't fail the project's style checks later on.
+pub(crate) fn watch(editor: &mut Editor, _: Option<&mut Window>, cx: &mut Context<Editor>) {
+    if !editor.buffer().read(cx).is_singleton() {
+        return;
+    }
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action(move |action: &Save, window, cx| {
+            let save_intent = action.save_intent.unwrap_or(SaveIntent::Save);
+            let conflict = if save_intent == SaveIntent::Save {
+                editor_handle
+                    .update(cx, |editor, cx| conflict(editor, cx))
+                    .ok()
+                    .flatten()
+            } else {
+                None
+            };
+            let Some(conflict) = conflict else {
+                cx.propagate();
+                return;
+            };
+
+            let message = match conflict.rows.len() {
+                1 => format!(
+                    "1 line doesn't match the indentation set by {}",
+                    conflict.editorconfig
+                ),
+                lines => format!(
+                    "{lines} lines don't match the indentation set by {}",
+                    conflict.editorconfig
+                ),
+            };
+            let answer = window.prompt(
+                PromptLevel::Warning,
+                &message,
+                Some("Saving them as they are may fail the project's style checks."),
+                &["Fix and Save", "Save Anyway", "Cancel"],
+                cx,
+            );
+            let editor_handle = editor_handle.clone();
+            window
+                .spawn(cx, async move |cx| {
+                    let answer = answer.await.ok()?;
+                    if answer == 2 {
+                        return None;
+                    }
+                    editor_handle
+                        .update_in(cx, |editor, window, cx| {
+                            if answer == 0 {
+                                let snapshot = conflict.buffer.read(cx).snapshot();
+                                let edits = line_fix_edits(
+                                    &snapshot,
+                                    conflict.rows,
+                                    conflict.tab_width,
+                                    conflict.configured,
+                                );
+                                fix_on_save::apply_conversion(&conflict.buffer, edits, cx);
+                            }
+                            // Saving through the workspace skips this check.
+                            let workspace = editor.workspace()?;
+                            workspace.update(cx, |workspace, cx| {
+                                workspace
+                                    .save_active_item(save_intent, window, cx)
+                                    .detach_and_prompt_err(
+                                        "Failed to save",
+                                        window,
+                                        cx,
+                                        |_, _, _| None,
+                                    );
+                            });
+                            Some(())
+                        })
+                        .ok()
+                        .flatten()
+                })
+                .detach();
+        })
+        .detach();
+}
+
+/// The lines of the file open in `editor` conflicting with the `.editorconfig` that determines
+/// its indentation. `None` when the check is off, an `.editorconfig` doesn't determine the
+/// file's indentation, saving already re-indents the file, or every line matches.
+fn conflict(editor: &Editor, cx: &mut Context<Editor>) -> Option<EditorconfigConflict> {
+    if !IndentSizeSelectorSettings::get_global(cx).check_editorconfig_on_save {
+        return None;
+    }
+    let buffer = file_buffer(editor, cx)?;
+    if fix_on_save::converts_on_save(&buffer, cx) {
+        return None;
+    }
+    let source = IndentSource::for_editor(editor, cx);
+    if !matches!(source, IndentSource::Editorconfig { .. }) {
+        return None;
+    }
+    let configured = read_indent_size(editor, cx)?.indent_size;
+    let rows = inconsistent_rows(&buffer.read(cx).snapshot(), configured);
+    if rows.is_empty() {
+        return None;
+    }
+    let whitespace = ResolvedWhitespaceOptions::from_settings(&LanguageSettings::for_buffer(
+        buffer.read(cx),
+        cx,
+    ));
+    Some(EditorconfigConflict {
+        buffer,
+        rows,
+        configured,
+        tab_width: whitespace.tab_width.get(),
+        editorconfig: source.label(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::IndentKind;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_conflict_with_editorconfig_on_save(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                ".editorconfig": "[*.rs]\nindent_style = space\nindent_size = 4\n",
+                "a.rs": "fn f() {\n  g();\n    h();\n  i();\n}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+        });
+        cx.run_until_parked();
+        let set_check = |enabled: bool, cx: &mut gpui::VisualTestContext| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings(cx, |content| {
+                    content
+                        .indent_size_selector
+                        .get_or_insert_default()
+                        .check_editorconfig_on_save = Some(enabled);
+                })
+            })
+        };
+
+        // Off by default.
+        assert!(
+            editor
+                .update(cx, |editor, cx| conflict(editor, cx))
+                .is_none()
+        );
+
+        set_check(true, cx);
+        let found = editor
+            .update(cx, |editor, cx| conflict(editor, cx))
+            .unwrap();
+        assert_eq!(found.rows, vec![1, 3]);
+        assert_eq!(
+            (found.configured.len, found.configured.kind),
+            (4, IndentKind::Space)
+        );
+        assert_eq!(found.editorconfig, SharedString::from(".editorconfig"));
+
+        // Fixing the conflicting lines leaves nothing to ask about.
+        let edits = line_fix_edits(
+            &buffer.read_with(cx, |buffer, _| buffer.snapshot()),
+            found.rows,
+            found.tab_width,
+            found.configured,
+        );
+        cx.update(|_, cx| fix_on_save::apply_conversion(&buffer, edits, cx));
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "fn f() {\n    g();\n    h();\n    i();\n}\n"
+        );
+        assert!(
+            editor
+                .update(cx, |editor, cx| conflict(editor, cx))
+                .is_none()
+        );
+    }
+}
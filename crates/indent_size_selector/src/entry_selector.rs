@@ -0,0 +1,399 @@
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, WeakEntity};
+use language::language_settings::AllLanguageSettings;
+use picker::{Picker, PickerDelegate};
+use project::{Project, ProjectPath};
+use settings::{IndentSizeSelectorOverrideScope, LocalSettingsPath, Settings as _, WorktreeId};
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::{ResultExt as _, rel_path::RelPath};
+use workspace::{ModalView, Workspace};
+
+use crate::{
+    IndentSizeSelectorSettings, apply_scope::directory_override_content, file_override_content,
+    fix_on_save, history, indentation_properties, modal_width, override_batch::OverrideBatch,
+    persistence, recent_sizes,
+};
+
+/// A file or directory of a worktree to set the indentation of, such as an entry selected in
+/// the project panel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndentationTarget {
+    pub worktree_id: WorktreeId,
+    pub path: Arc<RelPath>,
+    pub is_dir: bool,
+}
+
+/// Opens a selector that writes the chosen indentation as an `.editorconfig` override for each
+/// of `targets`: for everything under each directory, and for each file. Only the host of a
+/// shared project can.
+pub fn toggle_for_entries(
+    workspace: &mut Workspace,
+    targets: Vec<IndentationTarget>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    // Overrides written by a guest wouldn't reach the host or the other guests.
+    if targets.is_empty() || project.read(cx).is_via_collab() {
+        return;
+    }
+    workspace.toggle_modal(window, cx, move |window, cx| {
+        let delegate =
+            EntryIndentSizeSelectorDelegate::new(cx.entity().downgrade(), project, targets, cx);
+        EntryIndentSizeSelector {
+            picker: cx.new(|cx| Picker::uniform_list(delegate, window, cx)),
+        }
+    });
+}
+
+struct EntryIndentSizeSelector {
+    picker: Entity<Picker<EntryIndentSizeSelectorDelegate>>,
+}
+
+impl Render for EntryIndentSizeSelector {
+    fn render(&mut self, window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("EntryIndentSizeSelector")
+            .w(modal_width(window))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for EntryIndentSizeSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for EntryIndentSizeSelector {}
+impl ModalView for EntryIndentSizeSelector {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EntryIndentChoice {
+    Spaces(u32),
+    /// Tabs as wide as the default tab size.
+    Tabs,
+}
+
+impl EntryIndentChoice {
+    fn label(&self) -> String {
+        match self {
+            Self::Spaces(size) => format!("{size} spaces"),
+            Self::Tabs => "Tabs".to_string(),
+        }
+    }
+}
+
+struct EntryIndentSizeSelectorDelegate {
+    entry_indent_size_selector: WeakEntity<EntryIndentSizeSelector>,
+    project: Entity<Project>,
+    targets: Vec<IndentationTarget>,
+    choices: Vec<EntryIndentChoice>,
+    candidates: Arc<[StringMatchCandidate]>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl EntryIndentSizeSelectorDelegate {
+    fn new(
+        entry_indent_size_selector: WeakEntity<EntryIndentSizeSelector>,
+        project: Entity<Project>,
+        targets: Vec<IndentationTarget>,
+        cx: &App,
+    ) -> Self {
+        let settings = IndentSizeSelectorSettings::get_global(cx);
+        let sizes = settings
+            .sizes
+            .iter()
+            .copied()
+            .filter(|size| *size > 0)
+            .collect::<Vec<_>>();
+        let mut choices = recent_sizes::prioritize_recent(&sizes, &recent_sizes::recent_sizes(cx))
+            .into_iter()
+            .map(EntryIndentChoice::Spaces)
+            .collect::<Vec<_>>();
+        if settings.show_hard_tabs {
+            choices.push(EntryIndentChoice::Tabs);
+        }
+        let candidates = choices
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, choice)| StringMatchCandidate::new(candidate_id, &choice.label()))
+            .collect();
+
+        Self {
+            entry_indent_size_selector,
+            project,
+            targets,
+            choices,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    /// Writes the overrides for every target together, so that either all of them are written or
+    /// none is. The choice is also remembered for the files that are open.
+    fn apply(&self, choice: EntryIndentChoice, cx: &mut App) {
+        let (size, hard_tabs) = match choice {
+            EntryIndentChoice::Spaces(size) => (size, false),
+            EntryIndentChoice::Tabs => (
+                AllLanguageSettings::get_global(cx).defaults.tab_size.get(),
+                true,
+            ),
+        };
+        let properties = indentation_properties(size, hard_tabs, None);
+        let settings = IndentSizeSelectorSettings::get_global(cx);
+        let mut batch = OverrideBatch::default();
+        let mut open_buffers = Vec::new();
+        for target in &self.targets {
+            let override_path = LocalSettingsPath::InWorktree(target.path.clone());
+            let content = if target.is_dir {
+                directory_override_content(target.worktree_id, &override_path, &properties, cx)
+            } else {
+                let project_path = ProjectPath {
+                    worktree_id: target.worktree_id,
+                    path: target.path.clone(),
+                };
+                if let Some(buffer) = self.project.read(cx).get_open_buffer(&project_path, cx) {
+                    let language_name = buffer.read(cx).language().map(|language| language.name());
+                    if !settings.allows_scope(
+                        language_name.as_ref(),
+                        IndentSizeSelectorOverrideScope::File,
+                    ) {
+                        continue;
+                    }
+                    open_buffers.push(buffer);
+                }
+                file_override_content(target.worktree_id, &target.path, &properties, cx)
+            };
+            batch.set(target.worktree_id, override_path, Some(content));
+        }
+        if batch.commit(cx).log_err().is_none() {
+            return;
+        }
+
+        for buffer in &open_buffers {
+            history::set_indentation_override(buffer, None, cx);
+            persistence::remember_choice(buffer, size, hard_tabs, None, cx);
+            if fix_on_save::converts_on_confirm(false, cx) {
+                fix_on_save::convert_existing_indentation(buffer, &[], size, hard_tabs, cx);
+            }
+        }
+        if let EntryIndentChoice::Spaces(size) = choice {
+            recent_sizes::record_size(size, cx);
+        }
+    }
+}
+
+impl PickerDelegate for EntryIndentSizeSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match self.targets.as_slice() {
+            [target] if target.is_dir => "Set the indentation of this folder…".into(),
+            [_] => "Set the indentation of this file…".into(),
+            targets => format!("Set the indentation of {} entries…", targets.len()).into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let choice = self.choices[mat.candidate_id];
+            history::transact(cx, |cx| self.apply(choice, cx));
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.entry_indent_size_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string.clone(),
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                )),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::language_settings::LanguageSettings;
+    use project::FakeFs;
+    use serde_json::json;
+    use util::{path, rel_path::rel_path};
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_apply_to_entries(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "src": { "a.rs": "fn a() {}\n" },
+                "b.rs": "fn b() {}\n",
+                "c.rs": "fn c() {}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let open = async |path: &str, cx: &mut TestAppContext| {
+            project
+                .update(cx, |project, cx| project.open_local_buffer(path, cx))
+                .await
+                .unwrap()
+        };
+        let src_file = open(path!("/dir/src/a.rs"), cx).await;
+        let file = open(path!("/dir/b.rs"), cx).await;
+        let other_file = open(path!("/dir/c.rs"), cx).await;
+        let targets = vec![
+            IndentationTarget {
+                worktree_id,
+                path: rel_path("src").into(),
+                is_dir: true,
+            },
+            IndentationTarget {
+                worktree_id,
+                path: rel_path("b.rs").into(),
+                is_dir: false,
+            },
+        ];
+        let delegate = |targets: Vec<IndentationTarget>, cx: &mut TestAppContext| {
+            cx.update(|cx| {
+                EntryIndentSizeSelectorDelegate::new(
+                    WeakEntity::new_invalid(),
+                    project.clone(),
+                    targets,
+                    cx,
+                )
+            })
+        };
+
+        let selector = delegate(targets.clone(), cx);
+        assert_eq!(
+            selector.choices,
+            vec![
+                EntryIndentChoice::Spaces(2),
+                EntryIndentChoice::Spaces(4),
+                EntryIndentChoice::Spaces(8),
+                EntryIndentChoice::Tabs,
+            ]
+        );
+        let window = cx.add_empty_window();
+        assert_eq!(
+            window
+                .update(|window, cx| selector.placeholder_text(window, cx))
+                .as_ref(),
+            "Set the indentation of 2 entries…"
+        );
+
+        cx.update(|cx| selector.apply(EntryIndentChoice::Spaces(8), cx));
+        cx.run_until_parked();
+        let tab_size = |buffer: &Entity<language::Buffer>, cx: &mut TestAppContext| {
+            cx.update(|cx| {
+                LanguageSettings::for_buffer(buffer.read(cx), cx)
+                    .tab_size
+                    .get()
+            })
+        };
+        assert_eq!(tab_size(&src_file, cx), 8);
+        assert_eq!(tab_size(&file, cx), 8);
+        assert_eq!(tab_size(&other_file, cx), 4);
+
+        // The size just chosen is offered first.
+        let selector = delegate(targets[1..].to_vec(), cx);
+        assert_eq!(selector.choices[0], EntryIndentChoice::Spaces(8));
+    }
+}
@@ -0,0 +1,484 @@
+use ec4rs::{
+    PropertiesSource as _,
+    property::{IndentSize as EditorconfigIndentSize, IndentStyle, TabWidth},
+};
+use editor::Editor;
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity};
+use paths::EDITORCONFIG_NAME;
+use picker::{Picker, PickerDelegate};
+use settings::{
+    EditorconfigProperties, LanguageSettingsContent, LocalSettingsPath, ProjectSettingsContent,
+    SettingsStore,
+};
+use std::{path::PathBuf, sync::Arc};
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use util::paths::PathStyle;
+use workspace::{ModalView, OpenOptions, OpenVisible, Workspace};
+
+use crate::{
+    ExplainIndentation, apply_scope::OVERRIDE_MARKER, auto_detect, cursor_position, file_buffer,
+    indent_source::editorconfig_label, indentation_provider, modal_width,
+};
+
+/// A layer of the settings that may set the indentation of a file.
+#[derive(Clone, Debug, PartialEq)]
+struct IndentationLayer {
+    name: SharedString,
+    tab_size: Option<u32>,
+    hard_tabs: Option<bool>,
+    /// The file the layer is read from, which picking the layer opens.
+    path: Option<PathBuf>,
+}
+
+impl IndentationLayer {
+    fn from_language_settings(
+        name: impl Into<SharedString>,
+        settings: &LanguageSettingsContent,
+        path: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            tab_size: settings.tab_size.map(|tab_size| tab_size.get()),
+            hard_tabs: settings.hard_tabs,
+            path,
+        }
+    }
+
+    fn sets_indentation(&self) -> bool {
+        self.tab_size.is_some() || self.hard_tabs.is_some()
+    }
+
+    fn value_label(&self) -> String {
+        match (self.tab_size, self.hard_tabs) {
+            (Some(size), Some(true)) => format!("tabs, {size} columns wide"),
+            (Some(size), Some(false)) => format!("{size} spaces"),
+            (Some(size), None) => format!("size {size}"),
+            (None, Some(true)) => "tabs".to_string(),
+            (None, Some(false)) => "spaces".to_string(),
+            (None, None) => "not set".to_string(),
+        }
+    }
+}
+
+/// The layers that may set the indentation of the file open in `editor`, from the highest
+/// precedence to the lowest: an override for its buffer, its modeline, each `.editorconfig`
+/// from its directory up, the project's settings, the user's settings and Zed's defaults.
+/// Layers only appear for the file's language when they set its indentation.
+fn indentation_layers(editor: &Editor, cx: &App) -> Vec<IndentationLayer> {
+    let language_name = editor
+        .language_at(cursor_position(editor), cx)
+        .map(|language| language.name().to_string());
+    let store = cx.global::<SettingsStore>();
+    let mut layers = Vec::new();
+
+    let buffer = file_buffer(editor, cx);
+    if let Some(buffer) = &buffer {
+        let buffer_ref = buffer.read(cx);
+        if let Some(indentation_override) = buffer_ref.indentation_override() {
+            let name = if let Some(name) = indentation_provider::provider_of_override(buffer, cx) {
+                format!("Override from {name}")
+            } else if auto_detect::has_detected_override(buffer, cx) {
+                "Indentation detected when the file was opened".to_string()
+            } else {
+                "Override for this buffer until it's closed".to_string()
+            };
+            layers.push(IndentationLayer {
+                name: name.into(),
+                tab_size: Some(indentation_override.tab_size.get()),
+                hard_tabs: Some(indentation_override.hard_tabs),
+                path: None,
+            });
+        }
+        if let Some(modeline) = buffer_ref.modeline() {
+            let layer = IndentationLayer {
+                name: "Modeline in the file".into(),
+                tab_size: modeline
+                    .indent_size
+                    .or(modeline.tab_size)
+                    .map(|size| size.get()),
+                hard_tabs: modeline.hard_tabs,
+                path: None,
+            };
+            if layer.sets_indentation() {
+                layers.push(layer);
+            }
+        }
+    }
+
+    if let Some(file) = editor.file_at(cursor_position(editor), cx)
+        && let Some(worktree) = editor
+            .project()
+            .and_then(|project| project.read(cx).worktree_for_id(file.worktree_id(cx), cx))
+    {
+        let worktree_id = file.worktree_id(cx);
+        let worktree_root = worktree.read(cx).abs_path();
+        let path = file.path();
+
+        let mut editorconfigs = store
+            .editorconfig_store
+            .read(cx)
+            .local_editorconfig_settings(worktree_id)
+            .filter_map(|(config_path, content, config)| {
+                let (depth, config_file) = match &config_path {
+                    LocalSettingsPath::InWorktree(directory) => {
+                        if !path.starts_with(directory) {
+                            return None;
+                        }
+                        // An override for the file itself only exists in memory.
+                        let config_file = (directory != path).then(|| {
+                            worktree_root
+                                .join(directory.as_std_path())
+                                .join(EDITORCONFIG_NAME)
+                        });
+                        (Some(directory.ancestors().count()), config_file)
+                    }
+                    LocalSettingsPath::OutsideWorktree(directory) => {
+                        (None, Some(directory.join(EDITORCONFIG_NAME)))
+                    }
+                };
+                let mut properties = EditorconfigProperties::new();
+                for section in &config?.sections {
+                    section
+                        .apply_to(&mut properties, path.as_std_path())
+                        .log_err()?;
+                }
+                let is_override = config_path == LocalSettingsPath::InWorktree(path.clone())
+                    || content.contains(OVERRIDE_MARKER);
+                let label = editorconfig_label(&config_path);
+                let name = if is_override {
+                    format!("{label}, with an override from the selector")
+                } else {
+                    label.to_string()
+                };
+                let tab_size = match properties.get::<EditorconfigIndentSize>() {
+                    Ok(EditorconfigIndentSize::Value(size)) => Some(size as u32),
+                    _ => match properties.get::<TabWidth>() {
+                        Ok(TabWidth::Value(width)) => Some(width as u32),
+                        Err(_) => None,
+                    },
+                };
+                let layer = IndentationLayer {
+                    name: name.into(),
+                    tab_size,
+                    hard_tabs: properties
+                        .get::<IndentStyle>()
+                        .ok()
+                        .map(|style| style == IndentStyle::Tabs),
+                    path: config_file,
+                };
+                layer.sets_indentation().then_some((depth, layer))
+            })
+            .collect::<Vec<_>>();
+        editorconfigs.sort_by_key(|(depth, _)| std::cmp::Reverse(*depth));
+        layers.extend(editorconfigs.into_iter().map(|(_, layer)| layer));
+
+        let mut local_settings = store
+            .local_settings(worktree_id)
+            .filter(|(settings_path, _)| path.starts_with(settings_path))
+            .collect::<Vec<_>>();
+        local_settings
+            .sort_by_key(|(settings_path, _)| std::cmp::Reverse(settings_path.ancestors().count()));
+        for (settings_path, content) in local_settings {
+            let settings_file = settings_path.join(paths::local_settings_file_relative_path());
+            push_settings_layers(
+                &mut layers,
+                &format!(
+                    "Project settings in {}",
+                    settings_file.display(PathStyle::local())
+                ),
+                content,
+                language_name.as_deref(),
+                Some(worktree_root.join(settings_file.as_std_path())),
+            );
+        }
+    }
+
+    if let Some(user_settings) = store.raw_user_settings() {
+        push_settings_layers(
+            &mut layers,
+            "User settings",
+            &user_settings.content.project,
+            language_name.as_deref(),
+            Some(paths::settings_file().clone()),
+        );
+    }
+    push_settings_layers(
+        &mut layers,
+        "Zed's defaults",
+        &store.raw_default_settings().project,
+        language_name.as_deref(),
+        None,
+    );
+    layers
+}
+
+/// Adds the layers that `content`, read from `path`, has for files of `language_name` and for
+/// every language.
+fn push_settings_layers(
+    layers: &mut Vec<IndentationLayer>,
+    name: &str,
+    content: &ProjectSettingsContent,
+    language_name: Option<&str>,
+    path: Option<PathBuf>,
+) {
+    if let Some(language_name) = language_name
+        && let Some(settings) = content.all_languages.languages.0.get(language_name)
+    {
+        let layer = IndentationLayer::from_language_settings(
+            format!("{name} for {language_name}"),
+            settings,
+            path.clone(),
+        );
+        if layer.sets_indentation() {
+            layers.push(layer);
+        }
+    }
+    layers.push(IndentationLayer::from_language_settings(
+        name.to_string(),
+        &content.all_languages.defaults,
+        path,
+    ));
+}
+
+/// Lists the layers that may set the indentation of a file, marking the one in effect, and
+/// opens the file of the one picked.
+pub(crate) struct IndentationExplanation {
+    picker: Entity<Picker<IndentationExplanationDelegate>>,
+}
+
+impl IndentationExplanation {
+    fn toggle(editor: &Entity<Editor>, window: &mut Window, cx: &mut App) {
+        let Some(workspace) = editor.read(cx).workspace() else {
+            return;
+        };
+        let layers = indentation_layers(editor.read(cx), cx);
+        let winner = layers.iter().position(IndentationLayer::sets_indentation);
+        let weak_workspace = workspace.downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                let delegate = IndentationExplanationDelegate {
+                    indentation_explanation: cx.entity().downgrade(),
+                    workspace: weak_workspace,
+                    matches: (0..layers.len()).collect(),
+                    layers,
+                    winner,
+                    selected_index: 0,
+                };
+                Self {
+                    picker: cx.new(|cx| Picker::uniform_list(delegate, window, cx)),
+                }
+            });
+        });
+    }
+}
+
+impl Render for IndentationExplanation {
+    fn render(&mut self, window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("IndentationExplanation")
+            .w(modal_width(window))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for IndentationExplanation {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for IndentationExplanation {}
+impl ModalView for IndentationExplanation {}
+
+struct IndentationExplanationDelegate {
+    indentation_explanation: WeakEntity<IndentationExplanation>,
+    workspace: WeakEntity<Workspace>,
+    layers: Vec<IndentationLayer>,
+    /// The index of the layer whose indentation is in effect.
+    winner: Option<usize>,
+    /// The indices of the layers matching the query.
+    matches: Vec<usize>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for IndentationExplanationDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Open the settings that set the indentation…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(path) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|ix| self.layers[*ix].path.clone())
+        {
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace
+                        .open_abs_path(
+                            path,
+                            OpenOptions {
+                                visible: Some(OpenVisible::None),
+                                ..Default::default()
+                            },
+                            window,
+                            cx,
+                        )
+                        .detach_and_log_err(cx);
+                })
+                .ok();
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.indentation_explanation
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let query = query.trim().to_lowercase();
+        self.matches = self
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.name.to_lowercase().contains(&query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = 0;
+        cx.notify();
+        Task::ready(())
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let layer_ix = *self.matches.get(ix)?;
+        let layer = &self.layers[layer_ix];
+        let is_winner = self.winner == Some(layer_ix);
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(layer.name.clone()))
+                        .child(
+                            Label::new(layer.value_label())
+                                .size(LabelSize::Small)
+                                .color(if layer.sets_indentation() {
+                                    Color::Muted
+                                } else {
+                                    Color::Disabled
+                                }),
+                        ),
+                )
+                .when(is_winner, |item| {
+                    item.end_slot(
+                        Label::new("In effect")
+                            .size(LabelSize::Small)
+                            .color(Color::Accent),
+                    )
+                }),
+        )
+    }
+}
+
+/// Handles explaining the indentation of the file open in `editor`.
+pub(crate) fn register(editor: &mut Editor, _: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action(move |_: &ExplainIndentation, window, cx| {
+            if let Some(editor) = editor_handle.upgrade() {
+                IndentationExplanation::toggle(&editor, window, cx);
+            }
+        })
+        .detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn test_push_settings_layers() {
+        let content = ProjectSettingsContent {
+            all_languages: settings::AllLanguageSettingsContent {
+                defaults: LanguageSettingsContent {
+                    tab_size: NonZeroU32::new(4),
+                    ..Default::default()
+                },
+                languages: settings::LanguageToSettingsMap(
+                    [
+                        (
+                            "Go".to_string(),
+                            LanguageSettingsContent {
+                                hard_tabs: Some(true),
+                                ..Default::default()
+                            },
+                        ),
+                        ("Rust".to_string(), LanguageSettingsContent::default()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let layer_values = |language_name| {
+            let mut layers = Vec::new();
+            push_settings_layers(&mut layers, "User settings", &content, language_name, None);
+            layers
+                .into_iter()
+                .map(|layer| (layer.name.to_string(), layer.value_label()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            layer_values(Some("Go")),
+            [
+                ("User settings for Go".to_string(), "tabs".to_string()),
+                ("User settings".to_string(), "4 spaces".to_string()),
+            ]
+        );
+        // A language's settings that leave the indentation alone aren't a layer of it.
+        assert_eq!(
+            layer_values(Some("Rust")),
+            [("User settings".to_string(), "4 spaces".to_string())]
+        );
+    }
+}
@@ -0,0 +1,1029 @@
+use collections::{HashMap, HashSet};
+use editor::Editor;
+use futures::{FutureExt as _, future::Shared};
+use gpui::{App, AppContext as _, Context, Entity, EntityId, Global, Subscription, Task, Window};
+use language::{
+    Buffer, BufferSnapshot, DetectedIndent, Detection, ExoticWhitespace, IndentKind, IndentSize,
+    IndentViolation, LanguageName, Point, ToOffset, indent_violations, is_indent_char,
+    language_settings::{AllLanguageSettings, LanguageSettings, ResolvedWhitespaceOptions},
+};
+use project::{Project, ProjectPath};
+use settings::{Settings as _, SettingsLocation};
+use std::ops::Range;
+use workspace::{
+    Save, SaveIntent, Toast, Workspace, WorkspaceId,
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
+};
+
+use crate::{
+    IndentSizeSelectorSettings,
+    detection::{buffer_detection, detect_indentation_in_text, expected_indent, to_indent_size},
+    git_changes, history,
+    language_conventions::{TabRequirement, tab_requirement},
+    metrics::{self, Span},
+    persistence, read_indent_size, structure_check,
+};
+
+/// The files of each project that use an indentation other than their configured one.
+#[derive(Default)]
+struct RemainingFiles(HashMap<EntityId, ProjectScan>);
+
+impl Global for RemainingFiles {}
+
+struct ProjectScan {
+    /// `None` until the first scan of the project's files finishes.
+    files: Option<HashSet<ProjectPath>>,
+    scan: Shared<Task<()>>,
+}
+
+/// Re-indents the file open in `editor` to its configured indentation right before it's saved,
+/// when fixing files on save is enabled, or converting them on save is for its language.
+pub(crate) fn watch(editor: &mut Editor, _: Option<&mut Window>, cx: &mut Context<Editor>) {
+    if !editor.buffer().read(cx).is_singleton() {
+        return;
+    }
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action(move |action: &Save, _, cx| {
+            // The workspace performs the save once the buffer is fixed.
+            cx.propagate();
+            if action.save_intent == Some(SaveIntent::SaveWithoutFormat) {
+                return;
+            }
+            editor_handle
+                .update(cx, |editor, cx| fix_indentation(editor, cx))
+                .ok();
+        })
+        .detach();
+}
+
+fn fix_indentation(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    if !converts_on_save(&buffer, cx) {
+        return;
+    }
+    let Some(configured) = read_indent_size(editor, cx) else {
+        return;
+    };
+    let configured = configured.indent_size;
+    let settings = IndentSizeSelectorSettings::get_global(cx);
+    let normalize_exotic_whitespace = settings.normalize_exotic_whitespace;
+    // The lines git already has are left as they are, so that the fix doesn't bury the file's
+    // changes under whitespace ones.
+    let changed_rows = if settings.preserve_unmodified_lines {
+        editor
+            .project()
+            .and_then(|project| git_changes::changed_rows(&buffer, project, cx))
+    } else {
+        None
+    };
+    let (rows, detection) = match changed_rows {
+        Some(rows) if rows.is_empty() => (rows, None),
+        Some(rows) => {
+            let detection = detection_in_rows(&buffer, &rows, cx);
+            (rows, Some(detection))
+        }
+        None => (Vec::new(), Some(buffer_detection(&buffer, cx))),
+    };
+    if let Some(detection) = detection
+        && !indent_violations(
+            &detection,
+            expected_indent(configured),
+            normalize_exotic_whitespace,
+        )
+        .is_empty()
+    {
+        metrics::measure(Span::Conversion, cx, |cx| {
+            let snapshot = buffer.read(cx).snapshot();
+            let edits = conversion_edits(
+                &snapshot,
+                &rows,
+                to_indent_size(detection).unwrap_or(configured),
+                configured,
+                cx,
+            );
+            match structure_check::check_conversion(&snapshot, &edits) {
+                Ok(()) => buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx)),
+                Err(change) => structure_check::notify_unconverted(&buffer, &change, cx),
+            }
+        });
+    }
+
+    let (Some(project), Some(file)) = (editor.project(), buffer.read(cx).file()) else {
+        return;
+    };
+    let project_id = project.entity_id();
+    let project_path = ProjectPath {
+        worktree_id: file.worktree_id(cx),
+        path: file.path().clone(),
+    };
+    if cx.has_global::<RemainingFiles>()
+        && let Some(files) = cx
+            .global_mut::<RemainingFiles>()
+            .0
+            .get_mut(&project_id)
+            .and_then(|scan| scan.files.as_mut())
+    {
+        files.remove(&project_path);
+    }
+}
+
+/// Whether saving `buffer` re-indents it: when fixing every file on save is enabled, or when
+/// converting indentation on save is enabled for its language and location.
+pub(crate) fn converts_on_save(buffer: &Entity<Buffer>, cx: &App) -> bool {
+    IndentSizeSelectorSettings::get_global(cx).fix_on_save
+        || LanguageSettings::for_buffer(buffer.read(cx), cx).convert_indentation_on_save
+}
+
+/// Whether confirming an indentation also converts the existing lines it's applied to: always
+/// when the conversion is `requested`, as with a secondary confirm, and otherwise when the
+/// settings ask for it.
+pub(crate) fn converts_on_confirm(requested: bool, cx: &App) -> bool {
+    requested || IndentSizeSelectorSettings::get_global(cx).convert_existing_indentation
+}
+
+/// Re-indents the existing lines of `buffer` from the indentation detected in them to `size`
+/// (as tabs when `hard_tabs` is set) after it was applied, only in `rows` unless that's empty.
+/// The lines are converted in one edit, so that undoing it restores them at once. Read-only
+/// buffers are left alone.
+pub(crate) fn convert_existing_indentation(
+    buffer: &Entity<Buffer>,
+    rows: &[Range<u32>],
+    size: u32,
+    hard_tabs: bool,
+    cx: &mut App,
+) {
+    if size == 0 || buffer.read(cx).read_only() {
+        return;
+    }
+    let Some(from) = detected_in_rows(buffer, rows, cx) else {
+        return;
+    };
+    let to = IndentSize {
+        len: size,
+        kind: if hard_tabs {
+            IndentKind::Tab
+        } else {
+            IndentKind::Space
+        },
+    };
+    convert_buffer(buffer, rows, from, to, cx);
+}
+
+/// Re-indents the lines of `buffer` from `from` to `to` with [`conversion_edits`], only in
+/// `rows` unless that's empty. They're converted in one edit that's kept apart from the edits
+/// before it, so that undoing it reverts just the conversion. Buffers whose structure the edits
+/// would change are left as they are, telling which lines.
+pub(crate) fn convert_buffer(
+    buffer: &Entity<Buffer>,
+    rows: &[Range<u32>],
+    from: IndentSize,
+    to: IndentSize,
+    cx: &mut App,
+) {
+    metrics::measure(Span::Conversion, cx, |cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        let edits = conversion_edits(&snapshot, rows, from, to, cx);
+        match structure_check::check_conversion(&snapshot, &edits) {
+            Ok(()) => apply_conversion(buffer, edits, cx),
+            Err(change) => structure_check::notify_unconverted(buffer, &change, cx),
+        }
+    });
+}
+
+/// Applies the `edits` re-indenting `buffer` in one edit that's kept apart from the edits
+/// before it, so that undoing it reverts just the conversion. Reverting the selector's change it
+/// was made in reverts it too.
+pub(crate) fn apply_conversion<S: ToOffset>(
+    buffer: &Entity<Buffer>,
+    edits: Vec<(Range<S>, String)>,
+    cx: &mut App,
+) {
+    if edits.is_empty() {
+        return;
+    }
+    let transaction_id = buffer.update(cx, |buffer, cx| {
+        buffer.finalize_last_transaction();
+        buffer.edit(edits, None, cx);
+        buffer
+            .finalize_last_transaction()
+            .map(|transaction| transaction.id)
+    });
+    if let Some(transaction_id) = transaction_id {
+        history::record_conversion(buffer, transaction_id, cx);
+    }
+}
+
+/// The edits re-indenting the lines of `snapshot` from `from` to `to`, only in `rows` unless
+/// that's empty, keeping the spaces that align them when the settings ask for it.
+pub(crate) fn conversion_edits(
+    snapshot: &BufferSnapshot,
+    rows: &[Range<u32>],
+    from: IndentSize,
+    to: IndentSize,
+    cx: &App,
+) -> Vec<(Range<Point>, String)> {
+    ConversionOptions::from_settings(cx).edits(snapshot, rows, from, to)
+}
+
+/// How the settings ask for indentation to be converted, to compute conversions away from them,
+/// such as on the background executor.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ConversionOptions {
+    normalize_exotic_whitespace: bool,
+    align_with_spaces: bool,
+}
+
+impl ConversionOptions {
+    pub(crate) fn from_settings(cx: &App) -> Self {
+        let settings = IndentSizeSelectorSettings::get_global(cx);
+        Self {
+            normalize_exotic_whitespace: settings.normalize_exotic_whitespace,
+            align_with_spaces: settings.align_with_spaces,
+        }
+    }
+
+    /// Like [`conversion_edits`], with these options.
+    pub(crate) fn edits(
+        &self,
+        snapshot: &BufferSnapshot,
+        rows: &[Range<u32>],
+        from: IndentSize,
+        to: IndentSize,
+    ) -> Vec<(Range<Point>, String)> {
+        let edits_in_rows = |rows: Range<u32>| {
+            if self.align_with_spaces {
+                reindent_edits_keeping_alignment(
+                    snapshot,
+                    rows,
+                    from,
+                    to,
+                    self.normalize_exotic_whitespace,
+                )
+            } else {
+                reindent_edits_in_rows(snapshot, rows, from, to, self.normalize_exotic_whitespace)
+            }
+        };
+        if rows.is_empty() {
+            edits_in_rows(0..snapshot.row_count())
+        } else {
+            rows.iter()
+                .flat_map(|rows| edits_in_rows(rows.clone()))
+                .collect()
+        }
+    }
+}
+
+/// The indentation detected in `rows` of `buffer`, such as a block pasted from elsewhere, or in
+/// all of it when `rows` is empty.
+pub(crate) fn detected_in_rows(
+    buffer: &Entity<Buffer>,
+    rows: &[Range<u32>],
+    cx: &mut App,
+) -> Option<IndentSize> {
+    if rows.is_empty() {
+        return to_indent_size(buffer_detection(buffer, cx));
+    }
+    to_indent_size(detection_in_rows(buffer, rows, cx))
+}
+
+/// What detecting the indentation of just `rows` of `buffer` finds.
+fn detection_in_rows(buffer: &Entity<Buffer>, rows: &[Range<u32>], cx: &App) -> Detection {
+    let snapshot = buffer.read(cx).snapshot();
+    let mut text = String::new();
+    for rows in rows {
+        let end = Point::new(rows.end, 0).min(snapshot.max_point());
+        text.extend(snapshot.text_for_range(Point::new(rows.start, 0)..end));
+        text.push('\n');
+    }
+    detect_indentation_in_text(&text)
+}
+
+/// The rows of each non-empty selection in `editor`, when it edits a single buffer. A selection
+/// ending at the start of a line doesn't include that line.
+pub(crate) fn selected_rows(editor: &Editor, cx: &mut App) -> Vec<Range<u32>> {
+    if !editor.buffer().read(cx).is_singleton() {
+        return Vec::new();
+    }
+    let display_snapshot = editor.display_snapshot(cx);
+    let rows = editor
+        .selections
+        .all_adjusted(&display_snapshot)
+        .into_iter()
+        .filter(|selection| !selection.is_empty())
+        .map(|selection| {
+            let (start, end) = (selection.start, selection.end);
+            let end_row = if end.column == 0 && end.row > start.row {
+                end.row
+            } else {
+                end.row + 1
+            };
+            start.row..end_row
+        })
+        .collect::<Vec<_>>();
+    merge_rows(rows)
+}
+
+/// Sorts `rows`, merging the ranges that overlap or touch, since overlapping ranges would
+/// re-indent their rows twice.
+pub(crate) fn merge_rows(mut rows: Vec<Range<u32>>) -> Vec<Range<u32>> {
+    rows.sort_by_key(|rows| rows.start);
+    rows.dedup_by(|next, previous| {
+        if next.start <= previous.end {
+            previous.end = previous.end.max(next.end);
+            true
+        } else {
+            false
+        }
+    });
+    rows
+}
+
+/// The edits re-indenting each line of `snapshot` from `from` to `to`, keeping its depth. A tab
+/// is one level of indentation whatever its width. Lines continuing a multi-line literal, such
+/// as a Python docstring, a YAML block scalar or a heredoc, are left alone, since their leading
+/// whitespace is part of its content. With
+/// `normalize_exotic_whitespace`, [`ExoticWhitespace`] in the indentation counts as, and is
+/// replaced with, regular spaces.
+pub fn reindent_edits(
+    snapshot: &BufferSnapshot,
+    from: IndentSize,
+    to: IndentSize,
+    normalize_exotic_whitespace: bool,
+) -> Vec<(Range<Point>, String)> {
+    reindent_edits_in_rows(
+        snapshot,
+        0..snapshot.row_count(),
+        from,
+        to,
+        normalize_exotic_whitespace,
+    )
+}
+
+/// Like [`reindent_edits`], for the lines in `rows` alone, such as those of a selection.
+pub fn reindent_edits_in_rows(
+    snapshot: &BufferSnapshot,
+    rows: Range<u32>,
+    from: IndentSize,
+    to: IndentSize,
+    normalize_exotic_whitespace: bool,
+) -> Vec<(Range<Point>, String)> {
+    let mut edits = Vec::new();
+    for row in rows.start..rows.end.min(snapshot.row_count()) {
+        if starts_inside_literal(snapshot, row)
+            || (to.kind == IndentKind::Space && requires_tab(snapshot, row))
+        {
+            continue;
+        }
+        let Some((indent, kind, normalized)) =
+            leading_indent(snapshot, row, normalize_exotic_whitespace)
+        else {
+            continue;
+        };
+        let new_indent = if kind == from.kind {
+            let len = normalized.chars().count() as u32;
+            let depth = len / level_len(from);
+            let remainder = len % level_len(from);
+            std::iter::repeat_n(to.char(), (depth * level_len(to)) as usize)
+                .chain(std::iter::repeat_n(' ', remainder as usize))
+                .collect::<String>()
+        } else {
+            normalized
+        };
+        if new_indent != indent {
+            edits.push((
+                Point::new(row, 0)..Point::new(row, indent.len() as u32),
+                new_indent,
+            ));
+        }
+    }
+    edits
+}
+
+/// Like [`reindent_edits_in_rows`], following the "smart tabs" convention of indenting with tabs
+/// and aligning with spaces: the columns that align a continuation line past the indentation of
+/// the line it continues stay spaces, whatever the indentation is converted to. When indented
+/// with tabs, the spaces after a line's tabs align it. When indented with spaces, a line
+/// indented by a partial level, or by more than one level past the last line that was indented
+/// rather than aligned, is taken to be aligned.
+pub fn reindent_edits_keeping_alignment(
+    snapshot: &BufferSnapshot,
+    rows: Range<u32>,
+    from: IndentSize,
+    to: IndentSize,
+    normalize_exotic_whitespace: bool,
+) -> Vec<(Range<Point>, String)> {
+    let level = level_len(from);
+    // The column of the last line that was indented rather than aligned, starting from the
+    // line above `rows`.
+    let mut anchor = (0..rows.start.min(snapshot.row_count()))
+        .rev()
+        .find(|row| !snapshot.is_line_blank(*row))
+        .and_then(|row| leading_indent(snapshot, row, normalize_exotic_whitespace))
+        .filter(|(_, kind, _)| *kind == from.kind)
+        .map_or(0, |(_, _, normalized)| {
+            normalized.chars().count() as u32 / level * level
+        });
+    let mut edits = Vec::new();
+    for row in rows.start..rows.end.min(snapshot.row_count()) {
+        if starts_inside_literal(snapshot, row)
+            || (to.kind == IndentKind::Space && requires_tab(snapshot, row))
+        {
+            continue;
+        }
+        let Some((indent, kind, normalized)) =
+            leading_indent(snapshot, row, normalize_exotic_whitespace)
+        else {
+            continue;
+        };
+        let len = normalized.chars().count() as u32;
+        let new_indent = if from.kind == IndentKind::Space && kind == IndentKind::Tab {
+            normalized
+        } else {
+            let (depth, alignment) = if from.kind == IndentKind::Tab {
+                let depth = normalized.chars().take_while(|c| *c == '\t').count() as u32;
+                (depth, len - depth)
+            } else if len % level == 0 && len <= anchor + level {
+                anchor = len;
+                (len / level, 0)
+            } else if len > anchor {
+                (anchor / level, len - anchor)
+            } else {
+                anchor = len / level * level;
+                (len / level, len % level)
+            };
+            std::iter::repeat_n(to.char(), (depth * level_len(to)) as usize)
+                .chain(std::iter::repeat_n(' ', alignment as usize))
+                .collect::<String>()
+        };
+        if new_indent != indent {
+            edits.push((
+                Point::new(row, 0)..Point::new(row, indent.len() as u32),
+                new_indent,
+            ));
+        }
+    }
+    edits
+}
+
+/// Whether `row` of `snapshot` has to keep starting with a tab, as the recipe lines of a
+/// Makefile do, which make wouldn't run if they were indented with spaces.
+fn requires_tab(snapshot: &BufferSnapshot, row: u32) -> bool {
+    snapshot.language().is_some_and(|language| {
+        tab_requirement(language.name().as_ref()) == Some(TabRequirement::LeadingTabLines)
+    }) && snapshot.chars_at(Point::new(row, 0)).next() == Some('\t')
+}
+
+/// The kinds of syntax nodes, besides those that languages mark as strings, whose lines are text
+/// rather than code.
+const LITERAL_NODE_KINDS: &[&str] = &["block_scalar", "heredoc_body", "heredoc_content"];
+
+/// Whether `row` of `snapshot` starts inside a literal that began on a line above it, judging by
+/// its syntax tree.
+fn starts_inside_literal(snapshot: &BufferSnapshot, row: u32) -> bool {
+    let point = Point::new(row, 0);
+    if snapshot
+        .language_scope_at(point)
+        .is_some_and(|scope| scope.override_name() == Some("string"))
+    {
+        return true;
+    }
+    let offset = snapshot.point_to_offset(point);
+    let mut node = snapshot.syntax_ancestor(offset..offset);
+    while let Some(ancestor) = node {
+        if LITERAL_NODE_KINDS.contains(&ancestor.kind())
+            && ancestor.start_position().row < row as usize
+        {
+            return true;
+        }
+        node = ancestor.parent();
+    }
+    false
+}
+
+/// The leading whitespace of `row`, the kind of indentation it starts with, and the whitespace
+/// with [`ExoticWhitespace`] replaced with spaces, unless the line is blank or unindented.
+fn leading_indent(
+    snapshot: &BufferSnapshot,
+    row: u32,
+    normalize_exotic_whitespace: bool,
+) -> Option<(String, IndentKind, String)> {
+    if snapshot.is_line_blank(row) {
+        return None;
+    }
+    let indent = snapshot
+        .chars_at(Point::new(row, 0))
+        .take_while(|c| {
+            if normalize_exotic_whitespace {
+                is_indent_char(*c)
+            } else {
+                *c == ' ' || *c == '\t'
+            }
+        })
+        .collect::<String>();
+    let kind = match indent.chars().next()? {
+        '\t' => IndentKind::Tab,
+        _ => IndentKind::Space,
+    };
+    let normalized = indent
+        .chars()
+        .map(|c| match ExoticWhitespace::classify(c) {
+            Some(_) => ' ',
+            None => c,
+        })
+        .collect::<String>();
+    Some((indent, kind, normalized))
+}
+
+/// The number of indentation characters in one level of `indent_size`.
+pub(crate) fn level_len(indent_size: IndentSize) -> u32 {
+    match indent_size.kind {
+        IndentKind::Tab => 1,
+        IndentKind::Space => indent_size.len.max(1),
+    }
+}
+
+/// The number of files in `project` that don't use their configured indentation yet, or `None`
+/// while they're being counted. The first call starts counting them, recording the check for
+/// the workspace with `workspace_id` until it finishes.
+pub(crate) fn remaining_files(
+    project: &Entity<Project>,
+    workspace_id: Option<WorkspaceId>,
+    cx: &mut App,
+) -> Option<usize> {
+    check_project(project, workspace_id, cx);
+    cx.global::<RemainingFiles>().0[&project.entity_id()]
+        .files
+        .as_ref()
+        .map(HashSet::len)
+}
+
+/// Starts counting the files of `project` that need re-indenting, unless it's already been
+/// done, returning the count in progress.
+fn check_project(
+    project: &Entity<Project>,
+    workspace_id: Option<WorkspaceId>,
+    cx: &mut App,
+) -> Shared<Task<()>> {
+    let project_id = project.entity_id();
+    if let Some(scan) = cx.default_global::<RemainingFiles>().0.get(&project_id) {
+        return scan.scan.clone();
+    }
+    let scan = scan_project(project, workspace_id, cx).shared();
+    cx.global_mut::<RemainingFiles>().0.insert(
+        project_id,
+        ProjectScan {
+            files: None,
+            scan: scan.clone(),
+        },
+    );
+    scan
+}
+
+/// The wording for the count of files that still need re-indenting, from [`remaining_files`].
+pub(crate) fn remaining_files_message(remaining_files: Option<usize>) -> String {
+    match remaining_files {
+        None => "Counting the files in this project that need re-indenting…".to_string(),
+        Some(0) => "Every file in this project uses its configured indentation".to_string(),
+        Some(1) => "1 file in this project still needs re-indenting".to_string(),
+        Some(count) => format!("{count} files in this project still need re-indenting"),
+    }
+}
+
+/// Offers to resume the check of the workspace's project when Zed quit before it finished, or to
+/// discard it.
+pub(crate) fn offer_to_resume_check(
+    workspace: &mut Workspace,
+    _: Option<&mut Window>,
+    cx: &mut Context<Workspace>,
+) {
+    struct InterruptedProjectCheck;
+
+    let Some(workspace_id) = workspace.database_id() else {
+        return;
+    };
+    if !persistence::has_pending_check(workspace_id, cx) {
+        return;
+    }
+    let workspace_handle = cx.weak_entity();
+    workspace.show_notification(
+        NotificationId::unique::<InterruptedProjectCheck>(),
+        cx,
+        |cx| {
+            cx.new(|cx| {
+                MessageNotification::new(
+                    "Zed quit before it finished checking which files in this project need \
+                    re-indenting.",
+                    cx,
+                )
+                .with_title("Indentation Check Interrupted")
+                .show_suppress_button(false)
+                .primary_message("Resume")
+                .primary_on_click(move |_, cx| {
+                    workspace_handle
+                        .update(cx, |workspace, cx| {
+                            resume_check(workspace, workspace_id, cx)
+                        })
+                        .ok();
+                })
+                .secondary_message("Discard")
+                .secondary_on_click(move |_, cx| {
+                    persistence::forget_pending_check(workspace_id, cx)
+                })
+            })
+        },
+    );
+}
+
+/// Checks the workspace's project again, then shows how many files need re-indenting.
+fn resume_check(workspace: &mut Workspace, workspace_id: WorkspaceId, cx: &mut Context<Workspace>) {
+    struct ProjectCheckResumed;
+
+    let project = workspace.project().clone();
+    let scan = check_project(&project, Some(workspace_id), cx);
+    cx.spawn(async move |workspace, cx| {
+        scan.await;
+        workspace.update(cx, |workspace, cx| {
+            let message = remaining_files_message(remaining_files(&project, None, cx));
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<ProjectCheckResumed>(), message).autohide(),
+                cx,
+            );
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Re-renders the view behind `cx` whenever the count of remaining files changes.
+pub(crate) fn observe_remaining_files<T: 'static>(cx: &mut Context<T>) -> Subscription {
+    cx.observe_global::<RemainingFiles>(|_, cx| cx.notify())
+}
+
+fn scan_project(
+    project: &Entity<Project>,
+    workspace_id: Option<WorkspaceId>,
+    cx: &mut App,
+) -> Task<()> {
+    let project_id = project.entity_id();
+    if let Some(workspace_id) = workspace_id {
+        persistence::record_pending_check(workspace_id, cx);
+    }
+    let files_needing_reindent = files_needing_reindent(project.read(cx), cx);
+    cx.spawn(async move |cx| {
+        let files = cx
+            .background_spawn(files_needing_reindent)
+            .await
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+        cx.update(|cx| {
+            if let Some(scan) = cx.default_global::<RemainingFiles>().0.get_mut(&project_id) {
+                scan.files = Some(files);
+            }
+            if let Some(workspace_id) = workspace_id {
+                persistence::forget_pending_check(workspace_id, cx);
+            }
+        });
+    })
+}
+
+/// A file of a project that doesn't use its configured indentation.
+pub(crate) struct FileNeedingReindent {
+    pub(crate) path: ProjectPath,
+    pub(crate) language: LanguageName,
+    pub(crate) configured: IndentSize,
+    /// How the file breaks its configured indentation, which is never empty.
+    pub(crate) violations: Vec<IndentViolation>,
+}
+
+/// A file of a project, along with the indentation it's configured to use and the one it uses.
+pub(crate) struct ScannedFile {
+    pub(crate) path: ProjectPath,
+    pub(crate) language: LanguageName,
+    pub(crate) configured: IndentSize,
+    /// `None` when none of the file's lines is indented.
+    pub(crate) detected: Option<DetectedIndent>,
+    /// How the file breaks its configured indentation, empty when it doesn't.
+    pub(crate) violations: Vec<IndentViolation>,
+}
+
+impl ScannedFile {
+    /// The file, when it doesn't use its configured indentation.
+    pub(crate) fn needing_reindent(&self) -> Option<FileNeedingReindent> {
+        (!self.violations.is_empty()).then(|| FileNeedingReindent {
+            path: self.path.clone(),
+            language: self.language.clone(),
+            configured: self.configured,
+            violations: self.violations.clone(),
+        })
+    }
+}
+
+/// Reads the files of `project`'s visible worktrees in the background, resolving to those that
+/// don't use their configured indentation.
+pub(crate) fn files_needing_reindent(
+    project: &Project,
+    cx: &App,
+) -> impl Future<Output = Vec<FileNeedingReindent>> + Send + 'static {
+    let files = scan_indentation(project, cx);
+    async move {
+        files
+            .await
+            .iter()
+            .filter_map(ScannedFile::needing_reindent)
+            .collect()
+    }
+}
+
+/// A file of a project that a scan couldn't read, such as one that isn't UTF-8.
+pub(crate) struct UnreadableFile {
+    pub(crate) path: ProjectPath,
+    pub(crate) error: anyhow::Error,
+}
+
+/// Reads the files of `project`'s visible worktrees that have a language in the background,
+/// resolving to the indentation each one uses. Files that can't be read are left out.
+pub(crate) fn scan_indentation(
+    project: &Project,
+    cx: &App,
+) -> impl Future<Output = Vec<ScannedFile>> + Send + 'static {
+    let files = scan_files(project, cx);
+    async move { files.await.into_iter().filter_map(Result::ok).collect() }
+}
+
+/// Like [`scan_indentation`], but keeping the files that can't be read.
+pub(crate) fn scan_files(
+    project: &Project,
+    cx: &App,
+) -> impl Future<Output = Vec<Result<ScannedFile, UnreadableFile>>> + Send + 'static {
+    let fs = project.fs().clone();
+    let languages = project.languages().clone();
+    let normalize_exotic_whitespace =
+        IndentSizeSelectorSettings::get_global(cx).normalize_exotic_whitespace;
+
+    let mut candidates = Vec::new();
+    for worktree in project.visible_worktrees(cx) {
+        let worktree = worktree.read(cx);
+        let worktree_id = worktree.id();
+        let worktree_root = worktree.abs_path();
+        for entry in worktree.files(false, 0) {
+            let Some(language) = languages.language_for_file_path(entry.path.as_std_path()) else {
+                continue;
+            };
+            let location = SettingsLocation {
+                worktree_id,
+                path: &entry.path,
+            };
+            let settings = AllLanguageSettings::get(Some(location), cx).language(
+                Some(location),
+                Some(&language.name()),
+                cx,
+            );
+            candidates.push((
+                ProjectPath {
+                    worktree_id,
+                    path: entry.path.clone(),
+                },
+                language.name(),
+                worktree_root.join(entry.path.as_std_path()),
+                ResolvedWhitespaceOptions::from_settings(&settings).indent(),
+            ));
+        }
+    }
+
+    async move {
+        let mut files = Vec::new();
+        for (path, language, abs_path, configured) in candidates {
+            let text = match fs.load(&abs_path).await {
+                Ok(text) => text,
+                Err(error) => {
+                    files.push(Err(UnreadableFile { path, error }));
+                    continue;
+                }
+            };
+            let detection = detect_indentation_in_text(&text);
+            let violations = indent_violations(
+                &detection,
+                expected_indent(configured),
+                normalize_exotic_whitespace,
+            );
+            files.push(Ok(ScannedFile {
+                path,
+                language,
+                configured,
+                detected: detection.indent,
+                violations,
+            }));
+        }
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{Language, LanguageConfig};
+    use std::sync::Arc;
+
+    #[gpui::test]
+    fn test_reindent_edits(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("a:\n  b:\n\n    c: 1\n   d: 2\n", cx));
+        buffer.update(cx, |buffer, cx| {
+            let edits = reindent_edits(
+                &buffer.snapshot(),
+                IndentSize::spaces(2),
+                IndentSize::spaces(4),
+                false,
+            );
+            buffer.edit(edits, None, cx);
+            assert_eq!(buffer.text(), "a:\n    b:\n\n        c: 1\n     d: 2\n");
+
+            let edits = reindent_edits(
+                &buffer.snapshot(),
+                IndentSize::spaces(4),
+                IndentSize::tab(),
+                false,
+            );
+            buffer.edit(edits, None, cx);
+            assert_eq!(buffer.text(), "a:\n\tb:\n\n\t\tc: 1\n\t d: 2\n");
+
+            // Tabs configured to display 4 columns wide still make up one level each.
+            let edits = reindent_edits(
+                &buffer.snapshot(),
+                IndentSize::tab(),
+                IndentSize {
+                    len: 4,
+                    kind: IndentKind::Tab,
+                },
+                false,
+            );
+            assert!(edits.is_empty());
+        });
+
+        let buffer = cx.new(|cx| Buffer::local("a:\n\u{00A0} b:\n\u{3000}\tc: 1\n", cx));
+        buffer.update(cx, |buffer, cx| {
+            let edits = reindent_edits(
+                &buffer.snapshot(),
+                IndentSize::spaces(2),
+                IndentSize::spaces(2),
+                false,
+            );
+            assert!(edits.is_empty());
+
+            let edits = reindent_edits(
+                &buffer.snapshot(),
+                IndentSize::spaces(2),
+                IndentSize::spaces(4),
+                true,
+            );
+            buffer.edit(edits, None, cx);
+            assert_eq!(buffer.text(), "a:\n    b:\n    c: 1\n");
+        });
+    }
+
+    #[gpui::test]
+    fn test_reindent_edits_in_rows(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("a:\n  b:\n    c: 1\n  d:\n", cx));
+        buffer.update(cx, |buffer, cx| {
+            let edits = reindent_edits_in_rows(
+                &buffer.snapshot(),
+                1..3,
+                IndentSize::spaces(2),
+                IndentSize::tab(),
+                false,
+            );
+            buffer.edit(edits, None, cx);
+            assert_eq!(buffer.text(), "a:\n\tb:\n\t\tc: 1\n  d:\n");
+
+            // Rows past the end of the buffer are ignored.
+            let edits = reindent_edits_in_rows(
+                &buffer.snapshot(),
+                3..10,
+                IndentSize::spaces(2),
+                IndentSize::spaces(4),
+                false,
+            );
+            buffer.edit(edits, None, cx);
+            assert_eq!(buffer.text(), "a:\n\tb:\n\t\tc: 1\n    d:\n");
+        });
+    }
+
+    #[gpui::test]
+    async fn test_reindent_edits_skip_literals(cx: &mut TestAppContext) {
+        let text = "fn f() {\n  let s = \"a\n  b\";\n  g(s);\n}\n";
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language::rust_lang(), cx));
+        buffer
+            .read_with(cx, |buffer, _| buffer.parsing_idle())
+            .await;
+        buffer.update(cx, |buffer, cx| {
+            let edits = reindent_edits(
+                &buffer.snapshot(),
+                IndentSize::spaces(2),
+                IndentSize::spaces(4),
+                false,
+            );
+            buffer.edit(edits, None, cx);
+            assert_eq!(
+                buffer.text(),
+                "fn f() {\n    let s = \"a\n  b\";\n    g(s);\n}\n"
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn test_reindent_edits_keep_recipe_tabs(cx: &mut TestAppContext) {
+        let makefile = Arc::new(Language::new(
+            LanguageConfig {
+                name: "Makefile".into(),
+                ..Default::default()
+            },
+            None,
+        ));
+        let text = "all: build\n\tcargo build\n\nVAR = a \\\n\tb\n";
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(makefile, cx));
+        buffer.update(cx, |buffer, cx| {
+            for edits in [
+                reindent_edits(
+                    &buffer.snapshot(),
+                    IndentSize::tab(),
+                    IndentSize::spaces(4),
+                    false,
+                ),
+                reindent_edits_keeping_alignment(
+                    &buffer.snapshot(),
+                    0..buffer.row_count(),
+                    IndentSize::tab(),
+                    IndentSize::spaces(4),
+                    false,
+                ),
+            ] {
+                assert_eq!(edits, Vec::new());
+            }
+
+            // Converting a Makefile to tabs is harmless.
+            buffer.set_text("all: build\n    cargo build\n", cx);
+            let edits = reindent_edits(
+                &buffer.snapshot(),
+                IndentSize::spaces(4),
+                IndentSize::tab(),
+                false,
+            );
+            buffer.edit(edits, None, cx);
+            assert_eq!(buffer.text(), "all: build\n\tcargo build\n");
+        });
+    }
+
+    #[gpui::test]
+    fn test_reindent_edits_keeping_alignment(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| {
+            Buffer::local(
+                "fn f() {\n    let x = g(a,\n              b);\n    if x {\n        h(1,\n          2);\n    }\n}\n",
+                cx,
+            )
+        });
+        buffer.update(cx, |buffer, cx| {
+            let edits = reindent_edits_keeping_alignment(
+                &buffer.snapshot(),
+                0..buffer.row_count(),
+                IndentSize::spaces(4),
+                IndentSize::tab(),
+                false,
+            );
+            buffer.edit(edits, None, cx);
+            assert_eq!(
+                buffer.text(),
+                "fn f() {\n\tlet x = g(a,\n\t          b);\n\tif x {\n\t\th(1,\n\t\t  2);\n\t}\n}\n"
+            );
+
+            let edits = reindent_edits_keeping_alignment(
+                &buffer.snapshot(),
+                0..buffer.row_count(),
+                IndentSize::tab(),
+                IndentSize::spaces(2),
+                false,
+            );
+            buffer.edit(edits, None, cx);
+            assert_eq!(
+                buffer.text(),
+                "fn f() {\n  let x = g(a,\n            b);\n  if x {\n    h(1,\n      2);\n  }\n}\n"
+            );
+
+            // The alignment of a selected line is measured from the line above it.
+            let edits = reindent_edits_keeping_alignment(
+                &buffer.snapshot(),
+                2..3,
+                IndentSize::spaces(2),
+                IndentSize::spaces(4),
+                false,
+            );
+            buffer.edit(edits, None, cx);
+            assert_eq!(
+                buffer.text(),
+                "fn f() {\n  let x = g(a,\n              b);\n  if x {\n    h(1,\n      2);\n  }\n}\n"
+            );
+        });
+    }
+}
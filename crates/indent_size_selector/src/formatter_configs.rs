@@ -0,0 +1,282 @@
+use gpui::{App, Entity, Task};
+use language::{Buffer, language_settings::IndentationOverride};
+use project::{File, Fs};
+use settings::Settings as _;
+use std::{num::NonZeroU32, path::PathBuf, sync::Arc};
+use ui::SharedString;
+
+use crate::{IndentSizeSelectorSettings, IndentationProvider, register_indentation_provider};
+
+/// A formatter whose configuration file determines the indentation it formats files with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Formatter {
+    Prettier,
+    ClangFormat,
+    Rustfmt,
+}
+
+impl Formatter {
+    const ALL: [Self; 3] = [Self::Prettier, Self::ClangFormat, Self::Rustfmt];
+
+    /// The names of the configuration files the formatter looks for in each directory from the
+    /// file's up, in the order it prefers them.
+    fn config_file_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Prettier => &[
+                ".prettierrc",
+                ".prettierrc.json",
+                ".prettierrc.yaml",
+                ".prettierrc.yml",
+            ],
+            Self::ClangFormat => &[".clang-format", "_clang-format"],
+            Self::Rustfmt => &["rustfmt.toml", ".rustfmt.toml"],
+        }
+    }
+
+    /// The extensions of the files the formatter formats.
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Prettier => &[
+                "js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts", "css", "scss", "less",
+                "json", "jsonc", "json5", "html", "vue", "svelte", "md", "mdx", "yaml", "yml",
+                "graphql", "gql",
+            ],
+            Self::ClangFormat => &[
+                "c", "h", "cc", "cpp", "cxx", "c++", "hh", "hpp", "hxx", "h++", "m", "mm", "proto",
+            ],
+            Self::Rustfmt => &["rs"],
+        }
+    }
+
+    /// The indentation `content`, a configuration file of the formatter, has it format files
+    /// with, falling back to the formatter's defaults for what it leaves out.
+    fn parse(self, content: &str) -> Option<IndentationOverride> {
+        let (size, hard_tabs) = match self {
+            Self::Prettier => parse_prettierrc(content)?,
+            Self::ClangFormat => parse_clang_format(content),
+            Self::Rustfmt => parse_rustfmt_toml(content),
+        };
+        Some(IndentationOverride {
+            tab_size: NonZeroU32::new(size)?,
+            tab_width: None,
+            hard_tabs,
+        })
+    }
+}
+
+/// Reads `tabWidth` and `useTabs` from a `.prettierrc`, written in JSON or YAML.
+fn parse_prettierrc(content: &str) -> Option<(u32, bool)> {
+    let (tab_width, use_tabs) = if content.trim_start().starts_with('{') {
+        let config = serde_json::from_str::<serde_json::Value>(content).ok()?;
+        (
+            config
+                .get("tabWidth")
+                .and_then(|value| value.as_u64())
+                .map(|width| width as u32),
+            config.get("useTabs").and_then(|value| value.as_bool()),
+        )
+    } else {
+        let mut tab_width = None;
+        let mut use_tabs = None;
+        for (key, value) in top_level_values(content, ':') {
+            match key {
+                "tabWidth" => tab_width = value.parse().ok(),
+                "useTabs" => use_tabs = value.parse().ok(),
+                _ => {}
+            }
+        }
+        (tab_width, use_tabs)
+    };
+    Some((tab_width.unwrap_or(2), use_tabs.unwrap_or(false)))
+}
+
+/// Reads `IndentWidth` and `UseTab` from a `.clang-format`, along with the style it's based on,
+/// which sets the indent width it leaves out.
+fn parse_clang_format(content: &str) -> (u32, bool) {
+    let mut based_on_style = None;
+    let mut indent_width = None;
+    let mut use_tab = None;
+    for (key, value) in top_level_values(content, ':') {
+        match key {
+            "BasedOnStyle" if based_on_style.is_none() => based_on_style = Some(value),
+            "IndentWidth" if indent_width.is_none() => indent_width = value.parse().ok(),
+            "UseTab" if use_tab.is_none() => use_tab = Some(value),
+            _ => {}
+        }
+    }
+    let style_indent_width = match based_on_style.map(str::to_lowercase).as_deref() {
+        Some("webkit" | "microsoft") => 4,
+        _ => 2,
+    };
+    let hard_tabs = use_tab.is_some_and(|use_tab| !matches!(use_tab, "Never" | "false"));
+    (indent_width.unwrap_or(style_indent_width), hard_tabs)
+}
+
+/// Reads `tab_spaces` and `hard_tabs` from the root table of a `rustfmt.toml`.
+fn parse_rustfmt_toml(content: &str) -> (u32, bool) {
+    let root_table = if content.starts_with('[') {
+        ""
+    } else {
+        content
+            .find("\n[")
+            .map_or(content, |table_start| &content[..table_start])
+    };
+    let mut tab_spaces = None;
+    let mut hard_tabs = None;
+    for (key, value) in top_level_values(root_table, '=') {
+        match key {
+            "tab_spaces" => tab_spaces = value.parse().ok(),
+            "hard_tabs" => hard_tabs = value.parse().ok(),
+            _ => {}
+        }
+    }
+    (tab_spaces.unwrap_or(4), hard_tabs.unwrap_or(false))
+}
+
+/// The keys and values of the unindented lines of `content`, separated by `separator`, without
+/// trailing comments or quotes, which leaves out the nested keys of a YAML mapping.
+fn top_level_values(content: &str, separator: char) -> impl Iterator<Item = (&str, &str)> {
+    content.lines().filter_map(move |line| {
+        if line.starts_with(char::is_whitespace) || line.starts_with('#') {
+            return None;
+        }
+        let (key, value) = line.split_once(separator)?;
+        let value = value
+            .split_once(" #")
+            .map_or(value, |(value, _)| value)
+            .trim();
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        Some((key.trim(), value))
+    })
+}
+
+/// Supplies the indentation that the configuration of `formatter` nearest to a file has the
+/// formatter produce, so that formatting the file doesn't re-indent it.
+struct FormatterConfigProvider {
+    formatter: Formatter,
+}
+
+impl IndentationProvider for FormatterConfigProvider {
+    fn name(&self) -> SharedString {
+        match self.formatter {
+            Formatter::Prettier => "the Prettier configuration".into(),
+            Formatter::ClangFormat => "the clang-format configuration".into(),
+            Formatter::Rustfmt => "the rustfmt configuration".into(),
+        }
+    }
+
+    fn priority(&self) -> i32 {
+        // The providers registered by others know their files better.
+        -1
+    }
+
+    fn indentation(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Task<Option<IndentationOverride>> {
+        if !IndentSizeSelectorSettings::get_global(cx).read_formatter_configs {
+            return Task::ready(None);
+        }
+        let Some(candidates) = config_candidates(self.formatter, buffer, cx) else {
+            return Task::ready(None);
+        };
+        let formatter = self.formatter;
+        let fs = <dyn Fs>::global(cx);
+        cx.background_spawn(async move {
+            for candidate in candidates {
+                if let Ok(content) = fs.load(&candidate).await {
+                    return formatter.parse(&content);
+                }
+            }
+            None
+        })
+    }
+}
+
+/// The paths that a configuration of `formatter` for the file of `buffer` may have, from the
+/// file's directory up to the root of its worktree. `None` when the formatter doesn't format
+/// the file.
+fn config_candidates(
+    formatter: Formatter,
+    buffer: &Entity<Buffer>,
+    cx: &App,
+) -> Option<Vec<PathBuf>> {
+    let file = File::from_dyn(buffer.read(cx).file())?;
+    let extension = file.path().extension()?;
+    if !formatter.extensions().contains(&extension) {
+        return None;
+    }
+    let worktree_root = file.worktree.read(cx).abs_path();
+    let directory = file.path().parent()?;
+    Some(
+        directory
+            .ancestors()
+            .flat_map(|ancestor| {
+                let directory = worktree_root.join(ancestor.as_std_path());
+                formatter
+                    .config_file_names()
+                    .iter()
+                    .map(move |name| directory.join(name))
+            })
+            .collect(),
+    )
+}
+
+/// Registers a provider for each formatter's configuration.
+pub(crate) fn register(cx: &mut App) {
+    for formatter in Formatter::ALL {
+        register_indentation_provider(Arc::new(FormatterConfigProvider { formatter }), cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(formatter: Formatter, content: &str) -> Option<(u32, bool)> {
+        formatter
+            .parse(content)
+            .map(|indentation| (indentation.tab_size.get(), indentation.hard_tabs))
+    }
+
+    #[test]
+    fn test_parse_formatter_configs() {
+        assert_eq!(
+            parsed(Formatter::Prettier, r#"{ "tabWidth": 4, "semi": false }"#),
+            Some((4, false))
+        );
+        assert_eq!(
+            parsed(Formatter::Prettier, "useTabs: true\nsingleQuote: true\n"),
+            Some((2, true))
+        );
+        assert_eq!(parsed(Formatter::Prettier, "{ not json"), None);
+
+        assert_eq!(
+            parsed(
+                Formatter::ClangFormat,
+                "---\nLanguage: Cpp\nBasedOnStyle: WebKit\nUseTab: Never\n"
+            ),
+            Some((4, false))
+        );
+        assert_eq!(
+            parsed(
+                Formatter::ClangFormat,
+                "BasedOnStyle: LLVM\nIndentWidth: 8\nUseTab: ForIndentation # kernel style\n"
+            ),
+            Some((8, true))
+        );
+
+        assert_eq!(
+            parsed(
+                Formatter::Rustfmt,
+                "edition = \"2024\"\nhard_tabs = true\n\n[format]\ntab_spaces = 2\n"
+            ),
+            Some((4, true))
+        );
+        assert_eq!(
+            parsed(Formatter::Rustfmt, "tab_spaces = 2 # narrow\n"),
+            Some((2, false))
+        );
+    }
+}
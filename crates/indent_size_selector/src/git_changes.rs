@@ -0,0 +1,69 @@
+use gpui::{App, Entity};
+use language::{Buffer, Point};
+use project::Project;
+use std::ops::Range;
+
+use crate::fix_on_save::merge_rows;
+
+/// The rows of `buffer` that differ from the version of its file git has: those in the hunks
+/// of its uncommitted diff, along with those edited since the diff was last computed. `None`
+/// when there's no version to compare with, such as for an untracked file, or when the diff
+/// isn't loaded.
+pub(crate) fn changed_rows(
+    buffer: &Entity<Buffer>,
+    project: &Entity<Project>,
+    cx: &App,
+) -> Option<Vec<Range<u32>>> {
+    let snapshot = buffer.read(cx).snapshot();
+    let diff = project
+        .read(cx)
+        .git_store()
+        .read(cx)
+        .get_uncommitted_diff(snapshot.remote_id(), cx)?;
+    if !diff.read(cx).base_text_exists() {
+        return None;
+    }
+    let diff = diff.read(cx).snapshot(cx);
+    let hunk_rows = diff
+        .hunks(&snapshot)
+        .map(|hunk| rows_touched(hunk.range))
+        .collect::<Vec<_>>();
+    let edited_rows = snapshot
+        .edits_since::<Point>(diff.buffer_version())
+        .map(|edit| rows_touched(edit.new))
+        .collect::<Vec<_>>();
+    Some(merge_rows(
+        hunk_rows
+            .into_iter()
+            .chain(edited_rows)
+            .filter(|rows| !rows.is_empty())
+            .collect(),
+    ))
+}
+
+/// The rows with any text in `range`, which is empty when text was only removed there.
+fn rows_touched(range: Range<Point>) -> Range<u32> {
+    if range.is_empty() {
+        return range.start.row..range.start.row;
+    }
+    let end_row = if range.end.column == 0 {
+        range.end.row
+    } else {
+        range.end.row + 1
+    };
+    range.start.row..end_row.max(range.start.row + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_touched() {
+        assert_eq!(rows_touched(Point::new(2, 0)..Point::new(4, 0)), 2..4);
+        assert_eq!(rows_touched(Point::new(2, 3)..Point::new(2, 5)), 2..3);
+        assert_eq!(rows_touched(Point::new(2, 3)..Point::new(3, 1)), 2..4);
+        // Removing the text between lines leaves nothing to re-indent.
+        assert!(rows_touched(Point::new(2, 0)..Point::new(2, 0)).is_empty());
+    }
+}
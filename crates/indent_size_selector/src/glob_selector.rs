@@ -0,0 +1,364 @@
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, WeakEntity};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use settings::{IndentSizeSelectorOverrideScope, LocalSettingsPath, WorktreeId};
+use std::{num::NonZeroU32, ops::Range, sync::Arc};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::{ResultExt as _, rel_path::RelPath};
+use workspace::{ModalView, Workspace};
+
+use crate::{
+    apply_scope::set_override_section, editorconfig_override, escape_glob, file_type_glob,
+    fix_on_save, history, indentation_events, indentation_properties, is_compact, modal_width,
+    override_batch::OverrideBatch,
+};
+
+/// Asks for a glob, then overrides the indentation of every file in the worktree matching it.
+pub(crate) struct GlobSelector {
+    picker: Entity<Picker<GlobSelectorDelegate>>,
+}
+
+impl GlobSelector {
+    /// Opens the glob chooser for applying `size` (as tabs when `hard_tabs` is set, rendered
+    /// `tab_width` wide when it's given) to the files matching a glob in the worktree of `buffer`, suggesting globs matching its file.
+    /// The existing indentation of `buffer` is converted too when `convert` is set, only in
+    /// `selected_rows` unless that's empty.
+    pub(crate) fn toggle(
+        workspace: &Entity<Workspace>,
+        buffer: Entity<Buffer>,
+        size: NonZeroU32,
+        hard_tabs: bool,
+        tab_width: Option<NonZeroU32>,
+        convert: bool,
+        selected_rows: Vec<Range<u32>>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(file) = buffer.read(cx).file() else {
+            return;
+        };
+        let worktree_id = file.worktree_id(cx);
+        let suggestions = suggested_globs(file.path());
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                let delegate = GlobSelectorDelegate::new(
+                    cx.entity().downgrade(),
+                    buffer,
+                    worktree_id,
+                    suggestions,
+                    size,
+                    hard_tabs,
+                    tab_width,
+                    convert,
+                    selected_rows,
+                );
+                Self {
+                    picker: cx.new(|cx| Picker::uniform_list(delegate, window, cx)),
+                }
+            });
+        });
+    }
+}
+
+impl Render for GlobSelector {
+    fn render(&mut self, window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("GlobSelector")
+            .w(modal_width(window))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for GlobSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for GlobSelector {}
+impl ModalView for GlobSelector {}
+
+/// Globs matching files like the one at `path`: of its type anywhere, of its type under its
+/// directory, and anything under its directory.
+fn suggested_globs(path: &RelPath) -> Vec<String> {
+    let file_type = file_type_glob(path);
+    let mut globs = vec![format!("**/{file_type}")];
+    if let Some(directory) = path.parent()
+        && !directory.is_empty()
+    {
+        let directory = escape_glob(directory.as_unix_str());
+        globs.push(format!("{directory}/**/{file_type}"));
+        globs.push(format!("{directory}/**"));
+    }
+    globs
+}
+
+/// The worktree's root `.editorconfig` override with a section for `glob`, kept alongside the
+/// other sections the selector added there.
+fn glob_override_content(
+    worktree_id: WorktreeId,
+    glob: &str,
+    size: u32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+    cx: &App,
+) -> String {
+    let root = LocalSettingsPath::InWorktree(RelPath::empty().into());
+    let existing = editorconfig_override(worktree_id, &root, cx).unwrap_or_default();
+    set_override_section(
+        &existing,
+        glob,
+        &indentation_properties(size, hard_tabs, tab_width),
+    )
+}
+
+struct GlobSelectorDelegate {
+    glob_selector: WeakEntity<GlobSelector>,
+    buffer: Entity<Buffer>,
+    worktree_id: WorktreeId,
+    size: NonZeroU32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+    convert: bool,
+    selected_rows: Vec<Range<u32>>,
+    /// The suggested globs, preceded by the query when it isn't one of them.
+    globs: Vec<String>,
+    suggestions: Vec<String>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl GlobSelectorDelegate {
+    fn new(
+        glob_selector: WeakEntity<GlobSelector>,
+        buffer: Entity<Buffer>,
+        worktree_id: WorktreeId,
+        suggestions: Vec<String>,
+        size: NonZeroU32,
+        hard_tabs: bool,
+        tab_width: Option<NonZeroU32>,
+        convert: bool,
+        selected_rows: Vec<Range<u32>>,
+    ) -> Self {
+        Self {
+            glob_selector,
+            buffer,
+            worktree_id,
+            size,
+            hard_tabs,
+            tab_width,
+            convert,
+            selected_rows,
+            globs: suggestions.clone(),
+            suggestions,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    /// Writes the override for `glob`, then clears the one for the buffer alone, which would
+    /// otherwise keep shadowing it.
+    fn apply(&self, glob: &str, cx: &mut App) {
+        let content = glob_override_content(
+            self.worktree_id,
+            glob,
+            self.size.get(),
+            self.hard_tabs,
+            self.tab_width,
+            cx,
+        );
+        let mut batch = OverrideBatch::default();
+        batch.set(
+            self.worktree_id,
+            LocalSettingsPath::InWorktree(RelPath::empty().into()),
+            Some(content),
+        );
+        let old = indentation_events::resolved_indent_size(&self.buffer, cx);
+        let applied = history::transact(cx, |cx| {
+            if batch.commit(cx).log_err().is_some() {
+                history::set_indentation_override(&self.buffer, None, cx);
+                if self.convert {
+                    fix_on_save::convert_existing_indentation(
+                        &self.buffer,
+                        &self.selected_rows,
+                        self.size.get(),
+                        self.hard_tabs,
+                        cx,
+                    );
+                }
+                true
+            } else {
+                false
+            }
+        });
+        if applied {
+            indentation_events::emit_applied(
+                vec![(self.buffer.clone(), Some(old))],
+                self.size.get(),
+                self.hard_tabs,
+                IndentSizeSelectorOverrideScope::Worktree,
+                cx,
+            );
+        }
+    }
+}
+
+impl PickerDelegate for GlobSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Type or pick a glob, such as **/*.ts…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            self.apply(&self.globs[mat.candidate_id], cx);
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.glob_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let query = query.trim().to_string();
+        // A glob can't span lines in an `.editorconfig` section header.
+        let custom_glob =
+            (!query.is_empty() && !query.contains('\n') && !self.suggestions.contains(&query))
+                .then(|| query.clone());
+        let mut globs = self.suggestions.clone();
+        if let Some(custom_glob) = &custom_glob {
+            globs.insert(0, custom_glob.clone());
+        }
+        let candidates = globs
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, glob)| StringMatchCandidate::new(candidate_id, glob))
+            .collect::<Vec<_>>();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let mut matches = if query.is_empty() {
+                candidates
+                    .iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string.clone(),
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+            // The glob being typed always comes first, whatever it fuzzily matches.
+            if custom_glob.is_some() {
+                matches.retain(|mat| mat.candidate_id != 0);
+                matches.insert(
+                    0,
+                    StringMatch {
+                        candidate_id: 0,
+                        string: candidates[0].string.clone(),
+                        positions: Vec::new(),
+                        score: 0.0,
+                    },
+                );
+            }
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.globs = globs;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .when(!is_compact(window), |list_item| {
+                    list_item.end_slot(
+                        Label::new("in this worktree")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggested_globs() {
+        let suggest = |path: &str| suggested_globs(RelPath::unix(path).unwrap());
+
+        assert_eq!(suggest("main.rs"), vec!["**/*.rs"]);
+        assert_eq!(
+            suggest("packages/web/src/app.ts"),
+            vec!["**/*.ts", "packages/web/src/**/*.ts", "packages/web/src/**"]
+        );
+        assert_eq!(
+            suggest("tools/Makefile"),
+            vec!["**/Makefile", "tools/**/Makefile", "tools/**"]
+        );
+    }
+}
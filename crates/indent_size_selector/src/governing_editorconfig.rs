@@ -0,0 +1,358 @@
+use anyhow::Result;
+use editor::Editor;
+use gpui::{App, AppContext as _, Entity, PromptLevel, Window};
+use paths::EDITORCONFIG_NAME;
+use project::Project;
+use settings::WorktreeId;
+use std::{path::PathBuf, sync::Arc};
+use util::rel_path::RelPath;
+use workspace::{OpenOptions, OpenVisible, Workspace};
+
+use crate::cursor_position;
+
+/// The content of an `.editorconfig` created because none governed the file yet.
+const NEW_EDITORCONFIG: &str = "root = true\n\n[*]\n";
+
+/// The properties besides the indentation that an `.editorconfig` offered for a project starts
+/// with, which most projects want.
+const STANDARD_PROPERTIES: [(&str, &str); 3] = [
+    ("charset", "utf-8"),
+    ("insert_final_newline", "true"),
+    ("trim_trailing_whitespace", "true"),
+];
+
+/// Opens the `.editorconfig` nearest to the file open in `editor`, creating one at the
+/// worktree root when none of the file's directories has one.
+pub(crate) fn open_governing_editorconfig(
+    editor: &Entity<Editor>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let editor = editor.read(cx);
+    let Some(workspace) = editor.workspace() else {
+        return;
+    };
+    let Some(project) = editor.project() else {
+        return;
+    };
+    let project = project.read(cx);
+    let fs = project.fs().clone();
+
+    let file = editor.file_at(cursor_position(editor), cx);
+    let worktree = match &file {
+        Some(file) => project.worktree_for_id(file.worktree_id(cx), cx),
+        None => project.visible_worktrees(cx).next(),
+    };
+    let Some(worktree) = worktree else {
+        return;
+    };
+    let worktree_root = worktree.read(cx).abs_path();
+    let root_editorconfig = worktree_root.join(EDITORCONFIG_NAME);
+    let candidates = match file.as_ref().and_then(|file| file.path().parent()) {
+        Some(directory) => directory
+            .ancestors()
+            .map(|ancestor| {
+                worktree_root
+                    .join(ancestor.as_std_path())
+                    .join(EDITORCONFIG_NAME)
+            })
+            .collect::<Vec<_>>(),
+        None => vec![root_editorconfig.clone()],
+    };
+
+    workspace.update(cx, |_, cx| {
+        cx.spawn_in(window, async move |workspace, cx| {
+            let mut governing: Option<PathBuf> = None;
+            for candidate in candidates {
+                if fs.is_file(&candidate).await {
+                    governing = Some(candidate);
+                    break;
+                }
+            }
+            let path = match governing {
+                Some(path) => path,
+                None => {
+                    fs.atomic_write(root_editorconfig.clone(), NEW_EDITORCONFIG.to_string())
+                        .await?;
+                    root_editorconfig
+                }
+            };
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.open_abs_path(
+                        path,
+                        OpenOptions {
+                            visible: Some(OpenVisible::None),
+                            ..Default::default()
+                        },
+                        window,
+                        cx,
+                    )
+                })?
+                .await?;
+            Result::<()>::Ok(())
+        })
+        .detach_and_log_err(cx);
+    });
+}
+
+/// Asks whether to create an `.editorconfig` at the root of the worktree with `worktree_id`,
+/// when it has none, setting `properties` for every file along with the standard ones, so that
+/// editors besides Zed pick the indentation up too. Opens it for review once created.
+pub(crate) fn offer_project_editorconfig(
+    workspace: &Entity<Workspace>,
+    worktree_id: WorktreeId,
+    properties: Vec<(&'static str, String)>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let project = workspace.read(cx).project().read(cx);
+    if !project.is_local() {
+        return;
+    }
+    let Some(worktree) = project.worktree_for_id(worktree_id, cx) else {
+        return;
+    };
+    let worktree = worktree.read(cx);
+    let has_editorconfig = RelPath::unix(EDITORCONFIG_NAME)
+        .ok()
+        .and_then(|path| worktree.entry_for_path(path))
+        .is_some();
+    if has_editorconfig {
+        return;
+    }
+    let fs = project.fs().clone();
+    let path = worktree.abs_path().join(EDITORCONFIG_NAME);
+    let message = format!(
+        "Create an {EDITORCONFIG_NAME} for {}?",
+        worktree.root_name_str()
+    );
+    let content = editorconfig_template(&properties);
+    let answer = window.prompt(
+        PromptLevel::Info,
+        &message,
+        Some(
+            "It sets this indentation for other editors too, along with a UTF-8 charset, a \
+             final newline and no trailing whitespace.",
+        ),
+        &["Create", "Not Now"],
+        cx,
+    );
+    workspace.update(cx, |_, cx| {
+        cx.spawn_in(window, async move |workspace, cx| {
+            if answer.await? != 0 {
+                return Ok(());
+            }
+            // One may have been created while the prompt was open.
+            if !fs.is_file(&path).await {
+                fs.atomic_write(path.clone(), content).await?;
+            }
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.open_abs_path(
+                        path,
+                        OpenOptions {
+                            visible: Some(OpenVisible::None),
+                            ..Default::default()
+                        },
+                        window,
+                        cx,
+                    )
+                })?
+                .await?;
+            Result::<()>::Ok(())
+        })
+        .detach_and_log_err(cx);
+    });
+}
+
+/// The content of an `.editorconfig` offered for a project, setting the standard properties for
+/// every file, then `properties`, which win over them.
+fn editorconfig_template(properties: &[(&str, String)]) -> String {
+    let standard = STANDARD_PROPERTIES
+        .iter()
+        .map(|(key, value)| (*key, value.to_string()))
+        .collect::<Vec<_>>();
+    let content = set_section_properties(NEW_EDITORCONFIG, "*", &standard);
+    set_section_properties(&content, "*", properties)
+}
+
+/// The files a section written to an `.editorconfig` on disk applies to.
+pub(crate) enum EditorconfigTarget {
+    File(Arc<RelPath>),
+    /// Every file under the directory. The empty path covers the whole worktree.
+    Directory(Arc<RelPath>),
+}
+
+impl EditorconfigTarget {
+    /// The directory from which to look for the `.editorconfig` governing the target.
+    fn lookup_directory(&self) -> &RelPath {
+        match self {
+            Self::File(path) => path.parent().unwrap_or(RelPath::empty()),
+            Self::Directory(directory) => directory,
+        }
+    }
+
+    /// The section header matching the target in an `.editorconfig` in `config_directory`.
+    fn section(&self, config_directory: &RelPath) -> String {
+        let (path, suffix) = match self {
+            Self::File(path) => (path, ""),
+            Self::Directory(directory) => (directory, "/**"),
+        };
+        match path.strip_prefix(config_directory) {
+            Ok(relative) if !relative.is_empty() => {
+                format!("/{}{suffix}", relative.as_unix_str())
+            }
+            _ => "*".to_string(),
+        }
+    }
+}
+
+/// Writes `properties` into the section for `target` of the `.editorconfig` nearest to it on
+/// disk, creating one at the worktree root when there's none, and leaves every other section
+/// as it was.
+pub(crate) fn write_editorconfig_section(
+    project: &Entity<Project>,
+    worktree_id: WorktreeId,
+    target: EditorconfigTarget,
+    properties: Vec<(&'static str, String)>,
+    cx: &mut App,
+) {
+    let project = project.read(cx);
+    let fs = project.fs().clone();
+    let Some(worktree) = project.worktree_for_id(worktree_id, cx) else {
+        return;
+    };
+    let worktree_root = worktree.read(cx).abs_path();
+    let candidates = target
+        .lookup_directory()
+        .ancestors()
+        .map(|ancestor| {
+            (
+                ancestor.into_arc(),
+                worktree_root
+                    .join(ancestor.as_std_path())
+                    .join(EDITORCONFIG_NAME),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    cx.background_spawn(async move {
+        let mut governing = None;
+        for (directory, path) in candidates {
+            if fs.is_file(&path).await {
+                governing = Some((directory, path));
+                break;
+            }
+        }
+        let (directory, path, content) = match governing {
+            Some((directory, path)) => {
+                let content = fs.load(&path).await?;
+                (directory, path, content)
+            }
+            None => (
+                RelPath::empty().into_arc(),
+                worktree_root.join(EDITORCONFIG_NAME),
+                NEW_EDITORCONFIG.to_string(),
+            ),
+        };
+        let content = set_section_properties(&content, &target.section(&directory), &properties);
+        fs.atomic_write(path, content).await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Sets `properties` in the `[header]` section of `content`, appending the section when it's
+/// missing and keeping comments and every other section as they were.
+pub(crate) fn set_section_properties(
+    content: &str,
+    header: &str,
+    properties: &[(&str, String)],
+) -> String {
+    let mut lines = content.lines().map(str::to_string).collect::<Vec<_>>();
+    let section_header = format!("[{header}]");
+    let Some(section_start) = lines.iter().position(|line| line.trim() == section_header) else {
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(section_header);
+        lines.extend(
+            properties
+                .iter()
+                .map(|(key, value)| format!("{key} = {value}")),
+        );
+        return lines.join("\n") + "\n";
+    };
+
+    let mut section_end = lines[section_start + 1..]
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .map_or(lines.len(), |ix| section_start + 1 + ix);
+    let is_property = |line: &str| {
+        let line = line.trim_start();
+        !line.starts_with('#') && !line.starts_with(';') && line.contains('=')
+    };
+    for (key, value) in properties {
+        let property = format!("{key} = {value}");
+        let existing = (section_start + 1..section_end).find(|ix| {
+            is_property(&lines[*ix])
+                && lines[*ix]
+                    .split_once('=')
+                    .is_some_and(|(existing_key, _)| existing_key.trim().eq_ignore_ascii_case(key))
+        });
+        match existing {
+            Some(ix) => lines[ix] = property,
+            None => {
+                let insert_at = (section_start + 1..section_end)
+                    .rev()
+                    .find(|ix| is_property(&lines[*ix]))
+                    .map_or(section_start + 1, |ix| ix + 1);
+                lines.insert(insert_at, property);
+                section_end += 1;
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editorconfig_template() {
+        assert_eq!(
+            editorconfig_template(&[
+                ("indent_style", "tab".to_string()),
+                ("indent_size", "tab".to_string()),
+                ("tab_width", "4".to_string()),
+                ("trim_trailing_whitespace", "false".to_string()),
+            ]),
+            "root = true\n\n[*]\ncharset = utf-8\ninsert_final_newline = true\n\
+             trim_trailing_whitespace = false\nindent_style = tab\nindent_size = tab\ntab_width = 4\n"
+        );
+    }
+
+    #[test]
+    fn test_set_section_properties() {
+        let properties = [
+            ("indent_style", "space".to_string()),
+            ("indent_size", "4".to_string()),
+            ("tab_width", "4".to_string()),
+        ];
+        let content = "root = true\n\n[*]\nindent_style = space\nIndent_Size = 2\n\n# Go\n[*.go]\nindent_style = tab\n";
+
+        assert_eq!(
+            set_section_properties(content, "*", &properties),
+            "root = true\n\n[*]\nindent_style = space\nindent_size = 4\ntab_width = 4\n\n# Go\n[*.go]\nindent_style = tab\n"
+        );
+        assert_eq!(
+            set_section_properties(content, "/src/**", &properties),
+            "root = true\n\n[*]\nindent_style = space\nIndent_Size = 2\n\n# Go\n[*.go]\nindent_style = tab\n\n\
+             [/src/**]\nindent_style = space\nindent_size = 4\ntab_width = 4\n"
+        );
+    }
+}
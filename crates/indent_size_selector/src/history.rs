@@ -0,0 +1,483 @@
+use anyhow::Result;
+use gpui::{App, Context, Entity, Global, WeakEntity, Window};
+use language::{Buffer, TransactionId, language_settings::IndentationOverride};
+use project::{Project, ProjectPath};
+use settings::{LocalSettingsPath, WorktreeId};
+use util::ResultExt as _;
+use workspace::{Toast, Workspace, notifications::NotificationId};
+
+use crate::{
+    RevertLastChange,
+    apply_scope::{self, LanguageOverrideFile},
+    override_batch::OverrideBatch,
+    persistence,
+};
+
+/// How many changes are kept to revert, across every workspace.
+const MAX_ENTRIES: usize = 50;
+
+/// An override the selector changed, along with what was in place before.
+enum OverrideChange {
+    Editorconfig {
+        worktree_id: WorktreeId,
+        path: LocalSettingsPath,
+        previous: Option<String>,
+    },
+    Buffer {
+        buffer: WeakEntity<Buffer>,
+        previous: Option<IndentationOverride>,
+    },
+    /// The edit re-indenting the existing lines of a buffer.
+    Conversion {
+        buffer: WeakEntity<Buffer>,
+        transaction_id: TransactionId,
+    },
+    /// A write to a settings file, as the settings read before and after it.
+    Settings {
+        project: WeakEntity<Project>,
+        file: LanguageOverrideFile,
+        before: serde_json::Value,
+        after: serde_json::Value,
+    },
+}
+
+impl OverrideChange {
+    fn affects(&self, project: &Project, cx: &App) -> bool {
+        match self {
+            Self::Editorconfig { worktree_id, .. } => {
+                project.worktree_for_id(*worktree_id, cx).is_some()
+            }
+            Self::Buffer { buffer, .. } | Self::Conversion { buffer, .. } => {
+                buffer.upgrade().is_some_and(|buffer| {
+                    project.buffer_for_id(buffer.read(cx).remote_id(), cx) == Some(buffer)
+                })
+            }
+            Self::Settings {
+                project: changed_project,
+                ..
+            } => changed_project
+                .upgrade()
+                .is_some_and(|changed_project| std::ptr::eq(changed_project.read(cx), project)),
+        }
+    }
+}
+
+/// The overrides changed by one use of the selector, such as applying a size to a scope.
+#[derive(Default)]
+struct HistoryEntry {
+    /// Identifies the entry among those recorded, once it's recorded.
+    id: usize,
+    changes: Vec<OverrideChange>,
+    /// The workspace to notify of the changes once they're recorded, along with a summary of
+    /// them, from [`describe`].
+    summary: Option<(WeakEntity<Workspace>, String)>,
+}
+
+impl HistoryEntry {
+    fn affects(&self, project: &Project, cx: &App) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.affects(project, cx))
+    }
+
+    /// Puts back what was in place before each change, the latest first. Returns the paths
+    /// whose `.editorconfig` overrides were removed.
+    fn revert(self, cx: &mut App) -> Result<Vec<ProjectPath>> {
+        let mut batch = OverrideBatch::default();
+        let mut removed = Vec::new();
+        let mut buffers = Vec::new();
+        let mut conversions = Vec::new();
+        let mut settings = Vec::new();
+        for change in self.changes.into_iter().rev() {
+            match change {
+                OverrideChange::Editorconfig {
+                    worktree_id,
+                    path,
+                    previous,
+                } => {
+                    if previous.is_none()
+                        && let LocalSettingsPath::InWorktree(path) = &path
+                    {
+                        removed.push(ProjectPath {
+                            worktree_id,
+                            path: path.clone(),
+                        });
+                    }
+                    batch.set(worktree_id, path, previous);
+                }
+                OverrideChange::Buffer { buffer, previous } => buffers.push((buffer, previous)),
+                OverrideChange::Conversion {
+                    buffer,
+                    transaction_id,
+                } => conversions.push((buffer, transaction_id)),
+                OverrideChange::Settings {
+                    project,
+                    file,
+                    before,
+                    after,
+                } => settings.push((project, file, before, after)),
+            }
+        }
+        batch.commit(cx)?;
+
+        for (project, file, before, after) in settings {
+            if let Some(project) = project.upgrade() {
+                apply_scope::write_settings_changes(&project, file, after, before, cx);
+            }
+        }
+
+        for (buffer, previous) in buffers {
+            if let Some(buffer) = buffer.upgrade() {
+                buffer.update(cx, |buffer, cx| {
+                    buffer.set_indentation_override(previous, cx)
+                });
+            }
+        }
+
+        for (buffer, transaction_id) in conversions {
+            if let Some(buffer) = buffer.upgrade() {
+                buffer.update(cx, |buffer, cx| {
+                    buffer.undo_transaction(transaction_id, cx);
+                });
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// The changes the selector made, the latest last, and those of the use in progress.
+#[derive(Default)]
+struct IndentationHistory {
+    entries: Vec<HistoryEntry>,
+    current: Option<HistoryEntry>,
+    /// The id of the latest entry recorded.
+    last_id: usize,
+}
+
+impl Global for IndentationHistory {}
+
+/// A use of the selector whose changes are being recorded, from [`start_transaction`].
+#[must_use]
+pub(crate) struct Transaction {
+    /// Whether this transaction records the entry, rather than one it's nested in.
+    outermost: bool,
+}
+
+/// Starts recording the overrides changed from now on as one entry that [`RevertLastChange`]
+/// reverts together, until [`finish_transaction`]. Overrides changed outside of a transaction,
+/// such as when restoring a remembered choice, aren't recorded.
+pub(crate) fn start_transaction(cx: &mut App) -> Transaction {
+    let history = cx.default_global::<IndentationHistory>();
+    let outermost = history.current.is_none();
+    if outermost {
+        history.current = Some(HistoryEntry::default());
+    }
+    Transaction { outermost }
+}
+
+pub(crate) fn finish_transaction(transaction: Transaction, cx: &mut App) {
+    if !transaction.outermost {
+        return;
+    }
+    let history = cx.default_global::<IndentationHistory>();
+    let Some(mut entry) = history.current.take() else {
+        return;
+    };
+    if entry.changes.is_empty() {
+        return;
+    }
+    history.last_id += 1;
+    entry.id = history.last_id;
+    let summary = entry.summary.take();
+    let id = entry.id;
+    history.entries.push(entry);
+    if history.entries.len() > MAX_ENTRIES {
+        history.entries.remove(0);
+    }
+    if let Some((workspace, summary)) = summary {
+        offer_undo(workspace, id, summary, cx);
+    }
+}
+
+/// Summarizes the changes of the transaction in progress, such as "4 spaces via .editorconfig
+/// override for worktree 'api'", so that they're shown in `workspace` once they're recorded,
+/// with a button undoing them. Changes outside of a transaction aren't shown.
+pub(crate) fn describe(workspace: WeakEntity<Workspace>, summary: String, cx: &mut App) {
+    if let Some(entry) = cx.default_global::<IndentationHistory>().current.as_mut() {
+        entry.summary = Some((workspace, summary));
+    }
+}
+
+/// Shows `summary` of the entry with `id` in `workspace`, with a button reverting it.
+fn offer_undo(workspace: WeakEntity<Workspace>, id: usize, summary: String, cx: &mut App) {
+    struct IndentationChangeApplied;
+
+    let undo_workspace = workspace.clone();
+    workspace
+        .update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<IndentationChangeApplied>(),
+                    summary,
+                )
+                .on_click("Undo", move |_, cx| {
+                    undo_workspace
+                        .update(cx, |workspace, cx| revert(workspace, Some(id), cx))
+                        .log_err();
+                })
+                .autohide(),
+                cx,
+            );
+        })
+        .log_err();
+}
+
+/// Runs `f` in a transaction, recording the overrides it changes as one entry.
+pub(crate) fn transact<R>(cx: &mut App, f: impl FnOnce(&mut App) -> R) -> R {
+    let transaction = start_transaction(cx);
+    let result = f(cx);
+    finish_transaction(transaction, cx);
+    result
+}
+
+fn record(change: OverrideChange, cx: &mut App) {
+    if let Some(entry) = cx.default_global::<IndentationHistory>().current.as_mut() {
+        entry.changes.push(change);
+    }
+}
+
+/// Records that the `.editorconfig` override at `path` was replaced, having been `previous`.
+pub(crate) fn record_editorconfig(
+    worktree_id: WorktreeId,
+    path: LocalSettingsPath,
+    previous: Option<String>,
+    cx: &mut App,
+) {
+    record(
+        OverrideChange::Editorconfig {
+            worktree_id,
+            path,
+            previous,
+        },
+        cx,
+    );
+}
+
+/// Records that the settings in `file` changed from `before` to `after`, as serialized by
+/// [`apply_scope::update_settings`].
+pub(crate) fn record_settings(
+    project: &Entity<Project>,
+    file: LanguageOverrideFile,
+    before: serde_json::Value,
+    after: serde_json::Value,
+    cx: &mut App,
+) {
+    record(
+        OverrideChange::Settings {
+            project: project.downgrade(),
+            file,
+            before,
+            after,
+        },
+        cx,
+    );
+}
+
+/// Records that the existing lines of `buffer` were re-indented in `transaction_id`, so that
+/// they're put back along with the overrides changed with them.
+pub(crate) fn record_conversion(
+    buffer: &Entity<Buffer>,
+    transaction_id: TransactionId,
+    cx: &mut App,
+) {
+    record(
+        OverrideChange::Conversion {
+            buffer: buffer.downgrade(),
+            transaction_id,
+        },
+        cx,
+    );
+}
+
+/// Sets the override of `buffer` alone, recording what it replaces.
+pub(crate) fn set_indentation_override(
+    buffer: &Entity<Buffer>,
+    indentation_override: Option<IndentationOverride>,
+    cx: &mut App,
+) {
+    let previous = buffer.read(cx).indentation_override().copied();
+    if previous == indentation_override {
+        return;
+    }
+    record(
+        OverrideChange::Buffer {
+            buffer: buffer.downgrade(),
+            previous,
+        },
+        cx,
+    );
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_indentation_override(indentation_override, cx)
+    });
+}
+
+/// Reverts the latest change the selector made in the workspace's project, restoring the
+/// overrides, `.editorconfig` contents and settings it replaced and the lines it re-indented.
+pub(crate) fn revert_last_change(
+    workspace: &mut Workspace,
+    _: &RevertLastChange,
+    _: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    revert(workspace, None, cx);
+}
+
+/// Reverts the change with `id`, or else the latest one made in the workspace's project.
+fn revert(workspace: &mut Workspace, id: Option<usize>, cx: &mut Context<Workspace>) {
+    struct IndentationChangeReverted;
+
+    let project = workspace.project().clone();
+    let index = cx
+        .try_global::<IndentationHistory>()
+        .and_then(|history| match id {
+            Some(id) => history.entries.iter().position(|entry| entry.id == id),
+            None => history
+                .entries
+                .iter()
+                .rposition(|entry| entry.affects(project.read(cx), cx)),
+        });
+    let message = match index {
+        None if id.is_some() => "The indentation change was already reverted",
+        None => "No indentation change to revert in this project",
+        Some(index) => {
+            let entry = cx
+                .default_global::<IndentationHistory>()
+                .entries
+                .remove(index);
+            match entry.revert(cx).log_err() {
+                Some(removed) => {
+                    // A choice remembered for a file whose override is gone would be installed
+                    // again when it's next opened.
+                    for project_path in removed {
+                        if let Some(buffer) = project.read(cx).get_open_buffer(&project_path, cx) {
+                            persistence::forget_choice(&buffer, cx);
+                        }
+                    }
+                    "Reverted the last indentation change"
+                }
+                None => "Couldn't revert the last indentation change",
+            }
+        }
+    };
+    workspace.show_toast(
+        Toast::new(
+            NotificationId::unique::<IndentationChangeReverted>(),
+            message,
+        )
+        .autohide(),
+        cx,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use std::num::NonZeroU32;
+    use util::path;
+    use workspace::AppState;
+
+    use crate::{apply_to_path, editorconfig_override, fix_on_save};
+
+    #[gpui::test]
+    fn test_transactions_revert_together(cx: &mut TestAppContext) {
+        let indentation = |tab_size: u32| IndentationOverride {
+            tab_size: NonZeroU32::new(tab_size).unwrap(),
+            tab_width: None,
+            hard_tabs: false,
+        };
+        let buffer = cx.new(|cx| Buffer::local("", cx));
+        let current = |cx: &mut TestAppContext| {
+            buffer.read_with(cx, |buffer, _| buffer.indentation_override().copied())
+        };
+
+        cx.update(|cx| {
+            // Changes outside of a transaction aren't recorded.
+            set_indentation_override(&buffer, Some(indentation(8)), cx);
+            transact(cx, |cx| {
+                set_indentation_override(&buffer, Some(indentation(2)), cx);
+                transact(cx, |cx| {
+                    set_indentation_override(&buffer, Some(indentation(4)), cx)
+                });
+            });
+            transact(cx, |_| {});
+        });
+        assert_eq!(current(cx), Some(indentation(4)));
+
+        let entry = cx.update(|cx| {
+            let history = cx.default_global::<IndentationHistory>();
+            assert_eq!(history.entries.len(), 1);
+            history.entries.pop().unwrap()
+        });
+        assert_eq!(entry.changes.len(), 2);
+
+        // Both changes are reverted, back to the override in place before the transaction.
+        cx.update(|cx| entry.revert(cx)).unwrap();
+        assert_eq!(current(cx), Some(indentation(8)));
+    }
+
+    #[gpui::test]
+    async fn test_applying_and_converting_revert_together(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let text = "fn f() {\n\tg();\n}\n";
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.rs": text })).await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let current_override = |cx: &mut TestAppContext| {
+            cx.update(|cx| {
+                let file = buffer.read(cx).file().unwrap();
+                let path = LocalSettingsPath::InWorktree(file.path().clone());
+                editorconfig_override(file.worktree_id(cx), &path, cx)
+            })
+        };
+
+        // As a secondary confirm does: the size is set and the lines converted to it at once.
+        cx.update(|cx| {
+            transact(cx, |cx| {
+                assert!(apply_to_path(&buffer, 4, false, None, None, cx));
+                fix_on_save::convert_existing_indentation(&buffer, &[], 4, false, cx);
+            })
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "fn f() {\n    g();\n}\n"
+        );
+        assert!(current_override(cx).is_some());
+
+        let entry = cx.update(|cx| {
+            let history = cx.default_global::<IndentationHistory>();
+            assert_eq!(history.entries.len(), 1);
+            history.entries.pop().unwrap()
+        });
+        cx.update(|cx| entry.revert(cx)).unwrap();
+        cx.run_until_parked();
+        assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), text);
+        assert_eq!(current_override(cx), None);
+    }
+}
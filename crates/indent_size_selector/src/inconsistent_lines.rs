@@ -0,0 +1,144 @@
+use editor::Editor;
+use gpui::{AppContext as _, Context, Task, Window};
+use language::{BufferSnapshot, IndentKind, IndentSize, Point};
+use settings::{Settings as _, SettingsStore};
+use std::{cell::RefCell, rc::Rc};
+use ui::ActiveTheme as _;
+
+use crate::{IndentSizeSelectorSettings, read_indent_size};
+
+/// Marks the gutter of the lines whose indentation doesn't match the configured one.
+struct InconsistentLineMarkers;
+
+#[derive(Default)]
+struct MarkerScan {
+    /// The version and configured indentation last scanned, to skip scanning them again.
+    scanned: Option<(clock::Global, IndentSize)>,
+    task: Option<Task<()>>,
+}
+
+/// Marks the gutter of each line of the file opened in `editor` whose leading whitespace
+/// doesn't match its configured indentation, while `mark_inconsistent_lines` is enabled. The
+/// markers follow edits and changes to the indentation as they happen.
+pub(crate) fn watch(editor: &mut Editor, window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    if window.is_none() || !editor.mode().is_full() {
+        return;
+    }
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+
+    let scan = Rc::new(RefCell::new(MarkerScan::default()));
+    let refresh = move |editor: &mut Editor, cx: &mut Context<Editor>| {
+        refresh_markers(editor, &mut scan.borrow_mut(), cx);
+    };
+    // Edits, and overrides for the buffer alone, notify its observers.
+    cx.observe(&buffer, {
+        let refresh = refresh.clone();
+        move |editor, _, cx| refresh(editor, cx)
+    })
+    .detach();
+    cx.observe_global::<SettingsStore>({
+        let refresh = refresh.clone();
+        move |editor, cx| refresh(editor, cx)
+    })
+    .detach();
+    refresh(editor, cx);
+}
+
+fn refresh_markers(editor: &mut Editor, scan: &mut MarkerScan, cx: &mut Context<Editor>) {
+    let configured = if IndentSizeSelectorSettings::get_global(cx).mark_inconsistent_lines {
+        read_indent_size(editor, cx).map(|configured| configured.indent_size)
+    } else {
+        None
+    };
+    let (Some(configured), Some(buffer)) = (configured, editor.buffer().read(cx).as_singleton())
+    else {
+        if scan.scanned.take().is_some() {
+            scan.task = None;
+            editor.clear_gutter_highlights::<InconsistentLineMarkers>(cx);
+        }
+        return;
+    };
+    let snapshot = buffer.read(cx).snapshot();
+    let key = (snapshot.version().clone(), configured);
+    if scan.scanned.as_ref() == Some(&key) {
+        return;
+    }
+    scan.scanned = Some(key);
+    scan.task = Some(cx.spawn(async move |editor, cx| {
+        let rows = cx
+            .background_spawn(async move { inconsistent_rows(&snapshot, configured) })
+            .await;
+        editor
+            .update(cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let markers = rows
+                    .into_iter()
+                    .map(|row| {
+                        let start = snapshot.anchor_before(Point::new(row, 0));
+                        start..start
+                    })
+                    .collect::<Vec<_>>();
+                editor.highlight_gutter::<InconsistentLineMarkers>(
+                    markers,
+                    |cx| cx.theme().status().warning.opacity(0.4),
+                    cx,
+                );
+            })
+            .ok();
+    }));
+}
+
+/// The rows of the non-blank lines of `snapshot` whose leading whitespace doesn't match
+/// `configured`.
+pub(crate) fn inconsistent_rows(snapshot: &BufferSnapshot, configured: IndentSize) -> Vec<u32> {
+    (0..snapshot.row_count())
+        .filter(|row| {
+            if snapshot.is_line_blank(*row) {
+                return false;
+            }
+            let indent = snapshot
+                .chars_at(Point::new(*row, 0))
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .collect::<String>();
+            is_inconsistent(&indent, configured)
+        })
+        .collect()
+}
+
+/// Whether `indent` doesn't match `configured`: when it's spaces, any tab or a partial level of
+/// spaces; when it's tabs, any space before the last tab, or spaces alone. The spaces after a
+/// line's tabs are taken to align it.
+fn is_inconsistent(indent: &str, configured: IndentSize) -> bool {
+    match configured.kind {
+        IndentKind::Space => {
+            indent.contains('\t') || indent.len() as u32 % configured.len.max(1) != 0
+        }
+        IndentKind::Tab => {
+            let tabs = indent.trim_end_matches(' ');
+            tabs.contains(' ') || (tabs.is_empty() && !indent.is_empty())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_inconsistent() {
+        let spaces = IndentSize::spaces(4);
+        assert!(!is_inconsistent("", spaces));
+        assert!(!is_inconsistent("        ", spaces));
+        assert!(is_inconsistent("  ", spaces));
+        assert!(is_inconsistent("\t", spaces));
+
+        let tabs = IndentSize::tab();
+        assert!(!is_inconsistent("\t\t", tabs));
+        // Aligned past the line it continues.
+        assert!(!is_inconsistent("\t  ", tabs));
+        assert!(is_inconsistent("    ", tabs));
+        assert!(is_inconsistent(" \t", tabs));
+    }
+}
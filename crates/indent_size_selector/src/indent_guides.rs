@@ -0,0 +1,45 @@
+use editor::Editor;
+use gpui::{Context, Window};
+use settings::{IndentGuideColoring, SettingsStore};
+use std::{cell::Cell, rc::Rc};
+
+use crate::read_indent_size;
+
+/// Re-renders the indent guides and tabs of the file opened in `editor` as soon as its
+/// indentation changes, whether through an override for its buffer or through the settings and
+/// `.editorconfig` overrides, rather than after the next edit.
+pub(crate) fn watch(editor: &mut Editor, window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    if window.is_none() || !editor.mode().is_full() {
+        return;
+    }
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+
+    let indent_size = Rc::new(Cell::new(read_indent_size(editor, cx)));
+    let refresh_if_changed = move |editor: &mut Editor, cx: &mut Context<Editor>| {
+        let new_indent_size = read_indent_size(editor, cx);
+        if indent_size.replace(new_indent_size) != new_indent_size {
+            editor.refresh_indent_guides(cx);
+        }
+    };
+    // Overrides for the buffer alone notify its observers.
+    cx.observe(&buffer, {
+        let refresh_if_changed = refresh_if_changed.clone();
+        move |editor, _, cx| refresh_if_changed(editor, cx)
+    })
+    .detach();
+    cx.observe_global::<SettingsStore>(move |editor, cx| refresh_if_changed(editor, cx))
+        .detach();
+}
+
+/// Switches the indent guides of `editor` between a color for each depth, cycling through the
+/// theme's accents, and the theme's indent guide color.
+pub(crate) fn toggle_depth_coloring(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let coloring = if editor.indent_guide_coloring(cx) == IndentGuideColoring::IndentAware {
+        IndentGuideColoring::Fixed
+    } else {
+        IndentGuideColoring::IndentAware
+    };
+    editor.set_indent_guide_coloring(Some(coloring), cx);
+}
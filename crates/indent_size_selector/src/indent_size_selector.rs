@@ -0,0 +1,3035 @@
+//! Picks the indentation of the files open in Zed, and shows it in the status bar.
+//!
+//! The main points of interest are the selector, [`IndentSizeSelector`], opened by [`Toggle`],
+//! and its status item, [`Indentation`]. Other crates resolve a file's indentation with
+//! [`read_indent_size`] and [`indent_info`], detect it with [`detect_indentation`], convert it
+//! with [`reindent_edits`], and follow the selector's changes through [`IndentationEvents`].
+//! Items hidden from the documentation exist for Zed's own crates.
+
+mod apply_scope;
+mod auto_detect;
+mod buffer_setting_status;
+mod buffer_settings;
+mod bulk_results;
+mod charset;
+mod check_command;
+mod clear_override;
+mod consistency_report;
+mod convert_indentation;
+mod copy_converted;
+mod correction_watchdog;
+mod detection;
+mod editorconfig_changes;
+mod editorconfig_save_check;
+mod entry_selector;
+mod explain_indentation;
+mod fix_on_save;
+mod formatter_configs;
+mod git_changes;
+mod glob_selector;
+mod governing_editorconfig;
+mod history;
+mod inconsistent_lines;
+mod indent_guides;
+mod indent_size_selector_settings;
+mod indent_source;
+mod indentation;
+mod indentation_events;
+mod indentation_fixes;
+mod indentation_lints;
+mod indentation_provider;
+mod indentation_stats;
+mod language_conventions;
+mod line_ending;
+mod line_length;
+mod metrics;
+mod mismatch_notification;
+mod mixed_indentation;
+mod new_file_indentation;
+mod override_batch;
+mod persistence;
+mod previous_indentation;
+mod profiles;
+mod project_conventions;
+mod project_conversion;
+mod recent_sizes;
+mod reindent_on_paste;
+mod sample_preview;
+mod setting_scope_selector;
+mod structure_check;
+mod style_export;
+mod style_import;
+mod tab_requirements;
+mod tour;
+mod unresolved_prompt;
+mod unsupported_files;
+mod untitled_indentation;
+mod whitespace_on_save;
+
+use apply_scope::LanguageOverrideFile;
+use bulk_results::BulkApplyResults;
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    Action, AnyElement, BackgroundExecutor, DismissEvent, Entity, EventEmitter, FocusHandle,
+    Focusable, Subscription, Task, WeakEntity, actions,
+};
+use indentation_stats::IndentationStats;
+use language::{
+    Buffer, BufferEvent, IndentKind, IndentSize, LanguageName,
+    language_settings::{IndentationOverride, LanguageSettings, ResolvedWhitespaceOptions},
+};
+use language_conventions::{Convention, conventional_indentation, template_indentation};
+use metrics::Span;
+use mixed_indentation::MixedIndentation;
+use override_batch::OverrideBatch;
+use paths::EDITORCONFIG_NAME;
+use picker::{Picker, PickerDelegate};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use settings::{
+    IndentGuideColoring, IndentSizeSelectorConfirmTarget, IndentSizeSelectorOverrideScope,
+    IndentationProfileContent, LocalSettingsPath, Settings, SettingsStore, WorktreeId,
+};
+use std::{mem, num::NonZeroU32, rc::Rc, sync::Arc, time::Instant};
+use ui::{
+    DocumentationAside, DocumentationSide, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing,
+    prelude::*,
+};
+use unsupported_files::overridable_file;
+use util::{ResultExt, paths::PathStyle, rel_path::RelPath};
+use workspace::{ModalView, Toast, Workspace, item::ItemHandle, notifications::NotificationId};
+
+pub use buffer_setting_status::BufferSettingStatus;
+pub use buffer_settings::{BufferSetting, register_buffer_setting};
+pub use check_command::{IndentationCheck, check_indentation};
+pub use detection::{detect_buffer_indentation, detected_indentation};
+pub use entry_selector::{IndentationTarget, toggle_for_entries};
+pub use fix_on_save::{reindent_edits, reindent_edits_in_rows, reindent_edits_keeping_alignment};
+pub use indent_size_selector_settings::IndentSizeSelectorSettings;
+pub use indent_source::{IndentInfo, IndentSource, indent_info};
+pub use indentation::Indentation;
+pub use indentation_events::{IndentationChanged, IndentationEvents};
+pub use indentation_provider::{IndentationProvider, register_indentation_provider};
+pub use language::{
+    DetectOptions, DetectedIndent, Detection, DetectionConfidence, detect_indentation,
+};
+
+actions!(
+    indent_size_selector,
+    [
+        /// Toggles the indent size selector, applying the chosen indentation to every tab in
+        /// the active pane.
+        ToggleForPane,
+        /// Toggles the indent size selector, applying the chosen indentation to every file with
+        /// an excerpt in the active multibuffer, such as project search results.
+        ToggleForExcerpts,
+        /// Switches the active file between tabs and spaces, keeping its indent size, as picking
+        /// "Toggle Spaces/Tabs" in the selector does.
+        ToggleHardTabs,
+        /// Confirms the highlighted choice of the indent size selector, also re-indenting the
+        /// existing lines of the files it's applied to, whatever `convert_existing_indentation`
+        /// says, as `menu::SecondaryConfirm` does.
+        ConfirmAndConvert,
+        /// Confirms the highlighted choice of the indent size selector, writing it for the
+        /// files' languages in the user settings, wherever `confirm_writes_to` would.
+        ConfirmForLanguages,
+        /// Switches the active file between tabs expanding to the next multiple of the tab size
+        /// and elastic tabs, which line up the cells that tabs separate on consecutive lines, as
+        /// picking "Elastic Tabs" in the selector does.
+        ToggleElasticTabs,
+        /// Switches the indent guides of the active file between a color for each depth and a
+        /// single color, as the `indent_guides.coloring` setting does for every file of a
+        /// language.
+        ToggleIndentGuideColoring,
+        /// Toggles the line ending selector, which asks where to set the chosen line ending as
+        /// an `.editorconfig` override for the active file, converting its lines when asked to.
+        #[action(deprecated_aliases = ["line_ending_selector::Toggle"])]
+        ToggleEndOfLine,
+        /// Toggles trimming trailing whitespace when saving the active file, asking whether for
+        /// the file, its directory, its worktree, its language or every file.
+        ToggleTrimTrailingWhitespaceOnSave,
+        /// Toggles ensuring a final newline when saving the active file, asking whether for the
+        /// file, its directory, its worktree, its language or every file.
+        ToggleEnsureFinalNewlineOnSave,
+        /// Asks for the column that lines of the active file are kept within, then sets it as
+        /// the file's `max_line_length`, or for its directory, its worktree, its language or
+        /// every file. Setting it for a language or every file moves the wrap guide to it, as
+        /// `editorconfig_wrap_guide` does for the others.
+        SetLineLength,
+        /// Toggles the charset selector, which asks where to set the chosen charset as an
+        /// `.editorconfig` override for the active file, then reads the file again in it, or
+        /// saves the file in it to convert it.
+        SelectCharset,
+        /// Indents the active file with 2 spaces, as picking them in the selector does.
+        UseTwoSpaces,
+        /// Indents the active file with 4 spaces, as picking them in the selector does.
+        UseFourSpaces,
+        /// Indents the active file with tabs, keeping its indent size, as picking them in the
+        /// selector does.
+        UseTabs,
+        /// Removes the indentation overrides the selector installed for the active file and
+        /// its directories.
+        ClearIndentationOverride,
+        /// Detects the indentation of the active file from its content and applies it until the
+        /// file is closed.
+        Detect,
+        /// Lists every layer of the settings that may set the indentation of the active file,
+        /// from its override and `.editorconfig` files to Zed's defaults, marking the one in
+        /// effect, and opens the file of the one picked.
+        ExplainIndentation,
+        /// Reverts the latest indentation change made through the selector in the project,
+        /// restoring the overrides it replaced.
+        RevertLastChange,
+        /// Switches the active file between its indentation and the one the selector last
+        /// replaced, as alt-tab does between windows, for the file alone until it's closed.
+        ToggleLastIndentation,
+        /// Exports the project's whitespace conventions as an `.editorconfig` and a summary.
+        ExportIndentationStyle,
+        /// Previews re-indenting every file in the project that doesn't use its configured
+        /// indentation, then converts them once confirmed.
+        ConvertProjectIndentation,
+        /// Opens a report of the files converting the project's indentation would re-indent, and
+        /// of those it would skip, without changing them.
+        ReportProjectIndentationConversion,
+        /// Scans the project and lists the files whose indentation disagrees with their
+        /// settings, grouped by language, offering to convert them all.
+        ShowIndentationConsistency,
+        /// Samples files of each language in the project and reports the indentation most of
+        /// them use, offering to write it into the project's settings.
+        DetectProjectConventions,
+        /// Shows how long opening the selector, matching, applying and converting took.
+        ShowIndentationPerformance,
+        /// Walks through the status item, the selector and converting indentation on a
+        /// scratch buffer.
+        StartIndentationTour
+    ]
+);
+
+/// Imports an indentation style into the project from an `.editorconfig` or a JSON style
+/// descriptor (`indent_style`, `indent_size`, `tab_width`), read from a file or URL.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = indent_size_selector)]
+#[serde(deny_unknown_fields)]
+pub struct ImportIndentationStyle {
+    /// The path or URL to import from. Prompts for a file when omitted.
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Scans the project and writes the indentation each file uses, the one it's configured to use
+/// and whether they disagree to a file, such as to check the project in CI or share it in a
+/// review. Prompts for the file to write.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = indent_size_selector)]
+#[serde(deny_unknown_fields)]
+pub struct ExportIndentationConsistency {
+    #[serde(default)]
+    pub format: ConsistencyReportFormat,
+}
+
+/// A format [`ExportIndentationConsistency`] writes the report in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsistencyReportFormat {
+    /// A JSON document listing each file, to be read by tools.
+    #[default]
+    Json,
+    /// A Markdown table of the files, to be read by people.
+    Markdown,
+}
+
+/// Toggles the indent size selector modal.
+#[derive(PartialEq, Clone, Default, Deserialize, JsonSchema, Action)]
+#[action(namespace = indent_size_selector)]
+#[serde(deny_unknown_fields)]
+pub struct Toggle {
+    /// Where the chosen indentation is applied, instead of asking when `confirm_writes_to` is
+    /// `"ask"`.
+    #[serde(default)]
+    pub apply_to: Option<ApplyTo>,
+}
+
+/// A scope the indent size selector can be opened pre-targeted to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplyTo {
+    /// The open buffer only, until it's closed.
+    Buffer,
+    /// Every file of the active file's language, in the user settings.
+    Language,
+    /// Every file in the active file's worktree, as an `.editorconfig` override.
+    Worktree,
+    /// Every file in the active file's worktree, in its `.zed/settings.json`.
+    Workspace,
+}
+
+/// Applies an indentation to the active file without opening the selector, as confirming it in
+/// the selector would, such as from a key binding.
+#[derive(PartialEq, Clone, Deserialize, JsonSchema, Action)]
+#[action(namespace = indent_size_selector)]
+#[serde(deny_unknown_fields)]
+pub struct SetIndentSize {
+    /// The number of spaces to indent each level with, or the width of a tab with `hard_tabs`.
+    pub size: NonZeroU32,
+    /// Whether to indent with tabs rather than spaces.
+    #[serde(default)]
+    pub hard_tabs: bool,
+    /// How many columns a tab occupies, when it differs from `size`, such as tabs rendered 8
+    /// wide in code indented by 4. Defaults to `size`.
+    #[serde(default)]
+    pub tab_width: Option<NonZeroU32>,
+}
+
+/// Displays the tabs of the active file `width` columns wide in the active pane alone, leaving
+/// the file's indentation, its settings and other panes showing it as they are, such as to
+/// compare how it reads at different widths in a split.
+#[derive(PartialEq, Clone, Default, Deserialize, JsonSchema, Action)]
+#[action(namespace = indent_size_selector)]
+#[serde(deny_unknown_fields)]
+pub struct SetTabDisplayWidth {
+    /// The number of columns each tab occupies. Tabs are displayed with the file's tab size
+    /// again when omitted.
+    #[serde(default)]
+    pub width: Option<NonZeroU32>,
+}
+
+/// Copies the selected text, or the line at each cursor, with its indentation converted from
+/// the one it uses, leaving the file as it is, such as to share code in documentation, a chat
+/// or a project with other conventions.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = indent_size_selector)]
+#[serde(deny_unknown_fields)]
+pub struct CopyWithConvertedIndentation {
+    /// The number of spaces to indent each level with. Asks for the indentation when omitted.
+    #[serde(default)]
+    pub size: Option<NonZeroU32>,
+    /// Whether to indent with tabs rather than spaces, ignoring `size`.
+    #[serde(default)]
+    pub hard_tabs: bool,
+}
+
+/// Rewrites the leading whitespace of every line in the active file as spaces, keeping each
+/// line's depth, like Vim's `:retab`. Only the selected lines are rewritten when some are.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = indent_size_selector)]
+#[serde(deny_unknown_fields)]
+pub struct ConvertIndentationToSpaces {
+    /// The number of spaces to indent each level with. Defaults to the file's indent size.
+    #[serde(default)]
+    pub indent_size: Option<NonZeroU32>,
+}
+
+/// Rewrites the leading whitespace of every line in the active file as tabs, keeping each
+/// line's depth, like Vim's `:retab!`. Only the selected lines are rewritten when some are.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = indent_size_selector)]
+#[serde(deny_unknown_fields)]
+pub struct ConvertIndentationToTabs {
+    /// The number of spaces that make up each level of the existing indentation. Defaults to
+    /// the indentation detected in the lines being rewritten.
+    #[serde(default)]
+    pub indent_size: Option<NonZeroU32>,
+}
+
+pub fn init(cx: &mut App) {
+    buffer_settings::register_builtin_settings(cx);
+    formatter_configs::register(cx);
+    previous_indentation::init(cx);
+    cx.observe_new(IndentSizeSelector::register).detach();
+    cx.observe_new(convert_indentation::register).detach();
+    cx.observe_new(copy_converted::register).detach();
+    cx.observe_new(previous_indentation::register).detach();
+    cx.observe_new(explain_indentation::register).detach();
+    cx.observe_new(correction_watchdog::watch).detach();
+    cx.observe_new(fix_on_save::watch).detach();
+    cx.observe_new(editorconfig_save_check::watch).detach();
+    cx.observe_new(buffer_settings::register).detach();
+    cx.observe_new(whitespace_on_save::register).detach();
+    cx.observe_new(reindent_on_paste::register).detach();
+    cx.observe_new(persistence::restore_choice).detach();
+    cx.observe_new(untitled_indentation::watch).detach();
+    cx.observe_new(new_file_indentation::watch).detach();
+    cx.observe_new(auto_detect::watch).detach();
+    cx.observe_new(mismatch_notification::watch).detach();
+    cx.observe_new(auto_detect::register).detach();
+    cx.observe_new(indentation_provider::watch).detach();
+    cx.observe_new(indentation_fixes::watch).detach();
+    cx.observe_new(indent_guides::watch).detach();
+    cx.observe_new(inconsistent_lines::watch).detach();
+    cx.observe_new(unresolved_prompt::watch).detach();
+    cx.observe_new(fix_on_save::offer_to_resume_check).detach();
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(style_import::import_indentation_style);
+        workspace.register_action(style_export::export_indentation_style);
+        workspace.register_action(tour::start_indentation_tour);
+        workspace.register_action(metrics::IndentationPerformance::toggle);
+        workspace.register_action(history::revert_last_change);
+        workspace.register_action(project_conversion::convert_project_indentation);
+        workspace.register_action(project_conversion::report_project_indentation_conversion);
+        workspace.register_action(consistency_report::IndentationConsistencyReport::deploy);
+        workspace.register_action(consistency_report::export_indentation_consistency);
+        workspace.register_action(project_conventions::detect_project_conventions);
+    })
+    .detach();
+    cx.observe_new(editorconfig_changes::watch).detach();
+}
+
+/// The indentation the settings prescribe for a file, as [`read_indent_size`] reads it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrescribedIndentation {
+    /// A level of indentation: a tab, or the number of spaces it's made of. Its length is the
+    /// indent size either way.
+    pub indent_size: IndentSize,
+    /// How many columns a tab character occupies, which may differ from the indent size.
+    pub tab_width: u32,
+}
+
+/// Reads the indentation that the settings prescribe for the file open in `editor`, along with
+/// the width of its tabs. Files without a language, such as plain text or an unrecognized type,
+/// get the settings for every language.
+pub fn read_indent_size(editor: &Editor, cx: &App) -> Option<PrescribedIndentation> {
+    let whitespace = resolved_whitespace(editor, cx)?;
+    let kind = if whitespace.hard_tabs {
+        IndentKind::Tab
+    } else {
+        IndentKind::Space
+    };
+    Some(PrescribedIndentation {
+        indent_size: IndentSize {
+            len: whitespace.indent_size.get(),
+            kind,
+        },
+        tab_width: whitespace.tab_width.get(),
+    })
+}
+
+/// The whitespace options the settings resolve to for the file open in `editor`, at the
+/// language under its cursor.
+pub(crate) fn resolved_whitespace(editor: &Editor, cx: &App) -> Option<ResolvedWhitespaceOptions> {
+    let language_name = editor
+        .language_at(cursor_position(editor), cx)
+        .map(|language| language.name());
+    let buffer = file_buffer(editor, cx)?;
+    let settings = LanguageSettings::resolve(Some(buffer.read(cx)), language_name.as_ref(), cx);
+    let mut whitespace = ResolvedWhitespaceOptions::from_settings(&settings);
+    // The pane may display tabs with another width than the settings, for itself alone.
+    if let Some(tab_width) = editor.tab_size_override(cx) {
+        whitespace.tab_width = tab_width;
+    }
+    Some(whitespace)
+}
+
+/// Returns a short label for the indentation detected in `item`, such as `␣2` or `⇥`, when the
+/// tab switcher is configured to show it.
+#[doc(hidden)]
+pub fn tab_switcher_annotation(item: &dyn ItemHandle, cx: &mut App) -> Option<SharedString> {
+    if !IndentSizeSelectorSettings::get_global(cx).annotate_tab_switcher {
+        return None;
+    }
+    let editor = item.act_as::<Editor>(cx)?;
+    let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+    let indent_size = detected_indentation(&buffer, cx)?;
+    Some(match indent_size.kind {
+        IndentKind::Space => format!("␣{}", indent_size.len).into(),
+        IndentKind::Tab => "⇥".into(),
+    })
+}
+
+pub struct IndentSizeSelector {
+    picker: Entity<Picker<IndentSizeSelectorDelegate>>,
+    _observe_remaining_files: Subscription,
+    _detect_indentation: Task<()>,
+    _compute_stats: Task<()>,
+}
+
+impl IndentSizeSelector {
+    fn register(editor: &mut Editor, _window: Option<&mut Window>, cx: &mut Context<Editor>) {
+        let editor_handle = cx.weak_entity();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |action: &Toggle, window, cx| {
+                    Self::toggle_applying_to(
+                        &editor_handle,
+                        TargetScope::ActiveFile,
+                        action.apply_to,
+                        window,
+                        cx,
+                    );
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &ToggleForPane, window, cx| {
+                    Self::toggle(&editor_handle, TargetScope::Pane, window, cx);
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |action: &SetIndentSize, window, cx| {
+                    if let Some(editor) = editor_handle.upgrade() {
+                        Self::apply_to_active_file(
+                            &editor,
+                            action.size.get(),
+                            action.hard_tabs,
+                            action.tab_width,
+                            window,
+                            cx,
+                        );
+                    }
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &ToggleHardTabs, window, cx| {
+                    let Some(editor) = editor_handle.upgrade() else {
+                        return;
+                    };
+                    if let Some(current) = read_indent_size(editor.read(cx), cx) {
+                        let hard_tabs = current.indent_size.kind == IndentKind::Space;
+                        Self::apply_to_active_file(
+                            &editor,
+                            current.indent_size.len,
+                            hard_tabs,
+                            NonZeroU32::new(current.tab_width),
+                            window,
+                            cx,
+                        );
+                    }
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &ToggleElasticTabs, _, cx| {
+                    if let Some(editor) = editor_handle.upgrade() {
+                        editor.update(cx, |editor, cx| {
+                            let enabled = editor.elastic_tabstops(cx);
+                            editor.set_elastic_tabstops(!enabled, cx);
+                        });
+                    }
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &ToggleIndentGuideColoring, _, cx| {
+                    if let Some(editor) = editor_handle.upgrade() {
+                        editor.update(cx, indent_guides::toggle_depth_coloring);
+                    }
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |action: &SetTabDisplayWidth, _, cx| {
+                    if let Some(editor) = editor_handle.upgrade() {
+                        editor.update(cx, |editor, cx| {
+                            editor.set_tab_size_override(action.width, cx)
+                        });
+                    }
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &UseTwoSpaces, window, cx| {
+                    if let Some(editor) = editor_handle.upgrade() {
+                        Self::apply_to_active_file(&editor, 2, false, None, window, cx);
+                    }
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &UseFourSpaces, window, cx| {
+                    if let Some(editor) = editor_handle.upgrade() {
+                        Self::apply_to_active_file(&editor, 4, false, None, window, cx);
+                    }
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &UseTabs, window, cx| {
+                    let Some(editor) = editor_handle.upgrade() else {
+                        return;
+                    };
+                    if let Some(current) = read_indent_size(editor.read(cx), cx) {
+                        Self::apply_to_active_file(
+                            &editor,
+                            current.indent_size.len,
+                            true,
+                            NonZeroU32::new(current.tab_width),
+                            window,
+                            cx,
+                        );
+                    }
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &ClearIndentationOverride, _, cx| {
+                    if let Some(editor) = editor_handle.upgrade() {
+                        clear_override::clear_override(&editor, cx);
+                    }
+                }
+            })
+            .detach();
+        if !editor.buffer().read(cx).is_singleton() {
+            editor
+                .register_action(move |_: &ToggleForExcerpts, window, cx| {
+                    Self::toggle(&editor_handle, TargetScope::Excerpts, window, cx);
+                })
+                .detach();
+        }
+    }
+
+    /// Applies `size` and `hard_tabs` to the file open in `editor`, through the same path as
+    /// confirming them in the selector.
+    fn apply_to_active_file(
+        editor: &Entity<Editor>,
+        size: u32,
+        hard_tabs: bool,
+        tab_width: Option<NonZeroU32>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(buffer) = file_buffer(editor.read(cx), cx) else {
+            return;
+        };
+        let tab_width = tab_width.filter(|tab_width| tab_width.get() != size);
+        if !hard_tabs {
+            if !tab_requirements::allows_spaces(editor, std::slice::from_ref(&buffer), cx) {
+                return;
+            }
+            recent_sizes::record_size(size, cx);
+        }
+        keep_preview_tab(editor, cx);
+        let start = Instant::now();
+        let old = indentation_events::resolved_indent_size(&buffer, cx);
+        let transaction = history::start_transaction(cx);
+        if let Some(applied_scope) = apply_indentation(
+            editor,
+            TargetScope::ActiveFile,
+            std::slice::from_ref(&buffer),
+            None,
+            IndentSizeSelectorSettings::get_global(cx).confirm_writes_to,
+            size,
+            hard_tabs,
+            tab_width,
+            None,
+            fix_on_save::converts_on_confirm(false, cx),
+            window,
+            cx,
+        ) {
+            indentation_events::emit_applied(
+                vec![(buffer, Some(old))],
+                size,
+                hard_tabs,
+                applied_scope,
+                cx,
+            );
+        }
+        history::finish_transaction(transaction, cx);
+        metrics::record(Span::Apply, start, cx);
+    }
+
+    /// Confirms the highlighted choice, converting the existing lines it's applied to as well.
+    fn confirm_and_convert(
+        &mut self,
+        _: &ConfirmAndConvert,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker.delegate.confirm_converting(false, true, window, cx)
+        });
+    }
+
+    /// Confirms the highlighted choice, writing it for the languages of the files it's applied
+    /// to.
+    fn confirm_for_languages(
+        &mut self,
+        _: &ConfirmForLanguages,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker.delegate.confirm_converting(true, false, window, cx)
+        });
+    }
+
+    fn toggle(editor: &WeakEntity<Editor>, scope: TargetScope, window: &mut Window, cx: &mut App) {
+        Self::toggle_applying_to(editor, scope, None, window, cx);
+    }
+
+    /// Toggles the selector, applying the chosen indentation to `apply_to` when it's set rather
+    /// than where `confirm_writes_to` says.
+    fn toggle_applying_to(
+        editor: &WeakEntity<Editor>,
+        scope: TargetScope,
+        apply_to: Option<ApplyTo>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let start = Instant::now();
+        let Some((workspace, editor)) = editor
+            .update(cx, |editor, cx| Some((editor.workspace()?, cx.entity())))
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+
+        workspace.update(cx, |workspace, cx| {
+            let targets = match scope {
+                TargetScope::ActiveFile => file_buffer(editor.read(cx), cx).into_iter().collect(),
+                TargetScope::Pane => workspace
+                    .active_pane()
+                    .read(cx)
+                    .items_of_type::<Editor>()
+                    .filter_map(|editor| file_buffer(editor.read(cx), cx))
+                    .collect(),
+                TargetScope::Excerpts => editor
+                    .read(cx)
+                    .buffer()
+                    .read(cx)
+                    .all_buffers_iter()
+                    .collect(),
+            };
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                IndentSizeSelector::new(editor, scope, targets, apply_to, window, cx)
+            });
+        });
+        metrics::record(Span::PickerOpen, start, cx);
+    }
+
+    fn new(
+        editor: Entity<Editor>,
+        scope: TargetScope,
+        targets: Vec<Entity<Buffer>>,
+        apply_to: Option<ApplyTo>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let current_indent_size =
+            read_indent_size(editor.read(cx), cx).map(|current| current.indent_size);
+        let delegate = IndentSizeSelectorDelegate::new(
+            cx.entity().downgrade(),
+            editor,
+            scope,
+            targets,
+            apply_to,
+            current_indent_size,
+            cx,
+        );
+        let snapshots = delegate
+            .targets
+            .iter()
+            .map(|buffer| buffer.read(cx).snapshot())
+            .collect::<Vec<_>>();
+        // Scanning a large file for its indentation would otherwise hold up opening the selector.
+        let detection = match (scope, delegate.targets.first()) {
+            (TargetScope::ActiveFile, Some(buffer)) => {
+                Some(detection::buffer_detection_in_background(buffer, cx))
+            }
+            _ => None,
+        };
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let _observe_remaining_files =
+            picker.update(cx, |_, cx| fix_on_save::observe_remaining_files(cx));
+        let _detect_indentation = match detection {
+            Some(detection) => picker.update(cx, |_, cx| {
+                cx.spawn_in(window, async move |picker, cx| {
+                    let detection = detection.await;
+                    picker
+                        .update_in(cx, |picker, window, cx| {
+                            picker.delegate.add_detected_choice(detection);
+                            picker.refresh(window, cx);
+                        })
+                        .ok();
+                })
+            }),
+            None => Task::ready(()),
+        };
+        let _compute_stats = picker.update(cx, |_, cx| {
+            cx.spawn(async move |picker, cx| {
+                let stats = cx
+                    .background_spawn(async move { IndentationStats::compute(&snapshots) })
+                    .await;
+                picker
+                    .update(cx, |picker, cx| {
+                        picker.delegate.stats = stats.summary().map(SharedString::from);
+                        cx.notify();
+                    })
+                    .ok();
+            })
+        });
+        Self {
+            picker,
+            _observe_remaining_files,
+            _detect_indentation,
+            _compute_stats,
+        }
+    }
+}
+
+impl Render for IndentSizeSelector {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("IndentSizeSelector")
+            .w(modal_width(window))
+            .on_action(cx.listener(Self::confirm_and_convert))
+            .on_action(cx.listener(Self::confirm_for_languages))
+            .child(self.picker.clone())
+    }
+}
+
+/// The width of the selector's modals when the window has room for it.
+const MODAL_WIDTH: Rems = rems(34.);
+
+/// Below this window width, such as on small laptop screens, the selector's modals drop their
+/// secondary text, keeping only what's needed to pick from their lists.
+const COMPACT_WINDOW_WIDTH: Rems = rems(48.);
+
+/// The width of the selector's modals, narrowed to keep them within the window.
+pub(crate) fn modal_width(window: &Window) -> Pixels {
+    let rem_size = window.rem_size();
+    let margin = rems(2.).to_pixels(rem_size);
+    MODAL_WIDTH
+        .to_pixels(rem_size)
+        .min(window.viewport_size().width - margin)
+        .max(px(0.))
+}
+
+/// Whether the window is too narrow for the secondary text of the selector's modals.
+pub(crate) fn is_compact(window: &Window) -> bool {
+    window.viewport_size().width < COMPACT_WINDOW_WIDTH.to_pixels(window.rem_size())
+}
+
+impl Focusable for IndentSizeSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for IndentSizeSelector {}
+impl ModalView for IndentSizeSelector {}
+
+/// The files that confirming the selector applies the chosen indentation to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TargetScope {
+    /// The file open in the editor the selector was toggled from.
+    ActiveFile,
+    /// The files open in every tab of the active pane.
+    Pane,
+    /// The files with an excerpt in the multibuffer the selector was toggled from.
+    Excerpts,
+}
+
+/// Promotes `editor` to a permanent tab if it's a preview tab, so that opening another file
+/// doesn't replace the tab whose indentation was just changed, or warns when that's disabled.
+/// Overrides are stored against the file's path rather than the editor, so they survive the
+/// preview tab becoming permanent.
+fn keep_preview_tab(editor: &Entity<Editor>, cx: &mut App) {
+    let Some(workspace) = editor.read(cx).workspace() else {
+        return;
+    };
+    let item_id = editor.entity_id();
+    let Some(pane) = workspace
+        .read(cx)
+        .panes()
+        .iter()
+        .find(|pane| pane.read(cx).is_active_preview_item(item_id))
+        .cloned()
+    else {
+        return;
+    };
+
+    if IndentSizeSelectorSettings::get_global(cx).promote_preview_tabs {
+        pane.update(cx, |pane, cx| {
+            pane.unpreview_item_if_preview(item_id);
+            cx.notify();
+        });
+    } else {
+        workspace.update(cx, |workspace, cx| {
+            struct PreviewTabIndentationChanged;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<PreviewTabIndentationChanged>(),
+                    "The indentation was changed in a preview tab, which closes when another file is opened",
+                )
+                .autohide(),
+                cx,
+            );
+        });
+    }
+}
+
+/// The words of a query, lowercased, with numbers spelled out as digits, so that "Two" finds
+/// "2 spaces".
+fn query_words(query: &str) -> Vec<String> {
+    const NUMBER_WORDS: [&str; 12] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven",
+        "twelve",
+    ];
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let word = word.to_lowercase();
+            match NUMBER_WORDS.iter().position(|number| *number == word) {
+                Some(index) => (index + 1).to_string(),
+                None => word,
+            }
+        })
+        .collect()
+}
+
+/// The size of spaces typed in the query, alone or along with "spaces" in either order, such as
+/// "4", "two" or "spaces 4".
+fn typed_size(words: &[String]) -> Option<u32> {
+    let mut sizes = words.iter().filter_map(|word| word.parse::<u32>().ok());
+    let size = sizes.next()?;
+    (sizes.next().is_none()
+        && words
+            .iter()
+            .all(|word| word.parse::<u32>().is_ok() || word.starts_with("space")))
+    .then_some(size)
+}
+
+/// The choice that `query` applies without confirming it, when it's the first key typed since
+/// `awaiting_first_key` was set: the indent size of a digit, when the selector offers it. Any
+/// other first key, such as the "t" of "tabs", is searched for like the rest of the query.
+fn quick_set_candidate(
+    awaiting_first_key: &mut bool,
+    query: &str,
+    choices: &[IndentSizeChoice],
+) -> Option<usize> {
+    if query.is_empty() || !mem::take(awaiting_first_key) || query.len() != 1 {
+        return None;
+    }
+    let size = query.parse().ok()?;
+    choices
+        .iter()
+        .position(|choice| *choice == IndentSizeChoice::Spaces(size))
+}
+
+/// The candidates to match queries against, one per choice, and the words of each candidate
+/// along with its choice's aliases.
+fn candidates(
+    choices: &[IndentSizeChoice],
+    profiles: &[IndentationProfileContent],
+    buffer_settings: &[Arc<dyn BufferSetting>],
+) -> (Arc<[StringMatchCandidate]>, Arc<[Vec<String>]>) {
+    let candidates = choices
+        .iter()
+        .enumerate()
+        .map(|(candidate_id, choice)| {
+            StringMatchCandidate::new(candidate_id, &choice.label(profiles, buffer_settings))
+        })
+        .collect::<Arc<[_]>>();
+    let candidate_words = choices
+        .iter()
+        .zip(candidates.iter())
+        .map(|(choice, candidate)| {
+            let mut words = query_words(&candidate.string);
+            words.extend(choice.aliases().iter().map(|alias| alias.to_string()));
+            words
+        })
+        .collect();
+    (candidates, candidate_words)
+}
+
+/// The candidates matching `query`, by fuzzy matching or by its `words`, with those in
+/// `exact_candidate_ids` first, or every candidate for an empty query.
+async fn query_matches(
+    query: &str,
+    words: &[String],
+    candidates: &[StringMatchCandidate],
+    candidate_words: &[Vec<String>],
+    exact_candidate_ids: &[usize],
+    background: BackgroundExecutor,
+) -> Vec<StringMatch> {
+    let mut matches = if query.is_empty() {
+        candidates
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| StringMatch {
+                candidate_id: index,
+                string: candidate.string.clone(),
+                positions: Vec::new(),
+                score: 0.0,
+            })
+            .collect()
+    } else {
+        let mut matches = match_strings(
+            candidates,
+            query,
+            false,
+            true,
+            100,
+            &Default::default(),
+            background,
+        )
+        .await;
+        add_word_matches(&mut matches, words, candidate_words, candidates);
+        matches
+    };
+    prefer_exact_matches(&mut matches, exact_candidate_ids, candidates);
+    matches
+}
+
+/// Whether every word of the query begins one of `candidate_words`, in any order, so that
+/// "spaces 4" finds "4 spaces" and "hard tabs" finds the choice toggling them.
+fn matches_words(words: &[String], candidate_words: &[String]) -> bool {
+    !words.is_empty()
+        && words.iter().all(|word| {
+            candidate_words
+                .iter()
+                .any(|candidate_word| candidate_word.starts_with(word.as_str()))
+        })
+}
+
+/// Adds the candidates whose words match the query, as [`matches_words`] checks, to the end
+/// of `matches` when fuzzy matching missed them.
+fn add_word_matches(
+    matches: &mut Vec<StringMatch>,
+    words: &[String],
+    candidate_words: &[Vec<String>],
+    candidates: &[StringMatchCandidate],
+) {
+    for (candidate_id, candidate_words) in candidate_words.iter().enumerate() {
+        if matches_words(words, candidate_words)
+            && !matches.iter().any(|mat| mat.candidate_id == candidate_id)
+        {
+            matches.push(StringMatch {
+                candidate_id,
+                string: candidates[candidate_id].string.clone(),
+                positions: Vec::new(),
+                score: 0.0,
+            });
+        }
+    }
+}
+
+/// Moves the candidates that exactly match a typed size to the front of `matches`, ahead of
+/// fuzzy hits such as "4" matching the "4" in "14 spaces".
+fn prefer_exact_matches(
+    matches: &mut Vec<StringMatch>,
+    exact_candidate_ids: &[usize],
+    candidates: &[StringMatchCandidate],
+) {
+    for &candidate_id in exact_candidate_ids.iter().rev() {
+        let exact_match = match matches
+            .iter()
+            .position(|mat| mat.candidate_id == candidate_id)
+        {
+            Some(ix) => matches.remove(ix),
+            None => StringMatch {
+                candidate_id,
+                string: candidates[candidate_id].string.clone(),
+                positions: Vec::new(),
+                score: 0.0,
+            },
+        };
+        matches.insert(0, exact_match);
+    }
+}
+
+/// The indentation override in place on the active buffer before the selector previewed
+/// choices on it.
+struct PreviewRestore {
+    buffer: Entity<Buffer>,
+    original: Option<IndentationOverride>,
+}
+
+impl PreviewRestore {
+    fn capture(buffer: &Entity<Buffer>, cx: &App) -> Self {
+        Self {
+            buffer: buffer.clone(),
+            original: buffer.read(cx).indentation_override().copied(),
+        }
+    }
+
+    fn restore(&self, cx: &mut App) {
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.set_indentation_override(self.original, cx)
+        });
+    }
+}
+
+/// The position of the newest cursor in `editor`. In a multibuffer, such as project search
+/// results, the file open in the editor is the one whose excerpt contains it.
+pub(crate) fn cursor_position(editor: &Editor) -> editor::Anchor {
+    editor.selections.newest_anchor().head()
+}
+
+/// Returns the buffer of the file open in `editor`.
+fn file_buffer(editor: &Editor, cx: &App) -> Option<Entity<Buffer>> {
+    editor
+        .buffer()
+        .read(cx)
+        .point_to_buffer_point(cursor_position(editor), cx)
+        .map(|(buffer, _)| buffer)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IndentSizeChoice {
+    Spaces(u32),
+    ToggleHardTabs,
+    /// Switches the active editor between regular and elastic tabs.
+    ToggleElasticTabs {
+        enabled: bool,
+    },
+    /// Applies the profile at this index of the `profiles` setting.
+    Profile(usize),
+    /// Opens the `.editorconfig` governing the file, for changes the quick picks can't make.
+    EditEditorconfig,
+    /// Removes the overrides the selector installed for the file.
+    ClearOverride,
+    /// Toggles re-indenting the files of the active file's language on save, in its worktree.
+    ToggleConvertOnSave {
+        enabled: bool,
+    },
+    /// Switches the active editor's indent guides between a color per depth and a single one.
+    ToggleIndentGuideColoring {
+        enabled: bool,
+    },
+    /// Displays tabs this many columns wide in the active editor alone, or with the file's tab
+    /// size again when `None`.
+    DisplayTabWidth(Option<u32>),
+    /// Picks a value of the buffer setting at this index of the registered ones.
+    BufferSetting(usize),
+    /// Applies the indentation detected in the active file's content.
+    DetectFromBuffer {
+        indent: DetectedIndent,
+        confidence: DetectionConfidence,
+    },
+}
+
+impl IndentSizeChoice {
+    /// Words a query may use for the choice besides those of its label.
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Self::Spaces(_) => &["soft"],
+            Self::ToggleHardTabs => &["hard", "soft"],
+            Self::ToggleElasticTabs { .. } => &["tabstops"],
+            Self::EditEditorconfig => &["open", "config"],
+            Self::ClearOverride => &["reset", "remove", "default"],
+            Self::ToggleConvertOnSave { .. } => &["reindent", "fix"],
+            Self::ToggleIndentGuideColoring { .. } => &["rainbow", "colors"],
+            Self::DisplayTabWidth(_) => &["width", "view"],
+            Self::DetectFromBuffer { .. } => &["auto", "guess"],
+            Self::Profile(_) | Self::BufferSetting(_) => &[],
+        }
+    }
+
+    fn label(
+        &self,
+        profiles: &[IndentationProfileContent],
+        buffer_settings: &[Arc<dyn BufferSetting>],
+    ) -> String {
+        match self {
+            Self::Spaces(size) => format!("{size} spaces"),
+            Self::ToggleHardTabs => "Toggle Spaces/Tabs".to_string(),
+            Self::ToggleElasticTabs { enabled: false } => "Elastic Tabs".to_string(),
+            Self::ToggleElasticTabs { enabled: true } => "Stop Using Elastic Tabs".to_string(),
+            Self::Profile(index) => profiles::label(&profiles[*index]),
+            Self::EditEditorconfig => format!("Edit {EDITORCONFIG_NAME}…"),
+            Self::ClearOverride => "Clear Indentation Override".to_string(),
+            Self::ToggleConvertOnSave { enabled: false } => {
+                "Convert Indentation on Save".to_string()
+            }
+            Self::ToggleConvertOnSave { enabled: true } => {
+                "Stop Converting Indentation on Save".to_string()
+            }
+            Self::ToggleIndentGuideColoring { enabled: false } => {
+                "Color Indent Guides by Depth".to_string()
+            }
+            Self::ToggleIndentGuideColoring { enabled: true } => {
+                "Stop Coloring Indent Guides by Depth".to_string()
+            }
+            Self::DisplayTabWidth(Some(width)) => {
+                format!("Display Tabs {width} Columns Wide in This Pane")
+            }
+            Self::DisplayTabWidth(None) => "Display Tabs at the File's Tab Size".to_string(),
+            Self::BufferSetting(index) => format!("{}…", buffer_settings[*index].name()),
+            Self::DetectFromBuffer {
+                indent: DetectedIndent::Tabs,
+                ..
+            } => "Detect from Buffer (tabs)".to_string(),
+            Self::DetectFromBuffer {
+                indent: DetectedIndent::Spaces(size),
+                ..
+            } => format!("Detect from Buffer ({size} spaces)"),
+        }
+    }
+}
+
+struct IndentSizeSelectorDelegate {
+    indent_size_selector: WeakEntity<IndentSizeSelector>,
+    editor: Entity<Editor>,
+    scope: TargetScope,
+    /// The buffers that confirming applies the chosen indentation to.
+    targets: Vec<Entity<Buffer>>,
+    /// Where confirming applies the chosen indentation to the active file, if it was opened
+    /// pre-targeted.
+    apply_to: Option<ApplyTo>,
+    current_indent_size: Option<IndentSize>,
+    /// The choice conventional for the active buffer's language, listed first.
+    suggested: Option<(IndentSizeChoice, LanguageName)>,
+    /// The default of the template the active file's project was scaffolded from, listed next.
+    template_default: Option<(IndentSizeChoice, &'static str)>,
+    /// Restores the active file's override after previewing choices, unless one is confirmed.
+    preview_restore: Option<PreviewRestore>,
+    selection_completed: bool,
+    /// Describes what confirming will change.
+    scope_description: SharedString,
+    /// Summarizes how the targets' lines are indented, once they've been counted.
+    stats: Option<SharedString>,
+    current_source: IndentSource,
+    /// The `profiles` setting when the selector was opened.
+    profiles: Vec<IndentationProfileContent>,
+    /// The buffer settings registered when the selector was opened.
+    buffer_settings: Vec<Arc<dyn BufferSetting>>,
+    choices: Vec<IndentSizeChoice>,
+    /// Shared with each query's matching task rather than copied into it.
+    candidates: Arc<[StringMatchCandidate]>,
+    /// The words of each candidate's label along with its choice's aliases, lowercased.
+    candidate_words: Arc<[Vec<String>]>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    /// The match under the mouse, whose sample is previewed instead of the selected one's.
+    hovered_index: Option<usize>,
+    /// Whether nothing was typed yet, so that a digit applies its indent size at once.
+    awaiting_first_key: bool,
+}
+
+impl IndentSizeSelectorDelegate {
+    fn new(
+        indent_size_selector: WeakEntity<IndentSizeSelector>,
+        editor: Entity<Editor>,
+        scope: TargetScope,
+        targets: Vec<Entity<Buffer>>,
+        apply_to: Option<ApplyTo>,
+        current_indent_size: Option<IndentSize>,
+        cx: &App,
+    ) -> Self {
+        let settings = IndentSizeSelectorSettings::get_global(cx);
+        let sizes = settings
+            .sizes
+            .iter()
+            .copied()
+            .filter(|size| *size > 0)
+            .collect::<Vec<_>>();
+        let mut choices = recent_sizes::prioritize_recent(&sizes, &recent_sizes::recent_sizes(cx))
+            .into_iter()
+            .map(IndentSizeChoice::Spaces)
+            .collect::<Vec<_>>();
+        if settings.show_hard_tabs {
+            choices.push(IndentSizeChoice::ToggleHardTabs);
+            if scope == TargetScope::ActiveFile {
+                choices.push(IndentSizeChoice::ToggleElasticTabs {
+                    enabled: editor.read(cx).elastic_tabstops(cx),
+                });
+            }
+        }
+        let choice_for = |convention| match convention {
+            Convention::Spaces(size) => Some(IndentSizeChoice::Spaces(size)),
+            Convention::Tabs
+                if settings.show_hard_tabs
+                    && current_indent_size
+                        .is_none_or(|current| current.kind == IndentKind::Space) =>
+            {
+                Some(IndentSizeChoice::ToggleHardTabs)
+            }
+            Convention::Tabs => None,
+        };
+        let suggested = editor
+            .read(cx)
+            .language_at(cursor_position(editor.read(cx)), cx)
+            .and_then(|language| {
+                let choice = choice_for(conventional_indentation(language.name().as_ref())?)?;
+                Some((choice, language.name()))
+            });
+        let template_default = project_template(editor.read(cx), cx)
+            .and_then(|(template_name, convention)| Some((choice_for(convention)?, template_name)));
+        // Inserting the template's default first leaves it right after the language's convention.
+        for choice in [
+            template_default.map(|(choice, _)| choice),
+            suggested.as_ref().map(|(choice, _)| *choice),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            choices.retain(|existing| *existing != choice);
+            choices.insert(0, choice);
+        }
+        let profiles = settings.profiles.clone();
+        choices.extend((0..profiles.len()).map(IndentSizeChoice::Profile));
+        // What's detected in the active file is added once it's scanned in the background.
+        choices.push(IndentSizeChoice::EditEditorconfig);
+        if scope == TargetScope::ActiveFile
+            && targets
+                .first()
+                .is_some_and(|buffer| clear_override::has_override(buffer, cx))
+        {
+            choices.push(IndentSizeChoice::ClearOverride);
+        }
+        if scope == TargetScope::ActiveFile
+            && let Some(buffer) = targets.first()
+            && buffer.read(cx).language().is_some()
+        {
+            choices.push(IndentSizeChoice::ToggleConvertOnSave {
+                enabled: LanguageSettings::for_buffer(buffer.read(cx), cx)
+                    .convert_indentation_on_save,
+            });
+        }
+        if scope == TargetScope::ActiveFile {
+            choices.push(IndentSizeChoice::ToggleIndentGuideColoring {
+                enabled: editor.read(cx).indent_guide_coloring(cx)
+                    == IndentGuideColoring::IndentAware,
+            });
+        }
+        // Only a file indented with tabs looks any different with another tab width.
+        if scope == TargetScope::ActiveFile
+            && current_indent_size.is_some_and(|current| current.kind == IndentKind::Tab)
+        {
+            let tab_size_override = editor.read(cx).tab_size_override(cx);
+            let displayed_width = tab_size_override
+                .map(NonZeroU32::get)
+                .or(current_indent_size.map(|current| current.len));
+            choices.extend(
+                sizes
+                    .iter()
+                    .filter(|size| Some(**size) != displayed_width)
+                    .map(|size| IndentSizeChoice::DisplayTabWidth(Some(*size))),
+            );
+            if tab_size_override.is_some() {
+                choices.push(IndentSizeChoice::DisplayTabWidth(None));
+            }
+        }
+        let buffer_settings = buffer_settings::registered_settings(cx);
+        if scope == TargetScope::ActiveFile && !targets.is_empty() {
+            choices.extend((0..buffer_settings.len()).map(IndentSizeChoice::BufferSetting));
+        }
+        let (candidates, candidate_words) = candidates(&choices, &profiles, &buffer_settings);
+        let preview_restore = match (scope, targets.first()) {
+            (TargetScope::ActiveFile, Some(buffer)) => Some(PreviewRestore::capture(buffer, cx)),
+            _ => None,
+        };
+        let scope_description = scope_description(&editor, scope, &targets, apply_to, cx);
+        let current_source = IndentSource::for_editor(editor.read(cx), cx);
+
+        Self {
+            indent_size_selector,
+            editor,
+            scope,
+            targets,
+            apply_to,
+            current_indent_size,
+            suggested,
+            template_default,
+            preview_restore,
+            selection_completed: false,
+            scope_description,
+            stats: None,
+            current_source,
+            profiles,
+            buffer_settings,
+            choices,
+            candidates,
+            candidate_words,
+            matches: Vec::new(),
+            selected_index: 0,
+            hovered_index: None,
+            awaiting_first_key: IndentSizeSelectorSettings::get_global(cx).quick_set_keys,
+        }
+    }
+
+    /// Offers to use what was detected in the active file, unless the file is already indented
+    /// with it, before the choice editing its `.editorconfig`.
+    fn add_detected_choice(&mut self, detection: Detection) {
+        let Some((indent, confidence)) = detection.indent.zip(detection.confidence()) else {
+            return;
+        };
+        if self
+            .current_indent_size
+            .is_some_and(|current| detection::expected_indent(current) == indent)
+        {
+            return;
+        }
+        let index = self
+            .choices
+            .iter()
+            .position(|choice| *choice == IndentSizeChoice::EditEditorconfig)
+            .unwrap_or(self.choices.len());
+        self.choices.insert(
+            index,
+            IndentSizeChoice::DetectFromBuffer { indent, confidence },
+        );
+        (self.candidates, self.candidate_words) =
+            candidates(&self.choices, &self.profiles, &self.buffer_settings);
+    }
+
+    /// The match whose sample is previewed beside the selector: the hovered one, or else the
+    /// selected one.
+    fn previewed_index(&self) -> usize {
+        self.hovered_index.unwrap_or(self.selected_index)
+    }
+
+    fn is_current(&self, choice: IndentSizeChoice) -> bool {
+        match (choice, self.current_indent_size) {
+            (IndentSizeChoice::Spaces(size), Some(current)) => {
+                current.kind == IndentKind::Space && current.len == size
+            }
+            _ => false,
+        }
+    }
+
+    /// The indent size that confirming `choice` applies, if it applies one.
+    fn size_for(&self, choice: IndentSizeChoice) -> Option<u32> {
+        match choice {
+            IndentSizeChoice::Spaces(size) => Some(size),
+            IndentSizeChoice::Profile(index) => Some(self.profiles[index].tab_size.get()),
+            IndentSizeChoice::ToggleHardTabs
+            | IndentSizeChoice::DetectFromBuffer {
+                indent: DetectedIndent::Tabs,
+                ..
+            } => Some(
+                self.current_indent_size
+                    .map_or(4, |indent_size| indent_size.len),
+            ),
+            IndentSizeChoice::DetectFromBuffer {
+                indent: DetectedIndent::Spaces(size),
+                ..
+            } => Some(size),
+            IndentSizeChoice::ToggleElasticTabs { .. }
+            | IndentSizeChoice::EditEditorconfig
+            | IndentSizeChoice::ClearOverride
+            | IndentSizeChoice::ToggleConvertOnSave { .. }
+            | IndentSizeChoice::ToggleIndentGuideColoring { .. }
+            | IndentSizeChoice::DisplayTabWidth(_)
+            | IndentSizeChoice::BufferSetting(_) => None,
+        }
+    }
+
+    /// The key binding of the action that applies `choice` to the active file without opening
+    /// the selector, if it has one, shown beside the choice to teach the faster path.
+    fn key_binding_for(
+        &self,
+        choice: IndentSizeChoice,
+        window: &Window,
+        cx: &App,
+    ) -> Option<KeyBinding> {
+        if self.scope != TargetScope::ActiveFile {
+            return None;
+        }
+        let action: Box<dyn Action> = match choice {
+            IndentSizeChoice::Spaces(2) => Box::new(UseTwoSpaces),
+            IndentSizeChoice::Spaces(4) => Box::new(UseFourSpaces),
+            IndentSizeChoice::Spaces(size) => Box::new(SetIndentSize {
+                size: NonZeroU32::new(size)?,
+                hard_tabs: false,
+                tab_width: None,
+            }),
+            IndentSizeChoice::ToggleHardTabs => Box::new(ToggleHardTabs),
+            IndentSizeChoice::ToggleElasticTabs { .. } => Box::new(ToggleElasticTabs),
+            IndentSizeChoice::ToggleIndentGuideColoring { .. } => {
+                Box::new(ToggleIndentGuideColoring)
+            }
+            IndentSizeChoice::DisplayTabWidth(width) => Box::new(SetTabDisplayWidth {
+                width: width.and_then(NonZeroU32::new),
+            }),
+            IndentSizeChoice::ClearOverride => Box::new(ClearIndentationOverride),
+            IndentSizeChoice::DetectFromBuffer { .. } => Box::new(Detect),
+            IndentSizeChoice::BufferSetting(index) => self.buffer_settings[index].action()?,
+            IndentSizeChoice::Profile(_)
+            | IndentSizeChoice::EditEditorconfig
+            | IndentSizeChoice::ToggleConvertOnSave { .. } => return None,
+        };
+        // The actions are handled by the editor, whose bindings don't apply in the selector.
+        let key_binding =
+            KeyBinding::for_action_in(action.as_ref(), &self.editor.focus_handle(cx), cx);
+        key_binding
+            .has_binding(window)
+            .then(|| key_binding.size(rems_from_px(12.)))
+    }
+
+    /// Whether confirming `choice` indents with tabs rather than spaces.
+    fn hard_tabs_for(&self, choice: IndentSizeChoice) -> bool {
+        match choice {
+            IndentSizeChoice::ToggleHardTabs => self
+                .current_indent_size
+                .is_none_or(|indent_size| indent_size.kind == IndentKind::Space),
+            IndentSizeChoice::DetectFromBuffer { indent, .. } => indent == DetectedIndent::Tabs,
+            IndentSizeChoice::Profile(index) => self.profiles[index].hard_tabs.unwrap_or(false),
+            IndentSizeChoice::Spaces(_)
+            | IndentSizeChoice::ToggleElasticTabs { .. }
+            | IndentSizeChoice::EditEditorconfig
+            | IndentSizeChoice::ClearOverride
+            | IndentSizeChoice::ToggleConvertOnSave { .. }
+            | IndentSizeChoice::ToggleIndentGuideColoring { .. }
+            | IndentSizeChoice::DisplayTabWidth(_)
+            | IndentSizeChoice::BufferSetting(_) => false,
+        }
+    }
+
+    /// Applies the highlighted choice to the active file until the selector is dismissed.
+    fn show_selected_size(&self, cx: &mut App) {
+        let Some(preview_restore) = &self.preview_restore else {
+            return;
+        };
+        let Some(mat) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let choice = self.choices[mat.candidate_id];
+        let Some(tab_size) = self.size_for(choice).and_then(NonZeroU32::new) else {
+            return;
+        };
+        let hard_tabs = self.hard_tabs_for(choice);
+        preview_restore.buffer.update(cx, |buffer, cx| {
+            buffer.set_indentation_override(
+                Some(IndentationOverride {
+                    tab_size,
+                    tab_width: None,
+                    hard_tabs,
+                }),
+                cx,
+            )
+        });
+    }
+
+    /// Enables or disables re-indenting the files of the active file's language on save, in the
+    /// settings of its worktree, or the user's settings for a file outside of any.
+    fn set_convert_on_save(&self, enabled: bool, cx: &mut App) {
+        let Some(project) = self.editor.read(cx).project().cloned() else {
+            return;
+        };
+        let Some(buffer) = self.targets.first().map(|buffer| buffer.read(cx)) else {
+            return;
+        };
+        let Some(language_name) = buffer
+            .language()
+            .map(|language| language.name().to_string())
+        else {
+            return;
+        };
+        let file = match buffer.file() {
+            Some(file) => LanguageOverrideFile::Project(file.worktree_id(cx)),
+            None => LanguageOverrideFile::User,
+        };
+        apply_scope::update_language_settings(
+            &project,
+            file,
+            vec![language_name],
+            move |language_settings| {
+                language_settings.convert_indentation_on_save = Some(enabled);
+            },
+            cx,
+        );
+    }
+
+    /// Applies the highlighted choice to the targets as one change, converting their existing
+    /// lines too when `convert` is set.
+    fn confirm_converting(
+        &mut self,
+        for_languages: bool,
+        convert: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let start = Instant::now();
+        let transaction = history::start_transaction(cx);
+        self.confirm_selected(for_languages, convert, window, cx);
+        history::finish_transaction(transaction, cx);
+        metrics::record(Span::Apply, start, cx);
+    }
+
+    /// Applies the highlighted choice to the targets, then dismisses the selector. With
+    /// `for_languages`, it's written for the files' languages in the user settings, wherever
+    /// `confirm_writes_to` would.
+    fn confirm_selected(
+        &mut self,
+        for_languages: bool,
+        convert: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            match self.choices[mat.candidate_id] {
+                IndentSizeChoice::EditEditorconfig => {
+                    governing_editorconfig::open_governing_editorconfig(&self.editor, window, cx);
+                    self.dismissed(window, cx);
+                    return;
+                }
+                IndentSizeChoice::ClearOverride => {
+                    // The override the preview would restore is among those being cleared.
+                    self.selection_completed = true;
+                    clear_override::clear_override(&self.editor, cx);
+                    self.dismissed(window, cx);
+                    return;
+                }
+                IndentSizeChoice::ToggleElasticTabs { enabled } => {
+                    self.editor
+                        .update(cx, |editor, cx| editor.set_elastic_tabstops(!enabled, cx));
+                    self.dismissed(window, cx);
+                    return;
+                }
+                IndentSizeChoice::ToggleIndentGuideColoring { .. } => {
+                    self.editor.update(cx, indent_guides::toggle_depth_coloring);
+                    self.dismissed(window, cx);
+                    return;
+                }
+                IndentSizeChoice::DisplayTabWidth(width) => {
+                    self.editor.update(cx, |editor, cx| {
+                        editor.set_tab_size_override(width.and_then(NonZeroU32::new), cx)
+                    });
+                    self.dismissed(window, cx);
+                    return;
+                }
+                IndentSizeChoice::ToggleConvertOnSave { enabled } => {
+                    self.set_convert_on_save(!enabled, cx);
+                    self.dismissed(window, cx);
+                    return;
+                }
+                IndentSizeChoice::BufferSetting(index) => {
+                    let setting = self.buffer_settings[index].clone();
+                    let editor = self.editor.clone();
+                    // The setting's selector replaces this one once it's dismissed.
+                    window.defer(cx, move |window, cx| {
+                        buffer_settings::BufferSettingSelector::toggle(
+                            &editor, setting, window, cx,
+                        );
+                    });
+                    self.dismissed(window, cx);
+                    return;
+                }
+                IndentSizeChoice::Spaces(_)
+                | IndentSizeChoice::ToggleHardTabs
+                | IndentSizeChoice::Profile(_)
+                | IndentSizeChoice::DetectFromBuffer { .. } => {}
+            }
+            let Some(size) = self.size_for(self.choices[mat.candidate_id]) else {
+                self.dismissed(window, cx);
+                return;
+            };
+            let hard_tabs = self.hard_tabs_for(self.choices[mat.candidate_id]);
+            // Refusing spaces dismisses the selector, restoring the indentation it previewed.
+            if !hard_tabs && !tab_requirements::allows_spaces(&self.editor, &self.targets, cx) {
+                self.dismissed(window, cx);
+                return;
+            }
+
+            if let IndentSizeChoice::Spaces(size) = self.choices[mat.candidate_id] {
+                recent_sizes::record_size(size, cx);
+            }
+            if self.scope == TargetScope::ActiveFile {
+                keep_preview_tab(&self.editor, cx);
+            }
+
+            let profile = match self.choices[mat.candidate_id] {
+                IndentSizeChoice::Profile(index) => Some(self.profiles[index].clone()),
+                _ => None,
+            };
+            let convert = fix_on_save::converts_on_confirm(convert, cx);
+            let (apply_to, confirm_writes_to) = if for_languages {
+                (None, IndentSizeSelectorConfirmTarget::UserSettings)
+            } else {
+                (
+                    self.apply_to,
+                    IndentSizeSelectorSettings::get_global(cx).confirm_writes_to,
+                )
+            };
+            // Asking where to apply it to the active file dismisses the selector first, restoring
+            // the indentation it previewed.
+            self.selection_completed = self.scope != TargetScope::ActiveFile
+                || apply_to.is_some()
+                || confirm_writes_to != IndentSizeSelectorConfirmTarget::Ask;
+            // The active file is indented with the previewed choice by now, so its indentation
+            // before is the one the selector was opened with.
+            let previous = self
+                .targets
+                .iter()
+                .map(|buffer| {
+                    let old = if self.scope == TargetScope::ActiveFile {
+                        self.current_indent_size
+                    } else {
+                        Some(indentation_events::resolved_indent_size(buffer, cx))
+                    };
+                    (buffer.clone(), old)
+                })
+                .collect::<Vec<_>>();
+            if let Some(applied_scope) = apply_indentation(
+                &self.editor,
+                self.scope,
+                &self.targets,
+                apply_to,
+                confirm_writes_to,
+                size,
+                hard_tabs,
+                None,
+                profile,
+                convert,
+                window,
+                cx,
+            ) {
+                indentation_events::emit_applied(previous, size, hard_tabs, applied_scope, cx);
+            }
+        }
+        self.dismissed(window, cx);
+    }
+}
+
+/// Applies `size` and `hard_tabs` to `targets`, the files with the given `scope` relative to
+/// `editor`, as confirming them in the selector does: writing them to `apply_to` for the active
+/// file when it's set, or else where `confirm_writes_to` says, asking where to apply them to the
+/// active file, or applying them to every file in broader scopes. With `convert`, the targets'
+/// existing lines are re-indented too. The rest of `profile`, when one was chosen, is applied
+/// along with them, except to the buffers of broader scopes, which only take an indentation.
+/// A `tab_width` apart from `size` can't be written to settings files, so where to apply it to
+/// the active file is asked rather than taken from `confirm_writes_to`. Returns how broadly it
+/// was applied, or `None` when it's asked where to apply it.
+fn apply_indentation(
+    editor: &Entity<Editor>,
+    scope: TargetScope,
+    targets: &[Entity<Buffer>],
+    apply_to: Option<ApplyTo>,
+    confirm_writes_to: IndentSizeSelectorConfirmTarget,
+    size: u32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+    profile: Option<IndentationProfileContent>,
+    convert: bool,
+    window: &mut Window,
+    cx: &mut App,
+) -> Option<IndentSizeSelectorOverrideScope> {
+    if scope == TargetScope::ActiveFile
+        && let Some(apply_to) = apply_to
+        && let Some(size) = NonZeroU32::new(size)
+    {
+        if let Some(applied_scope) = apply_scope::ApplyScopeSelector::apply_preselected(
+            editor,
+            apply_to,
+            size,
+            hard_tabs,
+            tab_width,
+            profile.clone(),
+            convert,
+            cx,
+        ) {
+            return Some(applied_scope);
+        }
+        // A file outside of any worktree has no worktree or project settings to write to, so
+        // its language's override goes to the user settings instead, or its buffer alone is
+        // overridden without a language.
+        if matches!(apply_to, ApplyTo::Worktree | ApplyTo::Workspace)
+            && let Some(buffer) = targets.first()
+            && unsupported_files::is_outside_worktree(buffer.read(cx))
+        {
+            let fallback = if buffer.read(cx).language().is_some() {
+                ApplyTo::Language
+            } else {
+                ApplyTo::Buffer
+            };
+            return apply_scope::ApplyScopeSelector::apply_preselected(
+                editor, fallback, size, hard_tabs, tab_width, profile, convert, cx,
+            );
+        }
+    }
+
+    if confirm_writes_to != IndentSizeSelectorConfirmTarget::Ask && tab_width.is_none() {
+        apply_to_language(
+            editor,
+            targets,
+            size,
+            hard_tabs,
+            profile.as_ref(),
+            confirm_writes_to,
+            cx,
+        );
+        if let Some(workspace) = editor.read(cx).workspace() {
+            let indentation = if hard_tabs {
+                "Tabs".to_string()
+            } else {
+                format!("{size} spaces")
+            };
+            let settings = match confirm_writes_to {
+                IndentSizeSelectorConfirmTarget::ProjectSettings => "the project's settings",
+                IndentSizeSelectorConfirmTarget::Ask
+                | IndentSizeSelectorConfirmTarget::UserSettings => "your user settings",
+            };
+            history::describe(
+                workspace.downgrade(),
+                format!("{indentation} in {settings} for the files' languages"),
+                cx,
+            );
+        }
+        // Only the active file has a selection to limit the conversion to.
+        let selected_rows = if convert && scope == TargetScope::ActiveFile {
+            editor.update(cx, |editor, cx| fix_on_save::selected_rows(editor, cx))
+        } else {
+            Vec::new()
+        };
+        let project = editor.read(cx).project().cloned();
+        let mut not_applied = None;
+        for buffer in targets {
+            if convert {
+                if buffer.read(cx).read_only() {
+                    not_applied.get_or_insert(unsupported_files::READ_ONLY);
+                } else {
+                    fix_on_save::convert_existing_indentation(
+                        buffer,
+                        &selected_rows,
+                        size,
+                        hard_tabs,
+                        cx,
+                    );
+                }
+            }
+            // The language's override went to the user settings for a file outside of any
+            // worktree, which has no project settings.
+            let in_user_settings = unsupported_files::is_outside_worktree(buffer.read(cx))
+                && buffer.read(cx).language().is_some();
+            let project_settings_unavailable = project
+                .as_ref()
+                .filter(|_| {
+                    confirm_writes_to == IndentSizeSelectorConfirmTarget::ProjectSettings
+                        && buffer.read(cx).file().is_some()
+                        && !in_user_settings
+                })
+                .and_then(|project| {
+                    unsupported_files::project_settings_unavailable(project, buffer.read(cx), cx)
+                });
+            if let Some(reason) = project_settings_unavailable {
+                not_applied.get_or_insert(reason);
+            }
+            if buffer.read(cx).file().is_some() && project_settings_unavailable.is_none() {
+                history::set_indentation_override(buffer, None, cx);
+            } else if let Some(tab_size) = NonZeroU32::new(size) {
+                // Neither the user's nor the project's settings reach a buffer without a file,
+                // unless it has a language, and the project's settings can't always be written
+                // for one with a file, so it's overridden directly.
+                set_buffer_override(
+                    buffer,
+                    IndentationOverride {
+                        tab_size,
+                        tab_width: None,
+                        hard_tabs,
+                    },
+                    cx,
+                );
+            }
+        }
+        if scope == TargetScope::ActiveFile
+            && let Some(reason) = not_applied
+            && let Some(workspace) = editor.read(cx).workspace()
+        {
+            unsupported_files::show_not_applied(&workspace.downgrade(), reason, cx);
+        }
+        return Some(IndentSizeSelectorOverrideScope::Language);
+    }
+
+    if scope == TargetScope::ActiveFile {
+        if let Some(size) = NonZeroU32::new(size) {
+            let editor = editor.clone();
+            window.defer(cx, move |window, cx| {
+                apply_scope::ApplyScopeSelector::toggle(
+                    &editor, size, hard_tabs, tab_width, profile, convert, window, cx,
+                );
+            });
+        }
+        return None;
+    }
+
+    let workspace = editor.read(cx).workspace()?;
+    BulkApplyResults::apply_and_show(
+        &workspace, targets, size, hard_tabs, tab_width, None, convert, cx,
+    );
+    Some(IndentSizeSelectorOverrideScope::File)
+}
+
+/// Writes `size` and `hard_tabs` as an override for the languages of `targets` in the user
+/// settings, or in each target's project settings for `ProjectSettings`, so that every buffer of
+/// those languages picks it up, along with the rest of `profile`.
+fn apply_to_language(
+    editor: &Entity<Editor>,
+    targets: &[Entity<Buffer>],
+    size: u32,
+    hard_tabs: bool,
+    profile: Option<&IndentationProfileContent>,
+    confirm_writes_to: IndentSizeSelectorConfirmTarget,
+    cx: &mut App,
+) {
+    let Some(project) = editor.read(cx).project().cloned() else {
+        return;
+    };
+    let Some(tab_size) = NonZeroU32::new(size) else {
+        return;
+    };
+    let settings = IndentSizeSelectorSettings::get_global(cx);
+    let mut languages_by_file = Vec::<(Option<WorktreeId>, Vec<String>)>::new();
+    for buffer in targets {
+        let buffer = buffer.read(cx);
+        let Some(language) = buffer.language() else {
+            continue;
+        };
+        if !settings.allows_scope(
+            Some(&language.name()),
+            IndentSizeSelectorOverrideScope::Language,
+        ) {
+            continue;
+        }
+        let worktree_id = match confirm_writes_to {
+            IndentSizeSelectorConfirmTarget::ProjectSettings => {
+                let Some(file) = buffer.file() else {
+                    continue;
+                };
+                if unsupported_files::is_outside_worktree(buffer) {
+                    // It has no project settings, leaving the user's.
+                    None
+                } else if unsupported_files::project_settings_unavailable(&project, buffer, cx)
+                    .is_some()
+                {
+                    continue;
+                } else {
+                    Some(file.worktree_id(cx))
+                }
+            }
+            IndentSizeSelectorConfirmTarget::Ask
+            | IndentSizeSelectorConfirmTarget::UserSettings => None,
+        };
+        let language_name = language.name().to_string();
+        match languages_by_file
+            .iter_mut()
+            .find(|(existing, _)| *existing == worktree_id)
+        {
+            Some((_, language_names)) if language_names.contains(&language_name) => {}
+            Some((_, language_names)) => language_names.push(language_name),
+            None => languages_by_file.push((worktree_id, vec![language_name])),
+        }
+    }
+
+    for (worktree_id, language_names) in languages_by_file {
+        let file = match worktree_id {
+            Some(worktree_id) => LanguageOverrideFile::Project(worktree_id),
+            None => LanguageOverrideFile::User,
+        };
+        apply_scope::write_language_override(
+            &project,
+            file,
+            language_names,
+            tab_size,
+            hard_tabs,
+            profile.cloned(),
+            cx,
+        );
+    }
+}
+
+/// The template that the project of the file open in `editor` was scaffolded from, judging by
+/// the files at the root of its worktree, along with the indentation it starts with.
+fn project_template(editor: &Editor, cx: &App) -> Option<(&'static str, Convention)> {
+    let file = editor.file_at(cursor_position(editor), cx)?;
+    let worktree = editor
+        .project()?
+        .read(cx)
+        .worktree_for_id(file.worktree_id(cx), cx)?;
+    let worktree = worktree.read(cx);
+    template_indentation(|file_name| {
+        RelPath::unix(file_name)
+            .ok()
+            .and_then(|path| worktree.entry_for_path(path))
+            .is_some_and(|entry| entry.is_file())
+    })
+}
+
+fn scope_description(
+    editor: &Entity<Editor>,
+    scope: TargetScope,
+    targets: &[Entity<Buffer>],
+    apply_to: Option<ApplyTo>,
+    cx: &App,
+) -> SharedString {
+    let outside_worktree = scope == TargetScope::ActiveFile
+        && targets
+            .first()
+            .is_some_and(|buffer| unsupported_files::is_outside_worktree(buffer.read(cx)));
+    if outside_worktree {
+        let has_language = targets
+            .first()
+            .is_some_and(|buffer| buffer.read(cx).language().is_some());
+        let falls_back = matches!(apply_to, Some(ApplyTo::Worktree | ApplyTo::Workspace))
+            || (apply_to.is_none()
+                && IndentSizeSelectorSettings::get_global(cx).confirm_writes_to
+                    == IndentSizeSelectorConfirmTarget::ProjectSettings);
+        if falls_back {
+            return if has_language {
+                "This file is outside of any worktree, so it writes an override for the language \
+                 to your user settings"
+                    .into()
+            } else {
+                "This file is outside of any worktree, so it applies to this buffer until it's \
+                 closed"
+                    .into()
+            };
+        }
+    }
+    if scope == TargetScope::ActiveFile {
+        match apply_to {
+            None => {}
+            Some(ApplyTo::Buffer) => return "Applies it to this buffer until it's closed".into(),
+            Some(ApplyTo::Language) => {
+                return "Writes an override for the language to your user settings".into();
+            }
+            Some(ApplyTo::Worktree) => {
+                return format!("Writes an {EDITORCONFIG_NAME} override for this worktree").into();
+            }
+            Some(ApplyTo::Workspace) => {
+                return "Writes the indentation to this workspace's .zed/settings.json".into();
+            }
+        }
+    }
+    match IndentSizeSelectorSettings::get_global(cx).confirm_writes_to {
+        IndentSizeSelectorConfirmTarget::Ask => {}
+        IndentSizeSelectorConfirmTarget::UserSettings => {
+            return "Writes an override for the language to your user settings".into();
+        }
+        IndentSizeSelectorConfirmTarget::ProjectSettings => {
+            return "Writes an override for the language to the project's settings".into();
+        }
+    }
+    match scope {
+        TargetScope::ActiveFile => {}
+        TargetScope::Pane => {
+            return format!(
+                "Writes {EDITORCONFIG_NAME} overrides for the {} tabs in this pane",
+                targets.len()
+            )
+            .into();
+        }
+        TargetScope::Excerpts => {
+            return format!(
+                "Writes {EDITORCONFIG_NAME} overrides for the {} files in these results",
+                targets.len()
+            )
+            .into();
+        }
+    }
+    let editor = editor.read(cx);
+    match editor.file_at(cursor_position(editor), cx) {
+        Some(file) => format!(
+            "Previews on {}, then asks where to apply it",
+            file.path().display(PathStyle::local())
+        )
+        .into(),
+        None => "Asks where to apply it".into(),
+    }
+}
+
+/// The `.editorconfig` properties that set the indentation. Tabs indent by one tab `size`
+/// columns wide, unless `tab_width` sets the width of a tab apart from the `size` of a level.
+/// Spaces leave the width of a tab to the rest of the configuration without a `tab_width`,
+/// since code indented with spaces may still render the tabs it contains at a different width.
+pub(crate) fn indentation_properties(
+    size: u32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+) -> Vec<(&'static str, String)> {
+    let tab_width = tab_width
+        .map(NonZeroU32::get)
+        .filter(|tab_width| *tab_width != size);
+    let mut properties = if hard_tabs {
+        let indent_size = match tab_width {
+            Some(_) => size.to_string(),
+            None => "tab".to_string(),
+        };
+        vec![
+            ("indent_style", "tab".to_string()),
+            ("indent_size", indent_size),
+            ("tab_width", tab_width.unwrap_or(size).to_string()),
+        ]
+    } else {
+        vec![
+            ("indent_style", "space".to_string()),
+            ("indent_size", size.to_string()),
+        ]
+    };
+    if !hard_tabs && let Some(tab_width) = tab_width {
+        properties.push(("tab_width", tab_width.to_string()));
+    }
+    properties
+}
+
+/// Writes an `.editorconfig` override for the file of `buffer`, along with the rest of `profile`
+/// when one was chosen, and remembers it for later sessions, or overrides the buffer itself when
+/// no override can be written for its file, such as when it has none. Returns whether it was
+/// applied, which the buffer's language may not allow.
+pub(crate) fn apply_to_path(
+    buffer: &Entity<Buffer>,
+    size: u32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+    profile: Option<&IndentationProfileContent>,
+    cx: &mut App,
+) -> bool {
+    let language_name = buffer.read(cx).language().map(|language| language.name());
+    if !IndentSizeSelectorSettings::get_global(cx).allows_scope(
+        language_name.as_ref(),
+        IndentSizeSelectorOverrideScope::File,
+    ) {
+        return false;
+    }
+    apply_to_paths(
+        std::slice::from_ref(buffer),
+        size,
+        hard_tabs,
+        tab_width,
+        profile,
+        cx,
+    )
+}
+
+/// Like [`apply_to_path`] for each of `buffers`, without checking what their languages allow.
+/// The overrides for their files are written together, so when one of them fails none is
+/// applied and this returns `false`.
+pub(crate) fn apply_to_paths(
+    buffers: &[Entity<Buffer>],
+    size: u32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+    profile: Option<&IndentationProfileContent>,
+    cx: &mut App,
+) -> bool {
+    let Some(tab_size) = NonZeroU32::new(size) else {
+        return false;
+    };
+    let mut properties = indentation_properties(size, hard_tabs, tab_width);
+    if let Some(profile) = profile {
+        properties.extend(profiles::editorconfig_properties(profile));
+    }
+    let mut batch = OverrideBatch::default();
+    for buffer in buffers {
+        if let Some(file) = overridable_file(buffer.read(cx)) {
+            let worktree_id = file.worktree_id(cx);
+            let content = file_override_content(worktree_id, file.path(), &properties, cx);
+            batch.set(
+                worktree_id,
+                LocalSettingsPath::InWorktree(file.path().clone()),
+                Some(content),
+            );
+        }
+    }
+    if batch.commit(cx).log_err().is_none() {
+        return false;
+    }
+
+    for buffer in buffers {
+        if overridable_file(buffer.read(cx)).is_some() {
+            // An override for this buffer alone would keep shadowing the file's new indentation.
+            history::set_indentation_override(buffer, None, cx);
+            persistence::remember_choice(buffer, size, hard_tabs, tab_width, cx);
+        } else {
+            set_buffer_override(
+                buffer,
+                IndentationOverride {
+                    tab_size,
+                    tab_width,
+                    hard_tabs,
+                },
+                cx,
+            );
+        }
+    }
+    true
+}
+
+/// Returns the in-memory `.editorconfig` content set at `path`, such as an override from this
+/// selector.
+pub(crate) fn editorconfig_override(
+    worktree_id: WorktreeId,
+    path: &LocalSettingsPath,
+    cx: &App,
+) -> Option<String> {
+    cx.global::<SettingsStore>()
+        .editorconfig_store
+        .read(cx)
+        .local_editorconfig_settings(worktree_id)
+        .find(|(config_path, _, _)| config_path == path)
+        .map(|(_, content, _)| content.to_string())
+}
+
+/// The `.editorconfig` glob matching files of the same type as `path`, such as `*.json`, or
+/// files with the same name when it has no extension.
+fn file_type_glob(path: &RelPath) -> String {
+    match path.extension() {
+        Some(extension) => format!("*.{}", escape_glob(extension)),
+        None => escape_glob(path.file_name().unwrap_or_default()),
+    }
+}
+
+fn escape_glob(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '{' | '}' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Overrides the indentation of `buffer` until it's closed. Buffers without a file, such as
+/// untitled ones, keep the override when they're saved by moving it to the new file's path.
+pub(crate) fn set_buffer_override(
+    buffer: &Entity<Buffer>,
+    indentation_override: IndentationOverride,
+    cx: &mut App,
+) {
+    let carry_over = {
+        let buffer = buffer.read(cx);
+        // The indentation of new untitled buffers only lasts until they're closed, so one
+        // chosen in its place is carried over to the file.
+        let untitled_indentation = IndentSizeSelectorSettings::get_global(cx).untitled_indentation;
+        buffer.file().is_none()
+            && buffer
+                .indentation_override()
+                .is_none_or(|current| Some(*current) == untitled_indentation)
+    };
+    if carry_over {
+        cx.subscribe(buffer, |buffer, event: &BufferEvent, cx| {
+            if *event != BufferEvent::FileHandleChanged || buffer.read(cx).file().is_none() {
+                return;
+            }
+            if let Some(indentation_override) = buffer.read(cx).indentation_override().copied() {
+                apply_to_path(
+                    &buffer,
+                    indentation_override.tab_size.get(),
+                    indentation_override.hard_tabs,
+                    indentation_override.tab_width,
+                    None,
+                    cx,
+                );
+            }
+        })
+        .detach();
+    }
+    history::set_indentation_override(buffer, Some(indentation_override), cx);
+}
+
+/// Writes an `.editorconfig` override for the file of `buffer`, or overrides the buffer itself
+/// when no override can be written for its file, returning whether either was applied. The
+/// override's section only matches files of the same type, and its indentation keys are merged
+/// into any override already in place, keeping its other keys and sections.
+fn set_path_override(
+    buffer: &Entity<Buffer>,
+    size: u32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+    cx: &mut App,
+) -> bool {
+    let Some(file) = overridable_file(buffer.read(cx)).cloned() else {
+        let Some(tab_size) = NonZeroU32::new(size) else {
+            return false;
+        };
+        set_buffer_override(
+            buffer,
+            IndentationOverride {
+                tab_size,
+                tab_width,
+                hard_tabs,
+            },
+            cx,
+        );
+        return true;
+    };
+    let applied = set_file_override(
+        file.worktree_id(cx),
+        file.path(),
+        size,
+        hard_tabs,
+        tab_width,
+        cx,
+    );
+    if applied {
+        // An override for this buffer alone would keep shadowing the file's new indentation.
+        history::set_indentation_override(buffer, None, cx);
+    }
+    applied
+}
+
+/// The `.editorconfig` override that sets `properties`, such as the ones of an indentation, for
+/// the file at `path`, whether or not it's open, merged into the override already in place there.
+pub(crate) fn file_override_content(
+    worktree_id: WorktreeId,
+    path: &Arc<RelPath>,
+    properties: &[(&str, String)],
+    cx: &App,
+) -> String {
+    let override_path = LocalSettingsPath::InWorktree(path.clone());
+    let existing = editorconfig_override(worktree_id, &override_path, cx).unwrap_or_default();
+    governing_editorconfig::set_section_properties(&existing, &file_type_glob(path), properties)
+}
+
+/// Writes an `.editorconfig` override for the file at `path`, returning whether it was applied.
+fn set_file_override(
+    worktree_id: WorktreeId,
+    path: &Arc<RelPath>,
+    size: u32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+    cx: &mut App,
+) -> bool {
+    let properties = indentation_properties(size, hard_tabs, tab_width);
+    let config = file_override_content(worktree_id, path, &properties, cx);
+    let mut batch = OverrideBatch::default();
+    batch.set(
+        worktree_id,
+        LocalSettingsPath::InWorktree(path.clone()),
+        Some(config),
+    );
+    batch.commit(cx).log_err().is_some()
+}
+
+impl PickerDelegate for IndentSizeSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select an indent size…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn render_footer(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        if is_compact(window) {
+            return None;
+        }
+        let mixed_indentation = match self.scope {
+            TargetScope::ActiveFile => MixedIndentation::in_editor(&self.editor, cx),
+            _ => None,
+        };
+        let editor = self.editor.read(cx);
+        let project = editor.project().cloned();
+        let workspace_id = editor
+            .workspace()
+            .and_then(|workspace| workspace.read(cx).database_id());
+        let remaining_files = project
+            .filter(|_| IndentSizeSelectorSettings::get_global(cx).fix_on_save)
+            .map(|project| {
+                fix_on_save::remaining_files_message(fix_on_save::remaining_files(
+                    &project,
+                    workspace_id,
+                    cx,
+                ))
+            });
+        let exotic_whitespace = match (self.scope, self.targets.first()) {
+            (TargetScope::ActiveFile, Some(buffer)) => {
+                match detection::exotic_whitespace_lines(buffer, cx) {
+                    0 => None,
+                    1 => Some(
+                        "1 line is indented with unusual whitespace, such as non-breaking spaces"
+                            .to_string(),
+                    ),
+                    count => Some(format!(
+                        "{count} lines are indented with unusual whitespace, such as non-breaking spaces"
+                    )),
+                }
+            }
+            _ => None,
+        };
+        Some(
+            v_flex()
+                .w_full()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .p_1p5()
+                .gap_0p5()
+                .child(
+                    Label::new(self.scope_description.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    Label::new(format!(
+                        "The current indentation comes from {}",
+                        self.current_source.label()
+                    ))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+                .children(
+                    self.stats
+                        .clone()
+                        .map(|stats| Label::new(stats).size(LabelSize::Small).color(Color::Muted)),
+                )
+                .children(exotic_whitespace.map(|exotic_whitespace| {
+                    Label::new(exotic_whitespace)
+                        .size(LabelSize::Small)
+                        .color(Color::Warning)
+                }))
+                .children(mixed_indentation.map(|(buffer, mixed_indentation)| {
+                    h_flex()
+                        .w_full()
+                        .gap_1()
+                        .justify_between()
+                        .child(
+                            Label::new(mixed_indentation.description())
+                                .size(LabelSize::Small)
+                                .color(Color::Warning),
+                        )
+                        .child(
+                            Button::new(
+                                "normalize-mixed-indentation",
+                                mixed_indentation.fix_label(),
+                            )
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(
+                                move |_, _, _, cx| {
+                                    mixed_indentation.normalize(&buffer, cx);
+                                    cx.notify();
+                                },
+                            )),
+                        )
+                }))
+                .children(remaining_files.map(|remaining_files| {
+                    Label::new(remaining_files)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                }))
+                .child(
+                    h_flex()
+                        .w_full()
+                        .pt_1()
+                        .gap_0p5()
+                        .justify_end()
+                        .child(
+                            Button::new("confirm-for-languages", "For Languages")
+                                .key_binding(
+                                    KeyBinding::for_action(&ConfirmForLanguages, cx)
+                                        .map(|kb| kb.size(rems_from_px(12.))),
+                                )
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(ConfirmForLanguages.boxed_clone(), cx)
+                                }),
+                        )
+                        .child(
+                            Button::new("confirm-and-convert", "Apply and Convert")
+                                .key_binding(
+                                    KeyBinding::for_action(&menu::SecondaryConfirm, cx)
+                                        .map(|kb| kb.size(rems_from_px(12.))),
+                                )
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                                }),
+                        )
+                        .child(
+                            Button::new("confirm", "Apply")
+                                .key_binding(
+                                    KeyBinding::for_action(&menu::Confirm, cx)
+                                        .map(|kb| kb.size(rems_from_px(12.))),
+                                )
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                                }),
+                        ),
+                )
+                .into_any(),
+        )
+    }
+
+    /// Confirming applies the setting alone, while a secondary confirm converts the existing
+    /// lines to it as well, in the same change.
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.confirm_converting(false, secondary, window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if !self.selection_completed {
+            if let Some(preview_restore) = &self.preview_restore {
+                preview_restore.restore(cx);
+            }
+            self.selection_completed = true;
+        }
+
+        self.indent_size_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+        self.show_selected_size(cx);
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        let candidate_words = self.candidate_words.clone();
+        let start = Instant::now();
+        let words = query_words(&query);
+        let exact_size = typed_size(&words);
+        let quick_set_candidate_id =
+            quick_set_candidate(&mut self.awaiting_first_key, &query, &self.choices);
+        let exact_candidate_ids = self
+            .choices
+            .iter()
+            .enumerate()
+            .filter(|(_, choice)| {
+                exact_size.is_some_and(|size| **choice == IndentSizeChoice::Spaces(size))
+            })
+            .map(|(candidate_id, _)| candidate_id)
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = query_matches(
+                &query,
+                &words,
+                &candidates,
+                &candidate_words,
+                &exact_candidate_ids,
+                background,
+            )
+            .await;
+
+            this.update_in(cx, |this, window, cx| {
+                metrics::record(Span::Match, start, cx);
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                if let Some(ix) = quick_set_candidate_id.and_then(|candidate_id| {
+                    delegate
+                        .matches
+                        .iter()
+                        .position(|mat| mat.candidate_id == candidate_id)
+                }) {
+                    delegate.selected_index = ix;
+                    delegate.confirm(false, window, cx);
+                    return;
+                }
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let choice = self.choices[mat.candidate_id];
+        // The labels of suggested choices are secondary text, dropped on narrow windows.
+        let is_compact = is_compact(window);
+
+        let mut list_item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected)
+            .on_hover(cx.listener(move |picker, hovered, _, cx| {
+                let delegate = &mut picker.delegate;
+                if *hovered {
+                    delegate.hovered_index = Some(ix);
+                } else if delegate.hovered_index == Some(ix) {
+                    delegate.hovered_index = None;
+                }
+                cx.notify();
+            }))
+            .child(HighlightedLabel::new(
+                mat.string.clone(),
+                mat.positions.clone(),
+            ));
+
+        let mut detail = None;
+        if self.is_current(choice) {
+            detail = Some(
+                Icon::new(IconName::Check)
+                    .color(Color::Muted)
+                    .into_any_element(),
+            );
+        } else if !is_compact && let IndentSizeChoice::DetectFromBuffer { confidence, .. } = choice
+        {
+            detail = Some(
+                Label::new(format!("{} confidence", confidence.label()))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .into_any_element(),
+            );
+        } else if !is_compact
+            && let Some((_, language_name)) = self
+                .suggested
+                .as_ref()
+                .filter(|(suggested, _)| *suggested == choice)
+        {
+            detail = Some(
+                Label::new(format!("{language_name} convention"))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .into_any_element(),
+            );
+        } else if !is_compact
+            && let Some((_, template_name)) = self
+                .template_default
+                .as_ref()
+                .filter(|(template_default, _)| *template_default == choice)
+        {
+            detail = Some(
+                Label::new(format!("{template_name} template default"))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .into_any_element(),
+            );
+        }
+
+        let key_binding = self.key_binding_for(choice, window, cx);
+        if detail.is_some() || key_binding.is_some() {
+            list_item = list_item.end_slot(h_flex().gap_2().children(detail).children(key_binding));
+        }
+
+        Some(list_item)
+    }
+
+    fn documentation_aside(
+        &self,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<DocumentationAside> {
+        let choice = self.choices[self.matches.get(self.previewed_index())?.candidate_id];
+        let size = self.size_for(choice)?;
+        let hard_tabs = self.hard_tabs_for(choice);
+        Some(DocumentationAside::new(
+            DocumentationSide::Right,
+            Rc::new(move |cx| sample_preview::render_sample(size, hard_tabs, cx)),
+        ))
+    }
+
+    fn documentation_aside_index(&self) -> Option<usize> {
+        Some(self.previewed_index())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace};
+
+    #[test]
+    fn test_prefer_exact_matches() {
+        let candidates = ["14 spaces", "4 spaces", "Toggle Spaces/Tabs"]
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, label)| StringMatchCandidate::new(candidate_id, label))
+            .collect::<Vec<_>>();
+        let fuzzy_match = |candidate_id: usize| StringMatch {
+            candidate_id,
+            string: candidates[candidate_id].string.clone(),
+            positions: Vec::new(),
+            score: 1.0,
+        };
+
+        let mut matches = vec![fuzzy_match(0), fuzzy_match(1)];
+        prefer_exact_matches(&mut matches, &[1], &candidates);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|mat| mat.candidate_id)
+                .collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+
+        let mut matches = vec![fuzzy_match(0)];
+        prefer_exact_matches(&mut matches, &[1], &candidates);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|mat| mat.candidate_id)
+                .collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+    }
+
+    #[test]
+    fn test_query_words() {
+        assert_eq!(query_words("Spaces, Two"), vec!["spaces", "2"]);
+        assert_eq!(typed_size(&query_words("spaces 4")), Some(4));
+        assert_eq!(typed_size(&query_words("two spaces")), Some(2));
+        assert_eq!(typed_size(&query_words("4 tabs")), None);
+        assert_eq!(typed_size(&query_words("2 4")), None);
+
+        let words = |label: &str, choice: IndentSizeChoice| {
+            let mut words = query_words(label);
+            words.extend(choice.aliases().iter().map(|alias| alias.to_string()));
+            words
+        };
+        let toggle_hard_tabs = words("Toggle Spaces/Tabs", IndentSizeChoice::ToggleHardTabs);
+        assert!(matches_words(&query_words("hard tabs"), &toggle_hard_tabs));
+        assert!(matches_words(&query_words("tab"), &toggle_hard_tabs));
+        assert!(!matches_words(
+            &query_words("elastic tabs"),
+            &toggle_hard_tabs
+        ));
+        assert!(!matches_words(&query_words(""), &toggle_hard_tabs));
+        let four_spaces = words("4 spaces", IndentSizeChoice::Spaces(4));
+        assert!(matches_words(&query_words("spaces 4"), &four_spaces));
+        assert!(!matches_words(&query_words("spaces 2"), &four_spaces));
+    }
+
+    #[test]
+    fn test_quick_set_candidate() {
+        let choices = [
+            IndentSizeChoice::Spaces(2),
+            IndentSizeChoice::Spaces(4),
+            IndentSizeChoice::ToggleHardTabs,
+        ];
+        let first_key = |query: &str| quick_set_candidate(&mut true, query, &choices);
+        assert_eq!(first_key("4"), Some(1));
+        // Sizes the selector doesn't offer, letters and longer queries are left to be searched.
+        assert_eq!(first_key("3"), None);
+        assert_eq!(first_key("12"), None);
+        assert_eq!(first_key("t"), None);
+        assert_eq!(first_key(""), None);
+
+        // Only the first key typed applies its choice, and the empty query doesn't count.
+        let mut awaiting_first_key = true;
+        assert_eq!(
+            quick_set_candidate(&mut awaiting_first_key, "", &choices),
+            None
+        );
+        assert_eq!(
+            quick_set_candidate(&mut awaiting_first_key, "t", &choices),
+            None
+        );
+        assert_eq!(
+            quick_set_candidate(&mut awaiting_first_key, "2", &choices),
+            None
+        );
+        assert!(!awaiting_first_key);
+    }
+
+    #[gpui::test]
+    async fn test_typing_words_after_opening(cx: &mut TestAppContext) {
+        let choices = [
+            IndentSizeChoice::Spaces(2),
+            IndentSizeChoice::Spaces(4),
+            IndentSizeChoice::ToggleHardTabs,
+        ];
+        let labels = ["2 spaces", "4 spaces", "Toggle Spaces/Tabs"];
+        let candidates = labels
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, label)| StringMatchCandidate::new(candidate_id, label))
+            .collect::<Vec<_>>();
+        let candidate_words = labels
+            .iter()
+            .zip(&choices)
+            .map(|(label, choice)| {
+                let mut words = query_words(label);
+                words.extend(choice.aliases().iter().map(|alias| alias.to_string()));
+                words
+            })
+            .collect::<Vec<_>>();
+
+        // Typing "tabs" or "two" key by key filters the list rather than confirming a choice.
+        for (query, expected) in [("tabs", 2), ("two", 0)] {
+            let mut awaiting_first_key = true;
+            for len in 1..=query.len() {
+                assert_eq!(
+                    quick_set_candidate(&mut awaiting_first_key, &query[..len], &choices),
+                    None
+                );
+            }
+            let words = query_words(query);
+            let exact_candidate_ids = typed_size(&words)
+                .and_then(|size| {
+                    choices
+                        .iter()
+                        .position(|choice| *choice == IndentSizeChoice::Spaces(size))
+                })
+                .into_iter()
+                .collect::<Vec<_>>();
+            let matches = query_matches(
+                query,
+                &words,
+                &candidates,
+                &candidate_words,
+                &exact_candidate_ids,
+                cx.executor(),
+            )
+            .await;
+            assert_eq!(
+                matches.first().map(|mat| mat.candidate_id),
+                Some(expected),
+                "{query}"
+            );
+            assert!(matches.len() < candidates.len(), "{query}");
+        }
+    }
+
+    #[gpui::test]
+    async fn test_apply_tab_width_apart_from_indent_size(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn f() {\n\tg();\n}\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+        });
+        cx.run_until_parked();
+
+        // Tabs rendered 8 wide, indenting by 4 columns, as in `:set ts=8 sw=4 noet`.
+        let tab_width = NonZeroU32::new(8);
+        assert!(cx.update(|_, cx| apply_to_path(&buffer, 4, true, tab_width, None, cx)));
+        cx.run_until_parked();
+        let prescribed = cx.update(|_, cx| read_indent_size(editor.read(cx), cx).unwrap());
+        assert_eq!(
+            prescribed,
+            PrescribedIndentation {
+                indent_size: IndentSize {
+                    len: 4,
+                    kind: IndentKind::Tab,
+                },
+                tab_width: 8,
+            }
+        );
+        let content = cx.update(|_, cx| {
+            let file = buffer.read(cx).file().unwrap();
+            let path = LocalSettingsPath::InWorktree(file.path().clone());
+            editorconfig_override(file.worktree_id(cx), &path, cx).unwrap()
+        });
+        assert_eq!(
+            content,
+            "[*.rs]\nindent_style = tab\nindent_size = 4\ntab_width = 8\n"
+        );
+
+        // A buffer without a file is overridden with both.
+        let untitled = cx.update(|_, cx| cx.new(|cx| Buffer::local("", cx)));
+        assert!(cx.update(|_, cx| apply_to_path(&untitled, 4, true, tab_width, None, cx)));
+        let indentation_override =
+            cx.update(|_, cx| untitled.read(cx).indentation_override().copied());
+        assert_eq!(
+            indentation_override,
+            Some(IndentationOverride {
+                tab_size: NonZeroU32::new(4).unwrap(),
+                tab_width,
+                hard_tabs: true,
+            })
+        );
+    }
+
+    #[gpui::test]
+    async fn test_read_indent_size_of_plain_text(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings(cx, |content| {
+                    let defaults = &mut content.project.all_languages.defaults;
+                    defaults.tab_size = NonZeroU32::new(3);
+                    defaults.hard_tabs = Some(true);
+                });
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "notes.txt": "a\n\tb\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/notes.txt"), cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+        });
+        cx.run_until_parked();
+
+        // Without a language, the settings for every language apply.
+        assert!(buffer.read_with(cx, |buffer, _| buffer.language().is_none()));
+        let prescribed = cx.update(|_, cx| read_indent_size(editor.read(cx), cx));
+        assert_eq!(
+            prescribed,
+            Some(PrescribedIndentation {
+                indent_size: IndentSize {
+                    len: 3,
+                    kind: IndentKind::Tab,
+                },
+                tab_width: 3,
+            })
+        );
+        let whitespace = cx.update(|_, cx| resolved_whitespace(editor.read(cx), cx).unwrap());
+        assert!(whitespace.hard_tabs);
+        assert_eq!(whitespace.indent_size.get(), 3);
+    }
+
+    #[gpui::test]
+    async fn test_detected_choice_added_in_background(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({ "a.rs": "fn f() {\n  if a {\n    b();\n  }\n  c();\n}\n" }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace =
+            multi_workspace.read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone());
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.rs")), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        let editor = cx.update(|_, cx| item.act_as::<Editor>(cx).unwrap());
+        cx.run_until_parked();
+
+        cx.update(|window, cx| {
+            IndentSizeSelector::toggle(&editor.downgrade(), TargetScope::ActiveFile, window, cx)
+        });
+        let choices = |cx: &mut VisualTestContext| {
+            workspace.read_with(cx, |workspace, cx| {
+                let selector = workspace.active_modal::<IndentSizeSelector>(cx).unwrap();
+                let delegate = &selector.read(cx).picker.read(cx).delegate;
+                assert_eq!(delegate.candidates.len(), delegate.choices.len());
+                delegate.choices.clone()
+            })
+        };
+        // The file is scanned once the selector is open.
+        let detected = |choice: &IndentSizeChoice| {
+            matches!(
+                choice,
+                IndentSizeChoice::DetectFromBuffer {
+                    indent: DetectedIndent::Spaces(2),
+                    ..
+                }
+            )
+        };
+        assert!(!choices(cx).iter().any(detected));
+
+        cx.run_until_parked();
+        let choices = choices(cx);
+        let index = choices.iter().position(detected).unwrap();
+        assert_eq!(choices[index + 1], IndentSizeChoice::EditEditorconfig);
+    }
+
+    #[test]
+    fn test_indentation_properties_with_tab_width() {
+        assert_eq!(
+            indentation_properties(4, true, NonZeroU32::new(8)),
+            vec![
+                ("indent_style", "tab".to_string()),
+                ("indent_size", "4".to_string()),
+                ("tab_width", "8".to_string()),
+            ]
+        );
+        assert_eq!(
+            indentation_properties(2, false, NonZeroU32::new(8)),
+            vec![
+                ("indent_style", "space".to_string()),
+                ("indent_size", "2".to_string()),
+                ("tab_width", "8".to_string()),
+            ]
+        );
+        // A tab width matching the size is left implied.
+        assert_eq!(
+            indentation_properties(4, true, NonZeroU32::new(4)),
+            indentation_properties(4, true, None)
+        );
+    }
+
+    #[test]
+    fn test_file_type_glob() {
+        let glob = |path: &str| file_type_glob(RelPath::unix(path).unwrap());
+        assert_eq!(glob("config/settings.json"), "*.json");
+        assert_eq!(glob("Makefile"), "Makefile");
+        assert_eq!(glob("docs/[draft]"), "\\[draft\\]");
+    }
+
+    #[test]
+    fn test_indentation_section() {
+        let indentation_section = |glob, size, hard_tabs, tab_width| {
+            governing_editorconfig::set_section_properties(
+                "",
+                glob,
+                &indentation_properties(size, hard_tabs, tab_width),
+            )
+        };
+        assert_eq!(
+            indentation_section("*.rs", 4, false, None),
+            "[*.rs]\nindent_style = space\nindent_size = 4\n"
+        );
+        // Switching to tabs keeps the size as the width of a tab.
+        assert_eq!(
+            indentation_section("*.go", 8, true, None),
+            "[*.go]\nindent_style = tab\nindent_size = tab\ntab_width = 8\n"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use collections::HashMap;
+use language::{LanguageName, language_settings::IndentationOverride};
+use settings::{
+    IndentSizeSelectorConfirmTarget, IndentSizeSelectorOverrideScope,
+    IndentSizeSelectorTabsRequiredBehavior, IndentSizeSelectorUnresolvedBehavior,
+    IndentationProfileContent, RegisterSetting, Settings,
+};
+
+#[derive(Clone, Debug, PartialEq, RegisterSetting)]
+pub struct IndentSizeSelectorSettings {
+    /// The indent sizes offered by the selector, in spaces.
+    pub sizes: Vec<u32>,
+    /// Whether the selector offers switching between spaces and hard tabs.
+    pub show_hard_tabs: bool,
+    /// Whether the tab switcher shows the detected indentation of each file.
+    pub annotate_tab_switcher: bool,
+    /// Whether to mark the gutter of the lines indented differently than configured.
+    pub mark_inconsistent_lines: bool,
+    /// Whether to suggest a different indent size after repeated manual re-indentation.
+    pub suggest_from_corrections: bool,
+    /// Whether changing the indentation of a preview tab makes it permanent.
+    pub promote_preview_tabs: bool,
+    /// Where confirming a choice in the selector writes it.
+    pub confirm_writes_to: IndentSizeSelectorConfirmTarget,
+    /// Whether saving a file re-indents it to the configured indentation.
+    pub fix_on_save: bool,
+    /// Whether re-indenting on save only converts the lines changed since git's version.
+    pub preserve_unmodified_lines: bool,
+    /// Whether saving asks to fix the lines conflicting with the governing `.editorconfig`.
+    pub check_editorconfig_on_save: bool,
+    /// Whether fixing indentation on save also replaces unusual whitespace with spaces.
+    pub normalize_exotic_whitespace: bool,
+    /// Whether confirming an indentation also re-indents the existing lines it applies to.
+    pub convert_existing_indentation: bool,
+    /// Whether converting indentation keeps the spaces that align continuation lines.
+    pub align_with_spaces: bool,
+    /// Whether opening a file switches it to the indentation its content uses.
+    pub auto_detect_indentation: bool,
+    /// Whether opening a file indented differently than configured shows a notification.
+    pub notify_indentation_mismatch: bool,
+    /// What to do when opening a file whose indentation nothing determines.
+    pub when_unresolved: IndentSizeSelectorUnresolvedBehavior,
+    /// What to do when spaces are chosen for a file whose language requires tabs.
+    pub when_tabs_required: IndentSizeSelectorTabsRequiredBehavior,
+    /// The broadest scope each language's indentation may be applied to.
+    pub max_override_scope: HashMap<String, IndentSizeSelectorOverrideScope>,
+    /// The label of the indentation status bar item, with `{kind}` and `{size}` placeholders.
+    pub status_item_format: String,
+    /// The named indentations the selector offers next to the indent sizes.
+    pub profiles: Vec<IndentationProfileContent>,
+    /// The indentation of new untitled buffers, unless the default settings apply to them.
+    pub untitled_indentation: Option<IndentationOverride>,
+    /// Whether pasted lines are re-indented to the indentation of the file they're pasted into.
+    pub reindent_on_paste: bool,
+    /// Whether the configurations of Prettier, clang-format and rustfmt indent the files they
+    /// format.
+    pub read_formatter_configs: bool,
+    /// Whether empty files are indented as most files of their language in the project are.
+    pub new_files_use_project_convention: bool,
+    /// Whether a digit typed as the selector's first key applies its indent size at once.
+    pub quick_set_keys: bool,
+}
+
+impl IndentSizeSelectorSettings {
+    /// Whether an indentation chosen for a file of `language` may be applied to `scope`.
+    pub fn allows_scope(
+        &self,
+        language: Option<&LanguageName>,
+        scope: IndentSizeSelectorOverrideScope,
+    ) -> bool {
+        language
+            .and_then(|language| self.max_override_scope.get(language.as_ref()))
+            .is_none_or(|max_scope| scope <= *max_scope)
+    }
+}
+
+impl Settings for IndentSizeSelectorSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let selector = content.indent_size_selector.clone().unwrap();
+        Self {
+            sizes: selector.sizes.unwrap(),
+            show_hard_tabs: selector.show_hard_tabs.unwrap(),
+            annotate_tab_switcher: selector.annotate_tab_switcher.unwrap(),
+            mark_inconsistent_lines: selector.mark_inconsistent_lines.unwrap(),
+            suggest_from_corrections: selector.suggest_from_corrections.unwrap(),
+            promote_preview_tabs: selector.promote_preview_tabs.unwrap(),
+            confirm_writes_to: selector.confirm_writes_to.unwrap(),
+            fix_on_save: selector.fix_on_save.unwrap(),
+            preserve_unmodified_lines: selector.preserve_unmodified_lines.unwrap(),
+            check_editorconfig_on_save: selector.check_editorconfig_on_save.unwrap(),
+            normalize_exotic_whitespace: selector.normalize_exotic_whitespace.unwrap(),
+            convert_existing_indentation: selector.convert_existing_indentation.unwrap(),
+            align_with_spaces: selector.align_with_spaces.unwrap(),
+            auto_detect_indentation: selector.auto_detect_indentation.unwrap(),
+            notify_indentation_mismatch: selector.notify_indentation_mismatch.unwrap(),
+            when_unresolved: selector.when_unresolved.unwrap(),
+            when_tabs_required: selector.when_tabs_required.unwrap(),
+            max_override_scope: selector.max_override_scope.unwrap(),
+            status_item_format: selector.status_item_format.unwrap(),
+            profiles: selector.profiles.unwrap(),
+            untitled_indentation: selector.untitled_indentation.map(|indentation| {
+                IndentationOverride {
+                    tab_size: indentation.tab_size,
+                    tab_width: None,
+                    hard_tabs: indentation.hard_tabs.unwrap_or(false),
+                }
+            }),
+            reindent_on_paste: selector.reindent_on_paste.unwrap(),
+            read_formatter_configs: selector.read_formatter_configs.unwrap(),
+            new_files_use_project_convention: selector.new_files_use_project_convention.unwrap(),
+            quick_set_keys: selector.quick_set_keys.unwrap(),
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use ec4rs::{
+    PropertiesSource as _,
+    property::{IndentSize as EditorconfigIndentSize, IndentStyle, TabWidth},
+};
+use editor::Editor;
+use language::IndentKind;
+use paths::EDITORCONFIG_NAME;
+use settings::{
+    Editorconfig, EditorconfigProperties, LanguageSettingsContent, LocalSettingsPath,
+    ProjectSettingsContent, SettingsStore,
+};
+use ui::{App, SharedString};
+use util::{paths::PathStyle, rel_path::RelPath};
+
+use crate::{auto_detect, cursor_position, file_buffer, indentation_provider, resolved_whitespace};
+
+/// The indentation in effect for a file, as [`indent_info`] reads it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndentInfo {
+    /// How many columns each level of indentation occupies.
+    pub size: u32,
+    /// Whether each level is indented with tabs or spaces.
+    pub kind: IndentKind,
+    /// How many columns a tab character occupies.
+    pub tab_width: u32,
+    /// Where the indentation comes from.
+    pub source: IndentSource,
+}
+
+/// Reads the indentation in effect for the file open in `editor`, along with where it comes
+/// from, as the status item shows them. `None` when the editor has no file.
+pub fn indent_info(editor: &Editor, cx: &App) -> Option<IndentInfo> {
+    let whitespace = resolved_whitespace(editor, cx)?;
+    let kind = if whitespace.hard_tabs {
+        IndentKind::Tab
+    } else {
+        IndentKind::Space
+    };
+    Some(IndentInfo {
+        size: whitespace.indent_size.get(),
+        kind,
+        tab_width: whitespace.tab_width.get(),
+        source: IndentSource::for_editor(editor, cx),
+    })
+}
+
+/// Where the indentation currently in effect for a file comes from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IndentSource {
+    /// Zed's defaults, including those it ships for the file's language.
+    Default,
+    /// The user's settings, for the file's language when `language` is set, or else for every
+    /// language.
+    UserSettings { language: Option<SharedString> },
+    /// The project's settings, for the file's language when `language` is set, or else for
+    /// every language.
+    ProjectSettings { language: Option<SharedString> },
+    /// The `.editorconfig` at `path`, when it can be told apart from the others governing the
+    /// file.
+    Editorconfig { path: Option<SharedString> },
+    /// A Vim modeline or Emacs file variables in the file.
+    Modeline,
+    /// An `.editorconfig` override for the file that the selector installed.
+    SelectorOverride,
+    /// An override for the buffer until it's closed.
+    BufferOverride,
+    /// An override for the buffer, matching the indentation detected in it when it was opened.
+    Detected,
+    /// An override for the buffer, supplied by the registered provider with this name.
+    Provider { name: SharedString },
+}
+
+impl IndentSource {
+    /// Determines the source of the indentation for the file open in `editor`, checking the
+    /// layers from highest to lowest precedence.
+    pub fn for_editor(editor: &Editor, cx: &App) -> Self {
+        let language_name = editor
+            .language_at(cursor_position(editor), cx)
+            .map(|language| language.name().to_string());
+        let store = cx.global::<SettingsStore>();
+
+        if let Some(buffer) = file_buffer(editor, cx) {
+            let buffer_ref = buffer.read(cx);
+            if buffer_ref.indentation_override().is_some() {
+                if let Some(name) = indentation_provider::provider_of_override(&buffer, cx) {
+                    return Self::Provider { name };
+                }
+                if auto_detect::has_detected_override(&buffer, cx) {
+                    return Self::Detected;
+                }
+                return Self::BufferOverride;
+            }
+            if buffer_ref.modeline().is_some_and(|modeline| {
+                modeline.tab_size.is_some()
+                    || modeline.indent_size.is_some()
+                    || modeline.hard_tabs.is_some()
+            }) {
+                return Self::Modeline;
+            }
+        }
+        if let Some(file) = editor.file_at(cursor_position(editor), cx) {
+            let worktree_id = file.worktree_id(cx);
+            let path = file.path();
+            let editorconfig_store = store.editorconfig_store.read(cx);
+            let override_path = LocalSettingsPath::InWorktree(path.clone());
+            let has_override = editorconfig_store
+                .local_editorconfig_settings(worktree_id)
+                .any(|(config_path, _, _)| config_path == override_path);
+            if has_override {
+                return Self::SelectorOverride;
+            }
+            if let Some(properties) = editorconfig_store.properties(worktree_id, path)
+                && sets_editorconfig_indentation(&properties)
+            {
+                // The nearest `.editorconfig` setting any of the keys is taken to win, though
+                // one further up may still set the others.
+                let mut configs = editorconfig_store
+                    .local_editorconfig_settings(worktree_id)
+                    .filter_map(|(config_path, _, config)| {
+                        let depth = match &config_path {
+                            LocalSettingsPath::OutsideWorktree(_) => None,
+                            LocalSettingsPath::InWorktree(directory) => {
+                                if !path.starts_with(directory) {
+                                    return None;
+                                }
+                                Some(directory.ancestors().count())
+                            }
+                        };
+                        Some((depth, config_path, config?))
+                    })
+                    .collect::<Vec<_>>();
+                configs.sort_by_key(|(depth, _, _)| *depth);
+                let path = configs
+                    .into_iter()
+                    .rev()
+                    .find(|(_, _, config)| editorconfig_sets_indentation(config, path))
+                    .map(|(_, config_path, _)| editorconfig_label(&config_path));
+                return Self::Editorconfig { path };
+            }
+
+            let local_language = store
+                .local_settings(worktree_id)
+                .filter(|(settings_path, _)| path.starts_with(settings_path))
+                .filter_map(|(_, content)| sets_indentation(content, language_name.as_deref()))
+                .max();
+            if let Some(for_language) = local_language {
+                return Self::ProjectSettings {
+                    language: language_name
+                        .filter(|_| for_language)
+                        .map(SharedString::from),
+                };
+            }
+        }
+
+        let user_language = store.raw_user_settings().and_then(|settings| {
+            sets_indentation(&settings.content.project, language_name.as_deref())
+        });
+        if let Some(for_language) = user_language {
+            return Self::UserSettings {
+                language: language_name
+                    .filter(|_| for_language)
+                    .map(SharedString::from),
+            };
+        }
+        Self::Default
+    }
+
+    /// Whether nothing determines the indentation for the file open in `editor`, so that it
+    /// falls back to Zed's defaults: no override, `.editorconfig` or settings, and no defaults
+    /// that Zed ships for its language, if it has one.
+    pub(crate) fn is_unresolved(editor: &Editor, cx: &App) -> bool {
+        if Self::for_editor(editor, cx) != Self::Default {
+            return false;
+        }
+        let Some(language) = editor.language_at(cursor_position(editor), cx) else {
+            return true;
+        };
+        let defaults = &cx.global::<SettingsStore>().raw_default_settings().project;
+        !defaults
+            .all_languages
+            .languages
+            .0
+            .get(language.name().as_ref())
+            .is_some_and(sets_language_indentation)
+    }
+
+    /// Describes the source for the end of a sentence, such as "Indentation from …".
+    pub fn label(&self) -> SharedString {
+        match self {
+            Self::Default => "Zed's defaults".into(),
+            Self::UserSettings { language: None } => "your user settings".into(),
+            Self::UserSettings {
+                language: Some(language),
+            } => format!("your user settings for {language}").into(),
+            Self::ProjectSettings { language: None } => "the project's settings".into(),
+            Self::ProjectSettings {
+                language: Some(language),
+            } => format!("the project's settings for {language}").into(),
+            Self::Editorconfig { path: None } => "an .editorconfig file".into(),
+            Self::Editorconfig { path: Some(path) } => path.clone(),
+            Self::Modeline => "a modeline in the file".into(),
+            Self::SelectorOverride => "an earlier override from this selector".into(),
+            Self::BufferOverride => "an override for this buffer until it's closed".into(),
+            Self::Detected => "the indentation detected in the file when it was opened".into(),
+            Self::Provider { name } => name.clone(),
+        }
+    }
+}
+
+/// The path of the `.editorconfig` at `config_path`, relative to its worktree if it's in one.
+pub(crate) fn editorconfig_label(config_path: &LocalSettingsPath) -> SharedString {
+    match config_path {
+        LocalSettingsPath::InWorktree(directory) => directory
+            .join(RelPath::unix(EDITORCONFIG_NAME).unwrap())
+            .display(PathStyle::local())
+            .to_string()
+            .into(),
+        LocalSettingsPath::OutsideWorktree(path) => path.to_string_lossy().to_string().into(),
+    }
+}
+
+fn sets_editorconfig_indentation(properties: &EditorconfigProperties) -> bool {
+    properties.get::<IndentStyle>().is_ok()
+        || properties.get::<EditorconfigIndentSize>().is_ok()
+        || properties.get::<TabWidth>().is_ok()
+}
+
+/// Whether a section of `config` sets the indentation of the file at `path`.
+fn editorconfig_sets_indentation(config: &Editorconfig, path: &RelPath) -> bool {
+    let mut properties = EditorconfigProperties::new();
+    config.sections.iter().all(|section| {
+        section
+            .apply_to(&mut properties, path.as_std_path())
+            .is_ok()
+    }) && sets_editorconfig_indentation(&properties)
+}
+
+/// Whether `content` sets the indentation of files of `language_name`: `Some(true)` when set for
+/// that language, `Some(false)` when only set for every language.
+fn sets_indentation(content: &ProjectSettingsContent, language_name: Option<&str>) -> Option<bool> {
+    if language_name
+        .and_then(|name| content.all_languages.languages.0.get(name))
+        .is_some_and(sets_language_indentation)
+    {
+        Some(true)
+    } else if sets_language_indentation(&content.all_languages.defaults) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn sets_language_indentation(settings: &LanguageSettingsContent) -> bool {
+    settings.tab_size.is_some() || settings.hard_tabs.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::{IndentSize, language_settings::IndentationOverride};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use std::num::NonZeroU32;
+    use util::path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_indent_source_layers(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                ".editorconfig": "[*.rs]\nindent_style = space\nindent_size = 2\n",
+                "a.rs": "fn f() {\n\tg();\n}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+        });
+        cx.run_until_parked();
+        let info = |cx: &mut gpui::VisualTestContext| {
+            editor.update(cx, |editor, cx| indent_info(editor, cx).unwrap())
+        };
+
+        let editorconfig = info(cx);
+        assert_eq!(
+            (editorconfig.size, editorconfig.kind),
+            (2, IndentKind::Space)
+        );
+        assert_eq!(
+            editorconfig.source,
+            IndentSource::Editorconfig {
+                path: Some(".editorconfig".into())
+            }
+        );
+
+        // A modeline shadows the `.editorconfig`.
+        buffer.update(cx, |buffer, _| {
+            buffer.set_modeline(language::parse_modeline(&["// vim: sw=8 et"], &[]))
+        });
+        let modeline = info(cx);
+        assert_eq!(
+            (modeline.size, modeline.source),
+            (8, IndentSource::Modeline)
+        );
+
+        // An override for the buffer shadows the modeline, until it's cleared.
+        let indentation_override = IndentationOverride {
+            tab_size: NonZeroU32::new(3).unwrap(),
+            tab_width: None,
+            hard_tabs: false,
+        };
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_indentation_override(Some(indentation_override), cx)
+        });
+        let buffer_override = info(cx);
+        assert_eq!(
+            (buffer_override.size, buffer_override.source),
+            (3, IndentSource::BufferOverride)
+        );
+        buffer.update(cx, |buffer, cx| buffer.set_indentation_override(None, cx));
+        assert_eq!(info(cx).source, IndentSource::Modeline);
+
+        // So does the indentation detected in the file, which is told apart from other
+        // overrides.
+        editor.update(cx, |editor, cx| {
+            crate::auto_detect::apply_indentation(editor, &buffer, IndentSize::tab(), cx)
+        });
+        let detected = info(cx);
+        assert_eq!(
+            (detected.size, detected.kind, detected.source),
+            (8, IndentKind::Tab, IndentSource::Detected)
+        );
+
+        // Without the override and the modeline, the `.editorconfig` is in effect again.
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_indentation_override(None, cx);
+            buffer.set_modeline(None);
+        });
+        assert_eq!(info(cx), editorconfig);
+    }
+}
@@ -0,0 +1,898 @@
+use anyhow::Context as _;
+use ec4rs::{
+    PropertiesSource as _,
+    property::{IndentSize as EditorconfigIndentSize, IndentStyle, TabWidth},
+};
+use editor::Editor;
+use gpui::{ClickEvent, Entity, ExternalPaths, ScrollWheelEvent, Subscription, Task, WeakEntity};
+use language::{
+    Buffer, BufferEvent, BufferId, Detection, IndentKind, IndentLint, IndentSize, LanguageName,
+    ModelineSettings,
+    language_settings::{IndentationLintSeverity, IndentationOverride},
+};
+use paths::EDITORCONFIG_NAME;
+use settings::{Editorconfig, EditorconfigProperties, Settings as _, SettingsStore, WorktreeId};
+use std::{num::NonZeroU32, sync::Arc, time::Duration};
+use ui::{Tooltip, prelude::*};
+use util::{ResultExt as _, paths::PathStyle, rel_path::RelPath};
+use workspace::{StatusBarSettings, StatusItemView, item::ItemHandle};
+
+use crate::{
+    IndentSizeSelector, IndentSizeSelectorSettings, TargetScope, Toggle, cursor_position,
+    detection::buffer_detection,
+    file_buffer,
+    indent_source::IndentSource,
+    indentation_lints::{self, IndentationLintReport},
+    mixed_indentation::MixedIndentation,
+    project_conversion::ConversionProgress,
+    resolved_whitespace, set_buffer_override,
+    style_import::ImportTarget,
+};
+
+type IndentationLintKey = (
+    BufferId,
+    clock::Global,
+    u32,
+    Vec<(IndentLint, IndentationLintSeverity)>,
+);
+
+/// How long the item waits after the active editor notifies before reading it again, so that a
+/// burst of keystrokes or cursor moves updates it once.
+const UPDATE_DELAY: Duration = Duration::from_millis(50);
+
+/// What the indentation read for the file the cursor is in depends on, besides the settings.
+#[derive(Clone, Debug, PartialEq)]
+struct IndentSizeKey {
+    buffer_id: BufferId,
+    language: Option<LanguageName>,
+    path: Option<(WorktreeId, Arc<RelPath>)>,
+    modeline: Option<Arc<ModelineSettings>>,
+    indentation_override: Option<IndentationOverride>,
+    tab_size_override: Option<NonZeroU32>,
+}
+
+impl IndentSizeKey {
+    fn for_editor(editor: &Editor, cx: &App) -> Option<Self> {
+        let buffer = file_buffer(editor, cx)?;
+        let buffer = buffer.read(cx);
+        Some(Self {
+            buffer_id: buffer.remote_id(),
+            language: editor
+                .language_at(cursor_position(editor), cx)
+                .map(|language| language.name()),
+            path: buffer
+                .file()
+                .map(|file| (file.worktree_id(cx), file.path().clone())),
+            modeline: buffer.modeline().cloned(),
+            indentation_override: buffer.indentation_override().copied(),
+            tab_size_override: editor.tab_size_override(cx),
+        })
+    }
+}
+
+pub struct Indentation {
+    indent_size: Option<IndentSize>,
+    /// How many columns a tab occupies in the active file, when it differs from the indent size.
+    tab_width: Option<u32>,
+    /// The indentation and tab width last read with [`resolved_whitespace`], along with what
+    /// they were read for, so that they're only resolved again once the file, its language or
+    /// the settings change rather than on every keystroke. Cleared whenever the settings change.
+    indent_size_cache: Option<(IndentSizeKey, Option<(IndentSize, Option<u32>)>)>,
+    /// Where the indentation of the active file comes from.
+    source: Option<IndentSource>,
+    /// The path of the file the cursor is in, when the active editor shows excerpts of several
+    /// files, such as project search results.
+    excerpt_path: Option<SharedString>,
+    /// What was detected in the active file, when its indentation comes from detection.
+    detection: Option<Detection>,
+    /// The active file, when its indentation mixes tabs and spaces.
+    mixed_indentation: Option<(Entity<Buffer>, MixedIndentation)>,
+    /// The buffer, version and configured indentation last scanned for mixed indentation,
+    /// along with the scan running in the background.
+    mixed_indentation_scan: Option<((BufferId, clock::Global, IndentSize), Task<()>)>,
+    /// The lines of the active file breaking the indentation lints enabled for its language.
+    indentation_lints: Option<IndentationLintReport>,
+    /// The buffer, version, indent size and lints last linted, along with the scan running in
+    /// the background.
+    indentation_lints_scan: Option<(IndentationLintKey, Task<()>)>,
+    /// How far the mouse wheel has scrolled over the item since it last stepped the indentation.
+    scrolled: Pixels,
+    active_editor: Option<WeakEntity<Editor>>,
+    _observe_active_editor: Option<Subscription>,
+    /// Reads the active editor again once it stops notifying for [`UPDATE_DELAY`].
+    pending_update: Option<Task<()>>,
+    /// The buffer the cursor is in, observed for changes to its language or file that the
+    /// editor doesn't notify about.
+    observed_buffer: Option<(WeakEntity<Buffer>, Subscription)>,
+    _observe_conversion_progress: Subscription,
+    _observe_settings: Subscription,
+}
+
+impl Indentation {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            indent_size: None,
+            tab_width: None,
+            indent_size_cache: None,
+            source: None,
+            excerpt_path: None,
+            detection: None,
+            mixed_indentation: None,
+            mixed_indentation_scan: None,
+            indentation_lints: None,
+            indentation_lints_scan: None,
+            scrolled: px(0.),
+            active_editor: None,
+            _observe_active_editor: None,
+            pending_update: None,
+            observed_buffer: None,
+            _observe_conversion_progress: cx
+                .observe_global::<ConversionProgress>(|_, cx| cx.notify()),
+            // Editing or pulling an `.editorconfig` or settings file changes the active file's
+            // indentation without the editor notifying.
+            _observe_settings: cx.observe_global::<SettingsStore>(Self::refresh),
+        }
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        self.indent_size_cache = None;
+        if let Some(editor) = self
+            .active_editor
+            .as_ref()
+            .and_then(|editor| editor.upgrade())
+        {
+            self.update_indentation(editor, cx);
+        }
+    }
+
+    /// Reads the active editor again shortly after it notifies, coalescing the notifications it
+    /// sends on each keystroke and cursor move in the meantime.
+    fn schedule_update(&mut self, editor: Entity<Editor>, cx: &mut Context<Self>) {
+        if self.pending_update.is_some() {
+            return;
+        }
+        self.pending_update = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(UPDATE_DELAY).await;
+            this.update(cx, |this, cx| {
+                this.pending_update = None;
+                this.update_indentation(editor, cx);
+            })
+            .ok();
+        }));
+    }
+
+    /// Reads the indentation of the file the cursor is in. In editors showing excerpts of
+    /// several files, this follows the cursor from one file's excerpts to another's, as the
+    /// editor notifies whenever its selections change. The item only re-renders when what it
+    /// shows changed.
+    fn update_indentation(&mut self, editor: Entity<Editor>, cx: &mut Context<Self>) {
+        let (indent_size, tab_width) = self
+            .cached_indent_size(editor.read(cx), cx)
+            .map_or((None, None), |(indent_size, tab_width)| {
+                (Some(indent_size), tab_width)
+            });
+        let source = IndentSource::for_editor(editor.read(cx), cx);
+        let buffer = file_buffer(editor.read(cx), cx);
+        self.observe_buffer(buffer.as_ref(), cx);
+        let excerpt_path = buffer
+            .as_ref()
+            .filter(|_| !editor.read(cx).buffer().read(cx).is_singleton())
+            .and_then(|buffer| buffer.read(cx).file())
+            .map(|file| file.path().display(PathStyle::local()).to_string().into());
+        let detection = buffer
+            .filter(|_| source == IndentSource::Detected)
+            .map(|buffer| buffer_detection(&buffer, cx));
+        let changed = self.indent_size != indent_size
+            || self.tab_width != tab_width
+            || self.source.as_ref() != Some(&source)
+            || self.excerpt_path != excerpt_path
+            || self.detection != detection
+            || self.active_editor.as_ref() != Some(&editor.downgrade());
+        self.indent_size = indent_size;
+        self.tab_width = tab_width;
+        self.source = Some(source);
+        self.excerpt_path = excerpt_path;
+        self.detection = detection;
+        // The scans notify once they finish.
+        self.scan_mixed_indentation(&editor, cx);
+        self.scan_indentation_lints(&editor, cx);
+        self.active_editor = Some(editor.downgrade());
+        if changed {
+            cx.notify();
+        }
+    }
+
+    /// The indentation of the file open in `editor`, along with the width of a tab when it
+    /// differs from the indent size.
+    fn cached_indent_size(
+        &mut self,
+        editor: &Editor,
+        cx: &App,
+    ) -> Option<(IndentSize, Option<u32>)> {
+        let key = IndentSizeKey::for_editor(editor, cx)?;
+        if let Some((cached_key, indent_size)) = &self.indent_size_cache
+            && *cached_key == key
+        {
+            return *indent_size;
+        }
+        let indent_size = resolved_whitespace(editor, cx).map(|whitespace| {
+            let tab_width = whitespace.tab_width.get();
+            let indent_size = IndentSize {
+                len: whitespace.indent_size.get(),
+                kind: if whitespace.hard_tabs {
+                    IndentKind::Tab
+                } else {
+                    IndentKind::Space
+                },
+            };
+            (
+                indent_size,
+                (tab_width != indent_size.len).then_some(tab_width),
+            )
+        });
+        self.indent_size_cache = Some((key, indent_size));
+        indent_size
+    }
+
+    /// Re-reads the indentation when the language of `buffer` changes, such as when picking one
+    /// with the language selector, or when it's saved to a new file, such as an untitled buffer
+    /// saved with an extension.
+    fn observe_buffer(&mut self, buffer: Option<&Entity<Buffer>>, cx: &mut Context<Self>) {
+        let Some(buffer) = buffer else {
+            self.observed_buffer = None;
+            return;
+        };
+        if self
+            .observed_buffer
+            .as_ref()
+            .is_some_and(|(observed, _)| *observed == buffer.downgrade())
+        {
+            return;
+        }
+        let subscription = cx.subscribe(buffer, |this, _, event: &BufferEvent, cx| {
+            if matches!(
+                event,
+                BufferEvent::LanguageChanged(_) | BufferEvent::FileHandleChanged
+            ) {
+                this.refresh(cx);
+            }
+        });
+        self.observed_buffer = Some((buffer.downgrade(), subscription));
+    }
+
+    /// Scans the active file for mixed indentation in the background, since the editor notifies
+    /// on every keystroke and cursor move. The scan is skipped while the file and its configured
+    /// indentation are unchanged.
+    fn scan_mixed_indentation(&mut self, editor: &Entity<Editor>, cx: &mut Context<Self>) {
+        let Some((buffer, configured)) = file_buffer(editor.read(cx), cx).zip(self.indent_size)
+        else {
+            self.mixed_indentation = None;
+            self.mixed_indentation_scan = None;
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let key = (snapshot.remote_id(), snapshot.version().clone(), configured);
+        if self
+            .mixed_indentation_scan
+            .as_ref()
+            .is_some_and(|(scanned, _)| *scanned == key)
+        {
+            return;
+        }
+        let scan = cx.spawn(async move |this, cx| {
+            let mixed_indentation = cx
+                .background_spawn(
+                    async move { MixedIndentation::scan(&snapshot, configured).await },
+                )
+                .await;
+            this.update(cx, |this, cx| {
+                this.mixed_indentation =
+                    mixed_indentation.map(|mixed_indentation| (buffer, mixed_indentation));
+                cx.notify();
+            })
+            .ok();
+        });
+        self.mixed_indentation_scan = Some((key, scan));
+    }
+
+    /// Lints the indentation of the active file in the background, like
+    /// [`Self::scan_mixed_indentation`], skipping the scan while the file, its indentation and
+    /// the lints enabled for it are unchanged.
+    fn scan_indentation_lints(&mut self, editor: &Entity<Editor>, cx: &mut Context<Self>) {
+        let Some(((buffer, lints), indent_size)) =
+            indentation_lints::lints_for_editor(editor.read(cx), cx)
+                .zip(self.indent_size.map(|indent_size| indent_size.len))
+        else {
+            self.indentation_lints = None;
+            self.indentation_lints_scan = None;
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let key = (
+            snapshot.remote_id(),
+            snapshot.version().clone(),
+            indent_size,
+            lints.clone(),
+        );
+        if self
+            .indentation_lints_scan
+            .as_ref()
+            .is_some_and(|(scanned, _)| *scanned == key)
+        {
+            return;
+        }
+        let scan = cx.spawn(async move |this, cx| {
+            let report = cx
+                .background_spawn(async move {
+                    IndentationLintReport::scan(&snapshot, indent_size, &lints)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.indentation_lints = report;
+                cx.notify();
+            })
+            .ok();
+        });
+        self.indentation_lints_scan = Some((key, scan));
+    }
+
+    /// Steps the active file through the selector's sizes, then tabs, as the mouse wheel scrolls
+    /// over the item: one step per line scrolled, forward when scrolling up. Each step overrides
+    /// the file's indentation until it's closed.
+    fn cycle_on_scroll(
+        &mut self,
+        event: &ScrollWheelEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let line_height = window.line_height();
+        self.scrolled += event.delta.pixel_delta(line_height).y;
+        if self.scrolled.abs() < line_height {
+            return;
+        }
+        let forward = self.scrolled > px(0.);
+        self.scrolled = px(0.);
+
+        let Some(current) = self.indent_size else {
+            return;
+        };
+        let settings = IndentSizeSelectorSettings::get_global(cx);
+        if let Some(next) =
+            next_in_cycle(current, &settings.sizes, settings.show_hard_tabs, forward)
+        {
+            self.override_active_file(next, cx);
+        }
+    }
+
+    /// Flips the active file between tabs and spaces, keeping its indent size.
+    fn toggle_hard_tabs(&mut self, cx: &mut Context<Self>) {
+        let Some(current) = self.indent_size else {
+            return;
+        };
+        let kind = match current.kind {
+            IndentKind::Space => IndentKind::Tab,
+            IndentKind::Tab => IndentKind::Space,
+        };
+        self.override_active_file(
+            IndentSize {
+                len: current.len,
+                kind,
+            },
+            cx,
+        );
+    }
+
+    /// Overrides the indentation of the active file with `indent_size` until it's closed.
+    fn override_active_file(&mut self, indent_size: IndentSize, cx: &mut Context<Self>) {
+        let Some(editor) = self
+            .active_editor
+            .as_ref()
+            .and_then(|editor| editor.upgrade())
+        else {
+            return;
+        };
+        let Some(buffer) = file_buffer(editor.read(cx), cx) else {
+            return;
+        };
+        let Some(tab_size) = NonZeroU32::new(indent_size.len) else {
+            return;
+        };
+        set_buffer_override(
+            &buffer,
+            IndentationOverride {
+                tab_size,
+                tab_width: None,
+                hard_tabs: indent_size.kind == IndentKind::Tab,
+            },
+            cx,
+        );
+        // The item re-reads the indentation when the editor notifies.
+        editor.update(cx, |_, cx| cx.notify());
+    }
+
+    /// Moves the cursor to the first line of the active file indented with the kind its
+    /// configured indentation doesn't use, and opens the selector to offer normalizing it.
+    fn go_to_mixed_indentation(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(((_, mixed_indentation), editor)) = self
+            .mixed_indentation
+            .clone()
+            .zip(self.active_editor.clone())
+        else {
+            return;
+        };
+        editor
+            .update(cx, |editor, cx| {
+                editor.go_to_singleton_buffer_point(
+                    mixed_indentation.first_offending_line(),
+                    window,
+                    cx,
+                );
+            })
+            .ok();
+        IndentSizeSelector::toggle(&editor, TargetScope::ActiveFile, window, cx);
+    }
+
+    /// Moves the cursor to the first line of the active file breaking an indentation lint.
+    fn go_to_indentation_lint(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((report, editor)) = self
+            .indentation_lints
+            .as_ref()
+            .zip(self.active_editor.as_ref())
+        else {
+            return;
+        };
+        let point = report.first_offending_line();
+        editor
+            .update(cx, |editor, cx| {
+                editor.go_to_singleton_buffer_point(point, window, cx);
+            })
+            .ok();
+    }
+
+    /// Offers to copy a dropped `.editorconfig` into the root of the active file's worktree, or its
+    /// indentation into the `.editorconfig` already there.
+    fn adopt_editorconfig(
+        &mut self,
+        paths: &ExternalPaths,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(source_path) = paths
+            .paths()
+            .iter()
+            .find(|path| {
+                path.file_name()
+                    .is_some_and(|name| name == EDITORCONFIG_NAME)
+            })
+            .cloned()
+        else {
+            return;
+        };
+        let Some(editor) = self
+            .active_editor
+            .as_ref()
+            .and_then(|editor| editor.upgrade())
+        else {
+            return;
+        };
+        let editor = editor.read(cx);
+        let Some((target, file)) =
+            ImportTarget::for_editor(editor, cx).zip(editor.file_at(cursor_position(editor), cx))
+        else {
+            return;
+        };
+        let file_path = file.path().clone();
+
+        cx.spawn_in(window, async move |_, cx| {
+            let content = target
+                .fs()
+                .load(&source_path)
+                .await
+                .with_context(|| format!("loading {source_path:?}"))?;
+            let editorconfig = content.parse::<Editorconfig>()?;
+            let detail = indentation_summary(&editorconfig, &file_path).map(|summary| {
+                format!(
+                    "{} will use:\n{summary}",
+                    file_path.display(PathStyle::local())
+                )
+            });
+            target.install(content, detail, cx).await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_indentation(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div().when_some(self.indent_size, |el, indent_size| {
+            el.on_scroll_wheel(cx.listener(Self::cycle_on_scroll))
+                .drag_over::<ExternalPaths>(|style, _, _, cx| {
+                    style.bg(cx.theme().colors().drop_target_background)
+                })
+                .on_drop(cx.listener(|this, paths: &ExternalPaths, window, cx| {
+                    this.adopt_editorconfig(paths, window, cx);
+                }))
+                .child(
+                    h_flex()
+                        .gap_0p5()
+                        .when_some(
+                            self.mixed_indentation.clone(),
+                            |el, (_, mixed_indentation)| {
+                                el.child(
+                                    IconButton::new("go-to-mixed-indentation", IconName::Warning)
+                                        .icon_size(IconSize::Small)
+                                        .icon_color(Color::Warning)
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.go_to_mixed_indentation(window, cx);
+                                        }))
+                                        .tooltip(move |_window, cx| {
+                                            Tooltip::with_meta(
+                                                mixed_indentation.go_to_label(),
+                                                None,
+                                                mixed_indentation.description(),
+                                                cx,
+                                            )
+                                        }),
+                                )
+                            },
+                        )
+                        .when_some(self.indentation_lints.clone(), |el, report| {
+                            let (icon, color) = match report.severity() {
+                                IndentationLintSeverity::Error => (IconName::XCircle, Color::Error),
+                                _ => (IconName::Warning, Color::Warning),
+                            };
+                            el.child(
+                                IconButton::new("go-to-indentation-lint", icon)
+                                    .icon_size(IconSize::Small)
+                                    .icon_color(color)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.go_to_indentation_lint(window, cx);
+                                    }))
+                                    .tooltip(move |_window, cx| {
+                                        Tooltip::with_meta(
+                                            "Go to First Line Breaking an Indentation Lint",
+                                            None,
+                                            report.description(),
+                                            cx,
+                                        )
+                                    }),
+                            )
+                        })
+                        .child(
+                            Button::new("change-indent-size", {
+                                let label = indent_size_label(
+                                    indent_size,
+                                    self.tab_width,
+                                    &IndentSizeSelectorSettings::get_global(cx).status_item_format,
+                                );
+                                match self.detection {
+                                    Some(_) => format!("{label} (detected)").into(),
+                                    None => label,
+                                }
+                            })
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, event: &ClickEvent, window, cx| {
+                                // Alt-clicking flips between tabs and spaces without the
+                                // selector.
+                                if event.modifiers().alt {
+                                    this.toggle_hard_tabs(cx);
+                                } else if let Some(editor) = this.active_editor.as_ref() {
+                                    IndentSizeSelector::toggle(
+                                        editor,
+                                        TargetScope::ActiveFile,
+                                        window,
+                                        cx,
+                                    );
+                                }
+                            }))
+                            .tooltip({
+                                let meta = self
+                                    .source
+                                    .as_ref()
+                                    .map(|source| match &self.excerpt_path {
+                                        Some(path) => format!("In {path}, from {}", source.label()),
+                                        None => format!("From {}", source.label()),
+                                    })
+                                    .into_iter()
+                                    .chain(self.detection.as_ref().and_then(detection_summary))
+                                    .collect::<Vec<_>>()
+                                    .join(". ");
+                                move |_window, cx| {
+                                    if meta.is_empty() {
+                                        Tooltip::for_action(
+                                            "Select Indentation",
+                                            &Toggle::default(),
+                                            cx,
+                                        )
+                                    } else {
+                                        Tooltip::with_meta(
+                                            "Select Indentation",
+                                            Some(&Toggle::default()),
+                                            meta.clone(),
+                                            cx,
+                                        )
+                                    }
+                                }
+                            }),
+                        ),
+                )
+        })
+    }
+}
+
+/// Describes the indentation keys that `editorconfig` assigns to `path`.
+fn indentation_summary(editorconfig: &Editorconfig, path: &RelPath) -> Option<String> {
+    let mut properties = EditorconfigProperties::new();
+    for section in &editorconfig.sections {
+        section
+            .apply_to(&mut properties, path.as_std_path())
+            .log_err()?;
+    }
+
+    let mut lines = Vec::new();
+    if let Ok(style) = properties.get::<IndentStyle>() {
+        let style = match style {
+            IndentStyle::Tabs => "tab",
+            IndentStyle::Spaces => "space",
+        };
+        lines.push(format!("indent_style = {style}"));
+    }
+    match properties.get::<EditorconfigIndentSize>() {
+        Ok(EditorconfigIndentSize::Value(size)) => lines.push(format!("indent_size = {size}")),
+        Ok(EditorconfigIndentSize::UseTabWidth) => lines.push("indent_size = tab".to_string()),
+        Err(_) => {}
+    }
+    if let Ok(TabWidth::Value(width)) = properties.get::<TabWidth>() {
+        lines.push(format!("tab_width = {width}"));
+    }
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Describes how trustworthy `detection` is, such as "Detected from 12 of 14 lines (high
+/// confidence)".
+fn detection_summary(detection: &Detection) -> Option<String> {
+    let confidence = detection.confidence()?;
+    Some(format!(
+        "Detected from {} of {} {} ({} confidence)",
+        detection.supporting_lines,
+        detection.deciding_lines,
+        if detection.deciding_lines == 1 {
+            "line"
+        } else {
+            "lines"
+        },
+        confidence.label(),
+    ))
+}
+
+/// The indentation after `current` in the cycle of `sizes` followed by tabs, with `tabs`, or
+/// before it unless `forward`. Tabs keep the width of `current`. An indentation outside the cycle
+/// steps to its first or last entry.
+fn next_in_cycle(
+    current: IndentSize,
+    sizes: &[u32],
+    tabs: bool,
+    forward: bool,
+) -> Option<IndentSize> {
+    let mut cycle = sizes
+        .iter()
+        .map(|size| IndentSize::spaces(*size))
+        .collect::<Vec<_>>();
+    if tabs {
+        cycle.push(IndentSize {
+            len: current.len,
+            kind: IndentKind::Tab,
+        });
+    }
+    let len = cycle.len();
+    if len == 0 {
+        return None;
+    }
+    let index = match cycle.iter().position(|entry| *entry == current) {
+        Some(index) if forward => (index + 1) % len,
+        Some(index) => (index + len - 1) % len,
+        None if forward => 0,
+        None => len - 1,
+    };
+    Some(cycle[index])
+}
+
+/// Fills in the `{kind}` and `{size}` placeholders of the `status_item_format` setting. When
+/// tabs are `tab_width` columns wide rather than the indent size, it's shown first, and the
+/// indent size is labeled as such, as in "Tab: 8 / Indent: 4".
+fn indent_size_label(
+    indent_size: IndentSize,
+    tab_width: Option<u32>,
+    format: &str,
+) -> SharedString {
+    let kind = match (indent_size.kind, tab_width) {
+        (_, Some(_)) => "Indent",
+        (IndentKind::Space, None) => "Space",
+        (IndentKind::Tab, None) => "Tab",
+    };
+    let label = format
+        .replace("{kind}", kind)
+        .replace("{size}", &indent_size.len.to_string());
+    match tab_width {
+        Some(tab_width) => format!("Tab: {tab_width} / {label}").into(),
+        None => label.into(),
+    }
+}
+
+impl Render for Indentation {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let conversion_progress = ConversionProgress::get(cx).map(|(converted, total)| {
+            h_flex()
+                .gap_0p5()
+                .child(
+                    Label::new(format!("Converting indentation {converted}/{total}"))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    IconButton::new("cancel-indentation-conversion", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .on_click(|_, _, cx| ConversionProgress::cancel(cx))
+                        .tooltip(Tooltip::text("Cancel Conversion")),
+                )
+        });
+        // A conversion in progress stays visible so it can be cancelled.
+        let show_indentation = StatusBarSettings::get_global(cx).indentation_button;
+        h_flex()
+            .gap_2()
+            .children(conversion_progress)
+            .when(show_indentation, |el| el.child(self.render_indentation(cx)))
+    }
+}
+
+impl StatusItemView for Indentation {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The status bar calls this whenever another pane gains focus, including panes that
+        // follow a collaborator, so the item always reflects the focused pane's editor. Items
+        // such as channel notes and diffs wrap an editor rather than being one.
+        if let Some(editor) = active_pane_item.and_then(|item| item.act_as::<Editor>(cx)) {
+            if self.active_editor.as_ref() != Some(&editor.downgrade()) {
+                self._observe_active_editor = Some(cx.observe(&editor, Self::schedule_update));
+            }
+            self.pending_update = None;
+            self.update_indentation(editor, cx);
+        } else {
+            self.indent_size = None;
+            self.tab_width = None;
+            self.indent_size_cache = None;
+            self.source = None;
+            self.excerpt_path = None;
+            self.detection = None;
+            self.mixed_indentation = None;
+            self.mixed_indentation_scan = None;
+            self.indentation_lints = None;
+            self.indentation_lints_scan = None;
+            self.active_editor = None;
+            self._observe_active_editor = None;
+            self.pending_update = None;
+            self.observed_buffer = None;
+        }
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Fs as _, Project};
+    use serde_json::json;
+    use std::path::Path;
+    use util::path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_dropped_editorconfig_sets_the_indentation(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                ".editorconfig": "root = true\n\n[*]\ncharset = utf-8\n",
+                "a.rs": "fn f() {}\n",
+            }),
+        )
+        .await;
+        fs.insert_tree(
+            path!("/downloads"),
+            json!({
+                ".editorconfig": "[*]\nindent_style = space\nindent_size = 2\nend_of_line = crlf\n\n[Makefile]\nindent_style = tab\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let (indentation, cx) = cx.add_window_view(|_, cx| Indentation::new(cx));
+        let editor = cx.new_window_entity(|window, cx| {
+            Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+        });
+        indentation.update(cx, |indentation, _| {
+            indentation.active_editor = Some(editor.downgrade());
+        });
+
+        let dropped =
+            ExternalPaths(vec![Path::new(path!("/downloads/.editorconfig")).into()].into());
+        indentation.update_in(cx, |indentation, window, cx| {
+            indentation.adopt_editorconfig(&dropped, window, cx)
+        });
+        cx.run_until_parked();
+        cx.simulate_prompt_answer("Apply");
+        cx.run_until_parked();
+
+        // Only the indentation of the dropped file is taken, in the sections it sets it in.
+        assert_eq!(
+            fs.load(Path::new(path!("/dir/.editorconfig")))
+                .await
+                .unwrap(),
+            "root = true\n\n\
+             [*]\n\
+             charset = utf-8\n\
+             indent_style = space\n\
+             indent_size = 2\n\n\
+             [Makefile]\n\
+             indent_style = tab\n"
+        );
+    }
+
+    #[test]
+    fn test_next_in_cycle() {
+        let tab = |len| IndentSize {
+            len,
+            kind: IndentKind::Tab,
+        };
+        let sizes = [2, 4, 8];
+        let next = |current, forward| next_in_cycle(current, &sizes, true, forward);
+
+        assert_eq!(
+            next(IndentSize::spaces(2), true),
+            Some(IndentSize::spaces(4))
+        );
+        assert_eq!(next(IndentSize::spaces(8), true), Some(tab(8)));
+        assert_eq!(next(tab(8), true), Some(IndentSize::spaces(2)));
+        assert_eq!(next(IndentSize::spaces(2), false), Some(tab(2)));
+        // Sizes outside the cycle step to its ends.
+        assert_eq!(
+            next(IndentSize::spaces(3), true),
+            Some(IndentSize::spaces(2))
+        );
+        assert_eq!(next(IndentSize::spaces(3), false), Some(tab(3)));
+        assert_eq!(
+            next_in_cycle(IndentSize::spaces(4), &sizes, false, true),
+            Some(IndentSize::spaces(8))
+        );
+        assert_eq!(next_in_cycle(IndentSize::spaces(4), &[], false, true), None);
+    }
+
+    #[test]
+    fn test_indent_size_label() {
+        let format = "{kind}: {size}";
+        assert_eq!(
+            indent_size_label(IndentSize::spaces(4), None, format),
+            "Space: 4"
+        );
+        assert_eq!(
+            indent_size_label(IndentSize::spaces(4), Some(8), format),
+            "Tab: 8 / Indent: 4"
+        );
+        assert_eq!(
+            indent_size_label(IndentSize::spaces(2), Some(8), "⇥{size}"),
+            "Tab: 8 / ⇥2"
+        );
+    }
+}
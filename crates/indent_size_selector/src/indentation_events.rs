@@ -0,0 +1,129 @@
+use gpui::{App, AppContext as _, Entity, EventEmitter, Global};
+use language::{
+    Buffer, IndentKind, IndentSize,
+    language_settings::{LanguageSettings, ResolvedWhitespaceOptions},
+};
+use settings::IndentSizeSelectorOverrideScope;
+
+/// An indentation the selector applied to a buffer, emitted by [`IndentationEvents`].
+#[derive(Clone, Debug)]
+pub struct IndentationChanged {
+    pub buffer: Entity<Buffer>,
+    /// The indentation the settings resolved for the buffer before the change.
+    pub old: Option<IndentSize>,
+    pub new: IndentSize,
+    /// How broadly the change applies, beyond the buffer.
+    pub scope: IndentSizeSelectorOverrideScope,
+}
+
+/// Emits an [`IndentationChanged`] each time the selector applies an indentation, so that
+/// others can react to it without watching the settings. A change written to a settings file
+/// is emitted as it's applied, before the file is reloaded.
+pub struct IndentationEvents;
+
+impl EventEmitter<IndentationChanged> for IndentationEvents {}
+
+struct GlobalIndentationEvents(Entity<IndentationEvents>);
+
+impl Global for GlobalIndentationEvents {}
+
+impl IndentationEvents {
+    /// The emitter shared across the app, to subscribe to.
+    pub fn global(cx: &mut App) -> Entity<Self> {
+        if let Some(events) = cx.try_global::<GlobalIndentationEvents>() {
+            return events.0.clone();
+        }
+        let events = cx.new(|_| Self);
+        cx.set_global(GlobalIndentationEvents(events.clone()));
+        events
+    }
+}
+
+/// The indentation the settings resolve `buffer` to, at its language.
+pub(crate) fn resolved_indent_size(buffer: &Entity<Buffer>, cx: &App) -> IndentSize {
+    let buffer = buffer.read(cx);
+    let language_name = buffer.language().map(|language| language.name());
+    let settings = LanguageSettings::resolve(Some(buffer), language_name.as_ref(), cx);
+    let whitespace = ResolvedWhitespaceOptions::from_settings(&settings);
+    indent_size(whitespace.indent_size.get(), whitespace.hard_tabs)
+}
+
+/// Emits that `size` and `hard_tabs` were applied to each of `buffers` in `scope`, along with
+/// the indentation each one had before.
+pub(crate) fn emit_applied(
+    buffers: Vec<(Entity<Buffer>, Option<IndentSize>)>,
+    size: u32,
+    hard_tabs: bool,
+    scope: IndentSizeSelectorOverrideScope,
+    cx: &mut App,
+) {
+    if buffers.is_empty() {
+        return;
+    }
+    let new = indent_size(size, hard_tabs);
+    IndentationEvents::global(cx).update(cx, |_, cx| {
+        for (buffer, old) in buffers {
+            cx.emit(IndentationChanged {
+                buffer,
+                old,
+                new,
+                scope,
+            });
+        }
+    });
+}
+
+/// The indentation `size` columns wide, as [`crate::read_indent_size`] reads it.
+fn indent_size(size: u32, hard_tabs: bool) -> IndentSize {
+    IndentSize {
+        len: size,
+        kind: if hard_tabs {
+            IndentKind::Tab
+        } else {
+            IndentKind::Space
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[gpui::test]
+    fn test_emit_applied(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("a", cx));
+        let received = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let received = received.clone();
+            cx.subscribe(
+                &IndentationEvents::global(cx),
+                move |_, event: &IndentationChanged, _| {
+                    received
+                        .borrow_mut()
+                        .push((event.old, event.new, event.scope));
+                },
+            )
+            .detach();
+            emit_applied(
+                vec![(buffer.clone(), Some(IndentSize::spaces(4)))],
+                8,
+                true,
+                IndentSizeSelectorOverrideScope::Language,
+                cx,
+            );
+        });
+        assert_eq!(
+            *received.borrow(),
+            [(
+                Some(IndentSize::spaces(4)),
+                IndentSize {
+                    len: 8,
+                    kind: IndentKind::Tab
+                },
+                IndentSizeSelectorOverrideScope::Language
+            )]
+        );
+    }
+}
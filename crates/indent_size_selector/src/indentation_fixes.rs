@@ -0,0 +1,275 @@
+use anyhow::{Context as _, Result};
+use editor::{CodeActionProvider, Editor};
+use gpui::{App, AppContext as _, Context, Entity, Task, Window};
+use language::{
+    Anchor, Buffer, BufferSnapshot, IndentKind, IndentLint, IndentSize, LanguageServerId, Point,
+    ToPoint as _,
+    language_settings::{LanguageSettings, ResolvedWhitespaceOptions},
+};
+use project::{CodeAction, LspAction, ProjectTransaction};
+use serde::{Deserialize, Serialize};
+use std::{ops::Range, rc::Rc, sync::Arc};
+
+use crate::{fix_on_save, indentation_lints::enabled_lints};
+
+/// Which fix a code action offered by [`IndentationFixes`] applies, stored in its data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum IndentationFix {
+    /// Re-indents the lines in the action's range breaking an indentation lint.
+    Lines,
+    /// Converts the whole file to its configured indentation.
+    File,
+}
+
+/// Offers code actions fixing the lines flagged by the indentation lints enabled for a file's
+/// language, either just those lines or the whole file.
+struct IndentationFixes;
+
+/// Adds the indentation fixes to the code actions of each editor of a project.
+pub(crate) fn watch(editor: &mut Editor, window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let Some(window) = window else {
+        return;
+    };
+    if !editor.mode().is_full() || editor.project().is_none() {
+        return;
+    }
+    editor.add_code_action_provider(Rc::new(IndentationFixes), window, cx);
+}
+
+/// How `buffer` is configured to be indented, along with the lints enabled for it.
+struct BufferIndentation {
+    configured: IndentSize,
+    tab_width: u32,
+    lints: Vec<IndentLint>,
+}
+
+impl BufferIndentation {
+    /// `None` when `buffer` can't be edited or has no lints enabled.
+    fn for_buffer(buffer: &Buffer, cx: &App) -> Option<Self> {
+        if buffer.read_only() {
+            return None;
+        }
+        let settings = LanguageSettings::for_buffer(buffer, cx);
+        let lints = enabled_lints(&settings.indentation_lints)
+            .into_iter()
+            .map(|(lint, _)| lint)
+            .collect::<Vec<_>>();
+        if lints.is_empty() {
+            return None;
+        }
+        let whitespace = ResolvedWhitespaceOptions::from_settings(&settings);
+        Some(Self {
+            configured: IndentSize {
+                len: whitespace.indent_size.get(),
+                kind: if whitespace.hard_tabs {
+                    IndentKind::Tab
+                } else {
+                    IndentKind::Space
+                },
+            },
+            tab_width: whitespace.tab_width.get(),
+            lints,
+        })
+    }
+
+    /// Describes the configured indentation, such as "4 spaces" or "tabs".
+    fn label(&self) -> String {
+        match self.configured.kind {
+            IndentKind::Tab => "tabs".to_string(),
+            IndentKind::Space if self.configured.len == 1 => "1 space".to_string(),
+            IndentKind::Space => format!("{} spaces", self.configured.len),
+        }
+    }
+}
+
+/// The rows in `rows` of `buffer` breaking any of the lints enabled for it, in order.
+fn flagged_rows(
+    buffer: &BufferSnapshot,
+    rows: Range<u32>,
+    indentation: &BufferIndentation,
+) -> Vec<u32> {
+    let mut flagged = language::lint_indentation(
+        buffer.as_rope(),
+        indentation.configured.len,
+        &indentation.lints,
+    )
+    .into_iter()
+    .flat_map(|violation| violation.rows)
+    .filter(|row| rows.contains(row))
+    .collect::<Vec<_>>();
+    flagged.sort_unstable();
+    flagged.dedup();
+    flagged
+}
+
+/// The indentation replacing `indent`: as many levels of `configured` as its width, with tabs
+/// `tab_width` columns wide, is closest to. A level of tabs is one tab.
+fn fixed_indent(indent: &str, tab_width: u32, configured: IndentSize) -> String {
+    let width = indent.chars().fold(0, |width, c| {
+        if c == '\t' {
+            (width / tab_width + 1) * tab_width
+        } else {
+            width + 1
+        }
+    });
+    let level = match configured.kind {
+        IndentKind::Tab => tab_width,
+        IndentKind::Space => configured.len,
+    }
+    .max(1);
+    let depth = (width + level / 2) / level;
+    match configured.kind {
+        IndentKind::Tab => "\t".repeat(depth as usize),
+        IndentKind::Space => " ".repeat((depth * level) as usize),
+    }
+}
+
+/// The edits replacing the leading whitespace of each of `rows` of `snapshot` with its
+/// [`fixed_indent`], leaving out the rows it doesn't change.
+pub(crate) fn line_fix_edits(
+    snapshot: &BufferSnapshot,
+    rows: impl IntoIterator<Item = u32>,
+    tab_width: u32,
+    configured: IndentSize,
+) -> Vec<(Range<Point>, String)> {
+    rows.into_iter()
+        .filter_map(|row| {
+            let indent = snapshot
+                .chars_at(Point::new(row, 0))
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .collect::<String>();
+            let fixed = fixed_indent(&indent, tab_width, configured);
+            (fixed != indent).then(|| {
+                (
+                    Point::new(row, 0)..Point::new(row, indent.len() as u32),
+                    fixed,
+                )
+            })
+        })
+        .collect()
+}
+
+impl CodeActionProvider for IndentationFixes {
+    fn id(&self) -> Arc<str> {
+        "indentation_fixes".into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<Anchor>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let Some(indentation) = BufferIndentation::for_buffer(buffer.read(cx), cx) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        cx.background_spawn(async move {
+            let (start, end) = (
+                range.start.to_point(&snapshot),
+                range.end.to_point(&snapshot),
+            );
+            let flagged = flagged_rows(&snapshot, start.row..end.row + 1, &indentation);
+            let (Some(first), Some(last)) = (flagged.first(), flagged.last()) else {
+                return Ok(Vec::new());
+            };
+            let lines_range = snapshot.anchor_before(Point::new(*first, 0))
+                ..snapshot.anchor_after(Point::new(*last, snapshot.line_len(*last)));
+            let file_range =
+                snapshot.anchor_before(Point::zero())..snapshot.anchor_after(snapshot.max_point());
+            let lines_title = match flagged.len() {
+                1 => format!("Convert line to {}", indentation.label()),
+                lines => format!("Convert {lines} lines to {}", indentation.label()),
+            };
+            let action = |title: String, range, fix: IndentationFix| CodeAction {
+                server_id: LanguageServerId(0),
+                range,
+                lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                    title,
+                    kind: Some(lsp::CodeActionKind::QUICKFIX),
+                    data: serde_json::to_value(fix).ok(),
+                    ..Default::default()
+                })),
+                resolved: true,
+            };
+            Ok(vec![
+                action(lines_title, lines_range, IndentationFix::Lines),
+                action(
+                    "Convert file to configured indentation".to_string(),
+                    file_range,
+                    IndentationFix::File,
+                ),
+            ])
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _push_to_history: bool,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let LspAction::Action(lsp_action) = &action.lsp_action else {
+            return Task::ready(Ok(ProjectTransaction::default()));
+        };
+        let fix = lsp_action
+            .data
+            .clone()
+            .context("indentation fix without data")
+            .and_then(|data| Ok(serde_json::from_value::<IndentationFix>(data)?));
+        let fix = match fix {
+            Ok(fix) => fix,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let Some(indentation) = BufferIndentation::for_buffer(buffer.read(cx), cx) else {
+            return Task::ready(Ok(ProjectTransaction::default()));
+        };
+
+        match fix {
+            IndentationFix::Lines => {
+                let snapshot = buffer.read(cx).snapshot();
+                let rows = action.range.start.to_point(&snapshot).row
+                    ..action.range.end.to_point(&snapshot).row + 1;
+                let edits = line_fix_edits(
+                    &snapshot,
+                    flagged_rows(&snapshot, rows, &indentation),
+                    indentation.tab_width,
+                    indentation.configured,
+                );
+                fix_on_save::apply_conversion(&buffer, edits, cx);
+            }
+            IndentationFix::File => {
+                fix_on_save::convert_existing_indentation(
+                    &buffer,
+                    &[],
+                    indentation.configured.len,
+                    indentation.configured.kind == IndentKind::Tab,
+                    cx,
+                );
+            }
+        }
+        // The edits are made in the editor's own buffer, so there's nothing else to open.
+        Task::ready(Ok(ProjectTransaction::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_indent() {
+        let spaces = IndentSize::spaces(4);
+        assert_eq!(fixed_indent("   ", 4, spaces), "    ");
+        assert_eq!(fixed_indent("     ", 4, spaces), "    ");
+        assert_eq!(fixed_indent("  \t", 4, spaces), "    ");
+        assert_eq!(fixed_indent("\t  ", 4, spaces), "        ");
+        assert_eq!(fixed_indent(" ", 4, spaces), "");
+        assert_eq!(fixed_indent("      ", 4, IndentSize::tab()), "\t\t");
+        assert_eq!(fixed_indent("\t \t", 4, IndentSize::tab()), "\t\t");
+    }
+}
@@ -0,0 +1,147 @@
+use editor::Editor;
+use gpui::{App, Entity};
+use language::{
+    Buffer, BufferSnapshot, IndentLint, IndentLintViolation, Point,
+    language_settings::{IndentationLintSeverity, IndentationLints, LanguageSettings},
+};
+
+use crate::{cursor_position, file_buffer};
+
+/// The lints enabled in `lints`, along with how each is reported.
+pub(crate) fn enabled_lints(
+    lints: &IndentationLints,
+) -> Vec<(IndentLint, IndentationLintSeverity)> {
+    [
+        (
+            IndentLint::NotMultipleOfIndentSize,
+            lints.not_multiple_of_indent_size,
+        ),
+        (IndentLint::MixedTabsAndSpaces, lints.mixed_tabs_and_spaces),
+        (IndentLint::SpacesBeforeTabs, lints.spaces_before_tabs),
+    ]
+    .into_iter()
+    .filter(|(_, severity)| *severity != IndentationLintSeverity::Off)
+    .collect()
+}
+
+/// The file open in `editor`, along with the lints enabled for its language, when any are.
+pub(crate) fn lints_for_editor(
+    editor: &Editor,
+    cx: &App,
+) -> Option<(Entity<Buffer>, Vec<(IndentLint, IndentationLintSeverity)>)> {
+    let buffer = file_buffer(editor, cx)?;
+    let language_name = editor
+        .language_at(cursor_position(editor), cx)
+        .map(|language| language.name());
+    let settings = LanguageSettings::resolve(Some(buffer.read(cx)), language_name.as_ref(), cx);
+    let lints = enabled_lints(&settings.indentation_lints);
+    (!lints.is_empty()).then_some((buffer, lints))
+}
+
+/// The lines of a file breaking the indentation lints enabled for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct IndentationLintReport {
+    violations: Vec<(IndentLintViolation, IndentationLintSeverity)>,
+}
+
+impl IndentationLintReport {
+    /// Checks the lines of `snapshot` against `lints`, returning `None` when none of them breaks
+    /// any.
+    pub(crate) fn scan(
+        snapshot: &BufferSnapshot,
+        indent_size: u32,
+        lints: &[(IndentLint, IndentationLintSeverity)],
+    ) -> Option<Self> {
+        let checked = lints.iter().map(|(lint, _)| *lint).collect::<Vec<_>>();
+        let violations = language::lint_indentation(snapshot.as_rope(), indent_size, &checked)
+            .into_iter()
+            .filter_map(|violation| {
+                let (_, severity) = lints.iter().find(|(lint, _)| *lint == violation.lint)?;
+                Some((violation, *severity))
+            })
+            .collect::<Vec<_>>();
+        (!violations.is_empty()).then_some(Self { violations })
+    }
+
+    /// The most severe way any of the broken lints is reported.
+    pub(crate) fn severity(&self) -> IndentationLintSeverity {
+        self.violations
+            .iter()
+            .map(|(_, severity)| *severity)
+            .max()
+            .unwrap_or(IndentationLintSeverity::Off)
+    }
+
+    /// The start of the first line breaking any of the lints.
+    pub(crate) fn first_offending_line(&self) -> Point {
+        let row = self
+            .violations
+            .iter()
+            .filter_map(|(violation, _)| violation.rows.first().copied())
+            .min()
+            .unwrap_or_default();
+        Point::new(row, 0)
+    }
+
+    /// Describes the lines breaking each lint, one lint per line, such as "2 lines are indented
+    /// with tabs and spaces".
+    pub(crate) fn description(&self) -> String {
+        self.violations
+            .iter()
+            .map(|(violation, _)| violation.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+
+    #[gpui::test]
+    fn test_indentation_lint_report(cx: &mut TestAppContext) {
+        let lints = enabled_lints(&IndentationLints {
+            not_multiple_of_indent_size: IndentationLintSeverity::Warning,
+            mixed_tabs_and_spaces: IndentationLintSeverity::Error,
+            spaces_before_tabs: IndentationLintSeverity::Off,
+        });
+        assert_eq!(
+            lints,
+            vec![
+                (
+                    IndentLint::NotMultipleOfIndentSize,
+                    IndentationLintSeverity::Warning
+                ),
+                (
+                    IndentLint::MixedTabsAndSpaces,
+                    IndentationLintSeverity::Error
+                ),
+            ]
+        );
+
+        let buffer = cx.new(|cx| Buffer::local("a\n    b\n  \tc\n   d\n", cx));
+        let report = buffer
+            .read_with(cx, |buffer, _| {
+                IndentationLintReport::scan(&buffer.snapshot(), 4, &lints)
+            })
+            .unwrap();
+        assert_eq!(report.severity(), IndentationLintSeverity::Error);
+        assert_eq!(report.first_offending_line(), Point::new(2, 0));
+        assert_eq!(
+            report.description(),
+            "1 line is not indented by a multiple of the indent size\n\
+             1 line is indented with tabs and spaces"
+        );
+
+        let buffer = cx.new(|cx| Buffer::local("a\n    b\n", cx));
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| IndentationLintReport::scan(
+                &buffer.snapshot(),
+                4,
+                &lints
+            )),
+            None
+        );
+    }
+}
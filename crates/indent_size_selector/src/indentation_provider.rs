@@ -0,0 +1,226 @@
+use collections::HashMap;
+use editor::Editor;
+use gpui::{App, AsyncApp, Context, Entity, Global, Task, Window};
+use language::{Buffer, BufferId, language_settings::IndentationOverride};
+use std::sync::Arc;
+use ui::SharedString;
+
+use crate::{file_buffer, indent_source::IndentSource};
+
+/// Supplies the indentation of buffers from outside Zed's settings, such as a linter's config,
+/// a build system or a style service. Registered with [`register_indentation_provider`].
+pub trait IndentationProvider: 'static {
+    /// Names the provider where the source of a file's indentation is shown, such as the status
+    /// item's tooltip.
+    fn name(&self) -> SharedString;
+
+    /// Providers with a higher priority are asked first. Ties go to the provider registered
+    /// first.
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// The indentation of `buffer`, or `None` to leave it to the providers with a lower
+    /// priority, and then to the settings.
+    fn indentation(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Task<Option<IndentationOverride>>;
+}
+
+#[derive(Default)]
+struct IndentationProviders(Vec<Arc<dyn IndentationProvider>>);
+
+impl Global for IndentationProviders {}
+
+/// The overrides installed from what a provider supplied for each buffer, along with the
+/// provider's name.
+#[derive(Default)]
+struct ProvidedOverrides(HashMap<BufferId, (IndentationOverride, SharedString)>);
+
+impl Global for ProvidedOverrides {}
+
+/// Registers `provider` to be asked for the indentation of each file opened from then on.
+pub fn register_indentation_provider(provider: Arc<dyn IndentationProvider>, cx: &mut App) {
+    let providers = &mut cx.default_global::<IndentationProviders>().0;
+    providers.push(provider);
+    providers.sort_by_key(|provider| std::cmp::Reverse(provider.priority()));
+}
+
+/// Overrides the indentation of the file opened in `editor` with the one the first provider
+/// to supply one does, when only its language's defaults, the user's settings or detection
+/// would determine it otherwise.
+pub(crate) fn watch(editor: &mut Editor, window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let Some(window) = window else {
+        return;
+    };
+    if !editor.mode().is_full() || editor.buffer().read(cx).as_singleton().is_none() {
+        return;
+    }
+    if cx
+        .try_global::<IndentationProviders>()
+        .is_none_or(|providers| providers.0.is_empty())
+    {
+        return;
+    }
+
+    // Restoring an earlier choice for the file happens as the editor is created, and should win.
+    cx.defer_in(window, |editor, _, cx| {
+        if !is_left_to_providers(editor, cx) {
+            return;
+        }
+        let Some(buffer) = file_buffer(editor, cx) else {
+            return;
+        };
+        let provided = resolve(&buffer, cx);
+        cx.spawn(async move |editor, cx| {
+            let Some((indentation_override, name)) = provided.await else {
+                return;
+            };
+            editor
+                .update(cx, |editor, cx| {
+                    // A choice made while the providers were asked wins.
+                    if !is_left_to_providers(editor, cx) {
+                        return;
+                    }
+                    cx.default_global::<ProvidedOverrides>()
+                        .0
+                        .insert(buffer.read(cx).remote_id(), (indentation_override, name));
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.set_indentation_override(Some(indentation_override), cx)
+                    });
+                    // The status item re-reads the indentation whenever the editor notifies.
+                    cx.notify();
+                })
+                .ok();
+        })
+        .detach();
+    });
+}
+
+/// Whether only the language's defaults, the user settings or detection determine the
+/// indentation of the file open in `editor`, leaving it to be supplied from elsewhere.
+pub(crate) fn is_left_to_providers(editor: &Editor, cx: &App) -> bool {
+    matches!(
+        IndentSource::for_editor(editor, cx),
+        IndentSource::Default | IndentSource::UserSettings { .. } | IndentSource::Detected
+    )
+}
+
+/// Asks the registered providers for the indentation of `buffer` in order of priority, returning
+/// the first one supplied along with the name of its provider.
+fn resolve(
+    buffer: &Entity<Buffer>,
+    cx: &mut App,
+) -> Task<Option<(IndentationOverride, SharedString)>> {
+    let providers = cx
+        .try_global::<IndentationProviders>()
+        .map(|providers| providers.0.clone())
+        .unwrap_or_default();
+    let buffer = buffer.clone();
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        for provider in providers {
+            let indentation = cx.update(|cx| provider.indentation(&buffer, cx));
+            if let Some(indentation_override) = indentation.await {
+                return Some((indentation_override, provider.name()));
+            }
+        }
+        None
+    })
+}
+
+/// The name of the provider that supplied the override of `buffer`, unless it was installed
+/// some other way.
+pub(crate) fn provider_of_override(buffer: &Entity<Buffer>, cx: &App) -> Option<SharedString> {
+    let buffer = buffer.read(cx);
+    let indentation_override = buffer.indentation_override()?;
+    let (provided, name) = cx
+        .try_global::<ProvidedOverrides>()?
+        .0
+        .get(&buffer.remote_id())?;
+    (provided == indentation_override).then(|| name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use std::num::NonZeroU32;
+
+    struct TestProvider {
+        name: &'static str,
+        priority: i32,
+        tab_size: Option<u32>,
+    }
+
+    impl IndentationProvider for TestProvider {
+        fn name(&self) -> SharedString {
+            self.name.into()
+        }
+
+        fn priority(&self) -> i32 {
+            self.priority
+        }
+
+        fn indentation(
+            &self,
+            _: &Entity<Buffer>,
+            _: &mut App,
+        ) -> Task<Option<IndentationOverride>> {
+            Task::ready(self.tab_size.and_then(NonZeroU32::new).map(|tab_size| {
+                IndentationOverride {
+                    tab_size,
+                    tab_width: None,
+                    hard_tabs: false,
+                }
+            }))
+        }
+    }
+
+    #[gpui::test]
+    async fn test_resolve_in_priority_order(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("a\n", cx));
+        let resolved = |cx: &mut TestAppContext| cx.update(|cx| resolve(&buffer, cx));
+        assert_eq!(resolved(cx).await, None);
+
+        cx.update(|cx| {
+            register_indentation_provider(
+                Arc::new(TestProvider {
+                    name: "linter",
+                    priority: 0,
+                    tab_size: Some(2),
+                }),
+                cx,
+            );
+            register_indentation_provider(
+                Arc::new(TestProvider {
+                    name: "build system",
+                    priority: 0,
+                    tab_size: Some(8),
+                }),
+                cx,
+            );
+            // Asked first, but leaves it to the others.
+            register_indentation_provider(
+                Arc::new(TestProvider {
+                    name: "style service",
+                    priority: 10,
+                    tab_size: None,
+                }),
+                cx,
+            );
+        });
+        assert_eq!(
+            resolved(cx).await,
+            Some((
+                IndentationOverride {
+                    tab_size: NonZeroU32::new(2).unwrap(),
+                    tab_width: None,
+                    hard_tabs: false,
+                },
+                "linter".into()
+            ))
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use language::{BufferSnapshot, DetectedIndent, Point};
+
+/// How many lines of some buffers are indented with each unit of indentation, shown in the
+/// selector's footer to inform the choice.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct IndentationStats {
+    indented_lines: usize,
+    /// The number of lines indented with each unit, in the order they were first found.
+    lines_by_unit: Vec<(DetectedIndent, usize)>,
+}
+
+impl IndentationStats {
+    /// Counts the indented lines of `snapshots`. A line indented with spaces counts toward the
+    /// number of spaces it's indented past the enclosing line, or that the lines at its depth
+    /// were, so that a line two levels deep in a file indented by 4 spaces counts as 4-space
+    /// rather than 8-space.
+    pub(crate) fn compute<'a>(snapshots: impl IntoIterator<Item = &'a BufferSnapshot>) -> Self {
+        let mut stats = Self::default();
+        for snapshot in snapshots {
+            // The width and unit of each enclosing depth of the lines indented with spaces.
+            let mut depths = Vec::<(u32, u32)>::new();
+            for row in 0..snapshot.row_count() {
+                if snapshot.is_line_blank(row) {
+                    continue;
+                }
+                let mut indent = snapshot
+                    .chars_at(Point::new(row, 0))
+                    .take_while(|c| *c == ' ' || *c == '\t');
+                let unit = match indent.next() {
+                    None => {
+                        depths.clear();
+                        continue;
+                    }
+                    Some('\t') => DetectedIndent::Tabs,
+                    Some(_) => {
+                        let width = 1 + indent.take_while(|c| *c == ' ').count() as u32;
+                        while depths.last().is_some_and(|(depth, _)| *depth > width) {
+                            depths.pop();
+                        }
+                        let unit = match depths.last() {
+                            Some((depth, unit)) if *depth == width => *unit,
+                            Some((depth, _)) => {
+                                let unit = width - depth;
+                                depths.push((width, unit));
+                                unit
+                            }
+                            None => {
+                                depths.push((width, width));
+                                width
+                            }
+                        };
+                        DetectedIndent::Spaces(unit)
+                    }
+                };
+                stats.indented_lines += 1;
+                match stats
+                    .lines_by_unit
+                    .iter_mut()
+                    .find(|(counted, _)| *counted == unit)
+                {
+                    Some((_, lines)) => *lines += 1,
+                    None => stats.lines_by_unit.push((unit, 1)),
+                }
+            }
+        }
+        stats
+    }
+
+    /// Summarizes the stats, such as "312 indented lines: 83% 4-space, 12% 2-space, 5% tabs",
+    /// listing the three most common units, ties in the order they were first found. `None` when
+    /// no line is indented.
+    pub(crate) fn summary(&self) -> Option<String> {
+        if self.indented_lines == 0 {
+            return None;
+        }
+        let mut units = self.lines_by_unit.iter().collect::<Vec<_>>();
+        units.sort_by_key(|(_, lines)| std::cmp::Reverse(*lines));
+        let shares = units
+            .into_iter()
+            .take(3)
+            .map(|(unit, lines)| {
+                let percent = (*lines as f64 * 100. / self.indented_lines as f64).round();
+                match unit {
+                    DetectedIndent::Tabs => format!("{percent}% tabs"),
+                    DetectedIndent::Spaces(spaces) => format!("{percent}% {spaces}-space"),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(match self.indented_lines {
+            1 => format!("1 indented line: {shares}"),
+            lines => format!("{lines} indented lines: {shares}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::Buffer;
+
+    #[gpui::test]
+    fn test_indentation_stats(cx: &mut TestAppContext) {
+        let stats = |text: &'static str, cx: &mut TestAppContext| {
+            let buffer = cx.new(|cx| Buffer::local(text, cx));
+            buffer.read_with(cx, |buffer, _| {
+                IndentationStats::compute([&buffer.snapshot()]).summary()
+            })
+        };
+
+        assert_eq!(stats("a\nb\n", cx), None);
+        assert_eq!(
+            stats("a\n    b\n        c\n    d\n  e\n\tf\n", cx),
+            Some("5 indented lines: 60% 4-space, 20% 2-space, 20% tabs".to_string())
+        );
+    }
+}
@@ -0,0 +1,146 @@
+/// The indentation a language's community conventionally uses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Convention {
+    Spaces(u32),
+    Tabs,
+}
+
+const LANGUAGE_CONVENTIONS: &[(&str, Convention)] = &[
+    ("JavaScript", Convention::Spaces(2)),
+    ("TypeScript", Convention::Spaces(2)),
+    ("TSX", Convention::Spaces(2)),
+    ("JSON", Convention::Spaces(2)),
+    ("JSONC", Convention::Spaces(2)),
+    ("Rust", Convention::Spaces(4)),
+    ("Python", Convention::Spaces(4)),
+    ("Go", Convention::Tabs),
+    ("Makefile", Convention::Tabs),
+];
+
+/// Returns the conventional indentation of the language named `language_name`, if it has one.
+pub(crate) fn conventional_indentation(language_name: &str) -> Option<Convention> {
+    LANGUAGE_CONVENTIONS
+        .iter()
+        .find(|(name, _)| *name == language_name)
+        .map(|(_, convention)| *convention)
+}
+
+/// Which lines of a language's files have to be indented with tabs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TabRequirement {
+    /// The lines starting with a tab, which are recipe lines in a Makefile, since make only
+    /// recognizes a recipe by its leading tab.
+    LeadingTabLines,
+    /// Every line, as the language's formatter enforces.
+    AllLines,
+}
+
+impl TabRequirement {
+    /// Why the language's files have to be indented with tabs.
+    pub(crate) fn reason(&self) -> &'static str {
+        match self {
+            Self::LeadingTabLines => "their recipe lines must start with a tab",
+            Self::AllLines => "their formatter indents with tabs",
+        }
+    }
+}
+
+const TAB_REQUIREMENTS: &[(&str, TabRequirement)] = &[
+    ("Makefile", TabRequirement::LeadingTabLines),
+    ("Go", TabRequirement::AllLines),
+    ("Go Mod", TabRequirement::AllLines),
+    ("Go Work", TabRequirement::AllLines),
+];
+
+/// Returns which lines of the language named `language_name` have to be indented with tabs,
+/// if any do.
+pub(crate) fn tab_requirement(language_name: &str) -> Option<TabRequirement> {
+    TAB_REQUIREMENTS
+        .iter()
+        .find(|(name, _)| *name == language_name)
+        .map(|(_, requirement)| *requirement)
+}
+
+/// The languages whose indentation is part of their syntax, so that re-indenting their lines
+/// can change what they mean.
+const SIGNIFICANT_INDENTATION: &[&str] = &[
+    "Python",
+    "YAML",
+    "Haskell",
+    "F#",
+    "Nim",
+    "Elm",
+    "CoffeeScript",
+    "Sass",
+    "Pug",
+];
+
+/// Whether the indentation of the language named `language_name` is part of its syntax.
+pub(crate) fn has_significant_indentation(language_name: &str) -> bool {
+    SIGNIFICANT_INDENTATION.contains(&language_name)
+}
+
+/// The indentation that projects scaffolded by common templates start with, keyed by a file the
+/// template leaves at the root of the project. More specific templates come first.
+const TEMPLATE_CONVENTIONS: &[(&str, &str, Convention)] = &[
+    ("angular.json", "Angular", Convention::Spaces(2)),
+    ("next.config.js", "Next.js", Convention::Spaces(2)),
+    ("vite.config.ts", "Vite", Convention::Spaces(2)),
+    ("package.json", "npm", Convention::Spaces(2)),
+    ("Cargo.toml", "Cargo", Convention::Spaces(4)),
+    ("go.mod", "Go module", Convention::Tabs),
+    ("pyproject.toml", "Python project", Convention::Spaces(4)),
+    ("Gemfile", "Bundler", Convention::Spaces(2)),
+    ("mix.exs", "Mix", Convention::Spaces(2)),
+    ("pubspec.yaml", "Flutter", Convention::Spaces(2)),
+    ("composer.json", "Composer", Convention::Spaces(4)),
+];
+
+/// Returns the name and default indentation of the template a project was scaffolded from,
+/// judging by whether `has_root_file` finds the file each template leaves at its root.
+pub(crate) fn template_indentation(
+    has_root_file: impl Fn(&str) -> bool,
+) -> Option<(&'static str, Convention)> {
+    TEMPLATE_CONVENTIONS
+        .iter()
+        .find(|(file_name, _, _)| has_root_file(file_name))
+        .map(|(_, template_name, convention)| (*template_name, *convention))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_indentation() {
+        let detect = |root_files: &[&str]| template_indentation(|name| root_files.contains(&name));
+
+        assert_eq!(detect(&["README.md"]), None);
+        assert_eq!(
+            detect(&["Cargo.toml", "src"]),
+            Some(("Cargo", Convention::Spaces(4)))
+        );
+        assert_eq!(
+            detect(&["package.json", "angular.json"]),
+            Some(("Angular", Convention::Spaces(2)))
+        );
+        assert_eq!(detect(&["go.mod"]), Some(("Go module", Convention::Tabs)));
+    }
+
+    #[test]
+    fn test_tab_requirement() {
+        assert_eq!(
+            tab_requirement("Makefile"),
+            Some(TabRequirement::LeadingTabLines)
+        );
+        assert_eq!(tab_requirement("Go"), Some(TabRequirement::AllLines));
+        assert_eq!(tab_requirement("Rust"), None);
+    }
+
+    #[test]
+    fn test_has_significant_indentation() {
+        assert!(has_significant_indentation("Python"));
+        assert!(has_significant_indentation("YAML"));
+        assert!(!has_significant_indentation("Rust"));
+    }
+}
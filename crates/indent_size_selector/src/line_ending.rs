@@ -0,0 +1,113 @@
+use anyhow::Result;
+use ec4rs::property::EndOfLine as EditorconfigEndOfLine;
+use gpui::{Action, Entity};
+use language::{Buffer, LineEnding};
+use project::Project;
+use settings::Settings as _;
+use ui::prelude::*;
+use workspace::StatusBarSettings;
+
+use crate::{
+    ToggleEndOfLine,
+    buffer_settings::{BufferSetting, governing_properties},
+};
+
+/// The `.editorconfig` value of `end_of_line` for `line_ending`.
+fn editorconfig_value(line_ending: LineEnding) -> &'static str {
+    match line_ending {
+        LineEnding::Unix => "lf",
+        LineEnding::Windows => "crlf",
+    }
+}
+
+fn parse_line_ending(value: &str) -> Option<LineEnding> {
+    match value {
+        "lf" => Some(LineEnding::Unix),
+        "crlf" => Some(LineEnding::Windows),
+        _ => None,
+    }
+}
+
+/// The line ending of a file, which only `.editorconfig` overrides set, and which converting
+/// rewrites the file's lines with.
+pub(crate) struct LineEndingSetting;
+
+impl BufferSetting for LineEndingSetting {
+    fn name(&self) -> SharedString {
+        "Line Ending".into()
+    }
+
+    fn editorconfig_property(&self) -> &'static str {
+        "end_of_line"
+    }
+
+    fn candidates(&self, _buffer: &Buffer, _cx: &App) -> Vec<SharedString> {
+        [LineEnding::Unix, LineEnding::Windows]
+            .into_iter()
+            .map(|line_ending| editorconfig_value(line_ending).into())
+            .collect()
+    }
+
+    fn current_value(&self, buffer: &Buffer, _cx: &App) -> Option<SharedString> {
+        Some(editorconfig_value(buffer.line_ending()).into())
+    }
+
+    fn configured_value(&self, buffer: &Buffer, cx: &App) -> Option<SharedString> {
+        let line_ending = match governing_properties(buffer, cx)?
+            .get::<EditorconfigEndOfLine>()
+            .ok()?
+        {
+            EditorconfigEndOfLine::Lf => LineEnding::Unix,
+            EditorconfigEndOfLine::CrLf => LineEnding::Windows,
+            // Zed can't write lone carriage returns.
+            EditorconfigEndOfLine::Cr => return None,
+        };
+        Some(editorconfig_value(line_ending).into())
+    }
+
+    fn label(&self, value: &str) -> SharedString {
+        parse_line_ending(value)
+            .map(|line_ending| line_ending.label().into())
+            .unwrap_or_else(|| value.to_string().into())
+    }
+
+    fn action(&self) -> Option<Box<dyn Action>> {
+        Some(Box::new(ToggleEndOfLine))
+    }
+
+    fn converts(&self) -> bool {
+        true
+    }
+
+    /// Rewrites the line endings of the buffer, then saves it, since the lines only change on
+    /// disk.
+    fn apply_to_buffer(
+        &self,
+        value: &str,
+        buffer: &Entity<Buffer>,
+        project: &Entity<Project>,
+        convert: bool,
+        cx: &mut App,
+    ) -> Result<()> {
+        let Some(line_ending) = parse_line_ending(value) else {
+            anyhow::bail!("{value} is not a line ending");
+        };
+        if !convert || buffer.read(cx).line_ending() == line_ending {
+            return Ok(());
+        }
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_line_ending(line_ending, cx);
+        });
+        if buffer.read(cx).file().is_some() {
+            let buffer = buffer.clone();
+            project.update(cx, |project, cx| {
+                project.save_buffer(buffer, cx).detach_and_log_err(cx);
+            });
+        }
+        Ok(())
+    }
+
+    fn shows_status(&self, cx: &App) -> bool {
+        StatusBarSettings::get_global(cx).line_endings_button
+    }
+}
@@ -0,0 +1,113 @@
+use ec4rs::property::MaxLineLen;
+use gpui::Action;
+use language::{Buffer, language_settings::LanguageSettings};
+use settings::LanguageSettingsContent;
+use ui::prelude::*;
+
+use crate::{
+    SetLineLength,
+    buffer_settings::{BufferSetting, governing_properties},
+};
+
+/// The line lengths suggested besides the current one.
+const SUGGESTED_LINE_LENGTHS: [u32; 3] = [80, 100, 120];
+
+/// Sets `line_length` as the preferred line length in `settings`, moving the wrap guide that
+/// showed the previous one to it.
+pub(crate) fn set_language_settings(settings: &mut LanguageSettingsContent, line_length: u32) {
+    let previous = settings.preferred_line_length.replace(line_length);
+    let wrap_guides = settings.wrap_guides.get_or_insert_default();
+    wrap_guides.retain(|guide| Some(*guide as u32) != previous);
+    if !wrap_guides.contains(&(line_length as usize)) {
+        wrap_guides.push(line_length as usize);
+        wrap_guides.sort_unstable();
+    }
+}
+
+/// The column that lines of a file are kept within, which a wrap guide shows.
+pub(crate) struct LineLengthSetting;
+
+impl BufferSetting for LineLengthSetting {
+    fn name(&self) -> SharedString {
+        "Line Length".into()
+    }
+
+    fn editorconfig_property(&self) -> &'static str {
+        "max_line_length"
+    }
+
+    /// The current line length, followed by the suggested ones.
+    fn candidates(&self, buffer: &Buffer, cx: &App) -> Vec<SharedString> {
+        let current = LanguageSettings::for_buffer(buffer, cx).preferred_line_length;
+        let mut line_lengths = vec![current];
+        line_lengths.extend(
+            SUGGESTED_LINE_LENGTHS
+                .into_iter()
+                .filter(|line_length| *line_length != current),
+        );
+        line_lengths
+            .into_iter()
+            .map(|line_length| line_length.to_string().into())
+            .collect()
+    }
+
+    fn current_value(&self, buffer: &Buffer, cx: &App) -> Option<SharedString> {
+        Some(
+            LanguageSettings::for_buffer(buffer, cx)
+                .preferred_line_length
+                .to_string()
+                .into(),
+        )
+    }
+
+    fn configured_value(&self, buffer: &Buffer, cx: &App) -> Option<SharedString> {
+        match governing_properties(buffer, cx)?.get::<MaxLineLen>().ok()? {
+            MaxLineLen::Value(line_length) => Some(line_length.to_string().into()),
+            MaxLineLen::Off => None,
+        }
+    }
+
+    fn label(&self, value: &str) -> SharedString {
+        format!("{value} columns").into()
+    }
+
+    fn parse(&self, query: &str) -> Option<SharedString> {
+        let line_length = query.parse::<u32>().ok().filter(|typed| *typed > 0)?;
+        Some(line_length.to_string().into())
+    }
+
+    fn action(&self) -> Option<Box<dyn Action>> {
+        Some(Box::new(SetLineLength))
+    }
+
+    fn has_user_setting(&self) -> bool {
+        true
+    }
+
+    fn set_user_setting(&self, value: &str, settings: &mut LanguageSettingsContent) {
+        if let Ok(line_length) = value.parse() {
+            set_language_settings(settings, line_length);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_line_length_moves_wrap_guide() {
+        let mut settings = LanguageSettingsContent {
+            preferred_line_length: Some(80),
+            wrap_guides: Some(vec![72, 80]),
+            ..Default::default()
+        };
+        set_language_settings(&mut settings, 100);
+        assert_eq!(settings.preferred_line_length, Some(100));
+        assert_eq!(settings.wrap_guides, Some(vec![72, 100]));
+
+        let mut settings = LanguageSettingsContent::default();
+        set_language_settings(&mut settings, 120);
+        assert_eq!(settings.wrap_guides, Some(vec![120]));
+    }
+}
@@ -0,0 +1,183 @@
+use collections::HashMap;
+use gpui::{DismissEvent, EventEmitter, FocusHandle, Focusable, Global, Subscription};
+use std::time::{Duration, Instant};
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+use crate::ShowIndentationPerformance;
+
+/// A path of the selector whose duration is recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Span {
+    /// Opening the selector, from the action to the modal being shown.
+    PickerOpen,
+    /// Matching the query against the selector's choices.
+    Match,
+    /// Applying a confirmed choice, from the selector or the scope chooser.
+    Apply,
+    /// Re-indenting a buffer's lines, such as when fixing it on save.
+    Conversion,
+}
+
+impl Span {
+    const ALL: [Self; 4] = [Self::PickerOpen, Self::Match, Self::Apply, Self::Conversion];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::PickerOpen => "Open selector",
+            Self::Match => "Match query",
+            Self::Apply => "Apply choice",
+            Self::Conversion => "Convert indentation",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct SpanStats {
+    count: u32,
+    total: Duration,
+    last: Duration,
+    max: Duration,
+}
+
+impl SpanStats {
+    fn record(&mut self, duration: Duration) {
+        self.count += 1;
+        self.total += duration;
+        self.last = duration;
+        self.max = self.max.max(duration);
+    }
+
+    fn mean(&self) -> Duration {
+        self.total.checked_div(self.count).unwrap_or_default()
+    }
+}
+
+/// The durations recorded for each span since Zed started.
+#[derive(Default)]
+struct IndentationMetrics(HashMap<Span, SpanStats>);
+
+impl Global for IndentationMetrics {}
+
+/// Records that `span` took as long as has passed since `start`.
+pub(crate) fn record(span: Span, start: Instant, cx: &mut App) {
+    cx.default_global::<IndentationMetrics>()
+        .0
+        .entry(span)
+        .or_default()
+        .record(start.elapsed());
+}
+
+/// Runs `f`, recording how long it took as `span`.
+pub(crate) fn measure<R>(span: Span, cx: &mut App, f: impl FnOnce(&mut App) -> R) -> R {
+    let start = Instant::now();
+    let result = f(cx);
+    record(span, start, cx);
+    result
+}
+
+/// Shows how long the selector's paths took, so that regressions are noticeable.
+pub(crate) struct IndentationPerformance {
+    focus_handle: FocusHandle,
+    _observe_metrics: Subscription,
+}
+
+impl IndentationPerformance {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        _: &ShowIndentationPerformance,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        workspace.toggle_modal(window, cx, |_, cx| Self {
+            focus_handle: cx.focus_handle(),
+            _observe_metrics: cx.observe_global::<IndentationMetrics>(|_, cx| cx.notify()),
+        });
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.)
+}
+
+impl Render for IndentationPerformance {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let metrics = cx.try_global::<IndentationMetrics>();
+        let cell = |text: String| {
+            div()
+                .w(rems(6.))
+                .child(Label::new(text).size(LabelSize::Small))
+        };
+        let header = |text: &str| {
+            div().w(rems(6.)).child(
+                Label::new(text.to_string())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+        };
+
+        v_flex()
+            .key_context("IndentationPerformance")
+            .track_focus(&self.focus_handle)
+            .elevation_3(cx)
+            .w(rems(34.))
+            .p_2()
+            .gap_1()
+            .child(Headline::new("Indentation Performance").size(HeadlineSize::Small))
+            .child(
+                h_flex()
+                    .child(div().flex_1())
+                    .child(header("Count"))
+                    .child(header("Last"))
+                    .child(header("Mean"))
+                    .child(header("Max")),
+            )
+            .children(Span::ALL.iter().map(|span| {
+                let stats = metrics
+                    .and_then(|metrics| metrics.0.get(span))
+                    .copied()
+                    .unwrap_or_default();
+                h_flex()
+                    .child(div().flex_1().child(Label::new(span.label())))
+                    .child(cell(stats.count.to_string()))
+                    .child(cell(format_duration(stats.last)))
+                    .child(cell(format_duration(stats.mean())))
+                    .child(cell(format_duration(stats.max)))
+            }))
+            .on_action(cx.listener(|_, _: &menu::Cancel, _, cx| cx.emit(DismissEvent)))
+    }
+}
+
+impl Focusable for IndentationPerformance {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<DismissEvent> for IndentationPerformance {}
+impl ModalView for IndentationPerformance {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_stats() {
+        let mut stats = SpanStats::default();
+        assert_eq!(stats.mean(), Duration::ZERO);
+
+        stats.record(Duration::from_millis(4));
+        stats.record(Duration::from_millis(10));
+        stats.record(Duration::from_millis(1));
+        assert_eq!(
+            stats,
+            SpanStats {
+                count: 3,
+                total: Duration::from_millis(15),
+                last: Duration::from_millis(1),
+                max: Duration::from_millis(10),
+            }
+        );
+        assert_eq!(stats.mean(), Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,248 @@
+use editor::Editor;
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, WeakEntity};
+use language::{Buffer, DetectionConfidence, IndentKind, IndentSize};
+use settings::Settings as _;
+use ui::prelude::*;
+use util::paths::PathStyle;
+use workspace::notifications::{Notification, NotificationFrame, NotificationId, SuppressEvent};
+
+use crate::{
+    IndentSizeSelectorSettings, auto_detect,
+    detection::{buffer_detection_in_background, to_indent_size},
+    file_buffer, fix_on_save,
+    indent_source::IndentSource,
+    persistence,
+    project_conversion::indent_label,
+    read_indent_size,
+};
+
+/// Offers to use the indentation the file opened in `editor` clearly uses, or to convert the
+/// file, when it differs from the configured one and `notify_indentation_mismatch` is enabled.
+pub(crate) fn watch(editor: &mut Editor, window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let Some(window) = window else {
+        return;
+    };
+    if !editor.mode().is_full() || !editor.buffer().read(cx).is_singleton() {
+        return;
+    }
+    let settings = IndentSizeSelectorSettings::get_global(cx);
+    // Auto-detection already switches the file to the indentation it uses.
+    if !settings.notify_indentation_mismatch || settings.auto_detect_indentation {
+        return;
+    }
+
+    // The editor only joins its workspace once it's been added to a pane, and restoring an
+    // earlier choice for the file happens as the editor is created.
+    cx.defer_in(window, |editor, _, cx| {
+        let Some(buffer) = file_buffer(editor, cx) else {
+            return;
+        };
+        if buffer.read(cx).read_only() || persistence::is_mismatch_ignored(&buffer, cx) {
+            return;
+        }
+        let detection = buffer_detection_in_background(&buffer, cx);
+        cx.spawn(async move |editor, cx| {
+            let detection = detection.await;
+            if detection.confidence() != Some(DetectionConfidence::High) {
+                return;
+            }
+            let Some(detected) = to_indent_size(detection) else {
+                return;
+            };
+            editor
+                .update(cx, |editor, cx| notify(editor, buffer, detected, cx))
+                .ok();
+        })
+        .detach();
+    });
+}
+
+fn notify(
+    editor: &mut Editor,
+    buffer: Entity<Buffer>,
+    detected: IndentSize,
+    cx: &mut Context<Editor>,
+) {
+    // An indentation picked for the file is kept whatever its content uses.
+    if matches!(
+        IndentSource::for_editor(editor, cx),
+        IndentSource::SelectorOverride
+            | IndentSource::BufferOverride
+            | IndentSource::Detected
+            | IndentSource::Provider { .. }
+    ) {
+        return;
+    }
+    let Some(configured) = read_indent_size(editor, cx) else {
+        return;
+    };
+    let configured = configured.indent_size;
+    if auto_detect::detected_override(detected, configured).is_none() {
+        return;
+    }
+    let Some(workspace) = editor.workspace() else {
+        return;
+    };
+    let editor_handle = cx.weak_entity();
+    workspace.update(cx, |workspace, cx| {
+        workspace.show_notification(
+            NotificationId::composite::<IndentationMismatch>((
+                "indentation-mismatch",
+                buffer.entity_id(),
+            )),
+            cx,
+            |cx| {
+                cx.new(|cx| IndentationMismatch {
+                    focus_handle: cx.focus_handle(),
+                    editor: editor_handle,
+                    buffer,
+                    detected,
+                    configured,
+                })
+            },
+        );
+    });
+}
+
+/// Tells that a freshly opened file is indented differently than configured, with actions to
+/// use the file's indentation until it's closed, to convert the file to the configured one, or
+/// to stop telling about the file.
+struct IndentationMismatch {
+    focus_handle: FocusHandle,
+    editor: WeakEntity<Editor>,
+    buffer: Entity<Buffer>,
+    detected: IndentSize,
+    configured: IndentSize,
+}
+
+impl IndentationMismatch {
+    fn use_file_indentation(&mut self, cx: &mut Context<Self>) {
+        let (buffer, detected) = (self.buffer.clone(), self.detected);
+        self.editor
+            .update(cx, |editor, cx| {
+                auto_detect::apply_indentation(editor, &buffer, detected, cx);
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn convert_file(&mut self, cx: &mut Context<Self>) {
+        fix_on_save::convert_existing_indentation(
+            &self.buffer,
+            &[],
+            self.configured.len,
+            self.configured.kind == IndentKind::Tab,
+            cx,
+        );
+        cx.emit(DismissEvent);
+    }
+
+    fn ignore_file(&mut self, cx: &mut Context<Self>) {
+        persistence::ignore_mismatch(&self.buffer, cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Focusable for IndentationMismatch {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<DismissEvent> for IndentationMismatch {}
+impl EventEmitter<SuppressEvent> for IndentationMismatch {}
+impl Notification for IndentationMismatch {}
+
+impl Render for IndentationMismatch {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let path = self
+            .buffer
+            .read(cx)
+            .file()
+            .map(|file| file.path().display(PathStyle::local()).to_string())
+            .unwrap_or_else(|| "This file".to_string());
+        let message = format!(
+            "{path} is indented with {}, but configured to use {}.",
+            indent_label(self.detected),
+            indent_label(self.configured)
+        );
+
+        NotificationFrame::new()
+            .with_title(Some("Indentation Mismatch"))
+            .show_suppress_button(false)
+            .on_close(cx.listener(|_, _, _, cx| cx.emit(DismissEvent)))
+            .with_content(Label::new(message))
+            .with_suffix(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("use-file-indentation", "Use File's Style")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, _, cx| this.use_file_indentation(cx))),
+                    )
+                    .child(
+                        Button::new("convert-file", "Convert File")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, _, cx| this.convert_file(cx))),
+                    )
+                    .child(
+                        Button::new("ignore-file", "Ignore for This File")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, _, cx| this.ignore_file(cx))),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace};
+
+    #[gpui::test]
+    async fn test_notifies_of_mismatched_files(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "matching.txt": "    a\n".repeat(8),
+                "mismatched.txt": "\ta\n".repeat(8),
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace =
+            multi_workspace.read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone());
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let open_and_watch = async |path: &str, cx: &mut gpui::VisualTestContext| {
+            let item = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.open_path((worktree_id, rel_path(path)), None, true, window, cx)
+                })
+                .await
+                .unwrap();
+            let editor = cx.update(|_, cx| item.act_as::<Editor>(cx).unwrap());
+            editor.update_in(cx, |editor, window, cx| watch(editor, Some(window), cx));
+            cx.run_until_parked();
+            workspace.read_with(cx, |workspace, _| workspace.notification_ids().len())
+        };
+
+        // A file indented like configured shows nothing.
+        assert_eq!(open_and_watch("matching.txt", cx).await, 0);
+
+        // A file clearly indented with tabs, rather than the configured 4 spaces, tells of it.
+        assert_eq!(open_and_watch("mismatched.txt", cx).await, 1);
+    }
+}
@@ -0,0 +1,219 @@
+use editor::Editor;
+use gpui::{App, Entity, SharedString};
+use language::{Buffer, BufferSnapshot, DetectedIndent, Detection, IndentKind, IndentSize, Point};
+use smol::future::yield_now;
+
+use crate::{
+    detection::{buffer_detection, detect_in_chunks, row_chunks},
+    file_buffer,
+    fix_on_save::conversion_edits,
+    metrics::{self, Span},
+    read_indent_size,
+};
+
+/// A file whose lines are indented with tabs as well as spaces, along with the indentation to
+/// normalize them to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct MixedIndentation {
+    tab_indented_lines: usize,
+    space_indented_lines: usize,
+    /// The number of spaces in a level of the lines indented with spaces.
+    spaces: u32,
+    /// The indentation configured for the file.
+    target: IndentSize,
+    /// The first line indented with the kind the configured indentation doesn't use.
+    first_offending_row: u32,
+}
+
+/// Whether `detection` found lines indented with tabs as well as spaces.
+fn is_mixed(detection: &Detection) -> bool {
+    detection.tab_indented_lines > 0 && detection.space_indented_lines > 0
+}
+
+/// Whether `row` is indented with the kind `configured` doesn't use.
+fn is_offending(snapshot: &BufferSnapshot, row: u32, configured: IndentSize) -> bool {
+    let indent = snapshot.indent_size_for_line(row);
+    indent.len > 0 && indent.kind != configured.kind && !snapshot.is_line_blank(row)
+}
+
+impl MixedIndentation {
+    /// Detects whether the file open in `editor` mixes tabs and spaces in its indentation,
+    /// returning its buffer along with what was found.
+    pub(crate) fn in_editor(
+        editor: &Entity<Editor>,
+        cx: &mut App,
+    ) -> Option<(Entity<Buffer>, Self)> {
+        let buffer = file_buffer(editor.read(cx), cx)?;
+        let configured = read_indent_size(editor.read(cx), cx)?.indent_size;
+        let mixed = Self::detect(&buffer, configured, cx)?;
+        Some((buffer, mixed))
+    }
+
+    /// Detects whether `buffer` mixes tabs and spaces in its indentation, to normalize it to its
+    /// `configured` indentation. The lines indented with spaces are taken to use as many per
+    /// level as detected, or else as many as a tab is wide.
+    pub(crate) fn detect(
+        buffer: &Entity<Buffer>,
+        configured: IndentSize,
+        cx: &mut App,
+    ) -> Option<Self> {
+        let detection = buffer_detection(buffer, cx);
+        Self::from_detection(detection, &buffer.read(cx).snapshot(), configured)
+    }
+
+    /// Like [`Self::detect`], but without reusing earlier scans, and scanning `snapshot` a
+    /// chunk of rows at a time, so it can run in the background without holding up its
+    /// executor.
+    pub(crate) async fn scan(snapshot: &BufferSnapshot, configured: IndentSize) -> Option<Self> {
+        let detection = detect_in_chunks(snapshot).await;
+        if !is_mixed(&detection) {
+            return None;
+        }
+        let mut first_offending_row = None;
+        for mut rows in row_chunks(snapshot) {
+            first_offending_row = rows.find(|row| is_offending(snapshot, *row, configured));
+            if first_offending_row.is_some() {
+                break;
+            }
+            yield_now().await;
+        }
+        Some(Self::new(detection, first_offending_row?, configured))
+    }
+
+    fn from_detection(
+        detection: Detection,
+        snapshot: &BufferSnapshot,
+        configured: IndentSize,
+    ) -> Option<Self> {
+        if !is_mixed(&detection) {
+            return None;
+        }
+        let first_offending_row =
+            (0..=snapshot.max_point().row).find(|row| is_offending(snapshot, *row, configured))?;
+        Some(Self::new(detection, first_offending_row, configured))
+    }
+
+    fn new(detection: Detection, first_offending_row: u32, configured: IndentSize) -> Self {
+        let spaces = match detection.indent {
+            Some(DetectedIndent::Spaces(len)) => len,
+            _ => configured.len,
+        };
+        Self {
+            tab_indented_lines: detection.tab_indented_lines,
+            space_indented_lines: detection.space_indented_lines,
+            spaces,
+            target: configured,
+            first_offending_row,
+        }
+    }
+
+    /// The start of the first line indented with the kind the configured indentation doesn't
+    /// use.
+    pub(crate) fn first_offending_line(&self) -> Point {
+        Point::new(self.first_offending_row, 0)
+    }
+
+    /// The label of the button going to [`Self::first_offending_line`], such as "Go to First
+    /// Tab-Indented Line".
+    pub(crate) fn go_to_label(&self) -> SharedString {
+        match self.target.kind {
+            IndentKind::Tab => "Go to First Space-Indented Line".into(),
+            IndentKind::Space => "Go to First Tab-Indented Line".into(),
+        }
+    }
+
+    /// Describes how the file's lines are indented, such as "3 lines are indented with tabs and
+    /// 12 with spaces".
+    pub(crate) fn description(&self) -> String {
+        format!(
+            "{} {} indented with tabs and {} with spaces",
+            self.tab_indented_lines,
+            if self.tab_indented_lines == 1 {
+                "line is"
+            } else {
+                "lines are"
+            },
+            self.space_indented_lines,
+        )
+    }
+
+    /// The label of the button normalizing the file, such as "Normalize to 4 Spaces".
+    pub(crate) fn fix_label(&self) -> SharedString {
+        match self.target.kind {
+            IndentKind::Tab => "Normalize to Tabs".into(),
+            IndentKind::Space if self.target.len == 1 => "Normalize to 1 Space".into(),
+            IndentKind::Space => format!("Normalize to {} Spaces", self.target.len).into(),
+        }
+    }
+
+    /// Re-indents the lines of `buffer` indented with spaces, then those indented with tabs, to
+    /// the configured indentation, in one edit that can be undone on its own.
+    pub(crate) fn normalize(&self, buffer: &Entity<Buffer>, cx: &mut App) {
+        let (spaces, target) = (IndentSize::spaces(self.spaces), self.target);
+        metrics::measure(Span::Conversion, cx, |cx| {
+            buffer.update(cx, |buffer, cx| {
+                buffer.finalize_last_transaction();
+                buffer.start_transaction();
+                // Each pass only re-indents the lines starting with the kind it converts from,
+                // so the lines the first converted are left alone by the second.
+                for from in [spaces, IndentSize::tab()] {
+                    let edits = conversion_edits(&buffer.snapshot(), &[], from, target, cx);
+                    buffer.edit(edits, None, cx);
+                }
+                buffer.end_transaction(cx);
+                buffer.finalize_last_transaction();
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_normalize_mixed_indentation(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+        });
+
+        let buffer = cx.new(|cx| Buffer::local("a:\n\tb:\n\t\tc: 1\n  d:\n    e: 2\n", cx));
+        let mixed = cx
+            .update(|cx| MixedIndentation::detect(&buffer, IndentSize::spaces(4), cx))
+            .unwrap();
+        assert_eq!(
+            mixed.description(),
+            "2 lines are indented with tabs and 2 with spaces"
+        );
+        assert_eq!(mixed.fix_label(), "Normalize to 4 Spaces");
+        assert_eq!(mixed.first_offending_line(), Point::new(1, 0));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        assert_eq!(
+            MixedIndentation::scan(&snapshot, IndentSize::tab())
+                .await
+                .map(|mixed| mixed.first_offending_line()),
+            Some(Point::new(3, 0))
+        );
+
+        cx.update(|cx| mixed.normalize(&buffer, cx));
+        buffer.read_with(cx, |buffer, _| {
+            assert_eq!(
+                buffer.text(),
+                "a:\n    b:\n        c: 1\n    d:\n        e: 2\n"
+            );
+        });
+        assert_eq!(
+            cx.update(|cx| MixedIndentation::detect(&buffer, IndentSize::spaces(4), cx)),
+            None
+        );
+
+        // A single undo restores the mixed indentation.
+        buffer.update(cx, |buffer, cx| {
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), "a:\n\tb:\n\t\tc: 1\n  d:\n    e: 2\n");
+        });
+    }
+}
@@ -0,0 +1,157 @@
+use editor::Editor;
+use language::{DetectedIndent, language_settings::IndentationOverride};
+use settings::Settings as _;
+use std::num::NonZeroU32;
+use ui::prelude::*;
+
+use crate::{
+    IndentSizeSelectorSettings, indentation_provider::is_left_to_providers,
+    project_conventions::project_convention, resolved_whitespace,
+};
+
+/// Indents the empty file that `editor` was just opened for as most files of its language in
+/// the project are, when the `new_files_use_project_convention` setting is enabled and only the
+/// defaults or the user settings would indent it otherwise.
+pub(crate) fn watch(editor: &mut Editor, window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let Some(window) = window else {
+        return;
+    };
+    if !editor.mode().is_full()
+        || !IndentSizeSelectorSettings::get_global(cx).new_files_use_project_convention
+    {
+        return;
+    }
+
+    // Restoring an earlier choice for the file happens as the editor is created, and should win.
+    cx.defer_in(window, |editor, _, cx| {
+        if !is_new_file(editor, cx) {
+            return;
+        }
+        let (Some(project), Some(buffer)) = (
+            editor.project().cloned(),
+            editor.buffer().read(cx).as_singleton(),
+        ) else {
+            return;
+        };
+        let Some(language) = buffer.read(cx).file().and_then(|file| {
+            project
+                .read(cx)
+                .languages()
+                .language_for_file_path(file.path().as_std_path())
+        }) else {
+            return;
+        };
+        let convention = project_convention(&project, language.name(), cx);
+        cx.spawn(async move |editor, cx| {
+            let Some(indent) = convention.await else {
+                return;
+            };
+            editor
+                .update(cx, |editor, cx| {
+                    // Lines written while the project was sampled have an indentation of their own.
+                    if !is_new_file(editor, cx) {
+                        return;
+                    }
+                    let Some(whitespace) = resolved_whitespace(editor, cx) else {
+                        return;
+                    };
+                    let indentation_override = match indent {
+                        DetectedIndent::Tabs => IndentationOverride {
+                            tab_size: whitespace.tab_width,
+                            tab_width: None,
+                            hard_tabs: true,
+                        },
+                        DetectedIndent::Spaces(len) => {
+                            let Some(tab_size) = NonZeroU32::new(len) else {
+                                return;
+                            };
+                            IndentationOverride {
+                                tab_size,
+                                tab_width: None,
+                                hard_tabs: false,
+                            }
+                        }
+                    };
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.set_indentation_override(Some(indentation_override), cx)
+                    });
+                    // The status item re-reads the indentation whenever the editor notifies.
+                    cx.notify();
+                })
+                .ok();
+        })
+        .detach();
+    });
+}
+
+/// Whether `editor` shows an empty file of a folder opened in the project, whose indentation
+/// is left to the defaults or the user settings.
+fn is_new_file(editor: &Editor, cx: &App) -> bool {
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return false;
+    };
+    let buffer = buffer.read(cx);
+    // A file opened on its own has no project to take a convention from.
+    buffer.is_empty()
+        && buffer.file().is_some_and(|file| !file.path().is_empty())
+        && buffer.indentation_override().is_none()
+        && is_left_to_providers(editor, cx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use util::path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_new_file_uses_project_convention(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "a.rs": "fn a() {\n  b();\n  c();\n}\n",
+                "b.rs": "fn b() {\n  if c {\n    d();\n  }\n}\n",
+                "new.rs": "",
+                "written.rs": "fn f() {}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let open_and_watch = async |path: &str, cx: &mut TestAppContext| {
+            let buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(path, cx))
+                .await
+                .unwrap();
+            let (editor, cx) = cx.add_window_view(|window, cx| {
+                Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+            });
+            editor.update_in(cx, |editor, window, cx| watch(editor, Some(window), cx));
+            cx.run_until_parked();
+            buffer.read_with(cx, |buffer, _| buffer.indentation_override().copied())
+        };
+
+        // The empty file takes the 2 spaces the project's other Rust files use.
+        assert_eq!(
+            open_and_watch(path!("/dir/new.rs"), cx).await,
+            Some(IndentationOverride {
+                tab_size: NonZeroU32::new(2).unwrap(),
+                tab_width: None,
+                hard_tabs: false,
+            })
+        );
+
+        // A file with content of its own is left to the settings.
+        assert_eq!(open_and_watch(path!("/dir/written.rs"), cx).await, None);
+    }
+}
@@ -0,0 +1,237 @@
+use anyhow::Result;
+use gpui::App;
+use settings::{LocalSettingsKind, LocalSettingsPath, SettingsStore, WorktreeId};
+use util::ResultExt as _;
+
+use crate::history;
+
+/// `.editorconfig` overrides that are installed together: either all of them are, or none is.
+#[derive(Default)]
+pub(crate) struct OverrideBatch {
+    writes: Vec<OverrideWrite>,
+}
+
+struct OverrideWrite {
+    worktree_id: WorktreeId,
+    path: LocalSettingsPath,
+    content: Option<String>,
+}
+
+/// Where a batch reads and installs `.editorconfig` overrides.
+trait OverrideStore {
+    fn read(&self, worktree_id: WorktreeId, path: &LocalSettingsPath) -> Option<String>;
+
+    fn write(
+        &mut self,
+        worktree_id: WorktreeId,
+        path: LocalSettingsPath,
+        content: Option<&str>,
+    ) -> Result<()>;
+}
+
+struct SettingsStoreOverrides<'a> {
+    store: &'a mut SettingsStore,
+    cx: &'a mut App,
+}
+
+impl OverrideStore for SettingsStoreOverrides<'_> {
+    fn read(&self, worktree_id: WorktreeId, path: &LocalSettingsPath) -> Option<String> {
+        self.store
+            .editorconfig_store
+            .read(self.cx)
+            .local_editorconfig_settings(worktree_id)
+            .find(|(config_path, _, _)| config_path == path)
+            .map(|(_, content, _)| content.to_string())
+    }
+
+    fn write(
+        &mut self,
+        worktree_id: WorktreeId,
+        path: LocalSettingsPath,
+        content: Option<&str>,
+    ) -> Result<()> {
+        self.store.set_local_settings(
+            worktree_id,
+            path,
+            LocalSettingsKind::Editorconfig,
+            content,
+            self.cx,
+        )?;
+        Ok(())
+    }
+}
+
+impl OverrideBatch {
+    /// Stages setting the override at `path` to `content`, or removing it when that's `None`.
+    pub(crate) fn set(
+        &mut self,
+        worktree_id: WorktreeId,
+        path: LocalSettingsPath,
+        content: Option<String>,
+    ) {
+        self.writes.push(OverrideWrite {
+            worktree_id,
+            path,
+            content,
+        });
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Installs every staged override. When one of them fails, those already installed are
+    /// restored to what they were before, and the failure is returned. Otherwise, what they
+    /// replaced is recorded in the history of changes.
+    pub(crate) fn commit(self, cx: &mut App) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let installed = cx.update_global::<SettingsStore, _>(|store, cx| {
+            self.commit_to(&mut SettingsStoreOverrides { store, cx })
+        })?;
+        for (worktree_id, path, previous) in installed {
+            history::record_editorconfig(worktree_id, path, previous, cx);
+        }
+        Ok(())
+    }
+
+    /// Installs every staged override into `overrides`, returning what each one replaced.
+    fn commit_to(
+        self,
+        overrides: &mut impl OverrideStore,
+    ) -> Result<Vec<(WorktreeId, LocalSettingsPath, Option<String>)>> {
+        let mut installed = Vec::new();
+        for write in self.writes {
+            let previous = overrides.read(write.worktree_id, &write.path);
+            match overrides.write(
+                write.worktree_id,
+                write.path.clone(),
+                write.content.as_deref(),
+            ) {
+                Ok(()) => installed.push((write.worktree_id, write.path, previous)),
+                Err(error) => {
+                    // Restoring in reverse undoes repeated writes to the same path in order.
+                    for (worktree_id, path, previous) in installed.into_iter().rev() {
+                        overrides
+                            .write(worktree_id, path, previous.as_deref())
+                            .log_err();
+                    }
+                    return Err(error);
+                }
+            }
+        }
+        Ok(installed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{governing_editorconfig::set_section_properties, indentation_properties};
+    use collections::HashMap;
+    use util::rel_path::RelPath;
+
+    /// Overrides kept in memory, failing every write of `failing_content`.
+    #[derive(Default)]
+    struct FakeOverrides {
+        contents: HashMap<(WorktreeId, LocalSettingsPath), String>,
+        failing_content: Option<&'static str>,
+    }
+
+    impl OverrideStore for FakeOverrides {
+        fn read(&self, worktree_id: WorktreeId, path: &LocalSettingsPath) -> Option<String> {
+            self.contents.get(&(worktree_id, path.clone())).cloned()
+        }
+
+        fn write(
+            &mut self,
+            worktree_id: WorktreeId,
+            path: LocalSettingsPath,
+            content: Option<&str>,
+        ) -> Result<()> {
+            if content.is_some() && content == self.failing_content {
+                anyhow::bail!("invalid override");
+            }
+            match content {
+                Some(content) => {
+                    self.contents
+                        .insert((worktree_id, path), content.to_string());
+                }
+                None => {
+                    self.contents.remove(&(worktree_id, path));
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_commit_rolls_back_partial_batches() {
+        let worktree_id = WorktreeId::from_usize(1);
+        let path = |path: &str| LocalSettingsPath::InWorktree(RelPath::unix(path).unwrap().into());
+        let mut overrides = FakeOverrides::default();
+        overrides
+            .contents
+            .insert((worktree_id, path("a.rs")), "original".to_string());
+
+        let mut batch = OverrideBatch::default();
+        batch.set(worktree_id, path("a.rs"), Some("first".to_string()));
+        batch.set(worktree_id, path("b.rs"), Some("first".to_string()));
+        batch.set(worktree_id, path("a.rs"), None);
+        batch.commit_to(&mut overrides).unwrap();
+        assert_eq!(overrides.read(worktree_id, &path("a.rs")), None);
+        assert_eq!(
+            overrides.read(worktree_id, &path("b.rs")).as_deref(),
+            Some("first")
+        );
+
+        overrides.failing_content = Some("invalid");
+        let mut batch = OverrideBatch::default();
+        batch.set(worktree_id, path("a.rs"), Some("second".to_string()));
+        batch.set(worktree_id, path("b.rs"), Some("second".to_string()));
+        batch.set(worktree_id, path("b.rs"), Some("third".to_string()));
+        batch.set(worktree_id, path("c.rs"), Some("invalid".to_string()));
+        assert!(batch.commit_to(&mut overrides).is_err());
+        assert_eq!(overrides.read(worktree_id, &path("a.rs")), None);
+        assert_eq!(
+            overrides.read(worktree_id, &path("b.rs")).as_deref(),
+            Some("first")
+        );
+        assert_eq!(overrides.read(worktree_id, &path("c.rs")), None);
+    }
+
+    #[test]
+    fn test_commit_keeps_the_indentation_and_profile_together() {
+        let worktree_id = WorktreeId::from_usize(1);
+        let path = |path: &str| LocalSettingsPath::InWorktree(RelPath::unix(path).unwrap().into());
+        let original = "[*.rs]\nindent_style = tab\n";
+        // The scope chooser stages a profile's properties in the same write as its indentation.
+        let mut properties = indentation_properties(2, false, None);
+        properties.push(("max_line_length", "100".to_string()));
+        let content = set_section_properties(original, "*.rs", &properties);
+        let mut overrides = FakeOverrides::default();
+        overrides
+            .contents
+            .insert((worktree_id, path("a.rs")), original.to_string());
+
+        // A failing second write leaves neither the indentation nor the profile behind.
+        overrides.failing_content = Some("invalid");
+        let mut batch = OverrideBatch::default();
+        batch.set(worktree_id, path("a.rs"), Some(content.clone()));
+        batch.set(worktree_id, path("b.rs"), Some("invalid".to_string()));
+        assert!(batch.commit_to(&mut overrides).is_err());
+        assert_eq!(
+            overrides.read(worktree_id, &path("a.rs")).as_deref(),
+            Some(original)
+        );
+
+        let mut batch = OverrideBatch::default();
+        batch.set(worktree_id, path("a.rs"), Some(content));
+        batch.commit_to(&mut overrides).unwrap();
+        assert_eq!(
+            overrides.read(worktree_id, &path("a.rs")).as_deref(),
+            Some("[*.rs]\nindent_style = space\nindent_size = 2\nmax_line_length = 100\n")
+        );
+    }
+}
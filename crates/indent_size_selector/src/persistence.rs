@@ -0,0 +1,269 @@
+use anyhow::Result;
+use db::{
+    query,
+    sqlez::{domain::Domain, thread_safe_connection::ThreadSafeConnection},
+    sqlez_macros::sql,
+};
+use editor::Editor;
+use gpui::{App, Context, Entity, Window};
+use language::Buffer;
+use std::{
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+};
+use util::ResultExt as _;
+use workspace::{WorkspaceDb, WorkspaceId};
+
+use crate::set_path_override;
+
+pub(crate) struct IndentSizeSelectorDb(ThreadSafeConnection);
+
+impl Domain for IndentSizeSelectorDb {
+    const NAME: &str = stringify!(IndentSizeSelectorDb);
+    // Files are keyed by their absolute path rather than by worktree, since worktree ids only
+    // last for a session.
+    const MIGRATIONS: &[&str] = &[
+        sql!(
+            CREATE TABLE file_indent_sizes (
+                path TEXT PRIMARY KEY NOT NULL,
+                indent_size INTEGER NOT NULL,
+                hard_tabs INTEGER NOT NULL
+            ) STRICT;
+        ),
+        sql!(
+            CREATE TABLE pending_project_checks (
+                workspace_id INTEGER PRIMARY KEY NOT NULL,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                    ON DELETE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            CREATE TABLE ignored_indentation_mismatches (
+                path TEXT PRIMARY KEY NOT NULL
+            ) STRICT;
+        ),
+        sql!(
+            ALTER TABLE file_indent_sizes ADD COLUMN tab_width INTEGER;
+        ),
+    ];
+}
+
+db::static_connection!(IndentSizeSelectorDb, [WorkspaceDb]);
+
+impl IndentSizeSelectorDb {
+    query! {
+        fn get_file_indent_size(path: &Path) -> Result<Option<(u32, bool, Option<u32>)>> {
+            SELECT indent_size, hard_tabs, tab_width
+            FROM file_indent_sizes
+            WHERE path = ?
+        }
+    }
+
+    query! {
+        async fn save_file_indent_size(
+            path: PathBuf,
+            indent_size: u32,
+            hard_tabs: bool,
+            tab_width: Option<u32>
+        ) -> Result<()> {
+            INSERT INTO file_indent_sizes (path, indent_size, hard_tabs, tab_width)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT DO UPDATE SET
+                indent_size = ?2,
+                hard_tabs = ?3,
+                tab_width = ?4
+        }
+    }
+
+    query! {
+        async fn delete_file_indent_size(path: PathBuf) -> Result<()> {
+            DELETE FROM file_indent_sizes WHERE path = ?
+        }
+    }
+
+    query! {
+        fn has_pending_project_check(workspace_id: WorkspaceId) -> Result<bool> {
+            SELECT EXISTS(
+                SELECT 1 FROM pending_project_checks WHERE workspace_id = ?
+            )
+        }
+    }
+
+    query! {
+        async fn save_pending_project_check(workspace_id: WorkspaceId) -> Result<()> {
+            INSERT OR IGNORE INTO pending_project_checks (workspace_id) VALUES (?)
+        }
+    }
+
+    query! {
+        async fn delete_pending_project_check(workspace_id: WorkspaceId) -> Result<()> {
+            DELETE FROM pending_project_checks WHERE workspace_id = ?
+        }
+    }
+
+    query! {
+        fn is_indentation_mismatch_ignored(path: &Path) -> Result<bool> {
+            SELECT EXISTS(
+                SELECT 1 FROM ignored_indentation_mismatches WHERE path = ?
+            )
+        }
+    }
+
+    query! {
+        async fn save_ignored_indentation_mismatch(path: PathBuf) -> Result<()> {
+            INSERT OR IGNORE INTO ignored_indentation_mismatches (path) VALUES (?)
+        }
+    }
+}
+
+fn local_abs_path(buffer: &Entity<Buffer>, cx: &App) -> Option<PathBuf> {
+    let file = buffer.read(cx).file()?;
+    Some(file.as_local()?.abs_path(cx))
+}
+
+/// Remembers the indentation chosen for the file of `buffer`, so that it's applied again when
+/// the file is opened in a later session.
+pub(crate) fn remember_choice(
+    buffer: &Entity<Buffer>,
+    size: u32,
+    hard_tabs: bool,
+    tab_width: Option<NonZeroU32>,
+    cx: &App,
+) {
+    let Some(path) = local_abs_path(buffer, cx) else {
+        return;
+    };
+    let tab_width = tab_width.map(NonZeroU32::get);
+    let db = IndentSizeSelectorDb::global(cx);
+    db::write_and_log(cx, move || async move {
+        db.save_file_indent_size(path, size, hard_tabs, tab_width)
+            .await
+    });
+}
+
+/// Forgets the indentation chosen for the file of `buffer`, once it's been written somewhere
+/// that outlasts the session.
+pub(crate) fn forget_choice(buffer: &Entity<Buffer>, cx: &App) {
+    if let Some(path) = local_abs_path(buffer, cx) {
+        forget_choice_at(path, cx);
+    }
+}
+
+/// Forgets the indentation chosen for the file at `path`, whether or not it's open.
+pub(crate) fn forget_choice_at(path: PathBuf, cx: &App) {
+    let db = IndentSizeSelectorDb::global(cx);
+    db::write_and_log(
+        cx,
+        move || async move { db.delete_file_indent_size(path).await },
+    );
+}
+
+/// Applies the indentation chosen in an earlier session to the file open in `editor`.
+pub(crate) fn restore_choice(
+    editor: &mut Editor,
+    _: Option<&mut Window>,
+    cx: &mut Context<Editor>,
+) {
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let Some(path) = local_abs_path(&buffer, cx) else {
+        return;
+    };
+    if let Some((size, hard_tabs, tab_width)) = IndentSizeSelectorDb::global(cx)
+        .get_file_indent_size(&path)
+        .log_err()
+        .flatten()
+    {
+        let tab_width = tab_width.and_then(NonZeroU32::new);
+        set_path_override(&buffer, size, hard_tabs, tab_width, cx);
+    }
+}
+
+/// Records that the project of the workspace is being checked for files that need re-indenting,
+/// so that a check cut short by quitting can be offered again in the next session.
+pub(crate) fn record_pending_check(workspace_id: WorkspaceId, cx: &App) {
+    let db = IndentSizeSelectorDb::global(cx);
+    db::write_and_log(cx, move || async move {
+        db.save_pending_project_check(workspace_id).await
+    });
+}
+
+/// Forgets the check of the workspace's project, once it's finished or been discarded.
+pub(crate) fn forget_pending_check(workspace_id: WorkspaceId, cx: &App) {
+    let db = IndentSizeSelectorDb::global(cx);
+    db::write_and_log(cx, move || async move {
+        db.delete_pending_project_check(workspace_id).await
+    });
+}
+
+/// Whether a check of the workspace's project was still running when Zed last quit.
+pub(crate) fn has_pending_check(workspace_id: WorkspaceId, cx: &App) -> bool {
+    IndentSizeSelectorDb::global(cx)
+        .has_pending_project_check(workspace_id)
+        .log_err()
+        .unwrap_or(false)
+}
+
+/// Stops notifying that the file of `buffer` is indented differently than configured, in this
+/// session and later ones.
+pub(crate) fn ignore_mismatch(buffer: &Entity<Buffer>, cx: &App) {
+    let Some(path) = local_abs_path(buffer, cx) else {
+        return;
+    };
+    let db = IndentSizeSelectorDb::global(cx);
+    db::write_and_log(cx, move || async move {
+        db.save_ignored_indentation_mismatch(path).await
+    });
+}
+
+/// Whether notifying that the file of `buffer` is indented differently than configured was
+/// turned off with [`ignore_mismatch`].
+pub(crate) fn is_mismatch_ignored(buffer: &Entity<Buffer>, cx: &App) -> bool {
+    local_abs_path(buffer, cx).is_some_and(|path| {
+        IndentSizeSelectorDb::global(cx)
+            .is_indentation_mismatch_ignored(&path)
+            .log_err()
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_save_and_get_file_indent_size(cx: &mut gpui::TestAppContext) {
+        let db = cx.update(|cx| IndentSizeSelectorDb::global(cx));
+        let path = PathBuf::from("/tmp/test_file_indent_sizes.rs");
+
+        db.save_file_indent_size(path.clone(), 2, false, None)
+            .await
+            .unwrap();
+        db.save_file_indent_size(path.clone(), 4, true, Some(8))
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_file_indent_size(&path).unwrap(),
+            Some((4, true, Some(8)))
+        );
+
+        db.delete_file_indent_size(path.clone()).await.unwrap();
+        assert_eq!(db.get_file_indent_size(&path).unwrap(), None);
+    }
+
+    #[gpui::test]
+    async fn test_ignore_indentation_mismatch(cx: &mut gpui::TestAppContext) {
+        let db = cx.update(|cx| IndentSizeSelectorDb::global(cx));
+        let path = PathBuf::from("/tmp/test_ignored_indentation_mismatches.rs");
+
+        assert!(!db.is_indentation_mismatch_ignored(&path).unwrap());
+        db.save_ignored_indentation_mismatch(path.clone())
+            .await
+            .unwrap();
+        db.save_ignored_indentation_mismatch(path.clone())
+            .await
+            .unwrap();
+        assert!(db.is_indentation_mismatch_ignored(&path).unwrap());
+    }
+}
@@ -0,0 +1,181 @@
+use collections::HashMap;
+use editor::Editor;
+use gpui::{App, Global};
+use language::{BufferId, IndentKind, IndentSize, language_settings::IndentationOverride};
+use settings::IndentSizeSelectorOverrideScope;
+use std::num::NonZeroU32;
+use ui::prelude::*;
+use workspace::{Toast, notifications::NotificationId};
+
+use crate::{
+    ToggleLastIndentation, history,
+    indentation_events::{self, IndentationChanged, IndentationEvents},
+    read_indent_size, set_buffer_override,
+};
+
+/// The indentation each buffer had before the latest one the selector applied to it.
+#[derive(Default)]
+struct PreviousIndentations(HashMap<BufferId, IndentSize>);
+
+impl Global for PreviousIndentations {}
+
+/// Remembers the indentation each buffer had before the selector changed it, to switch back to.
+pub(crate) fn init(cx: &mut App) {
+    cx.subscribe(
+        &IndentationEvents::global(cx),
+        |_, event: &IndentationChanged, cx| {
+            if let Some(old) = event.old
+                && old != event.new
+            {
+                let buffer_id = event.buffer.read(cx).remote_id();
+                cx.default_global::<PreviousIndentations>()
+                    .0
+                    .insert(buffer_id, old);
+            }
+        },
+    )
+    .detach();
+}
+
+/// Handles switching the active file between its indentation and the one it had before.
+pub(crate) fn register(editor: &mut Editor, _: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action(move |_: &ToggleLastIndentation, _, cx| {
+            if let Some(editor) = editor_handle.upgrade() {
+                toggle_last_indentation(&editor, cx);
+            }
+        })
+        .detach();
+}
+
+/// Overrides the indentation of the buffer open in `editor` with the one it had before the
+/// latest change, which then becomes the one to switch back to.
+fn toggle_last_indentation(editor: &Entity<Editor>, cx: &mut App) {
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let Some(current) = read_indent_size(editor.read(cx), cx) else {
+        return;
+    };
+    let current = current.indent_size;
+    let previous = cx
+        .try_global::<PreviousIndentations>()
+        .and_then(|previous| previous.0.get(&buffer.read(cx).remote_id()))
+        .copied()
+        .filter(|previous| *previous != current);
+    let Some(previous) = previous else {
+        if let Some(workspace) = editor.read(cx).workspace() {
+            struct NoPreviousIndentation;
+
+            workspace.update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<NoPreviousIndentation>(),
+                        "No other indentation was applied to this file to switch back to",
+                    )
+                    .autohide(),
+                    cx,
+                );
+            });
+        }
+        return;
+    };
+    let Some(tab_size) = NonZeroU32::new(previous.len) else {
+        return;
+    };
+    let hard_tabs = previous.kind == IndentKind::Tab;
+    history::transact(cx, |cx| {
+        set_buffer_override(
+            &buffer,
+            IndentationOverride {
+                tab_size,
+                tab_width: None,
+                hard_tabs,
+            },
+            cx,
+        );
+    });
+    indentation_events::emit_applied(
+        vec![(buffer, Some(current))],
+        previous.len,
+        hard_tabs,
+        IndentSizeSelectorOverrideScope::Buffer,
+        cx,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use language::Buffer;
+    use workspace::AppState;
+
+    fn open_editor(cx: &mut TestAppContext) -> (Entity<Editor>, &mut VisualTestContext) {
+        let buffer = cx.new(|cx| Buffer::local("fn f() {\n    g();\n}\n", cx));
+        cx.add_window_view(|window, cx| Editor::for_buffer(buffer, None, window, cx))
+    }
+
+    /// Applies `size` spaces to the buffer open in `editor`, as the selector does.
+    fn apply(editor: &Entity<Editor>, size: u32, cx: &mut VisualTestContext) {
+        cx.update(|_, cx| {
+            let buffer = editor.read(cx).buffer().read(cx).as_singleton().unwrap();
+            let old = read_indent_size(editor.read(cx), cx).map(|current| current.indent_size);
+            let indentation_override = IndentationOverride {
+                tab_size: NonZeroU32::new(size).unwrap(),
+                tab_width: None,
+                hard_tabs: false,
+            };
+            set_buffer_override(&buffer, indentation_override, cx);
+            indentation_events::emit_applied(
+                vec![(buffer, old)],
+                size,
+                false,
+                IndentSizeSelectorOverrideScope::Buffer,
+                cx,
+            );
+        });
+    }
+
+    fn indent_size(editor: &Entity<Editor>, cx: &mut VisualTestContext) -> IndentSize {
+        cx.update(|_, cx| read_indent_size(editor.read(cx), cx).unwrap().indent_size)
+    }
+
+    #[gpui::test]
+    fn test_toggle_last_indentation(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            init(cx);
+        });
+
+        let (editor, cx) = open_editor(cx);
+        apply(&editor, 2, cx);
+        apply(&editor, 8, cx);
+        assert_eq!(indent_size(&editor, cx), IndentSize::spaces(8));
+        cx.update(|_, cx| toggle_last_indentation(&editor, cx));
+        assert_eq!(indent_size(&editor, cx), IndentSize::spaces(2));
+        // The indentation switched away from is the one to switch back to.
+        cx.update(|_, cx| toggle_last_indentation(&editor, cx));
+        assert_eq!(indent_size(&editor, cx), IndentSize::spaces(8));
+    }
+
+    #[gpui::test]
+    fn test_toggle_last_indentation_without_history(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            init(cx);
+        });
+
+        let (editor, cx) = open_editor(cx);
+        let before = indent_size(&editor, cx);
+        cx.update(|_, cx| toggle_last_indentation(&editor, cx));
+        assert_eq!(indent_size(&editor, cx), before);
+        cx.update(|_, cx| {
+            let buffer = editor.read(cx).buffer().read(cx).as_singleton().unwrap();
+            assert_eq!(buffer.read(cx).indentation_override(), None);
+        });
+    }
+}
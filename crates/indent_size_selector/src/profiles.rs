@@ -0,0 +1,88 @@
+use settings::{IndentationProfileContent, LanguageSettingsContent};
+
+use crate::line_length;
+
+/// Describes `profile` in the selector, such as "web: 2 spaces" or "corp-java: 4 spaces, 120
+/// columns".
+pub(crate) fn label(profile: &IndentationProfileContent) -> String {
+    let mut parts = vec![if profile.hard_tabs == Some(true) {
+        format!("tabs ({} wide)", profile.tab_size)
+    } else {
+        format!("{} spaces", profile.tab_size)
+    }];
+    if let Some(max_line_length) = profile.max_line_length {
+        parts.push(format!("{max_line_length} columns"));
+    }
+    if profile.trim_trailing_whitespace == Some(true) {
+        parts.push("trailing whitespace trimmed".to_string());
+    }
+    if profile.insert_final_newline == Some(true) {
+        parts.push("final newline".to_string());
+    }
+    format!("{}: {}", profile.name, parts.join(", "))
+}
+
+/// The `.editorconfig` properties that `profile` sets besides the indentation.
+pub(crate) fn editorconfig_properties(
+    profile: &IndentationProfileContent,
+) -> Vec<(&'static str, String)> {
+    let mut properties = Vec::new();
+    if let Some(max_line_length) = profile.max_line_length {
+        properties.push(("max_line_length", max_line_length.to_string()));
+    }
+    if let Some(trim) = profile.trim_trailing_whitespace {
+        properties.push(("trim_trailing_whitespace", trim.to_string()));
+    }
+    if let Some(insert) = profile.insert_final_newline {
+        properties.push(("insert_final_newline", insert.to_string()));
+    }
+    properties
+}
+
+/// Sets the settings that `profile` sets besides the indentation in `settings`.
+pub(crate) fn set_language_settings(
+    profile: &IndentationProfileContent,
+    settings: &mut LanguageSettingsContent,
+) {
+    if let Some(max_line_length) = profile.max_line_length {
+        line_length::set_language_settings(settings, max_line_length);
+    }
+    if let Some(trim) = profile.trim_trailing_whitespace {
+        settings.remove_trailing_whitespace_on_save = Some(trim);
+    }
+    if let Some(insert) = profile.insert_final_newline {
+        settings.ensure_final_newline_on_save = Some(insert);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn test_profile_properties() {
+        let profile = IndentationProfileContent {
+            name: "corp-java".to_string(),
+            tab_size: NonZeroU32::new(4).unwrap(),
+            hard_tabs: None,
+            max_line_length: Some(120),
+            trim_trailing_whitespace: Some(false),
+            insert_final_newline: None,
+        };
+        assert_eq!(label(&profile), "corp-java: 4 spaces, 120 columns");
+        assert_eq!(
+            editorconfig_properties(&profile),
+            vec![
+                ("max_line_length", "120".to_string()),
+                ("trim_trailing_whitespace", "false".to_string()),
+            ]
+        );
+
+        let mut settings = LanguageSettingsContent::default();
+        set_language_settings(&profile, &mut settings);
+        assert_eq!(settings.preferred_line_length, Some(120));
+        assert_eq!(settings.remove_trailing_whitespace_on_save, Some(false));
+        assert_eq!(settings.ensure_final_newline_on_save, None);
+    }
+}
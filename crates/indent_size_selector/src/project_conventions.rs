@@ -0,0 +1,392 @@
+use collections::HashMap;
+use editor::Editor;
+use gpui::{App, AppContext as _, Context, Entity, EntityId, Global, Task, WeakEntity, Window};
+use language::{DetectedIndent, LanguageName};
+use project::{Project, ProjectPath};
+use std::{collections::BTreeMap, fmt::Write as _, num::NonZeroU32};
+use util::{ResultExt as _, paths::PathStyle};
+use workspace::{
+    Toast, Workspace,
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
+};
+
+use crate::{
+    DetectProjectConventions,
+    apply_scope::{self, LanguageOverrideFile},
+    detection::detect_indentation_in_text,
+};
+
+/// The number of files of each language whose indentation is detected.
+const SAMPLED_FILES_PER_LANGUAGE: usize = 25;
+
+/// The indentation most of the sampled files of a language use, and the files that don't.
+#[derive(Clone, Debug, PartialEq)]
+struct LanguageConvention {
+    language: LanguageName,
+    indent: DetectedIndent,
+    /// The number of sampled files whose indentation could be detected.
+    sampled_files: usize,
+    outliers: Vec<(ProjectPath, DetectedIndent)>,
+}
+
+/// Finds the indentation most files of each language use among `samples`. Ties go to tabs, then
+/// to the narrower indentation.
+fn aggregate(samples: Vec<(LanguageName, ProjectPath, DetectedIndent)>) -> Vec<LanguageConvention> {
+    let mut files_by_language = BTreeMap::<LanguageName, Vec<(ProjectPath, DetectedIndent)>>::new();
+    for (language, path, indent) in samples {
+        files_by_language
+            .entry(language)
+            .or_default()
+            .push((path, indent));
+    }
+
+    files_by_language
+        .into_iter()
+        .filter_map(|(language, files)| {
+            let mut counts = Vec::<(DetectedIndent, usize)>::new();
+            for (_, indent) in &files {
+                match counts.iter_mut().find(|(existing, _)| existing == indent) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((*indent, 1)),
+                }
+            }
+            let (indent, _) = counts.into_iter().max_by_key(|(indent, count)| {
+                let width = match indent {
+                    DetectedIndent::Tabs => 0,
+                    DetectedIndent::Spaces(len) => *len,
+                };
+                (*count, std::cmp::Reverse(width))
+            })?;
+            Some(LanguageConvention {
+                language,
+                indent,
+                sampled_files: files.len(),
+                outliers: files
+                    .into_iter()
+                    .filter(|(_, file_indent)| *file_indent != indent)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+fn indent_label(indent: DetectedIndent) -> String {
+    match indent {
+        DetectedIndent::Tabs => "tabs".to_string(),
+        DetectedIndent::Spaces(1) => "1 space".to_string(),
+        DetectedIndent::Spaces(len) => format!("{len} spaces"),
+    }
+}
+
+/// Describes the convention of each language as Markdown, listing the files that break it.
+fn report(conventions: &[LanguageConvention]) -> String {
+    let mut out = format!(
+        "# Project indentation conventions\n\n\
+         Up to {SAMPLED_FILES_PER_LANGUAGE} files of each language were sampled.\n"
+    );
+    if conventions.is_empty() {
+        out.push_str("\nNo indented files were found.\n");
+        return out;
+    }
+    out.push_str("\n| Language | Convention | Files |\n| --- | --- | --- |\n");
+    for convention in conventions {
+        writeln!(
+            out,
+            "| {} | {} | {} of {} |",
+            convention.language,
+            indent_label(convention.indent),
+            convention.sampled_files - convention.outliers.len(),
+            convention.sampled_files,
+        )
+        .unwrap();
+    }
+    for convention in conventions {
+        if convention.outliers.is_empty() {
+            continue;
+        }
+        writeln!(out, "\n## {} outliers\n", convention.language).unwrap();
+        for (path, indent) in &convention.outliers {
+            writeln!(
+                out,
+                "- `{}`: {}",
+                path.path.display(PathStyle::local()),
+                indent_label(*indent)
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+/// Samples files of each language across the project, reports the indentation most of them use
+/// along with the files that don't, and offers to write it into the project's settings.
+pub(crate) fn detect_project_conventions(
+    workspace: &mut Workspace,
+    _: &DetectProjectConventions,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    // The files are read from the local file system without opening them.
+    if !project.read(cx).is_local() {
+        show_toast(
+            workspace,
+            "Detecting the conventions of a project is only supported for local projects",
+            cx,
+        );
+        return;
+    }
+
+    let conventions = detect(&project, cx);
+    let markdown = project.read(cx).languages().language_for_name("Markdown");
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let conventions = conventions.await;
+        let markdown = markdown.await.log_err();
+        workspace.update_in(cx, |workspace, window, cx| {
+            let buffer = project.update(cx, |project, cx| {
+                project.create_local_buffer(&report(&conventions), markdown, false, cx)
+            });
+            let editor = cx.new(|cx| Editor::for_buffer(buffer, Some(project.clone()), window, cx));
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+            if !conventions.is_empty() {
+                offer_to_write(workspace, project, conventions, cx);
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The conventions last detected in each project, by its entity.
+#[derive(Default)]
+struct DetectedConventions(HashMap<EntityId, Vec<LanguageConvention>>);
+
+impl Global for DetectedConventions {}
+
+/// Samples files of each language across the visible worktrees of `project`, which is local, and
+/// finds the indentation most of them use, remembering it for [`project_convention`].
+fn detect(project: &Entity<Project>, cx: &mut App) -> Task<Vec<LanguageConvention>> {
+    let fs = project.read(cx).fs().clone();
+    let languages = project.read(cx).languages().clone();
+    let mut candidates = Vec::new();
+    let mut candidates_by_language = BTreeMap::<LanguageName, usize>::new();
+    for worktree in project.read(cx).visible_worktrees(cx) {
+        let worktree = worktree.read(cx);
+        for entry in worktree.files(false, 0) {
+            let Some(language) = languages.language_for_file_path(entry.path.as_std_path()) else {
+                continue;
+            };
+            let sampled = candidates_by_language.entry(language.name()).or_default();
+            if *sampled == SAMPLED_FILES_PER_LANGUAGE {
+                continue;
+            }
+            *sampled += 1;
+            candidates.push((
+                language.name(),
+                ProjectPath {
+                    worktree_id: worktree.id(),
+                    path: entry.path.clone(),
+                },
+                worktree.abs_path().join(entry.path.as_std_path()),
+            ));
+        }
+    }
+
+    let project_id = project.entity_id();
+    let samples = cx.background_spawn(async move {
+        let mut samples = Vec::new();
+        for (language, path, abs_path) in candidates {
+            let Ok(text) = fs.load(&abs_path).await else {
+                continue;
+            };
+            if let Some(indent) = detect_indentation_in_text(&text).indent {
+                samples.push((language, path, indent));
+            }
+        }
+        samples
+    });
+    cx.spawn(async move |cx| {
+        let conventions = aggregate(samples.await);
+        cx.update(|cx| {
+            cx.default_global::<DetectedConventions>()
+                .0
+                .insert(project_id, conventions.clone());
+        });
+        conventions
+    })
+}
+
+/// The indentation most files of `language` in `project` use, from the conventions detected in
+/// it last, or detected now when they haven't been yet. `None` when the project isn't local or
+/// has no indented files of the language.
+pub(crate) fn project_convention(
+    project: &Entity<Project>,
+    language: LanguageName,
+    cx: &mut App,
+) -> Task<Option<DetectedIndent>> {
+    let convention_of = move |conventions: &[LanguageConvention]| {
+        conventions
+            .iter()
+            .find(|convention| convention.language == language)
+            .map(|convention| convention.indent)
+    };
+    if let Some(conventions) = cx
+        .try_global::<DetectedConventions>()
+        .and_then(|detected| detected.0.get(&project.entity_id()))
+    {
+        return Task::ready(convention_of(conventions));
+    }
+    if !project.read(cx).is_local() {
+        return Task::ready(None);
+    }
+    let conventions = detect(project, cx);
+    cx.background_spawn(async move { convention_of(&conventions.await) })
+}
+
+/// Offers to write the convention of each language into the settings of each of the project's
+/// worktrees.
+fn offer_to_write(
+    workspace: &mut Workspace,
+    project: Entity<Project>,
+    conventions: Vec<LanguageConvention>,
+    cx: &mut Context<Workspace>,
+) {
+    let message = match conventions.as_slice() {
+        [convention] => format!(
+            "Write {} indentation for {} files into the project's settings?",
+            indent_label(convention.indent),
+            convention.language
+        ),
+        _ => format!(
+            "Write the indentation of {} languages into the project's settings?",
+            conventions.len()
+        ),
+    };
+    let workspace_handle = cx.weak_entity();
+    workspace.show_notification(NotificationId::unique::<LanguageConvention>(), cx, |cx| {
+        cx.new(|cx| {
+            MessageNotification::new(message, cx)
+                .with_title("Project Indentation Conventions")
+                .show_suppress_button(false)
+                .primary_message("Write to Project Settings")
+                .primary_on_click(move |_, cx| {
+                    write_conventions(&project, &conventions, &workspace_handle, cx);
+                })
+                .secondary_message("Dismiss")
+        })
+    });
+}
+
+fn write_conventions(
+    project: &Entity<Project>,
+    conventions: &[LanguageConvention],
+    workspace: &WeakEntity<Workspace>,
+    cx: &mut App,
+) {
+    let worktree_ids = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .map(|worktree| worktree.read(cx).id())
+        .collect::<Vec<_>>();
+    for worktree_id in worktree_ids {
+        let conventions = conventions
+            .iter()
+            .map(|convention| (convention.language.to_string(), convention.indent))
+            .collect::<Vec<_>>();
+        apply_scope::update_settings(
+            project,
+            LanguageOverrideFile::Project(worktree_id),
+            move |settings| {
+                for (language_name, indent) in conventions {
+                    let language_settings =
+                        settings.languages_mut().entry(language_name).or_default();
+                    match indent {
+                        DetectedIndent::Tabs => language_settings.hard_tabs = Some(true),
+                        DetectedIndent::Spaces(len) => {
+                            language_settings.hard_tabs = Some(false);
+                            language_settings.tab_size = NonZeroU32::new(len);
+                        }
+                    }
+                }
+            },
+            cx,
+        );
+    }
+    workspace
+        .update(cx, |workspace, cx| {
+            show_toast(
+                workspace,
+                "Wrote the indentation conventions into the project's settings",
+                cx,
+            )
+        })
+        .ok();
+}
+
+fn show_toast(workspace: &mut Workspace, message: &'static str, cx: &mut Context<Workspace>) {
+    struct ProjectIndentationConventions;
+
+    workspace.show_toast(
+        Toast::new(
+            NotificationId::unique::<ProjectIndentationConventions>(),
+            message,
+        )
+        .autohide(),
+        cx,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use settings::WorktreeId;
+    use util::rel_path::RelPath;
+
+    #[test]
+    fn test_aggregate_conventions() {
+        let path = |path: &str| ProjectPath {
+            worktree_id: WorktreeId::from_usize(1),
+            path: RelPath::unix(path).unwrap().into(),
+        };
+        let rust = LanguageName::new_static("Rust");
+        let go = LanguageName::new_static("Go");
+        let conventions = aggregate(vec![
+            (rust.clone(), path("src/a.rs"), DetectedIndent::Spaces(4)),
+            (go.clone(), path("main.go"), DetectedIndent::Tabs),
+            (rust.clone(), path("src/b.rs"), DetectedIndent::Spaces(2)),
+            (rust.clone(), path("src/c.rs"), DetectedIndent::Spaces(4)),
+            // Ties go to tabs.
+            (go.clone(), path("gen.go"), DetectedIndent::Spaces(4)),
+        ]);
+        assert_eq!(
+            conventions,
+            vec![
+                LanguageConvention {
+                    language: go,
+                    indent: DetectedIndent::Tabs,
+                    sampled_files: 2,
+                    outliers: vec![(path("gen.go"), DetectedIndent::Spaces(4))],
+                },
+                LanguageConvention {
+                    language: rust,
+                    indent: DetectedIndent::Spaces(4),
+                    sampled_files: 3,
+                    outliers: vec![(path("src/b.rs"), DetectedIndent::Spaces(2))],
+                },
+            ]
+        );
+
+        assert_eq!(
+            report(&conventions),
+            "# Project indentation conventions\n\n\
+             Up to 25 files of each language were sampled.\n\n\
+             | Language | Convention | Files |\n| --- | --- | --- |\n\
+             | Go | tabs | 1 of 2 |\n\
+             | Rust | 4 spaces | 2 of 3 |\n\n\
+             ## Go outliers\n\n\
+             - `gen.go`: 4 spaces\n\n\
+             ## Rust outliers\n\n\
+             - `src/b.rs`: 2 spaces\n"
+        );
+    }
+}
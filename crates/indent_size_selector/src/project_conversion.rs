@@ -0,0 +1,518 @@
+use anyhow::Result;
+use editor::{Editor, MultibufferSelectionMode};
+use gpui::{App, AppContext as _, Context, Entity, Global, Task, WeakEntity, Window};
+use language::{Buffer, IndentKind, IndentSize, Point};
+use project::ProjectPath;
+use std::{borrow::Cow, collections::HashMap, fmt::Write as _, ops::Range, rc::Rc};
+use util::{ResultExt as _, paths::PathStyle};
+use workspace::{
+    Toast, Workspace,
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
+};
+
+use crate::{
+    ConvertProjectIndentation, ReportProjectIndentationConversion,
+    detection::{buffer_detection, to_indent_size},
+    fix_on_save::{self, ConversionOptions},
+    mixed_indentation::MixedIndentation,
+    structure_check::{self, StructureChange},
+};
+
+/// The number of files whose edits are computed together on the background executor. The
+/// conversion can be cancelled between each of these chunks.
+const CHUNK_SIZE: usize = 50;
+
+/// The project conversion in progress, whose progress the status bar shows.
+#[derive(Default)]
+pub(crate) struct ConversionProgress(Option<ActiveConversion>);
+
+impl Global for ConversionProgress {}
+
+struct ActiveConversion {
+    converted: usize,
+    total: usize,
+    workspace: WeakEntity<Workspace>,
+    _task: Task<()>,
+}
+
+impl ConversionProgress {
+    /// The number of files converted so far and the number being converted, while a conversion
+    /// is in progress.
+    pub(crate) fn get(cx: &App) -> Option<(usize, usize)> {
+        let conversion = cx.try_global::<Self>()?.0.as_ref()?;
+        Some((conversion.converted, conversion.total))
+    }
+
+    /// Stops the conversion in progress, keeping the files converted so far.
+    pub(crate) fn cancel(cx: &mut App) {
+        if !cx.has_global::<Self>() {
+            return;
+        }
+        // Dropping the conversion's task stops it.
+        let Some(conversion) = cx.global_mut::<Self>().0.take() else {
+            return;
+        };
+        let message = format!(
+            "Cancelled converting the indentation after {} of {} files",
+            conversion.converted, conversion.total
+        );
+        conversion
+            .workspace
+            .update(cx, |workspace, cx| show_toast(workspace, message, cx))
+            .ok();
+    }
+}
+
+/// A file of the project to re-indent from the indentation detected in it to its configured one.
+struct FileConversion {
+    path: ProjectPath,
+    buffer: Entity<Buffer>,
+    from: IndentSize,
+    to: IndentSize,
+    /// The lines that converting the file changes, as they were when it was planned.
+    changed_lines: Vec<Range<Point>>,
+}
+
+/// Why a file that doesn't use its configured indentation is left out of a conversion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SkipReason {
+    /// No indentation could be detected to convert from.
+    Ambiguous,
+    /// The file mixes tabs and spaces, so it needs normalizing instead.
+    Mixed,
+    /// Converting the file would change its syntax tree, as its language's indentation is part
+    /// of its syntax.
+    StructureChanged(StructureChange),
+}
+
+impl SkipReason {
+    fn label(&self) -> Cow<'static, str> {
+        match self {
+            Self::Ambiguous => "its indentation couldn't be detected".into(),
+            Self::Mixed => {
+                "it mixes tabs and spaces, normalize it from the indent size selector".into()
+            }
+            Self::StructureChanged(change) => format!(
+                "converting it would change the structure of {}",
+                change.lines_label()
+            )
+            .into(),
+        }
+    }
+}
+
+/// The files a project conversion re-indents, and those it leaves alone.
+struct ConversionPlan {
+    conversions: Vec<FileConversion>,
+    skipped: Vec<(ProjectPath, SkipReason)>,
+}
+
+impl ConversionPlan {
+    fn line_count(&self) -> usize {
+        self.conversions
+            .iter()
+            .map(|conversion| conversion.changed_lines.len())
+            .sum()
+    }
+
+    /// Describes the files this plan converts and skips as Markdown, for reviewing it before
+    /// converting them.
+    fn report(&self) -> String {
+        let line_count = self.line_count();
+        let mut out = format!(
+            "# Indentation conversion report\n\nConverting would re-indent {line_count} {} in {} {}",
+            if line_count == 1 { "line" } else { "lines" },
+            self.conversions.len(),
+            if self.conversions.len() == 1 {
+                "file"
+            } else {
+                "files"
+            },
+        );
+        match self.skipped.len() {
+            0 => out.push_str(".\n"),
+            1 => out.push_str(", skipping 1 file.\n"),
+            count => writeln!(out, ", skipping {count} files.").unwrap(),
+        }
+        if !self.conversions.is_empty() {
+            out.push_str("\n| File | From | To | Lines |\n| --- | --- | --- | --- |\n");
+            for conversion in &self.conversions {
+                writeln!(
+                    out,
+                    "| `{}` | {} | {} | {} |",
+                    conversion.path.path.display(PathStyle::local()),
+                    indent_label(conversion.from),
+                    indent_label(conversion.to),
+                    conversion.changed_lines.len(),
+                )
+                .unwrap();
+            }
+        }
+        if !self.skipped.is_empty() {
+            out.push_str("\n## Skipped files\n\n");
+            for (path, reason) in &self.skipped {
+                writeln!(
+                    out,
+                    "- `{}`: {}",
+                    path.path.display(PathStyle::local()),
+                    reason.label()
+                )
+                .unwrap();
+            }
+        }
+        out
+    }
+}
+
+pub(crate) fn indent_label(indent_size: IndentSize) -> String {
+    match indent_size.kind {
+        IndentKind::Tab => "tabs".to_string(),
+        IndentKind::Space if indent_size.len == 1 => "1 space".to_string(),
+        IndentKind::Space => format!("{} spaces", indent_size.len),
+    }
+}
+
+/// Finds the files of the project that don't use their configured indentation, then shows the
+/// lines that converting them would change in a multibuffer, to convert once confirmed.
+pub(crate) fn convert_project_indentation(
+    workspace: &mut Workspace,
+    _: &ConvertProjectIndentation,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(plan) = plan_conversion(workspace, window, cx) else {
+        return;
+    };
+    cx.spawn_in(window, async move |workspace, cx| {
+        let plan = plan.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            show_preview(workspace, plan, window, cx)
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Opens a report of what converting the project's indentation would change, without changing
+/// anything.
+pub(crate) fn report_project_indentation_conversion(
+    workspace: &mut Workspace,
+    _: &ReportProjectIndentationConversion,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(plan) = plan_conversion(workspace, window, cx) else {
+        return;
+    };
+    let project = workspace.project().clone();
+    let markdown = project.read(cx).languages().language_for_name("Markdown");
+    cx.spawn_in(window, async move |workspace, cx| {
+        let report = plan.await?.report();
+        let markdown = markdown.await.log_err();
+        workspace.update_in(cx, |workspace, window, cx| {
+            let buffer = project.update(cx, |project, cx| {
+                project.create_local_buffer(&report, markdown, false, cx)
+            });
+            let editor = cx.new(|cx| Editor::for_buffer(buffer, Some(project), window, cx));
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Opens the files of the project that don't use their configured indentation, planning which
+/// lines of each to re-indent. Files that mix tabs and spaces, or whose indentation can't be
+/// detected, are skipped.
+fn plan_conversion(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Option<Task<Result<ConversionPlan>>> {
+    let project = workspace.project().clone();
+    // The files are read from the local file system before they're opened.
+    if !project.read(cx).is_local() {
+        show_toast(
+            workspace,
+            "Converting the indentation of a project is only supported for local projects",
+            cx,
+        );
+        return None;
+    }
+    let files_needing_reindent = fix_on_save::files_needing_reindent(project.read(cx), cx);
+    Some(cx.spawn_in(window, async move |_, cx| {
+        let files = cx.background_spawn(files_needing_reindent).await;
+        let mut plan = ConversionPlan {
+            conversions: Vec::new(),
+            skipped: Vec::new(),
+        };
+        for file in files {
+            let (path, to) = (file.path, file.configured);
+            let Some(buffer) = project
+                .update(cx, |project, cx| project.open_buffer(path.clone(), cx))
+                .await
+                .log_err()
+            else {
+                continue;
+            };
+            // Lines inside literals are told apart by the buffer's syntax tree.
+            buffer
+                .read_with(cx, |buffer, _| buffer.parsing_idle())
+                .await;
+            cx.update(|_, cx| {
+                if MixedIndentation::detect(&buffer, to, cx).is_some() {
+                    plan.skipped.push((path, SkipReason::Mixed));
+                    return;
+                }
+                let Some(from) = to_indent_size(buffer_detection(&buffer, cx)) else {
+                    plan.skipped.push((path, SkipReason::Ambiguous));
+                    return;
+                };
+                let snapshot = buffer.read(cx).snapshot();
+                let edits = fix_on_save::conversion_edits(&snapshot, &[], from, to, cx);
+                if let Err(change) = structure_check::check_conversion(&snapshot, &edits) {
+                    plan.skipped
+                        .push((path, SkipReason::StructureChanged(change)));
+                    return;
+                }
+                let changed_lines = edits
+                    .into_iter()
+                    .map(|(range, _)| range)
+                    .collect::<Vec<_>>();
+                if !changed_lines.is_empty() {
+                    plan.conversions.push(FileConversion {
+                        path,
+                        buffer,
+                        from,
+                        to,
+                        changed_lines,
+                    });
+                }
+            })?;
+        }
+        anyhow::Ok(plan)
+    }))
+}
+
+/// Opens a multibuffer with the lines of each file that would be re-indented, along with a
+/// notification to convert them or cancel.
+fn show_preview(
+    workspace: &mut Workspace,
+    plan: ConversionPlan,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if plan.conversions.is_empty() {
+        let message = if plan.skipped.is_empty() {
+            "Every file in this project uses its configured indentation"
+        } else {
+            "No file can be converted. Report the conversion to see why files were skipped"
+        };
+        show_toast(workspace, message, cx);
+        return;
+    }
+    let line_count = plan.line_count();
+    let conversions = plan.conversions;
+    let locations = conversions
+        .iter()
+        .map(|conversion| (conversion.buffer.clone(), conversion.changed_lines.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let Some((editor, _)) = Editor::open_locations_in_multibuffer(
+        workspace,
+        locations,
+        "Indentation Conversion".to_string(),
+        false,
+        false,
+        MultibufferSelectionMode::First,
+        window,
+        cx,
+    ) else {
+        return;
+    };
+    let message = format!(
+        "Re-indent {line_count} {} in {} {} to their configured indentation?",
+        if line_count == 1 { "line" } else { "lines" },
+        conversions.len(),
+        if conversions.len() == 1 {
+            "file"
+        } else {
+            "files"
+        },
+    );
+    let workspace_handle = cx.weak_entity();
+    let editor = editor.downgrade();
+    let conversions = Rc::new(conversions);
+    workspace.show_notification(NotificationId::unique::<FileConversion>(), cx, |cx| {
+        cx.new(|cx| {
+            MessageNotification::new(message, cx)
+                .with_title("Convert Project Indentation")
+                .show_suppress_button(false)
+                .primary_message("Convert")
+                .primary_on_click(move |_, cx| {
+                    convert(&conversions, &workspace_handle, cx);
+                })
+                .secondary_message("Cancel")
+                .secondary_on_click(move |window, cx| {
+                    close_preview(&editor, window, cx);
+                })
+        })
+    });
+}
+
+/// Re-indents each file in one edit that can be undone on its own, leaving them to be saved from
+/// the preview. The edits of each chunk of files are computed on the background executor, and
+/// the progress is shown in the status bar, where the conversion can be cancelled between chunks.
+fn convert(conversions: &Rc<Vec<FileConversion>>, workspace: &WeakEntity<Workspace>, cx: &mut App) {
+    if ConversionProgress::get(cx).is_some() {
+        workspace
+            .update(cx, |workspace, cx| {
+                show_toast(
+                    workspace,
+                    "Wait for the indentation conversion in progress to finish, or cancel it",
+                    cx,
+                )
+            })
+            .ok();
+        return;
+    }
+    let options = ConversionOptions::from_settings(cx);
+    let conversions = conversions.clone();
+    let task_workspace = workspace.clone();
+    let task = cx.spawn(async move |cx| {
+        for chunk in conversions.chunks(CHUNK_SIZE) {
+            let snapshots = cx.update(|cx| {
+                chunk
+                    .iter()
+                    .map(|conversion| {
+                        let snapshot = conversion.buffer.read(cx).snapshot();
+                        (snapshot, conversion.from, conversion.to)
+                    })
+                    .collect::<Vec<_>>()
+            });
+            // The edits are anchored, so that they still apply to files edited meanwhile. Files
+            // edited since the conversion was planned are checked again.
+            let edits = cx
+                .background_spawn(async move {
+                    snapshots
+                        .iter()
+                        .map(|(snapshot, from, to)| {
+                            let edits = options.edits(snapshot, &[], *from, *to);
+                            structure_check::check_conversion(snapshot, &edits)?;
+                            Ok::<_, StructureChange>(
+                                edits
+                                    .into_iter()
+                                    .map(|(range, new_indent)| {
+                                        let range = snapshot.anchor_before(range.start)
+                                            ..snapshot.anchor_after(range.end);
+                                        (range, new_indent)
+                                    })
+                                    .collect::<Vec<_>>(),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+            cx.update(|cx| {
+                for (conversion, edits) in chunk.iter().zip(edits) {
+                    match edits {
+                        Ok(edits) => fix_on_save::apply_conversion(&conversion.buffer, edits, cx),
+                        Err(change) => {
+                            structure_check::notify_unconverted(&conversion.buffer, &change, cx)
+                        }
+                    }
+                }
+                cx.update_global::<ConversionProgress, _>(|progress, _| {
+                    if let Some(conversion) = &mut progress.0 {
+                        conversion.converted += chunk.len();
+                    }
+                });
+            });
+        }
+
+        cx.update(|cx| {
+            let message = match conversions.len() {
+                1 => "Converted the indentation of 1 file. Save it to keep the change".to_string(),
+                count => format!(
+                    "Converted the indentation of {count} files. Save them to keep the changes"
+                ),
+            };
+            task_workspace
+                .update(cx, |workspace, cx| show_toast(workspace, message, cx))
+                .ok();
+            cx.set_global(ConversionProgress(None));
+        });
+    });
+    cx.set_global(ConversionProgress(Some(ActiveConversion {
+        converted: 0,
+        total: conversions.len(),
+        workspace: workspace.clone(),
+        _task: task,
+    })));
+}
+
+fn close_preview(editor: &WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+    let Some(editor) = editor.upgrade() else {
+        return;
+    };
+    let Some(workspace) = editor.read(cx).workspace() else {
+        return;
+    };
+    workspace.update(cx, |workspace, cx| {
+        for pane in workspace.panes().to_vec() {
+            pane.update(cx, |pane, cx| {
+                pane.remove_item(editor.entity_id(), false, true, window, cx)
+            });
+        }
+    });
+}
+
+fn show_toast(
+    workspace: &mut Workspace,
+    message: impl Into<Cow<'static, str>>,
+    cx: &mut Context<Workspace>,
+) {
+    struct ProjectIndentationConversion;
+
+    workspace.show_toast(
+        Toast::new(
+            NotificationId::unique::<ProjectIndentationConversion>(),
+            message,
+        )
+        .autohide(),
+        cx,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use settings::WorktreeId;
+    use util::rel_path::RelPath;
+
+    #[gpui::test]
+    fn test_conversion_report(cx: &mut TestAppContext) {
+        let path = |path: &str| ProjectPath {
+            worktree_id: WorktreeId::from_usize(1),
+            path: RelPath::unix(path).unwrap().into(),
+        };
+        let plan = ConversionPlan {
+            conversions: vec![FileConversion {
+                path: path("src/main.rs"),
+                buffer: cx.new(|cx| Buffer::local("", cx)),
+                from: IndentSize::spaces(2),
+                to: IndentSize::tab(),
+                changed_lines: vec![Point::new(1, 0)..Point::new(1, 2); 3],
+            }],
+            skipped: vec![(path("Makefile"), SkipReason::Mixed)],
+        };
+        assert_eq!(
+            plan.report(),
+            "# Indentation conversion report\n\n\
+             Converting would re-indent 3 lines in 1 file, skipping 1 file.\n\n\
+             | File | From | To | Lines |\n| --- | --- | --- | --- |\n\
+             | `src/main.rs` | 2 spaces | tabs | 3 |\n\n\
+             ## Skipped files\n\n\
+             - `Makefile`: it mixes tabs and spaces, normalize it from the indent size selector\n"
+        );
+    }
+}
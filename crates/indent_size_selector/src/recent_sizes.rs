@@ -0,0 +1,54 @@
+use db::kvp::KeyValueStore;
+use gpui::App;
+use util::ResultExt as _;
+
+const RECENT_INDENT_SIZES_KEY: &str = "indent_size_selector_recent_sizes";
+const MAX_RECENT_SIZES: usize = 3;
+
+/// Returns the indent sizes most recently chosen in the selector, most recent first.
+pub(crate) fn recent_sizes(cx: &App) -> Vec<u32> {
+    KeyValueStore::global(cx)
+        .read_kvp(RECENT_INDENT_SIZES_KEY)
+        .log_err()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).log_err())
+        .unwrap_or_default()
+}
+
+/// Records `size` as the most recently chosen indent size.
+pub(crate) fn record_size(size: u32, cx: &App) {
+    let mut sizes = recent_sizes(cx);
+    sizes.retain(|recent| *recent != size);
+    sizes.insert(0, size);
+    sizes.truncate(MAX_RECENT_SIZES);
+    let Some(value) = serde_json::to_string(&sizes).log_err() else {
+        return;
+    };
+    let kvp = KeyValueStore::global(cx);
+    db::write_and_log(cx, move || async move {
+        kvp.write_kvp(RECENT_INDENT_SIZES_KEY.to_string(), value)
+            .await
+    });
+}
+
+/// Orders `sizes` so that the recently chosen ones come first, most recent first.
+pub(crate) fn prioritize_recent(sizes: &[u32], recent: &[u32]) -> Vec<u32> {
+    let mut ordered = recent
+        .iter()
+        .copied()
+        .filter(|size| sizes.contains(size))
+        .collect::<Vec<_>>();
+    ordered.extend(sizes.iter().copied().filter(|size| !recent.contains(size)));
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prioritize_recent() {
+        assert_eq!(prioritize_recent(&[2, 4, 8], &[]), vec![2, 4, 8]);
+        assert_eq!(prioritize_recent(&[2, 4, 8], &[8, 3, 2]), vec![8, 2, 4]);
+    }
+}
@@ -0,0 +1,189 @@
+use editor::{ClipboardSelection, Editor, actions::Paste};
+use gpui::ClipboardEntry;
+use language::IndentSize;
+use settings::Settings as _;
+use ui::prelude::*;
+
+use crate::{
+    IndentSizeSelectorSettings,
+    detection::{detect_indentation_in_text, to_indent_size},
+    fix_on_save::level_len,
+    read_indent_size,
+};
+
+/// Handles pasting into `editor`, re-indenting the pasted lines to the indentation of the file
+/// when the `reindent_on_paste` setting is enabled and they use another one. Other pastes go on
+/// to the editor's own handler.
+pub(crate) fn register(editor: &mut Editor, _: Option<&mut Window>, cx: &mut Context<Editor>) {
+    if !editor.mode().is_full() {
+        return;
+    }
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action(move |_: &Paste, window, cx| {
+            let pasted = editor_handle
+                .update(cx, |editor, cx| paste_reindented(editor, window, cx))
+                .unwrap_or(false);
+            if !pasted {
+                cx.propagate();
+            }
+        })
+        .detach();
+}
+
+/// Pastes the clipboard re-indented from the indentation detected in it to the configured one,
+/// returning whether it did.
+fn paste_reindented(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) -> bool {
+    if !IndentSizeSelectorSettings::get_global(cx).reindent_on_paste || editor.read_only(cx) {
+        return false;
+    }
+    let Some(to) = read_indent_size(editor, cx) else {
+        return false;
+    };
+    let to = to.indent_size;
+    let Some(item) = cx.read_from_clipboard() else {
+        return false;
+    };
+    let Some(clipboard_string) = item.entries().iter().find_map(|entry| match entry {
+        ClipboardEntry::String(string) => Some(string),
+        _ => None,
+    }) else {
+        return false;
+    };
+    let text = clipboard_string.text();
+    let Some(from) = to_indent_size(detect_indentation_in_text(text)) else {
+        return false;
+    };
+    if from == to {
+        return false;
+    }
+    let (text, selections) = reindent_clipboard(
+        text,
+        clipboard_string.metadata_json::<Vec<ClipboardSelection>>(),
+        from,
+        to,
+    );
+    editor.do_paste(&text, selections, true, window, cx);
+    true
+}
+
+/// Re-indents the clipboard `text` from `from` to `to`, along with the `selections` it was
+/// copied from, whose lengths and first line indents change with it. Each selection's text is
+/// followed by a newline separating it from the next unless it's made of entire lines, as the
+/// editor pastes it.
+fn reindent_clipboard(
+    text: &str,
+    selections: Option<Vec<ClipboardSelection>>,
+    from: IndentSize,
+    to: IndentSize,
+) -> (String, Option<Vec<ClipboardSelection>>) {
+    let Some(mut selections) = selections else {
+        return (reindent_text(text, from, to), None);
+    };
+    let mut reindented = String::with_capacity(text.len());
+    let mut start = 0;
+    for selection in &mut selections {
+        let end = start + selection.len;
+        let Some(selected) = text.get(start..end) else {
+            // The text doesn't match its selections, which the editor then ignores as well.
+            return (reindent_text(text, from, to), None);
+        };
+        let selected = reindent_text(selected, from, to);
+        selection.len = selected.len();
+        selection.first_line_indent = reindent_len(selection.first_line_indent, from, to);
+        reindented.push_str(&selected);
+        start = end;
+        if !selection.is_entire_line
+            && let Some(separator) = text[start..].chars().next()
+        {
+            reindented.push(separator);
+            start += separator.len_utf8();
+        }
+    }
+    reindented.push_str(&reindent_text(&text[start..], from, to));
+    (reindented, Some(selections))
+}
+
+/// Re-indents each line of `text` from `from` to `to`, keeping its depth and any columns past
+/// its last full level, like [`crate::reindent_edits`]. Blank lines and lines indented with the
+/// other kind of whitespace are left alone.
+pub(crate) fn reindent_text(text: &str, from: IndentSize, to: IndentSize) -> String {
+    text.split_inclusive('\n')
+        .map(|line| {
+            let indent_len = line
+                .find(|c: char| c != ' ' && c != '\t')
+                .unwrap_or(line.len());
+            let (indent, rest) = line.split_at(indent_len);
+            if indent.is_empty() || rest.trim().is_empty() || !indent.starts_with(from.char()) {
+                return line.to_string();
+            }
+            let len = indent.chars().count() as u32;
+            let depth = len / level_len(from);
+            let remainder = len % level_len(from);
+            std::iter::repeat_n(to.char(), (depth * level_len(to)) as usize)
+                .chain(std::iter::repeat_n(' ', remainder as usize))
+                .chain(rest.chars())
+                .collect()
+        })
+        .collect()
+}
+
+/// The number of characters that `len` characters of indentation with `from` take with `to`.
+fn reindent_len(len: u32, from: IndentSize, to: IndentSize) -> u32 {
+    let depth = len / level_len(from);
+    let remainder = len % level_len(from);
+    depth * level_len(to) + remainder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reindent_text() {
+        let text = "fn a() {\n    if b {\n        c();\n      // d\n    }\n\n}\n";
+        assert_eq!(
+            reindent_text(text, IndentSize::spaces(4), IndentSize::spaces(2)),
+            "fn a() {\n  if b {\n    c();\n    // d\n  }\n\n}\n"
+        );
+        assert_eq!(
+            reindent_text(text, IndentSize::spaces(4), IndentSize::tab()),
+            "fn a() {\n\tif b {\n\t\tc();\n\t  // d\n\t}\n\n}\n"
+        );
+        assert_eq!(
+            reindent_text(
+                "a:\n\tb: 1\n  c: 2",
+                IndentSize::tab(),
+                IndentSize::spaces(2)
+            ),
+            "a:\n  b: 1\n  c: 2"
+        );
+    }
+
+    #[test]
+    fn test_reindent_clipboard() {
+        let selection = |len, is_entire_line, first_line_indent| ClipboardSelection {
+            len,
+            is_entire_line,
+            first_line_indent,
+            file_path: None,
+            line_range: None,
+        };
+        let text = "if a {\n\tb();\n}\n\tc();";
+        let (reindented, selections) = reindent_clipboard(
+            text,
+            Some(vec![selection(15, true, 1), selection(5, false, 1)]),
+            IndentSize::tab(),
+            IndentSize::spaces(4),
+        );
+        assert_eq!(reindented, "if a {\n    b();\n}\n    c();");
+        let selections = selections.unwrap();
+        assert_eq!(
+            selections
+                .iter()
+                .map(|selection| (selection.len, selection.first_line_indent))
+                .collect::<Vec<_>>(),
+            [(18, 4), (8, 4)]
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use gpui::AnyElement;
+use ui::prelude::*;
+
+/// A short snippet, as the depth and text of each line, nested deep enough for indent sizes to
+/// look different side by side.
+const SAMPLE: [(usize, &str); 7] = [
+    (0, "fn handle(items) {"),
+    (1, "for item in items {"),
+    (2, "if item.is_ready() {"),
+    (3, "process(item);"),
+    (2, "}"),
+    (1, "}"),
+    (0, "}"),
+];
+
+/// How a tab is drawn in the sample, padded to the width it's shown at.
+const TAB_MARKER: char = '→';
+
+/// The leading whitespace of a line `depth` levels deep, with tabs drawn as a marker padded to
+/// `size` columns.
+fn indentation(depth: usize, size: u32, hard_tabs: bool) -> String {
+    let size = size.max(1) as usize;
+    if hard_tabs {
+        let tab = format!("{TAB_MARKER}{}", " ".repeat(size - 1));
+        tab.repeat(depth)
+    } else {
+        " ".repeat(depth * size)
+    }
+}
+
+/// Renders the sample indented with `size` spaces, or with tabs `size` columns wide when
+/// `hard_tabs` is set, to compare indentations before picking one.
+pub(crate) fn render_sample(size: u32, hard_tabs: bool, cx: &App) -> AnyElement {
+    let title = if hard_tabs {
+        format!("Tabs, {size} columns wide")
+    } else {
+        format!("{size} spaces")
+    };
+    v_flex()
+        .p_2()
+        .gap_1()
+        .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+        .child(v_flex().children(SAMPLE.iter().map(|(depth, text)| {
+            h_flex()
+                .child(
+                    Label::new(indentation(*depth, size, hard_tabs))
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(Color::Disabled),
+                )
+                .child(Label::new(*text).buffer_font(cx).size(LabelSize::Small))
+        })))
+        .into_any_element()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indentation() {
+        assert_eq!(indentation(2, 2, false), "    ");
+        assert_eq!(indentation(1, 4, false), "    ");
+        assert_eq!(indentation(2, 4, true), "→   →   ");
+        assert_eq!(indentation(0, 8, true), "");
+    }
+}
@@ -0,0 +1,311 @@
+use editor::Editor;
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use settings::update_settings_file;
+use std::sync::Arc;
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, Workspace};
+
+use crate::{
+    apply_scope::{self, ApplyScope, LanguageOverrideFile},
+    buffer_settings::BufferSetting,
+    file_buffer, is_compact, modal_width,
+    unsupported_files::{self, UnsupportedFile, overridable_file},
+};
+
+/// A value picked for a [`BufferSetting`], to apply at the same scopes as the indentation.
+#[derive(Clone)]
+pub(crate) struct SettingValue {
+    pub(crate) setting: Arc<dyn BufferSetting>,
+    /// The value, in the `.editorconfig` syntax.
+    pub(crate) value: SharedString,
+    /// Whether the buffer's content is converted to the value once it's applied.
+    pub(crate) convert: bool,
+}
+
+impl SettingValue {
+    fn placeholder_text(&self) -> String {
+        let verb = if self.convert { "Convert" } else { "Set" };
+        format!(
+            "{verb} {} to {} for…",
+            self.setting.name(),
+            self.setting.label(&self.value)
+        )
+    }
+
+    /// Brings the buffer in line with the value once it's been applied at `scope`, explaining
+    /// in `workspace` when it can't be.
+    fn apply_to_buffer(
+        &self,
+        workspace: &WeakEntity<Workspace>,
+        project: &Entity<Project>,
+        buffer: &Entity<Buffer>,
+        scope: &ApplyScope,
+        cx: &mut App,
+    ) {
+        // Applying the value to the buffer alone only changes its content.
+        let convert = self.convert || *scope == ApplyScope::Buffer;
+        if let Err(error) = self
+            .setting
+            .apply_to_buffer(&self.value, buffer, project, convert, cx)
+        {
+            unsupported_files::show_not_applied(workspace, error.to_string(), cx);
+        }
+    }
+}
+
+/// Asks where to apply a [`SettingValue`] for the file open in an editor, then applies it there:
+/// as an `.editorconfig` override for the file, its directory or its worktree, in the user
+/// settings for its language or every file, or only to its buffer for settings that convert it.
+pub(crate) struct SettingScopeSelector {
+    picker: Entity<Picker<SettingScopeSelectorDelegate>>,
+}
+
+impl SettingScopeSelector {
+    /// Opens the scope chooser for applying `setting` to the file open in `editor`.
+    pub(crate) fn toggle(
+        editor: &Entity<Editor>,
+        setting: SettingValue,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let editor = editor.read(cx);
+        let (Some(workspace), Some(project), Some(buffer)) = (
+            editor.workspace(),
+            editor.project().cloned(),
+            file_buffer(editor, cx),
+        ) else {
+            return;
+        };
+        let workspace_handle = workspace.downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                let delegate = SettingScopeSelectorDelegate::new(
+                    cx.entity().downgrade(),
+                    workspace_handle,
+                    project,
+                    buffer,
+                    setting,
+                    cx,
+                );
+                Self {
+                    picker: cx.new(|cx| Picker::nonsearchable_uniform_list(delegate, window, cx)),
+                }
+            });
+        });
+    }
+}
+
+impl Render for SettingScopeSelector {
+    fn render(&mut self, window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SettingScopeSelector")
+            .w(modal_width(window))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for SettingScopeSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SettingScopeSelector {}
+impl ModalView for SettingScopeSelector {}
+
+struct SettingScopeSelectorDelegate {
+    setting_scope_selector: WeakEntity<SettingScopeSelector>,
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    buffer: Entity<Buffer>,
+    setting: SettingValue,
+    scopes: Vec<ApplyScope>,
+    selected_index: usize,
+}
+
+impl SettingScopeSelectorDelegate {
+    fn new(
+        setting_scope_selector: WeakEntity<SettingScopeSelector>,
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        buffer: Entity<Buffer>,
+        setting: SettingValue,
+        cx: &App,
+    ) -> Self {
+        let mut scopes = Vec::new();
+        if setting.setting.converts() {
+            scopes.push(ApplyScope::Buffer);
+        }
+        if overridable_file(buffer.read(cx)).is_some() {
+            scopes.push(ApplyScope::File);
+        }
+        if let Some(file) = buffer.read(cx).file()
+            && let Some(directory) = file.path().parent()
+            && !directory.is_empty()
+        {
+            scopes.push(ApplyScope::Directory(directory.into()));
+        }
+        if let Some(language) = buffer.read(cx).language() {
+            scopes.push(ApplyScope::Language(language.name()));
+        }
+        // A file opened on its own is the only file in its worktree.
+        if UnsupportedFile::for_buffer(buffer.read(cx)) != Some(UnsupportedFile::SingleFile)
+            && let Some(worktree) = buffer
+                .read(cx)
+                .file()
+                .and_then(|file| project.read(cx).worktree_for_id(file.worktree_id(cx), cx))
+        {
+            scopes.push(ApplyScope::Worktree(
+                worktree.read(cx).root_name_str().to_string().into(),
+            ));
+        }
+        if let Some(file) = buffer.read(cx).file()
+            && unsupported_files::project_settings_unavailable(&project, buffer.read(cx), cx)
+                .is_none()
+        {
+            scopes.push(ApplyScope::Workspace(file.worktree_id(cx)));
+        }
+        scopes.push(ApplyScope::Global);
+        if project.read(cx).is_via_collab() {
+            // As with the indentation, a guest's overrides wouldn't reach the host.
+            scopes.retain(|scope| !scope.is_override());
+        }
+        if !setting.setting.has_user_setting() {
+            scopes.retain(|scope| {
+                !matches!(
+                    scope,
+                    ApplyScope::Language(_) | ApplyScope::Workspace(_) | ApplyScope::Global
+                )
+            });
+        }
+
+        Self {
+            setting_scope_selector,
+            workspace,
+            project,
+            buffer,
+            setting,
+            scopes,
+            selected_index: 0,
+        }
+    }
+
+    fn apply(&self, scope: &ApplyScope, cx: &mut App) {
+        let setting = self.setting.setting.clone();
+        let value = self.setting.value.clone();
+        match scope {
+            ApplyScope::Buffer => {}
+            ApplyScope::Language(language_name) => apply_scope::update_language_settings(
+                &self.project,
+                LanguageOverrideFile::User,
+                vec![language_name.to_string()],
+                move |language_settings| setting.set_user_setting(&value, language_settings),
+                cx,
+            ),
+            ApplyScope::Workspace(worktree_id) => apply_scope::update_settings(
+                &self.project,
+                LanguageOverrideFile::Project(*worktree_id),
+                move |settings| {
+                    setting.set_user_setting(&value, &mut settings.project.all_languages.defaults)
+                },
+                cx,
+            ),
+            ApplyScope::Global => update_settings_file(
+                self.project.read(cx).fs().clone(),
+                cx,
+                move |settings, _| {
+                    setting.set_user_setting(&value, &mut settings.project.all_languages.defaults)
+                },
+            ),
+            _ => {
+                let properties = [(setting.editorconfig_property(), value.to_string())];
+                if !apply_scope::set_override_properties(&self.buffer, scope, &properties, cx) {
+                    unsupported_files::show_not_applied(
+                        &self.workspace,
+                        format!("{}: the setting couldn't be applied", scope.label()),
+                        cx,
+                    );
+                    return;
+                }
+            }
+        }
+        self.setting
+            .apply_to_buffer(&self.workspace, &self.project, &self.buffer, scope, cx);
+    }
+}
+
+impl PickerDelegate for SettingScopeSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        self.setting.placeholder_text().into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.scopes.len()
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(scope) = self.scopes.get(self.selected_index) {
+            self.apply(scope, cx);
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.setting_scope_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        _query: String,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        Task::ready(())
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let scope = self.scopes.get(ix)?;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(Label::new(scope.label()))
+                .when(!is_compact(window), |list_item| {
+                    list_item.end_slot(
+                        Label::new(scope.detail())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                }),
+        )
+    }
+}
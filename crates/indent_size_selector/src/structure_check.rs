@@ -0,0 +1,200 @@
+use gpui::{App, AppContext as _, Entity};
+use language::{Buffer, BufferSnapshot, Grammar, Point, ToTreeSitterPoint as _, with_parser};
+use std::ops::Range;
+use workspace::notifications::{
+    NotificationId, show_app_notification, simple_message_notification::MessageNotification,
+};
+
+use crate::language_conventions::has_significant_indentation;
+
+/// The lines of a file whose place in its syntax tree converting its indentation would change,
+/// such as a Python statement moving out of the block it's in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct StructureChange {
+    /// Zero-based, in ascending order.
+    pub(crate) rows: Vec<u32>,
+}
+
+impl StructureChange {
+    /// The lines as they're numbered in the editor, such as "lines 3, 7 and 12".
+    pub(crate) fn lines_label(&self) -> String {
+        let mut lines = self
+            .rows
+            .iter()
+            .map(|row| (row + 1).to_string())
+            .collect::<Vec<_>>();
+        match lines.len() {
+            0 => String::new(),
+            1 => format!("line {}", lines[0]),
+            _ => {
+                let last = lines.pop().unwrap_or_default();
+                format!("lines {} and {last}", lines.join(", "))
+            }
+        }
+    }
+}
+
+/// Checks that applying the re-indenting `edits` to `snapshot` leaves each of its lines in the
+/// same place of its syntax tree, for languages whose indentation is part of their syntax.
+/// Re-indenting the files of other languages, or of languages without a grammar, always passes.
+pub(crate) fn check_conversion(
+    snapshot: &BufferSnapshot,
+    edits: &[(Range<Point>, String)],
+) -> Result<(), StructureChange> {
+    if edits.is_empty() {
+        return Ok(());
+    }
+    let Some(language) = snapshot.language() else {
+        return Ok(());
+    };
+    let Some(grammar) = language.grammar() else {
+        return Ok(());
+    };
+    if !has_significant_indentation(language.name().as_ref()) {
+        return Ok(());
+    }
+    let old = snapshot.text();
+    let new = apply_edits(snapshot, edits);
+    let rows = changed_rows(grammar, &old, &new);
+    if rows.is_empty() {
+        Ok(())
+    } else {
+        Err(StructureChange { rows })
+    }
+}
+
+/// Tells that the indentation of `buffer` was left as it is, since converting it would have
+/// made the `change`.
+pub(crate) fn notify_unconverted(buffer: &Entity<Buffer>, change: &StructureChange, cx: &mut App) {
+    struct IndentationNotConverted;
+
+    let buffer = buffer.read(cx);
+    let file_name = buffer.file().map_or("the file".to_string(), |file| {
+        format!("`{}`", file.file_name(cx))
+    });
+    let language = buffer
+        .language()
+        .map_or("its language".to_string(), |language| {
+            language.name().to_string()
+        });
+    let message = format!(
+        "Converting the indentation of {file_name} would change the structure of {}, since \
+         indentation is part of {language}'s syntax. The file was left as it is.",
+        change.lines_label()
+    );
+    show_app_notification(
+        NotificationId::unique::<IndentationNotConverted>(),
+        cx,
+        move |cx| {
+            cx.new(|cx| {
+                MessageNotification::new(message.clone(), cx)
+                    .with_title("Indentation Not Converted")
+            })
+        },
+    );
+}
+
+/// The text of `snapshot` with `edits` applied, which are in order and don't overlap.
+fn apply_edits(snapshot: &BufferSnapshot, edits: &[(Range<Point>, String)]) -> String {
+    let mut text = String::with_capacity(snapshot.len());
+    let mut offset = 0;
+    for (range, new_text) in edits {
+        let start = snapshot.point_to_offset(range.start);
+        text.extend(snapshot.text_for_range(offset..start));
+        text.push_str(new_text);
+        offset = snapshot.point_to_offset(range.end);
+    }
+    text.extend(snapshot.text_for_range(offset..snapshot.len()));
+    text
+}
+
+/// The rows whose first token has other ancestors in the syntax tree of `new` than in that of
+/// `old`, whose lines only differ by their leading whitespace.
+fn changed_rows(grammar: &Grammar, old: &str, new: &str) -> Vec<u32> {
+    let (Some(old_lines), Some(new_lines)) =
+        (line_structure(grammar, old), line_structure(grammar, new))
+    else {
+        return Vec::new();
+    };
+    old_lines
+        .iter()
+        .zip(&new_lines)
+        .enumerate()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(row, _)| row as u32)
+        .collect()
+}
+
+/// The kinds of the nodes enclosing the first token of each line of `text`, innermost first,
+/// or `None` for blank lines. `None` when `text` can't be parsed.
+fn line_structure(grammar: &Grammar, text: &str) -> Option<Vec<Option<Vec<u16>>>> {
+    let tree = with_parser(|parser| {
+        parser.set_language(&grammar.ts_language).ok()?;
+        parser.parse(text, None)
+    })?;
+    let root = tree.root_node();
+    let lines = text
+        .split('\n')
+        .enumerate()
+        .map(|(row, line)| {
+            let content = line.trim_start();
+            if content.trim_end().is_empty() {
+                return None;
+            }
+            let column = line.len() - content.len();
+            let point = Point::new(row as u32, column as u32).to_ts_point();
+            let mut node = root.descendant_for_point_range(point, point);
+            let mut kinds = Vec::new();
+            while let Some(ancestor) = node {
+                kinds.push(ancestor.kind_id());
+                node = ancestor.parent();
+            }
+            Some(kinds)
+        })
+        .collect();
+    Some(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::Buffer;
+
+    #[gpui::test]
+    fn test_apply_edits(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("a:\n  b: 1\n  c: 2\n", cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let edits = vec![
+            (Point::new(1, 0)..Point::new(1, 2), "\t".to_string()),
+            (Point::new(2, 0)..Point::new(2, 2), "    ".to_string()),
+        ];
+        assert_eq!(apply_edits(&snapshot, &edits), "a:\n\tb: 1\n    c: 2\n");
+    }
+
+    #[test]
+    fn test_changed_rows() {
+        let language = language::rust_lang();
+        let grammar = language.grammar().unwrap();
+        let old = "fn a() {\n    b();\n\n    c();\n}\n";
+
+        assert!(changed_rows(grammar, old, "fn a() {\n\tb();\n\n\tc();\n}\n").is_empty());
+        // The lines whose enclosing nodes differ are listed, but not those that are the same.
+        let rows = changed_rows(grammar, old, "fn a() {\n    b();\n\n    c(;\n}\n");
+        assert!(rows.contains(&3));
+        assert!(!rows.contains(&1));
+    }
+
+    #[test]
+    fn test_lines_label() {
+        let label = |rows: &[u32]| {
+            StructureChange {
+                rows: rows.to_vec(),
+            }
+            .lines_label()
+        };
+        assert_eq!(label(&[2]), "line 3");
+        assert_eq!(label(&[2, 6]), "lines 3 and 7");
+        assert_eq!(label(&[2, 6, 11]), "lines 3, 7 and 12");
+    }
+}
@@ -0,0 +1,294 @@
+use anyhow::Result;
+use gpui::{App, Context, Entity, PathPromptOptions, Window};
+use language::{
+    IndentKind, LanguageName,
+    language_settings::{AllLanguageSettings, LanguageSettings, ResolvedWhitespaceOptions},
+};
+use paths::EDITORCONFIG_NAME;
+use project::{DirectoryLister, Project, Worktree};
+use settings::{Settings as _, SettingsLocation};
+use std::{collections::BTreeMap, fmt::Write as _};
+use util::{ResultExt as _, rel_path::RelPath};
+use workspace::{Toast, Workspace, notifications::NotificationId};
+
+use crate::ExportIndentationStyle;
+
+/// The file the human-readable summary of an exported style bundle is written to.
+const SUMMARY_FILE_NAME: &str = "WHITESPACE.md";
+
+/// The whitespace settings that apply to a group of files.
+#[derive(Clone, Debug, PartialEq)]
+struct Convention {
+    indent_size: u32,
+    tab_width: u32,
+    hard_tabs: bool,
+    trim_trailing_whitespace: bool,
+    insert_final_newline: bool,
+}
+
+impl Convention {
+    fn from_settings(settings: &LanguageSettings) -> Self {
+        let whitespace = ResolvedWhitespaceOptions::from_settings(settings);
+        Self {
+            indent_size: whitespace.indent_size.get(),
+            tab_width: whitespace.tab_width.get(),
+            hard_tabs: whitespace.hard_tabs,
+            trim_trailing_whitespace: whitespace.trim_trailing_whitespace,
+            insert_final_newline: whitespace.insert_final_newline,
+        }
+    }
+
+    fn write_editorconfig_keys(&self, out: &mut String) {
+        let indent_style = if self.hard_tabs { "tab" } else { "space" };
+        writeln!(out, "indent_style = {indent_style}").ok();
+        writeln!(out, "indent_size = {}", self.indent_size).ok();
+        writeln!(out, "tab_width = {}", self.tab_width).ok();
+        writeln!(
+            out,
+            "trim_trailing_whitespace = {}",
+            self.trim_trailing_whitespace
+        )
+        .ok();
+        writeln!(out, "insert_final_newline = {}", self.insert_final_newline).ok();
+    }
+
+    fn indentation_label(&self) -> String {
+        if self.hard_tabs {
+            format!("tabs ({} columns)", self.tab_width)
+        } else {
+            format!("{} spaces", self.indent_size)
+        }
+    }
+}
+
+/// The convention of one file type, along with what its open buffers actually contain.
+#[derive(Clone, Debug, PartialEq)]
+struct FileTypeConvention {
+    language: Option<LanguageName>,
+    convention: Convention,
+    space_indented_lines: usize,
+    tab_indented_lines: usize,
+}
+
+/// A portable description of a project's whitespace conventions.
+#[derive(Debug, PartialEq)]
+struct StyleBundle {
+    project_name: String,
+    defaults: Convention,
+    file_types: BTreeMap<String, FileTypeConvention>,
+}
+
+impl StyleBundle {
+    /// Gathers the effective settings of `worktree`, along with the indentation found in its
+    /// open buffers.
+    fn collect(project: &Project, worktree: &Entity<Worktree>, cx: &App) -> Self {
+        let worktree = worktree.read(cx);
+        let worktree_id = worktree.id();
+        let root_location = SettingsLocation {
+            worktree_id,
+            path: RelPath::empty(),
+        };
+        let root_settings = AllLanguageSettings::get(Some(root_location), cx).language(
+            Some(root_location),
+            None,
+            cx,
+        );
+        let defaults = Convention::from_settings(&root_settings);
+
+        let mut file_types = BTreeMap::<String, FileTypeConvention>::default();
+        for buffer in project.opened_buffers(cx) {
+            let buffer = buffer.read(cx);
+            let Some(file) = buffer.file() else {
+                continue;
+            };
+            if file.worktree_id(cx) != worktree_id {
+                continue;
+            }
+            let Some(extension) = file.path().extension() else {
+                continue;
+            };
+
+            let snapshot = buffer.snapshot();
+            let file_type =
+                file_types
+                    .entry(extension.to_string())
+                    .or_insert_with(|| FileTypeConvention {
+                        language: buffer.language().map(|language| language.name()),
+                        convention: Convention::from_settings(&LanguageSettings::for_buffer(
+                            buffer, cx,
+                        )),
+                        space_indented_lines: 0,
+                        tab_indented_lines: 0,
+                    });
+            for row in 0..=snapshot.max_point().row {
+                if snapshot.is_line_blank(row) {
+                    continue;
+                }
+                let indent = snapshot.indent_size_for_line(row);
+                match indent.kind {
+                    _ if indent.len == 0 => {}
+                    IndentKind::Space => file_type.space_indented_lines += 1,
+                    IndentKind::Tab => file_type.tab_indented_lines += 1,
+                }
+            }
+        }
+
+        Self {
+            project_name: worktree.root_name_str().to_string(),
+            defaults,
+            file_types,
+        }
+    }
+
+    fn editorconfig(&self) -> String {
+        let mut out = String::from("root = true\n\n[*]\n");
+        self.defaults.write_editorconfig_keys(&mut out);
+        for (extension, file_type) in &self.file_types {
+            if file_type.convention != self.defaults {
+                writeln!(out, "\n[*.{extension}]").ok();
+                file_type.convention.write_editorconfig_keys(&mut out);
+            }
+        }
+        out
+    }
+
+    fn summary(&self) -> String {
+        let mut out = format!(
+            "# Whitespace conventions\n\nExported from `{}`.\n\nDefault indentation: {}.\n",
+            self.project_name,
+            self.defaults.indentation_label()
+        );
+        if self.file_types.is_empty() {
+            return out;
+        }
+        out.push_str(
+            "\n| Files | Language | Indentation | Space-indented lines | Tab-indented lines |\n",
+        );
+        out.push_str("| --- | --- | --- | --- | --- |\n");
+        for (extension, file_type) in &self.file_types {
+            let language = file_type
+                .language
+                .as_ref()
+                .map_or("Plain Text", |language| language.as_ref());
+            writeln!(
+                out,
+                "| `*.{extension}` | {language} | {} | {} | {} |",
+                file_type.convention.indentation_label(),
+                file_type.space_indented_lines,
+                file_type.tab_indented_lines,
+            )
+            .ok();
+        }
+        out
+    }
+}
+
+pub(crate) fn export_indentation_style(
+    workspace: &mut Workspace,
+    _: &ExportIndentationStyle,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().read(cx);
+    let Some(worktree) = project.visible_worktrees(cx).next() else {
+        return;
+    };
+    let bundle = StyleBundle::collect(project, &worktree, cx);
+    let fs = project.fs().clone();
+    let directory = workspace.prompt_for_open_path(
+        PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Export Indentation Style".into()),
+        },
+        DirectoryLister::Local(workspace.project().clone(), fs.clone()),
+        window,
+        cx,
+    );
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(directory) = directory
+            .await
+            .log_err()
+            .flatten()
+            .and_then(|paths| paths.into_iter().next())
+        else {
+            return Ok(());
+        };
+        fs.atomic_write(directory.join(EDITORCONFIG_NAME), bundle.editorconfig())
+            .await?;
+        fs.atomic_write(directory.join(SUMMARY_FILE_NAME), bundle.summary())
+            .await?;
+        workspace.update(cx, |workspace, cx| {
+            struct ExportedIndentationStyle;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<ExportedIndentationStyle>(),
+                    format!("Exported indentation style to {}", directory.display()),
+                )
+                .autohide(),
+                cx,
+            );
+        })?;
+        Result::<()>::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_bundle_only_emits_differing_file_types() {
+        let defaults = Convention {
+            indent_size: 4,
+            tab_width: 4,
+            hard_tabs: false,
+            trim_trailing_whitespace: true,
+            insert_final_newline: true,
+        };
+        let bundle = StyleBundle {
+            project_name: "api".into(),
+            defaults: defaults.clone(),
+            file_types: BTreeMap::from_iter([
+                (
+                    "go".to_string(),
+                    FileTypeConvention {
+                        language: Some("Go".into()),
+                        convention: Convention {
+                            hard_tabs: true,
+                            ..defaults.clone()
+                        },
+                        space_indented_lines: 0,
+                        tab_indented_lines: 12,
+                    },
+                ),
+                (
+                    "rs".to_string(),
+                    FileTypeConvention {
+                        language: Some("Rust".into()),
+                        convention: defaults,
+                        space_indented_lines: 40,
+                        tab_indented_lines: 1,
+                    },
+                ),
+            ]),
+        };
+
+        assert_eq!(
+            bundle.editorconfig(),
+            "root = true\n\n\
+             [*]\nindent_style = space\nindent_size = 4\ntab_width = 4\n\
+             trim_trailing_whitespace = true\ninsert_final_newline = true\n\n\
+             [*.go]\nindent_style = tab\nindent_size = 4\ntab_width = 4\n\
+             trim_trailing_whitespace = true\ninsert_final_newline = true\n"
+        );
+        assert!(
+            bundle
+                .summary()
+                .contains("| `*.rs` | Rust | 4 spaces | 40 | 1 |")
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use anyhow::{Context as _, Result, bail};
+use editor::Editor;
+use futures::{AsyncReadExt as _, channel::oneshot};
+use gpui::{App, AsyncWindowContext, Context, PathPromptOptions, PromptLevel, Window};
+use http_client::{AsyncBody, HttpClient};
+use paths::EDITORCONFIG_NAME;
+use project::{DirectoryLister, Fs, Worktree};
+use serde::Deserialize;
+use settings::Editorconfig;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+use crate::{
+    ImportIndentationStyle, cursor_position, governing_editorconfig::set_section_properties,
+};
+
+/// The worktree that an imported `.editorconfig` is copied into.
+pub(crate) struct ImportTarget {
+    worktree_root: Arc<Path>,
+    worktree_name: String,
+    fs: Arc<dyn Fs>,
+}
+
+impl ImportTarget {
+    /// Targets the worktree containing the file open in `editor`.
+    pub(crate) fn for_editor(editor: &Editor, cx: &App) -> Option<Self> {
+        let project = editor.project()?.read(cx);
+        let file = editor.file_at(cursor_position(editor), cx)?;
+        let worktree = project.worktree_for_id(file.worktree_id(cx), cx)?;
+        Some(Self::new(project.fs().clone(), worktree.read(cx)))
+    }
+
+    /// Targets the worktree of the active editor, falling back to the first visible worktree.
+    fn for_workspace(workspace: &Workspace, cx: &App) -> Option<Self> {
+        if let Some(target) = workspace
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| Self::for_editor(editor.read(cx), cx))
+        {
+            return Some(target);
+        }
+        let project = workspace.project().read(cx);
+        let worktree = project.visible_worktrees(cx).next()?;
+        Some(Self::new(project.fs().clone(), worktree.read(cx)))
+    }
+
+    fn new(fs: Arc<dyn Fs>, worktree: &Worktree) -> Self {
+        Self {
+            worktree_root: worktree.abs_path(),
+            worktree_name: worktree.root_name_str().to_string(),
+            fs,
+        }
+    }
+
+    pub(crate) fn fs(&self) -> &Arc<dyn Fs> {
+        &self.fs
+    }
+
+    /// Asks for confirmation and writes `content` as the `.editorconfig` at the worktree root.
+    /// When there's one already, only the indentation keys of `content` are set in it, and every
+    /// other property and section it has is kept.
+    pub(crate) async fn install(
+        self,
+        content: String,
+        detail: Option<String>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<()> {
+        let target_path = self.worktree_root.join(EDITORCONFIG_NAME);
+        let (message, content) = if self.fs.is_file(&target_path).await {
+            let existing = self
+                .fs
+                .load(&target_path)
+                .await
+                .with_context(|| format!("loading {target_path:?}"))?;
+            (
+                format!(
+                    "Update the indentation in the {EDITORCONFIG_NAME} of {}?",
+                    self.worktree_name
+                ),
+                merge_indentation(&existing, &content),
+            )
+        } else {
+            (
+                format!("Copy {EDITORCONFIG_NAME} into {}?", self.worktree_name),
+                content,
+            )
+        };
+        let answer = cx.update(|window, cx| {
+            window.prompt(
+                PromptLevel::Info,
+                &message,
+                detail.as_deref(),
+                &["Apply", "Cancel"],
+                cx,
+            )
+        })?;
+        if answer.await != Ok(0) {
+            return Ok(());
+        }
+        self.fs.atomic_write(target_path, content).await
+    }
+}
+
+/// A minimal JSON alternative to `.editorconfig` for sharing an indentation style.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct StyleDescriptor {
+    indent_style: Option<DescriptorIndentStyle>,
+    indent_size: Option<u32>,
+    tab_width: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum DescriptorIndentStyle {
+    Space,
+    Tab,
+}
+
+impl StyleDescriptor {
+    fn to_editorconfig(&self) -> String {
+        let mut content = String::from("[*]\n");
+        if let Some(indent_style) = self.indent_style {
+            let indent_style = match indent_style {
+                DescriptorIndentStyle::Space => "space",
+                DescriptorIndentStyle::Tab => "tab",
+            };
+            content.push_str(&format!("indent_style = {indent_style}\n"));
+        }
+        if let Some(indent_size) = self.indent_size {
+            content.push_str(&format!("indent_size = {indent_size}\n"));
+        }
+        if let Some(tab_width) = self.tab_width {
+            content.push_str(&format!("tab_width = {tab_width}\n"));
+        }
+        content
+    }
+}
+
+/// Normalizes imported content into `.editorconfig` syntax, accepting either format.
+fn editorconfig_from_import(content: &str) -> Result<String> {
+    if content.trim_start().starts_with('{') {
+        let descriptor: StyleDescriptor =
+            serde_json::from_str(content).context("parsing indentation style descriptor")?;
+        return Ok(descriptor.to_editorconfig());
+    }
+    content.parse::<Editorconfig>()?;
+    Ok(content.to_string())
+}
+
+/// Lists the indentation keys of an `.editorconfig`, prefixed by the section that sets them.
+fn adopted_keys(content: &str) -> Vec<String> {
+    let mut section = None;
+    let mut keys = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            section = Some(line);
+        } else if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim();
+            if matches!(key, "indent_style" | "indent_size" | "tab_width") {
+                let entry = format!("{key} = {}", value.trim());
+                keys.push(match section {
+                    Some(section) => format!("{section} {entry}"),
+                    None => entry,
+                });
+            }
+        }
+    }
+    keys
+}
+
+/// Sets the indentation keys of the `imported` `.editorconfig` in the sections of `existing`
+/// with the same headers, appending the sections it lacks. Every other property and section of
+/// `existing` is kept, and the other properties of `imported` are left out.
+fn merge_indentation(existing: &str, imported: &str) -> String {
+    let mut sections: Vec<(&str, Vec<(&str, String)>)> = Vec::new();
+    for line in imported.lines().map(str::trim) {
+        if let Some(header) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            sections.push((header, Vec::new()));
+        } else if let Some((key, value)) = line.split_once('=')
+            && let Some((_, properties)) = sections.last_mut()
+        {
+            let key = key.trim();
+            if matches!(key, "indent_style" | "indent_size" | "tab_width") {
+                properties.push((key, value.trim().to_string()));
+            }
+        }
+    }
+    sections
+        .iter()
+        .filter(|(_, properties)| !properties.is_empty())
+        .fold(existing.to_string(), |content, (header, properties)| {
+            set_section_properties(&content, header, properties)
+        })
+}
+
+async fn load_source(
+    source: &str,
+    fs: &Arc<dyn Fs>,
+    http_client: Arc<dyn HttpClient>,
+) -> Result<String> {
+    if source.starts_with("https://") || source.starts_with("http://") {
+        let mut response = http_client.get(source, AsyncBody::default(), true).await?;
+        let mut body = String::new();
+        response
+            .body_mut()
+            .read_to_string(&mut body)
+            .await
+            .context("error reading response body")?;
+        if !response.status().is_success() {
+            bail!(
+                "status error {} fetching {source}",
+                response.status().as_u16()
+            );
+        }
+        Ok(body)
+    } else {
+        fs.load(&PathBuf::from(source))
+            .await
+            .with_context(|| format!("loading {source}"))
+    }
+}
+
+enum ImportSource {
+    Given(String),
+    Prompted(oneshot::Receiver<Option<Vec<PathBuf>>>),
+}
+
+pub(crate) fn import_indentation_style(
+    workspace: &mut Workspace,
+    action: &ImportIndentationStyle,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(target) = ImportTarget::for_workspace(workspace, cx) else {
+        return;
+    };
+    let source = match &action.source {
+        Some(source) => ImportSource::Given(source.clone()),
+        None => {
+            let paths = workspace.prompt_for_open_path(
+                PathPromptOptions {
+                    files: true,
+                    directories: false,
+                    multiple: false,
+                    prompt: Some("Import Indentation Style".into()),
+                },
+                DirectoryLister::Local(workspace.project().clone(), target.fs().clone()),
+                window,
+                cx,
+            );
+            ImportSource::Prompted(paths)
+        }
+    };
+    let http_client = cx.http_client();
+
+    cx.spawn_in(window, async move |_, cx| {
+        let source = match source {
+            ImportSource::Given(source) => source,
+            ImportSource::Prompted(paths) => {
+                let Some(path) = paths
+                    .await
+                    .log_err()
+                    .flatten()
+                    .and_then(|paths| paths.into_iter().next())
+                else {
+                    return Ok(());
+                };
+                path.to_string_lossy().into_owned()
+            }
+        };
+        let content = load_source(&source, target.fs(), http_client).await?;
+        let content = editorconfig_from_import(&content)?;
+        let keys = adopted_keys(&content);
+        let detail = if keys.is_empty() {
+            format!("{source} doesn't set any indentation keys.")
+        } else {
+            format!("The following keys will be adopted:\n{}", keys.join("\n"))
+        };
+        target.install(content, Some(detail), cx).await
+    })
+    .detach_and_log_err(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editorconfig_from_json_descriptor() {
+        let content =
+            editorconfig_from_import(r#"{ "indent_style": "tab", "tab_width": 8 }"#).unwrap();
+        assert_eq!(content, "[*]\nindent_style = tab\ntab_width = 8\n");
+        assert!(editorconfig_from_import(r#"{ "indent": 2 }"#).is_err());
+    }
+
+    #[test]
+    fn test_merge_indentation() {
+        let existing = "root = true\n\n\
+                        [*]\n\
+                        indent_style = tab\n\
+                        charset = utf-8\n\n\
+                        [*.md]\n\
+                        trim_trailing_whitespace = false\n";
+        let imported = "root = true\n\n\
+                        [*]\n\
+                        indent_style = space\n\
+                        indent_size = 2\n\
+                        end_of_line = crlf\n\n\
+                        [Makefile]\n\
+                        indent_style = tab\n";
+        assert_eq!(
+            merge_indentation(existing, imported),
+            "root = true\n\n\
+             [*]\n\
+             indent_style = space\n\
+             charset = utf-8\n\
+             indent_size = 2\n\n\
+             [*.md]\n\
+             trim_trailing_whitespace = false\n\n\
+             [Makefile]\n\
+             indent_style = tab\n"
+        );
+    }
+
+    #[test]
+    fn test_adopted_keys() {
+        let content = "root = true\n\n[*]\nindent_style = space\ncharset = utf-8\n\n[*.go]\nindent_style = tab\n";
+        assert_eq!(
+            adopted_keys(&editorconfig_from_import(content).unwrap()),
+            vec!["[*] indent_style = space", "[*.go] indent_style = tab"]
+        );
+    }
+}
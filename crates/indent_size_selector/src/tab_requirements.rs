@@ -0,0 +1,161 @@
+use editor::Editor;
+use gpui::{App, Entity};
+use language::{Buffer, LanguageName};
+use settings::{IndentSizeSelectorTabsRequiredBehavior, Settings as _};
+use workspace::{Toast, notifications::NotificationId};
+
+use crate::{
+    IndentSizeSelectorSettings,
+    language_conventions::{TabRequirement, tab_requirement},
+};
+
+/// Whether spaces may be applied to `targets`, as `when_tabs_required` decides when the language
+/// of any of them requires hard tabs. Tells so in the workspace of `editor` when it warns about
+/// them or refuses to apply them.
+pub(crate) fn allows_spaces(
+    editor: &Entity<Editor>,
+    targets: &[Entity<Buffer>],
+    cx: &mut App,
+) -> bool {
+    let behavior = IndentSizeSelectorSettings::get_global(cx).when_tabs_required;
+    if behavior == IndentSizeSelectorTabsRequiredBehavior::Off {
+        return true;
+    }
+    let Some((language, requirement)) = targets.iter().find_map(|buffer| {
+        let language = buffer.read(cx).language()?.name();
+        let requirement = tab_requirement(language.as_ref())?;
+        Some((language, requirement))
+    }) else {
+        return true;
+    };
+
+    let refused = behavior == IndentSizeSelectorTabsRequiredBehavior::Refuse;
+    if let Some(workspace) = editor.read(cx).workspace() {
+        struct TabsRequired;
+
+        let message = message(&language, requirement, refused);
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<TabsRequired>(), message).autohide(),
+                cx,
+            );
+        });
+    }
+    !refused
+}
+
+fn message(language: &LanguageName, requirement: TabRequirement, refused: bool) -> String {
+    let reason = requirement.reason();
+    match (requirement, refused) {
+        (_, true) => {
+            format!("Didn't switch to spaces: {language} files require tabs, as {reason}")
+        }
+        (TabRequirement::LeadingTabLines, false) => {
+            format!("{language} files require tabs, as {reason}. Those lines keep their tabs")
+        }
+        (TabRequirement::AllLines, false) => {
+            format!("{language} files require tabs, as {reason}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{Language, LanguageConfig};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::sync::Arc;
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace};
+
+    #[gpui::test]
+    async fn test_allows_spaces(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn f() {}\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(language::rust_lang())
+        });
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace =
+            multi_workspace.read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone());
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.rs")), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        let editor = cx.update(|_, cx| item.act_as::<Editor>(cx).unwrap());
+        let rust = editor.read_with(cx, |editor, cx| {
+            editor.buffer().read(cx).as_singleton().unwrap()
+        });
+        let buffer_of = |name: &str, cx: &mut gpui::VisualTestContext| {
+            let language = Arc::new(Language::new(
+                LanguageConfig {
+                    name: name.to_string().into(),
+                    ..Default::default()
+                },
+                None,
+            ));
+            cx.new(|cx| Buffer::local("all:\n\tbuild\n", cx).with_language(language, cx))
+        };
+        let makefile = buffer_of("Makefile", cx);
+        let go = buffer_of("Go", cx);
+        let set_behavior = |behavior: IndentSizeSelectorTabsRequiredBehavior,
+                            cx: &mut gpui::VisualTestContext| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings(cx, |content| {
+                    content
+                        .indent_size_selector
+                        .get_or_insert_default()
+                        .when_tabs_required = Some(behavior);
+                })
+            })
+        };
+        let allows = |targets: &[Entity<Buffer>], cx: &mut gpui::VisualTestContext| {
+            let allowed = cx.update(|_, cx| allows_spaces(&editor, targets, cx));
+            let toasts = workspace.read_with(cx, |workspace, _| workspace.notification_ids().len());
+            (allowed, toasts)
+        };
+
+        set_behavior(IndentSizeSelectorTabsRequiredBehavior::Off, cx);
+        assert_eq!(allows(&[go.clone()], cx), (true, 0));
+
+        // Nothing is said of languages that don't require tabs.
+        set_behavior(IndentSizeSelectorTabsRequiredBehavior::Refuse, cx);
+        assert_eq!(allows(&[rust.clone()], cx), (true, 0));
+
+        set_behavior(IndentSizeSelectorTabsRequiredBehavior::Warn, cx);
+        assert_eq!(allows(&[rust.clone(), makefile.clone()], cx), (true, 1));
+
+        set_behavior(IndentSizeSelectorTabsRequiredBehavior::Refuse, cx);
+        assert_eq!(allows(&[rust, go], cx), (false, 1));
+    }
+
+    #[test]
+    fn test_message() {
+        let makefile = LanguageName::new_static("Makefile");
+        let go = LanguageName::new_static("Go");
+        assert_eq!(
+            message(&makefile, TabRequirement::LeadingTabLines, false),
+            "Makefile files require tabs, as their recipe lines must start with a tab. \
+             Those lines keep their tabs"
+        );
+        assert_eq!(
+            message(&go, TabRequirement::AllLines, true),
+            "Didn't switch to spaces: Go files require tabs, as their formatter indents with tabs"
+        );
+    }
+}
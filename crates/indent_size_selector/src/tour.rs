@@ -0,0 +1,244 @@
+use editor::Editor;
+use gpui::{Context, WeakEntity, Window};
+use language::{IndentSize, language_settings::IndentationOverride};
+use std::num::NonZeroU32;
+use ui::prelude::*;
+use workspace::{
+    Workspace,
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
+};
+
+use crate::{
+    IndentSizeSelector, StartIndentationTour, TargetScope, apply_scope::ApplyScopeSelector,
+    reindent_edits, set_buffer_override,
+};
+
+/// The text of the tour's scratch buffer, indented with 2 spaces.
+const SAMPLE: &str = r#"{
+  "name": "indentation-tour",
+  "scripts": {
+    "build": "cargo build",
+    "test": "cargo test"
+  }
+}
+"#;
+
+/// The indent size the tour converts the scratch buffer to.
+const CONVERTED_SIZE: NonZeroU32 = NonZeroU32::new(4).unwrap();
+
+/// A step of the tour, each shown as a notification with a button leading to the next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TourStep {
+    StatusItem,
+    Selector,
+    Scopes,
+    Conversion,
+}
+
+impl TourStep {
+    const ALL: [Self; 4] = [
+        Self::StatusItem,
+        Self::Selector,
+        Self::Scopes,
+        Self::Conversion,
+    ];
+
+    fn message(&self) -> &'static str {
+        match self {
+            Self::StatusItem => {
+                "The status bar shows the indentation Zed uses for the active file. This scratch \
+                 buffer is indented with 2 spaces. Clicking the indicator opens the indent size \
+                 selector."
+            }
+            Self::Selector => {
+                "The selector lists indent sizes, the ones you used recently first, and previews \
+                 the highlighted one on the file. Dismiss it to keep the file as it was."
+            }
+            Self::Scopes => {
+                "Once you pick a size, the selector asks where it applies: this buffer, its file, \
+                 its directory, every open buffer or file of its language, or every file."
+            }
+            Self::Conversion => {
+                "A new indent size only affects new indentation, so the tour just converted the \
+                 lines of this buffer from 2 to 4 spaces, as fixing indentation on save does. \
+                 Close the buffer without saving when you're done."
+            }
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|step| step == self)
+            .unwrap_or_default()
+    }
+
+    fn next(&self) -> Option<Self> {
+        Self::ALL.get(self.index() + 1).copied()
+    }
+}
+
+/// Opens a scratch buffer and walks through the status item, the selector, the scopes it
+/// applies to and converting existing indentation on it. Nothing is written to disk.
+pub(crate) fn start_indentation_tour(
+    workspace: &mut Workspace,
+    _: &StartIndentationTour,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let languages = workspace.project().read(cx).languages().clone();
+    let create = Editor::new_in_workspace(workspace, window, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let editor = create.await?;
+        let language = languages.language_for_name("JSON").await.ok();
+        workspace.update_in(cx, |workspace, window, cx| {
+            editor.update(cx, |editor, cx| {
+                editor.set_text(SAMPLE, window, cx);
+                if let Some(buffer) = editor.buffer().read(cx).as_singleton() {
+                    buffer.update(cx, |buffer, cx| buffer.set_language(language, cx));
+                }
+            });
+            show_step(workspace, editor.downgrade(), TourStep::StatusItem, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn show_step(
+    workspace: &mut Workspace,
+    editor: WeakEntity<Editor>,
+    step: TourStep,
+    cx: &mut Context<Workspace>,
+) {
+    struct IndentationTour;
+
+    let workspace_handle = cx.weak_entity();
+    // Each step has its own notification, so that dismissing one doesn't dismiss the next.
+    let id = NotificationId::composite::<IndentationTour>(step.index());
+    workspace.show_notification(id, cx, |cx| {
+        cx.new(|cx| {
+            let notification = MessageNotification::new(step.message(), cx)
+                .with_title(format!(
+                    "Indentation Tour ({}/{})",
+                    step.index() + 1,
+                    TourStep::ALL.len()
+                ))
+                .show_suppress_button(false);
+            match step.next() {
+                Some(next) => {
+                    notification
+                        .primary_message("Next")
+                        .primary_on_click(move |window, cx| {
+                            run_step(next, &editor, window, cx);
+                            workspace_handle
+                                .update(cx, |workspace, cx| {
+                                    show_step(workspace, editor.clone(), next, cx)
+                                })
+                                .ok();
+                        })
+                }
+                None => notification.primary_message("Finish"),
+            }
+        })
+    });
+}
+
+/// Shows the part of the subsystem that `step` is about on the scratch buffer open in `editor`.
+fn run_step(step: TourStep, editor: &WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+    let Some(editor_entity) = editor.upgrade() else {
+        return;
+    };
+    match step {
+        TourStep::StatusItem => {}
+        TourStep::Selector => {
+            IndentSizeSelector::toggle(editor, TargetScope::ActiveFile, window, cx);
+        }
+        TourStep::Scopes => {
+            ApplyScopeSelector::toggle(
+                &editor_entity,
+                CONVERTED_SIZE,
+                false,
+                None,
+                None,
+                false,
+                window,
+                cx,
+            );
+        }
+        TourStep::Conversion => {
+            let Some(buffer) = editor_entity.read(cx).buffer().read(cx).as_singleton() else {
+                return;
+            };
+            let edits = reindent_edits(
+                &buffer.read(cx).snapshot(),
+                IndentSize::spaces(2),
+                IndentSize::spaces(CONVERTED_SIZE.get()),
+                false,
+            );
+            buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            set_buffer_override(
+                &buffer,
+                IndentationOverride {
+                    tab_size: CONVERTED_SIZE,
+                    tab_width: None,
+                    hard_tabs: false,
+                },
+                cx,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use util::path;
+    use workspace::{AppState, MultiWorkspace};
+
+    #[gpui::test]
+    async fn test_tour_shows_each_step_once(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({})).await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace =
+            multi_workspace.read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone());
+        let notification_ids = |cx: &mut gpui::VisualTestContext| {
+            workspace.read_with(cx, |workspace, _| workspace.notification_ids())
+        };
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            start_indentation_tour(workspace, &StartIndentationTour, window, cx)
+        });
+        cx.run_until_parked();
+        let editor = workspace.read_with(cx, |workspace, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        });
+        assert_eq!(editor.update(cx, |editor, cx| editor.text(cx)), SAMPLE);
+        let first_step = notification_ids(cx);
+        assert_eq!(first_step.len(), 1);
+
+        // Showing a step again replaces its notification.
+        workspace.update(cx, |workspace, cx| {
+            show_step(workspace, editor.downgrade(), TourStep::StatusItem, cx)
+        });
+        assert_eq!(notification_ids(cx), first_step);
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.dismiss_notification(&first_step[0], cx)
+        });
+        assert!(notification_ids(cx).is_empty());
+
+        // The last step has nothing after it.
+        assert_eq!(TourStep::StatusItem.next(), Some(TourStep::Selector));
+        assert_eq!(TourStep::Conversion.next(), None);
+    }
+}
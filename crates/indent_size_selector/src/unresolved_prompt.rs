@@ -0,0 +1,159 @@
+use editor::Editor;
+use gpui::{Context, Window};
+use settings::{IndentSizeSelectorUnresolvedBehavior, Settings as _};
+use util::paths::PathStyle;
+use workspace::{Toast, notifications::NotificationId};
+
+use crate::{
+    IndentSizeSelector, IndentSizeSelectorSettings, TargetScope, indent_source::IndentSource,
+};
+
+/// Opens the selector, or offers to, when the file opened in `editor` has no indentation of its
+/// own and would silently use Zed's defaults.
+pub(crate) fn watch(editor: &mut Editor, window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let Some(window) = window else {
+        return;
+    };
+    if !editor.mode().is_full() || !editor.buffer().read(cx).is_singleton() {
+        return;
+    }
+    let behavior = IndentSizeSelectorSettings::get_global(cx).when_unresolved;
+    if behavior == IndentSizeSelectorUnresolvedBehavior::Off {
+        return;
+    }
+
+    // The editor only joins its workspace once it's been added to a pane.
+    cx.defer_in(window, move |editor, window, cx| {
+        let Some(file) = editor
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .and_then(|buffer| buffer.read(cx).file().cloned())
+        else {
+            return;
+        };
+        if !IndentSource::is_unresolved(editor, cx) {
+            return;
+        }
+        let Some(workspace) = editor.workspace() else {
+            return;
+        };
+        let editor_handle = cx.weak_entity();
+        match behavior {
+            IndentSizeSelectorUnresolvedBehavior::Off => {}
+            IndentSizeSelectorUnresolvedBehavior::Open => {
+                // Toggling the selector updates the editor, so it waits for this update to end.
+                window.defer(cx, move |window, cx| {
+                    IndentSizeSelector::toggle(&editor_handle, TargetScope::ActiveFile, window, cx);
+                });
+            }
+            IndentSizeSelectorUnresolvedBehavior::Notify => {
+                workspace.update(cx, |workspace, cx| {
+                    struct UnresolvedIndentation;
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<UnresolvedIndentation>(),
+                            format!(
+                                "Nothing sets the indentation of {}, so it uses Zed's defaults",
+                                file.path().display(PathStyle::local())
+                            ),
+                        )
+                        .on_click(
+                            "Select Indentation",
+                            move |window, cx| {
+                                IndentSizeSelector::toggle(
+                                    &editor_handle,
+                                    TargetScope::ActiveFile,
+                                    window,
+                                    cx,
+                                );
+                            },
+                        ),
+                        cx,
+                    );
+                })
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace};
+
+    /// Opens a file that nothing sets the indentation of with `behavior`, returning how many
+    /// notifications its workspace shows and whether the selector opened.
+    async fn open_unresolved(
+        behavior: IndentSizeSelectorUnresolvedBehavior,
+        cx: &mut TestAppContext,
+    ) -> (usize, bool) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings(cx, |content| {
+                    content
+                        .indent_size_selector
+                        .get_or_insert_default()
+                        .when_unresolved = Some(behavior);
+                });
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.txt": "a\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace =
+            multi_workspace.read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone());
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.txt")), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        let editor = cx.update(|_, cx| item.act_as::<Editor>(cx).unwrap());
+        editor.update_in(cx, |editor, window, cx| watch(editor, Some(window), cx));
+        cx.run_until_parked();
+        workspace.read_with(cx, |workspace, cx| {
+            (
+                workspace.notification_ids().len(),
+                workspace.active_modal::<IndentSizeSelector>(cx).is_some(),
+            )
+        })
+    }
+
+    #[gpui::test]
+    async fn test_unresolved_off(cx: &mut TestAppContext) {
+        assert_eq!(
+            open_unresolved(IndentSizeSelectorUnresolvedBehavior::Off, cx).await,
+            (0, false)
+        );
+    }
+
+    #[gpui::test]
+    async fn test_unresolved_notify(cx: &mut TestAppContext) {
+        assert_eq!(
+            open_unresolved(IndentSizeSelectorUnresolvedBehavior::Notify, cx).await,
+            (1, false)
+        );
+    }
+
+    #[gpui::test]
+    async fn test_unresolved_open(cx: &mut TestAppContext) {
+        assert_eq!(
+            open_unresolved(IndentSizeSelectorUnresolvedBehavior::Open, cx).await,
+            (0, true)
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use gpui::{App, Entity, WeakEntity};
+use language::{Buffer, DiskState, File};
+use project::Project;
+use std::{borrow::Cow, sync::Arc};
+use workspace::{Toast, Workspace, notifications::NotificationId};
+
+/// Why the indentation picked for a file can't be written as an `.editorconfig` override for
+/// its path, leaving only its buffer to be overridden.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum UnsupportedFile {
+    /// Opened on its own rather than in a folder, so it's the root of its worktree and no
+    /// section could match its path.
+    SingleFile,
+    /// Deleted since it was opened, or an old version of a file, such as one from git.
+    NotOnDisk,
+}
+
+impl UnsupportedFile {
+    pub(crate) fn for_file(file: &dyn File) -> Option<Self> {
+        if file.path().is_empty() {
+            Some(Self::SingleFile)
+        } else if matches!(
+            file.disk_state(),
+            DiskState::Deleted | DiskState::Historic { .. }
+        ) {
+            Some(Self::NotOnDisk)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn for_buffer(buffer: &Buffer) -> Option<Self> {
+        Self::for_file(buffer.file()?.as_ref())
+    }
+}
+
+/// The file of `buffer`, when an `.editorconfig` override can be written for its path.
+pub(crate) fn overridable_file(buffer: &Buffer) -> Option<&Arc<dyn File>> {
+    buffer
+        .file()
+        .filter(|file| UnsupportedFile::for_file(file.as_ref()).is_none())
+}
+
+/// Whether the file of `buffer` was opened on its own, outside of any worktree, so that it has
+/// neither worktree nor project settings.
+pub(crate) fn is_outside_worktree(buffer: &Buffer) -> bool {
+    UnsupportedFile::for_buffer(buffer) == Some(UnsupportedFile::SingleFile)
+}
+
+/// The reason given when the existing lines of a read-only buffer would have been converted.
+pub(crate) const READ_ONLY: &str =
+    "This file is read-only, so its existing lines were left as they were";
+
+/// Shows why the indentation picked in the selector wasn't applied the way it was asked to be.
+pub(crate) fn show_not_applied(
+    workspace: &WeakEntity<Workspace>,
+    message: impl Into<Cow<'static, str>>,
+    cx: &mut App,
+) {
+    struct IndentationNotApplied;
+
+    let message = message.into();
+    workspace
+        .update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<IndentationNotApplied>(), message),
+                cx,
+            );
+        })
+        .ok();
+}
+
+/// Why the project settings can't be written for the file of `buffer` in `project`, leaving
+/// only its buffer to be overridden, if they can't.
+pub(crate) fn project_settings_unavailable(
+    project: &Entity<Project>,
+    buffer: &Buffer,
+    cx: &App,
+) -> Option<&'static str> {
+    if project.read(cx).is_via_collab() {
+        // Written locally, they'd land beside a path that only exists on the host.
+        Some(
+            "Only the host can change the settings of a shared project, so this indentation \
+             applies until the file is closed",
+        )
+    } else if is_outside_worktree(buffer) {
+        Some(
+            "This file was opened on its own, so it has no project settings, and its indentation \
+             applies until it's closed",
+        )
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ApplyTo, apply_scope::ApplyScopeSelector, apply_to_path};
+    use editor::Editor;
+    use gpui::TestAppContext;
+    use language::{Capability, language_settings::IndentationOverride};
+    use project::{FakeFs, Fs as _};
+    use serde_json::json;
+    use std::{num::NonZeroU32, path::Path};
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace};
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+    }
+
+    fn two_spaces() -> Option<IndentationOverride> {
+        Some(IndentationOverride {
+            tab_size: NonZeroU32::new(2).unwrap(),
+            tab_width: None,
+            hard_tabs: false,
+        })
+    }
+
+    #[gpui::test]
+    async fn test_read_only_file_keeps_its_lines(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn f() {\n\tg();\n}\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace =
+            multi_workspace.read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone());
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.rs")), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        let editor = cx.update(|_, cx| item.act_as::<Editor>(cx).unwrap());
+        let buffer = editor.read_with(cx, |editor, cx| {
+            editor.buffer().read(cx).as_singleton().unwrap()
+        });
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_capability(Capability::ReadOnly, cx)
+        });
+        let toasts = |cx: &mut gpui::VisualTestContext| {
+            workspace.read_with(cx, |workspace, _| workspace.notification_ids().len())
+        };
+        assert_eq!(toasts(cx), 0);
+
+        // The indentation still applies to the buffer, but its lines aren't converted.
+        let apply = |cx: &mut gpui::VisualTestContext| {
+            cx.update(|_, cx| {
+                ApplyScopeSelector::apply_preselected(
+                    &editor,
+                    ApplyTo::Buffer,
+                    NonZeroU32::new(2).unwrap(),
+                    false,
+                    None,
+                    None,
+                    true,
+                    cx,
+                )
+            })
+        };
+        assert!(apply(cx).is_some());
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "fn f() {\n\tg();\n}\n"
+        );
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.indentation_override().copied()),
+            two_spaces()
+        );
+        assert_eq!(toasts(cx), 1);
+
+        // Applying it again replaces the toast rather than stacking another one.
+        apply(cx);
+        assert_eq!(toasts(cx), 1);
+    }
+
+    #[gpui::test]
+    async fn test_deleted_file_overrides_its_buffer(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn f() {}\n" }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        buffer.read_with(cx, |buffer, _| {
+            assert_eq!(UnsupportedFile::for_buffer(buffer), None);
+            assert!(overridable_file(buffer).is_some());
+        });
+
+        fs.remove_file(Path::new(path!("/dir/a.rs")), Default::default())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        buffer.read_with(cx, |buffer, cx| {
+            assert_eq!(
+                UnsupportedFile::for_buffer(buffer),
+                Some(UnsupportedFile::NotOnDisk)
+            );
+            assert!(overridable_file(buffer).is_none());
+            assert_eq!(project_settings_unavailable(&project, buffer, cx), None);
+        });
+
+        assert!(cx.update(|cx| apply_to_path(&buffer, 2, false, None, None, cx)));
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.indentation_override().copied()),
+            two_spaces()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_single_file_overrides_its_buffer(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn f() {}\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/dir/a.rs").as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        buffer.read_with(cx, |buffer, cx| {
+            assert_eq!(
+                UnsupportedFile::for_buffer(buffer),
+                Some(UnsupportedFile::SingleFile)
+            );
+            assert!(is_outside_worktree(buffer));
+            assert!(overridable_file(buffer).is_none());
+            assert!(
+                project_settings_unavailable(&project, buffer, cx)
+                    .is_some_and(|reason| reason.starts_with("This file was opened on its own"))
+            );
+        });
+
+        assert!(cx.update(|cx| apply_to_path(&buffer, 2, false, None, None, cx)));
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.indentation_override().copied()),
+            two_spaces()
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use editor::Editor;
+use settings::Settings as _;
+use ui::prelude::*;
+
+use crate::IndentSizeSelectorSettings;
+
+/// Indents the untitled buffer that `editor` was just created for with the `untitled_indentation`
+/// setting. The buffer keeps it once saved, until it's closed.
+pub(crate) fn watch(editor: &mut Editor, _window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    if !editor.mode().is_full() {
+        return;
+    }
+    let Some(indentation) = IndentSizeSelectorSettings::get_global(cx).untitled_indentation else {
+        return;
+    };
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let is_new = {
+        let buffer = buffer.read(cx);
+        // Another editor, such as a split, may already show the buffer.
+        buffer.file().is_none() && buffer.is_empty() && buffer.indentation_override().is_none()
+    };
+    if is_new {
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_indentation_override(Some(indentation), cx)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::language_settings::IndentationOverride;
+    use project::{FakeFs, Project, ProjectPath};
+    use serde_json::json;
+    use settings::{IndentationContent, SettingsStore};
+    use std::num::NonZeroU32;
+    use util::{path, rel_path::rel_path};
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_untitled_indentation(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings(cx, |content| {
+                    content
+                        .indent_size_selector
+                        .get_or_insert_default()
+                        .untitled_indentation = Some(IndentationContent {
+                        tab_size: NonZeroU32::new(2).unwrap(),
+                        hard_tabs: Some(true),
+                    });
+                });
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.txt": "" })).await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let untitled = IndentationOverride {
+            tab_size: NonZeroU32::new(2).unwrap(),
+            tab_width: None,
+            hard_tabs: true,
+        };
+
+        let buffer = project.update(cx, |project, cx| {
+            project.create_local_buffer("", None, false, cx)
+        });
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+        });
+        editor.update_in(cx, |editor, window, cx| watch(editor, Some(window), cx));
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.indentation_override().copied()),
+            Some(untitled)
+        );
+
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        project
+            .update(cx, |project, cx| {
+                project.save_buffer_as(
+                    buffer.clone(),
+                    ProjectPath {
+                        worktree_id,
+                        path: rel_path("b.txt").into(),
+                    },
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.indentation_override().copied()),
+            Some(untitled)
+        );
+
+        // Files that are already saved aren't untitled.
+        let file_buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.txt"), cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            Editor::for_buffer(file_buffer.clone(), Some(project.clone()), window, cx)
+        });
+        editor.update_in(cx, |editor, window, cx| watch(editor, Some(window), cx));
+        assert_eq!(
+            file_buffer.read_with(cx, |buffer, _| buffer.indentation_override().copied()),
+            None
+        );
+    }
+}
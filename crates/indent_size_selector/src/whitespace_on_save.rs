@@ -0,0 +1,215 @@
+use ec4rs::property::{FinalNewline, TrimTrailingWs};
+use editor::Editor;
+use gpui::Entity;
+use language::{Buffer, language_settings::LanguageSettings};
+use settings::LanguageSettingsContent;
+use std::sync::Arc;
+use ui::prelude::*;
+
+use crate::{
+    ToggleEnsureFinalNewlineOnSave, ToggleTrimTrailingWhitespaceOnSave,
+    buffer_settings::{BufferSetting, governing_properties},
+    file_buffer,
+    setting_scope_selector::{SettingScopeSelector, SettingValue},
+};
+
+/// A whitespace cleanup applied when saving, which can be toggled at the same scopes as the
+/// indentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum WhitespaceOnSave {
+    TrimTrailingWhitespace,
+    EnsureFinalNewline,
+}
+
+impl WhitespaceOnSave {
+    /// Whether saving the file of `buffer` applies it.
+    pub(crate) fn is_enabled(self, buffer: &Buffer, cx: &App) -> bool {
+        let settings = LanguageSettings::for_buffer(buffer, cx);
+        match self {
+            Self::TrimTrailingWhitespace => settings.remove_trailing_whitespace_on_save,
+            Self::EnsureFinalNewline => settings.ensure_final_newline_on_save,
+        }
+    }
+}
+
+impl BufferSetting for WhitespaceOnSave {
+    fn name(&self) -> SharedString {
+        match self {
+            Self::TrimTrailingWhitespace => "Trim Trailing Whitespace on Save".into(),
+            Self::EnsureFinalNewline => "Ensure Final Newline on Save".into(),
+        }
+    }
+
+    fn editorconfig_property(&self) -> &'static str {
+        match self {
+            Self::TrimTrailingWhitespace => "trim_trailing_whitespace",
+            Self::EnsureFinalNewline => "insert_final_newline",
+        }
+    }
+
+    fn candidates(&self, _buffer: &Buffer, _cx: &App) -> Vec<SharedString> {
+        vec!["true".into(), "false".into()]
+    }
+
+    fn current_value(&self, buffer: &Buffer, cx: &App) -> Option<SharedString> {
+        Some(self.is_enabled(buffer, cx).to_string().into())
+    }
+
+    fn configured_value(&self, buffer: &Buffer, cx: &App) -> Option<SharedString> {
+        let properties = governing_properties(buffer, cx)?;
+        let enabled = match self {
+            Self::TrimTrailingWhitespace => match properties.get::<TrimTrailingWs>().ok()? {
+                TrimTrailingWs::Value(enabled) => enabled,
+            },
+            Self::EnsureFinalNewline => match properties.get::<FinalNewline>().ok()? {
+                FinalNewline::Value(enabled) => enabled,
+            },
+        };
+        Some(enabled.to_string().into())
+    }
+
+    fn label(&self, value: &str) -> SharedString {
+        let label = if value == "true" { "On" } else { "Off" };
+        label.into()
+    }
+
+    fn has_user_setting(&self) -> bool {
+        true
+    }
+
+    fn set_user_setting(&self, value: &str, settings: &mut LanguageSettingsContent) {
+        let enabled = value == "true";
+        match self {
+            Self::TrimTrailingWhitespace => {
+                settings.remove_trailing_whitespace_on_save = Some(enabled);
+            }
+            Self::EnsureFinalNewline => settings.ensure_final_newline_on_save = Some(enabled),
+        }
+    }
+}
+
+/// Handles the actions toggling each cleanup for the file open in `editor`.
+pub(crate) fn register(
+    editor: &mut Editor,
+    _window: Option<&mut Window>,
+    cx: &mut Context<Editor>,
+) {
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action(move |_: &ToggleTrimTrailingWhitespaceOnSave, window, cx| {
+            if let Some(editor) = editor_handle.upgrade() {
+                toggle(
+                    &editor,
+                    WhitespaceOnSave::TrimTrailingWhitespace,
+                    window,
+                    cx,
+                );
+            }
+        })
+        .detach();
+    let editor_handle = cx.weak_entity();
+    editor
+        .register_action(move |_: &ToggleEnsureFinalNewlineOnSave, window, cx| {
+            if let Some(editor) = editor_handle.upgrade() {
+                toggle(&editor, WhitespaceOnSave::EnsureFinalNewline, window, cx);
+            }
+        })
+        .detach();
+}
+
+/// Asks where to switch `setting` for the file open in `editor`, away from whether it applies to
+/// the file now.
+fn toggle(editor: &Entity<Editor>, setting: WhitespaceOnSave, window: &mut Window, cx: &mut App) {
+    let Some(buffer) = file_buffer(editor.read(cx), cx) else {
+        return;
+    };
+    let enabled = !setting.is_enabled(buffer.read(cx), cx);
+    SettingScopeSelector::toggle(
+        editor,
+        SettingValue {
+            setting: Arc::new(setting),
+            value: enabled.to_string().into(),
+            convert: false,
+        },
+        window,
+        cx,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+    use workspace::{
+        AppState,
+        item::{Item as _, SaveOptions},
+    };
+
+    #[gpui::test]
+    async fn test_whitespace_cleanups_on_save(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.txt": "a  \nb" }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.txt"), cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+        });
+        let set_cleanups = |enabled: &str, cx: &mut gpui::VisualTestContext| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings(cx, |content| {
+                    for setting in [
+                        WhitespaceOnSave::TrimTrailingWhitespace,
+                        WhitespaceOnSave::EnsureFinalNewline,
+                    ] {
+                        setting
+                            .set_user_setting(enabled, &mut content.project.all_languages.defaults);
+                    }
+                });
+            })
+        };
+        let save = async |cx: &mut gpui::VisualTestContext| {
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    editor.save(
+                        SaveOptions {
+                            format: true,
+                            autosave: false,
+                        },
+                        project.clone(),
+                        window,
+                        cx,
+                    )
+                })
+                .await
+                .unwrap();
+            fs.load(Path::new(path!("/dir/a.txt"))).await.unwrap()
+        };
+
+        set_cleanups("false", cx);
+        buffer.read_with(cx, |buffer, cx| {
+            assert!(!WhitespaceOnSave::TrimTrailingWhitespace.is_enabled(buffer, cx));
+            assert!(!WhitespaceOnSave::EnsureFinalNewline.is_enabled(buffer, cx));
+        });
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "z")], None, cx));
+        assert_eq!(save(cx).await, "za  \nb");
+
+        set_cleanups("true", cx);
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..1, "")], None, cx));
+        assert_eq!(save(cx).await, "a\nb\n");
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
     DebuggerTextObject, LanguageScope, ModelineSettings, Outline, OutlineConfig, PLAIN_TEXT,
     RunnableCapture, RunnableTag, TextObject, TreeSitterOptions,
     diagnostic_set::{DiagnosticEntry, DiagnosticEntryRef, DiagnosticGroup},
-    language_settings::{AutoIndentMode, LanguageSettings},
+    language_settings::{AutoIndentMode, IndentationOverride, LanguageSettings},
     outline::OutlineItem,
     row_chunk::RowChunks,
     syntax_map::{
@@ -133,6 +133,7 @@ pub struct Buffer {
     has_unsaved_edits: Cell<(clock::Global, bool)>,
     change_bits: Vec<rc::Weak<Cell<bool>>>,
     modeline: Option<Arc<ModelineSettings>>,
+    indentation_override: Option<IndentationOverride>,
     _subscriptions: Vec<gpui::Subscription>,
     tree_sitter_data: Arc<TreeSitterData>,
     encoding: &'static Encoding,
@@ -194,6 +195,7 @@ pub struct BufferSnapshot {
     non_text_state_update_count: usize,
     pub capability: Capability,
     modeline: Option<Arc<ModelineSettings>>,
+    indentation_override: Option<IndentationOverride>,
 }
 
 /// The kind and amount of indentation in a particular line. For now,
@@ -1112,6 +1114,7 @@ impl Buffer {
             has_conflict: false,
             change_bits: Default::default(),
             modeline: None,
+            indentation_override: None,
             _subscriptions: Vec::new(),
             encoding: encoding_rs::UTF_8,
             has_bom: false,
@@ -1150,6 +1153,7 @@ impl Buffer {
                 non_text_state_update_count: 0,
                 capability: Capability::ReadOnly,
                 modeline,
+                indentation_override: None,
             }
         }
     }
@@ -1177,6 +1181,7 @@ impl Buffer {
             non_text_state_update_count: 0,
             capability: Capability::ReadOnly,
             modeline: None,
+            indentation_override: None,
         }
     }
 
@@ -1208,6 +1213,7 @@ impl Buffer {
             non_text_state_update_count: 0,
             capability: Capability::ReadOnly,
             modeline: None,
+            indentation_override: None,
         }
     }
 
@@ -1239,6 +1245,7 @@ impl Buffer {
             non_text_state_update_count: self.non_text_state_update_count,
             capability: self.capability,
             modeline: self.modeline.clone(),
+            indentation_override: self.indentation_override,
         }
     }
 
@@ -1506,6 +1513,27 @@ impl Buffer {
         self.modeline.as_ref()
     }
 
+    /// Overrides the indentation of this buffer until it's closed, or clears the override.
+    pub fn set_indentation_override(
+        &mut self,
+        indentation_override: Option<IndentationOverride>,
+        cx: &mut Context<Self>,
+    ) {
+        if indentation_override != self.indentation_override {
+            self.indentation_override = indentation_override;
+            // Snapshots that were already taken, such as those of the multibuffers showing this
+            // buffer, would otherwise keep serving the previous indentation until the next edit.
+            self.non_text_state_update_count += 1;
+            self.was_changed();
+            cx.notify();
+        }
+    }
+
+    /// Returns the [`IndentationOverride`].
+    pub fn indentation_override(&self) -> Option<&IndentationOverride> {
+        self.indentation_override.as_ref()
+    }
+
     /// Assign the buffer a new [`Capability`].
     pub fn set_capability(&mut self, capability: Capability, cx: &mut Context<Self>) {
         if self.capability != capability {
@@ -3849,6 +3877,11 @@ impl BufferSnapshot {
         self.modeline.as_ref()
     }
 
+    /// Returns the [`IndentationOverride`].
+    pub fn indentation_override(&self) -> Option<&IndentationOverride> {
+        self.indentation_override.as_ref()
+    }
+
     /// Returns the main [`Language`].
     pub fn language(&self) -> Option<&Arc<Language>> {
         self.language.as_ref()
@@ -5512,6 +5545,7 @@ impl Clone for BufferSnapshot {
             non_text_state_update_count: self.non_text_state_update_count,
             capability: self.capability,
             modeline: self.modeline.clone(),
+            indentation_override: self.indentation_override,
         }
     }
 }
@@ -15,9 +15,9 @@ use settings::{DocumentFoldingRanges, DocumentSymbols, IntoGpui, SemanticTokens}
 
 pub use settings::{
     AutoIndentMode, CompletionSettingsContent, EditPredictionPromptFormat, EditPredictionProvider,
-    EditPredictionsMode, FormatOnSave, Formatter, FormatterList, InlayHintKind,
-    LanguageSettingsContent, LspInsertMode, RewrapBehavior, ShowWhitespaceSetting, SoftWrap,
-    WordsCompletionMode,
+    EditPredictionsMode, FormatOnSave, Formatter, FormatterList, IndentationLintSeverity,
+    InlayHintKind, LanguageSettingsContent, LspInsertMode, RewrapBehavior, ShowWhitespaceSetting,
+    SoftWrap, WordsCompletionMode,
 };
 use settings::{RegisterSetting, Settings, SettingsLocation, SettingsStore, merge_from::MergeFrom};
 use shellexpand;
@@ -57,6 +57,9 @@ pub struct WhitespaceMap {
 pub struct LanguageSettings {
     /// How many columns a tab should occupy.
     pub tab_size: NonZeroU32,
+    /// How many columns a tab character occupies, when an `.editorconfig` or a modeline sets
+    /// it apart from `tab_size`, which is then the width of a level of indentation.
+    pub tab_width: Option<NonZeroU32>,
     /// Whether to indent lines using tab characters, as opposed to multiple
     /// spaces.
     pub hard_tabs: bool,
@@ -72,6 +75,8 @@ pub struct LanguageSettings {
     pub show_wrap_guides: bool,
     /// Character counts at which to show wrap guides (vertical rulers) in the editor.
     pub wrap_guides: Vec<usize>,
+    /// Whether to show a wrap guide at the `max_line_length` that an `.editorconfig` sets.
+    pub editorconfig_wrap_guide: bool,
     /// Indent guide related settings.
     pub indent_guides: IndentGuideSettings,
     /// Whether or not to perform a buffer format before saving.
@@ -82,6 +87,11 @@ pub struct LanguageSettings {
     /// Whether or not to ensure there's a single newline at the end of a buffer
     /// when saving it.
     pub ensure_final_newline_on_save: bool,
+    /// Whether or not to re-indent a buffer to its configured indentation when
+    /// saving it, if it's indented differently.
+    pub convert_indentation_on_save: bool,
+    /// How each indentation lint is reported for the buffer.
+    pub indentation_lints: IndentationLints,
     /// How to perform a buffer format.
     pub formatter: settings::FormatterList,
     /// Zed's Prettier integration settings.
@@ -194,6 +204,23 @@ pub struct CompletionSettings {
     pub lsp_insert_mode: LspInsertMode,
 }
 
+/// How each indentation lint is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndentationLints {
+    /// Lines indented with a number of spaces that isn't a multiple of the indent size.
+    ///
+    /// Default: off
+    pub not_multiple_of_indent_size: IndentationLintSeverity,
+    /// Lines indented with tabs as well as spaces.
+    ///
+    /// Default: off
+    pub mixed_tabs_and_spaces: IndentationLintSeverity,
+    /// Lines whose indentation has a space before a tab.
+    ///
+    /// Default: off
+    pub spaces_before_tabs: IndentationLintSeverity,
+}
+
 /// The settings for indent guides.
 #[derive(Debug, Clone, PartialEq)]
 pub struct IndentGuideSettings {
@@ -312,6 +339,9 @@ impl LanguageSettings {
         if let Some(modeline) = buffer.modeline() {
             merge_with_modeline(settings.to_mut(), modeline);
         }
+        if let Some(indentation_override) = buffer.indentation_override() {
+            indentation_override.apply_to(settings.to_mut());
+        }
 
         settings
     }
@@ -338,6 +368,9 @@ impl LanguageSettings {
         if let Some(modeline) = buffer.modeline() {
             merge_with_modeline(settings.to_mut(), modeline);
         }
+        if let Some(indentation_override) = buffer.indentation_override() {
+            indentation_override.apply_to(settings.to_mut());
+        }
 
         settings
     }
@@ -589,6 +622,16 @@ fn merge_with_modeline(settings: &mut LanguageSettings, modeline: &ModelineSetti
     settings
         .hard_tabs
         .merge_from_option(modeline.hard_tabs.as_ref());
+    // Zed indents by as many columns as a tab is wide, so when indenting with spaces, the width
+    // of a level (Vim's `shiftwidth`, Emacs's `c-basic-offset`) takes the place of the tab width.
+    if !settings.hard_tabs {
+        settings
+            .tab_size
+            .merge_from_option(modeline.indent_size.as_ref());
+    }
+    if let Some(tab_width) = modeline.tab_size {
+        settings.tab_width = (tab_width != settings.tab_size).then_some(tab_width);
+    }
     settings
         .preferred_line_length
         .merge_from_option(modeline.preferred_line_length.map(u32::from).as_ref());
@@ -621,6 +664,9 @@ fn merge_with_editorconfig(settings: &mut LanguageSettings, cfg: &EditorconfigPr
             TabWidth::Value(u) => NonZeroU32::new(u as u32),
         }),
     });
+    let tab_width = cfg.get::<TabWidth>().ok().and_then(|w| match w {
+        TabWidth::Value(u) => NonZeroU32::new(u as u32),
+    });
     let hard_tabs = cfg
         .get::<IndentStyle>()
         .map(|v| v.eq(&IndentStyle::Tabs))
@@ -641,7 +687,19 @@ fn merge_with_editorconfig(settings: &mut LanguageSettings, cfg: &EditorconfigPr
     settings
         .preferred_line_length
         .merge_from_option(preferred_line_length.as_ref());
+    if let Some(preferred_line_length) = preferred_line_length
+        && settings.editorconfig_wrap_guide
+        && !settings
+            .wrap_guides
+            .contains(&(preferred_line_length as usize))
+    {
+        settings.wrap_guides.push(preferred_line_length as usize);
+        settings.wrap_guides.sort_unstable();
+    }
     settings.tab_size.merge_from_option(tab_size.as_ref());
+    if let Some(tab_width) = tab_width {
+        settings.tab_width = (tab_width != settings.tab_size).then_some(tab_width);
+    }
     settings.hard_tabs.merge_from_option(hard_tabs.as_ref());
     settings
         .remove_trailing_whitespace_on_save
@@ -660,16 +718,19 @@ impl settings::Settings for AllLanguageSettings {
             let completions = settings.completions.unwrap();
             let prettier = settings.prettier.unwrap();
             let indent_guides = settings.indent_guides.unwrap();
+            let indentation_lints = settings.indentation_lints.unwrap();
             let tasks = settings.tasks.unwrap();
             let whitespace_map = settings.whitespace_map.unwrap();
 
             LanguageSettings {
                 tab_size: settings.tab_size.unwrap(),
+                tab_width: None,
                 hard_tabs: settings.hard_tabs.unwrap(),
                 soft_wrap: settings.soft_wrap.unwrap(),
                 preferred_line_length: settings.preferred_line_length.unwrap(),
                 show_wrap_guides: settings.show_wrap_guides.unwrap(),
                 wrap_guides: settings.wrap_guides.unwrap(),
+                editorconfig_wrap_guide: settings.editorconfig_wrap_guide.unwrap(),
                 indent_guides: IndentGuideSettings {
                     enabled: indent_guides.enabled.unwrap(),
                     line_width: indent_guides.line_width.unwrap(),
@@ -682,6 +743,14 @@ impl settings::Settings for AllLanguageSettings {
                     .remove_trailing_whitespace_on_save
                     .unwrap(),
                 ensure_final_newline_on_save: settings.ensure_final_newline_on_save.unwrap(),
+                convert_indentation_on_save: settings.convert_indentation_on_save.unwrap(),
+                indentation_lints: IndentationLints {
+                    not_multiple_of_indent_size: indentation_lints
+                        .not_multiple_of_indent_size
+                        .unwrap(),
+                    mixed_tabs_and_spaces: indentation_lints.mixed_tabs_and_spaces.unwrap(),
+                    spaces_before_tabs: indentation_lints.spaces_before_tabs.unwrap(),
+                },
                 formatter: settings.formatter.unwrap(),
                 prettier: PrettierSettings {
                     allowed: prettier.allowed.unwrap(),
@@ -869,10 +938,121 @@ pub struct JsxTagAutoCloseSettings {
     pub enabled: bool,
 }
 
+/// The whitespace conventions that apply to a buffer, resolved from its language settings.
+///
+/// Saving, formatting requests, the indentation UI and the edits assistants generate all
+/// derive their whitespace handling from this, so they can't disagree about what a buffer's
+/// settings mean.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedWhitespaceOptions {
+    /// How many columns each level of indentation occupies.
+    pub indent_size: NonZeroU32,
+    /// How many columns a tab character occupies.
+    pub tab_width: NonZeroU32,
+    /// Whether to indent using tab characters rather than spaces.
+    pub hard_tabs: bool,
+    /// Whether to remove trailing whitespace from lines when saving.
+    pub trim_trailing_whitespace: bool,
+    /// Whether to ensure a single newline at the end of the buffer when saving.
+    pub insert_final_newline: bool,
+}
+
+impl ResolvedWhitespaceOptions {
+    pub fn from_settings(settings: &LanguageSettings) -> Self {
+        Self {
+            indent_size: settings.tab_size,
+            tab_width: settings.tab_width.unwrap_or(settings.tab_size),
+            hard_tabs: settings.hard_tabs,
+            trim_trailing_whitespace: settings.remove_trailing_whitespace_on_save,
+            insert_final_newline: settings.ensure_final_newline_on_save,
+        }
+    }
+
+    pub fn for_buffer(buffer: &Buffer, cx: &App) -> Self {
+        Self::from_settings(&LanguageSettings::for_buffer(buffer, cx))
+    }
+
+    pub fn for_buffer_snapshot(buffer: &BufferSnapshot, cx: &App) -> Self {
+        Self::from_settings(&LanguageSettings::for_buffer_snapshot(buffer, None, cx))
+    }
+
+    /// Rewrites `indent`, the leading whitespace of a line generated for a buffer with these
+    /// options, such as by an assistant, in the buffer's style. Every `level_len` columns of
+    /// `indent` are taken as a level, as is each of its tabs, and the columns left over are kept
+    /// as spaces.
+    pub fn normalize_indent(&self, indent: &str, level_len: u32) -> String {
+        let level_len = level_len.max(1);
+        let columns = indent
+            .chars()
+            .map(|c| if c == '\t' { level_len } else { 1 })
+            .sum::<u32>();
+        let level = if self.hard_tabs {
+            "\t".to_string()
+        } else {
+            " ".repeat(self.indent_size.get() as usize)
+        };
+        level.repeat((columns / level_len) as usize) + &" ".repeat((columns % level_len) as usize)
+    }
+
+    /// Rewrites the leading whitespace of each line of `text`, generated for a buffer with these
+    /// options, in the buffer's style, as [`Self::normalize_indent`] does, taking a level of the
+    /// indentation detected in `text` as a level.
+    pub fn normalize_indentation(&self, text: &str) -> String {
+        let detection = crate::detect_indentation(&text.into(), &crate::DetectOptions::default());
+        let level_len = match detection.indent {
+            Some(crate::DetectedIndent::Spaces(len)) => len,
+            Some(crate::DetectedIndent::Tabs) | None => self.indent_size.get(),
+        };
+        text.split('\n')
+            .map(|line| {
+                let content = line.trim_start_matches([' ', '\t']);
+                if content.is_empty() {
+                    return line.to_string();
+                }
+                self.normalize_indent(&line[..line.len() - content.len()], level_len) + content
+            })
+            .join("\n")
+    }
+
+    /// The indentation a single level of indent inserts.
+    pub fn indent(&self) -> crate::IndentSize {
+        if self.hard_tabs {
+            crate::IndentSize::tab()
+        } else {
+            crate::IndentSize::spaces(self.indent_size.get())
+        }
+    }
+}
+
+/// Indentation that applies to a single buffer for as long as it's open.
+///
+/// It isn't stored in any settings file or in the [`SettingsStore`], and takes precedence
+/// over every other source, including the buffer's modeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndentationOverride {
+    pub tab_size: NonZeroU32,
+    /// How many columns a tab character occupies, when it's set apart from `tab_size`, which is
+    /// then the width of a level of indentation.
+    pub tab_width: Option<NonZeroU32>,
+    pub hard_tabs: bool,
+}
+
+impl IndentationOverride {
+    /// Applies the override to `settings`, such as those a host resolves for a buffer whose
+    /// override only exists on a remote replica.
+    pub fn apply_to(&self, settings: &mut LanguageSettings) {
+        settings.tab_size = self.tab_size;
+        settings.tab_width = self
+            .tab_width
+            .filter(|tab_width| *tab_width != self.tab_size);
+        settings.hard_tabs = self.hard_tabs;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use gpui::TestAppContext;
+    use gpui::{AppContext as _, TestAppContext};
     use util::rel_path::rel_path;
 
     #[gpui::test]
@@ -1092,4 +1272,168 @@ mod tests {
             ])
         );
     }
+
+    #[gpui::test]
+    fn test_resolved_whitespace_options(cx: &mut App) {
+        cx.set_global(SettingsStore::test(cx));
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |content| {
+                let defaults = &mut content.project.all_languages.defaults;
+                defaults.tab_size = NonZeroU32::new(3);
+                defaults.hard_tabs = Some(false);
+                defaults.remove_trailing_whitespace_on_save = Some(false);
+                defaults.ensure_final_newline_on_save = Some(true);
+            });
+        });
+
+        let options =
+            ResolvedWhitespaceOptions::from_settings(&LanguageSettings::resolve(None, None, cx));
+        let three = NonZeroU32::new(3).unwrap();
+        assert_eq!(
+            options,
+            ResolvedWhitespaceOptions {
+                indent_size: three,
+                tab_width: three,
+                hard_tabs: false,
+                trim_trailing_whitespace: false,
+                insert_final_newline: true,
+            }
+        );
+        assert_eq!(options.indent(), crate::IndentSize::spaces(3));
+
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |content| {
+                content.project.all_languages.defaults.hard_tabs = Some(true);
+            });
+        });
+        let options =
+            ResolvedWhitespaceOptions::from_settings(&LanguageSettings::resolve(None, None, cx));
+        assert!(options.hard_tabs);
+        assert_eq!(options.indent(), crate::IndentSize::tab());
+    }
+
+    #[test]
+    fn test_normalize_generated_indentation() {
+        let options = |indent_size: u32, hard_tabs: bool| ResolvedWhitespaceOptions {
+            indent_size: NonZeroU32::new(indent_size).unwrap(),
+            tab_width: NonZeroU32::new(indent_size).unwrap(),
+            hard_tabs,
+            trim_trailing_whitespace: true,
+            insert_final_newline: true,
+        };
+
+        assert_eq!(options(4, true).normalize_indent("      ", 4), "\t  ");
+        assert_eq!(options(2, false).normalize_indent("\t\t", 4), "    ");
+        assert_eq!(
+            options(4, false).normalize_indent("        ", 4),
+            "        "
+        );
+
+        let generated = "fn a() {\n    if b {\n        c();\n    }\n\n}";
+        assert_eq!(
+            options(4, true).normalize_indentation(generated),
+            "fn a() {\n\tif b {\n\t\tc();\n\t}\n\n}"
+        );
+        assert_eq!(
+            options(2, false).normalize_indentation(generated),
+            "fn a() {\n  if b {\n    c();\n  }\n\n}"
+        );
+        assert_eq!(
+            options(4, false).normalize_indentation("a:\n\tb: 1\n"),
+            "a:\n    b: 1\n"
+        );
+    }
+
+    #[gpui::test]
+    fn test_indentation_override(cx: &mut App) {
+        cx.set_global(SettingsStore::test(cx));
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |content| {
+                content.project.all_languages.defaults.tab_size = NonZeroU32::new(4);
+            });
+        });
+
+        let buffer = cx.new(|cx| Buffer::local("", cx));
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_modeline(Some(ModelineSettings {
+                tab_size: NonZeroU32::new(8),
+                ..Default::default()
+            }));
+            buffer.set_indentation_override(
+                Some(IndentationOverride {
+                    tab_size: NonZeroU32::new(2).unwrap(),
+                    tab_width: None,
+                    hard_tabs: true,
+                }),
+                cx,
+            );
+        });
+        let settings = LanguageSettings::for_buffer(buffer.read(cx), cx);
+        assert_eq!(settings.tab_size.get(), 2);
+        assert_eq!(settings.tab_width, None);
+        assert!(settings.hard_tabs);
+        let snapshot = buffer.read(cx).snapshot();
+        assert_eq!(
+            LanguageSettings::for_buffer_snapshot(&snapshot, None, cx)
+                .tab_size
+                .get(),
+            2
+        );
+
+        // Tabs are rendered apart from the indent size when the override says so.
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_indentation_override(
+                Some(IndentationOverride {
+                    tab_size: NonZeroU32::new(4).unwrap(),
+                    tab_width: NonZeroU32::new(8),
+                    hard_tabs: true,
+                }),
+                cx,
+            );
+        });
+        let settings = LanguageSettings::for_buffer(buffer.read(cx), cx);
+        assert_eq!(settings.tab_size.get(), 4);
+        assert_eq!(settings.tab_width, NonZeroU32::new(8));
+        let whitespace = ResolvedWhitespaceOptions::from_settings(&settings);
+        assert_eq!(whitespace.indent_size.get(), 4);
+        assert_eq!(whitespace.tab_width.get(), 8);
+
+        buffer.update(cx, |buffer, cx| buffer.set_indentation_override(None, cx));
+        let settings = LanguageSettings::for_buffer(buffer.read(cx), cx);
+        assert_eq!(settings.tab_size.get(), 8);
+        assert!(!settings.hard_tabs);
+    }
+
+    #[gpui::test]
+    fn test_modeline_indent_size(cx: &mut App) {
+        cx.set_global(SettingsStore::test(cx));
+        let buffer = cx.new(|cx| Buffer::local("", cx));
+        let settings_for = |modeline: &str, cx: &mut App| {
+            buffer.update(cx, |buffer, _| {
+                buffer.set_modeline(crate::modeline::parse_modeline(&[modeline], &[]))
+            });
+            let settings = LanguageSettings::for_buffer(buffer.read(cx), cx);
+            (
+                settings.tab_size.get(),
+                settings.tab_width.map(NonZeroU32::get),
+                settings.hard_tabs,
+            )
+        };
+
+        // The width of a level wins when indenting with spaces, with tabs keeping their width.
+        assert_eq!(settings_for("# vim: ts=8 sw=4 et", cx), (4, Some(8), false));
+        assert_eq!(
+            settings_for(
+                "/* -*- tab-width: 8; c-basic-offset: 2; indent-tabs-mode: nil -*- */",
+                cx
+            ),
+            (2, Some(8), false)
+        );
+        // The tab width wins when indenting with tabs.
+        assert_eq!(settings_for("# vim: ts=8 sw=4 noet", cx), (8, None, true));
+        assert_eq!(
+            settings_for("-*- tab-width: 8; indent-tabs-mode: t -*-", cx),
+            (8, None, true)
+        );
+    }
 }
@@ -162,7 +162,15 @@ fn parse_emacs_key_value(part: &str, settings: &mut ModelineSettings, bare: bool
             "mode" => {
                 settings.mode = Some(value.to_string());
             }
-            "c-basic-offset" | "python-indent-offset" => {
+            "c-basic-offset"
+            | "python-indent-offset"
+            | "js-indent-level"
+            | "typescript-indent-level"
+            | "css-indent-offset"
+            | "sh-basic-offset"
+            | "ruby-indent-level"
+            | "lua-indent-level"
+            | "rust-indent-offset" => {
                 if let Ok(size) = value.parse::<NonZeroU32>() {
                     settings.indent_size = Some(size);
                 }
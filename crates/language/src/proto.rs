@@ -1,13 +1,16 @@
 //! Handles conversions of `language` items to and from the [`rpc`] protocol.
 
-use crate::{CursorShape, Diagnostic, DiagnosticSourceKind, diagnostic_set::DiagnosticEntry};
+use crate::{
+    CursorShape, Diagnostic, DiagnosticSourceKind, diagnostic_set::DiagnosticEntry,
+    language_settings::IndentationOverride,
+};
 use anyhow::{Context as _, Result};
 use clock::ReplicaId;
 use gpui::SharedString;
 use lsp::{DiagnosticSeverity, LanguageServerId};
 use rpc::proto;
 use serde_json::Value;
-use std::{ops::Range, str::FromStr, sync::Arc};
+use std::{num::NonZeroU32, ops::Range, str::FromStr, sync::Arc};
 use text::*;
 
 pub use proto::{BufferState, File, Operation};
@@ -582,6 +585,28 @@ pub fn deserialize_timestamp(timestamp: proto::LamportTimestamp) -> clock::Lampo
     }
 }
 
+/// Serializes an [`IndentationOverride`] to be sent over RPC.
+pub fn serialize_indentation_override(
+    indentation_override: &IndentationOverride,
+) -> proto::IndentationOverride {
+    proto::IndentationOverride {
+        tab_size: indentation_override.tab_size.get(),
+        tab_width: indentation_override.tab_width.map(NonZeroU32::get),
+        hard_tabs: indentation_override.hard_tabs,
+    }
+}
+
+/// Deserializes an [`IndentationOverride`] from the RPC representation.
+pub fn deserialize_indentation_override(
+    indentation_override: proto::IndentationOverride,
+) -> Option<IndentationOverride> {
+    Some(IndentationOverride {
+        tab_size: NonZeroU32::new(indentation_override.tab_size)?,
+        tab_width: indentation_override.tab_width.and_then(NonZeroU32::new),
+        hard_tabs: indentation_override.hard_tabs,
+    })
+}
+
 /// Serializes a range of [`FullOffset`]s to be sent over RPC.
 pub fn serialize_range(range: &Range<FullOffset>) -> proto::Range {
     proto::Range {
@@ -0,0 +1,590 @@
+use collections::HashMap;
+use rope::Rope;
+
+/// Controls how [`detect_indentation`] inspects a text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DetectOptions {
+    /// The number of indented lines inspected before settling on an indentation.
+    pub max_scanned_lines: usize,
+}
+
+impl Default for DetectOptions {
+    fn default() -> Self {
+        Self {
+            max_scanned_lines: 1000,
+        }
+    }
+}
+
+/// A unit of indentation found in a text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DetectedIndent {
+    Tabs,
+    Spaces(u32),
+}
+
+/// A whitespace character other than a space or a tab. These look like indentation, but they
+/// silently break alignment and are rejected by many compilers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExoticWhitespace {
+    /// U+00A0 and U+202F, which often come from text pasted out of web pages.
+    NoBreakSpace,
+    /// U+3000, which CJK input methods insert.
+    IdeographicSpace,
+    /// U+000B.
+    VerticalTab,
+    /// The remaining fixed-width spaces, such as U+2003 (em space).
+    OtherSpace,
+}
+
+impl ExoticWhitespace {
+    pub fn classify(c: char) -> Option<Self> {
+        match c {
+            '\u{00A0}' | '\u{202F}' => Some(Self::NoBreakSpace),
+            '\u{3000}' => Some(Self::IdeographicSpace),
+            '\u{000B}' => Some(Self::VerticalTab),
+            '\u{1680}' | '\u{2000}'..='\u{200A}' | '\u{205F}' => Some(Self::OtherSpace),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::NoBreakSpace => "non-breaking space",
+            Self::IdeographicSpace => "ideographic space",
+            Self::VerticalTab => "vertical tab",
+            Self::OtherSpace => "unusual space",
+        }
+    }
+}
+
+/// Whether `c` indents a line, counting [`ExoticWhitespace`] as well as spaces and tabs.
+pub fn is_indent_char(c: char) -> bool {
+    c == ' ' || c == '\t' || ExoticWhitespace::classify(c).is_some()
+}
+
+/// What [`detect_indentation`] found in a text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Detection {
+    /// The indentation the text uses, or `None` when none of its inspected lines is indented.
+    pub indent: Option<DetectedIndent>,
+    /// The number of inspected lines indented with spaces.
+    pub space_indented_lines: usize,
+    /// The number of inspected lines indented with tabs.
+    pub tab_indented_lines: usize,
+    /// The number of inspected lines whose indentation contains [`ExoticWhitespace`].
+    pub exotic_whitespace_lines: usize,
+    /// The number of inspected lines that back `indent`: those indented with tabs, or those
+    /// whose indentation differs from the line before by the detected number of spaces.
+    pub supporting_lines: usize,
+    /// The number of inspected lines that weighed in on `indent`: those indented with tabs,
+    /// along with those whose indentation differs from the line before.
+    pub deciding_lines: usize,
+}
+
+/// How much to trust the indentation [`detect_indentation`] found in a text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DetectionConfidence {
+    /// Few lines weighed in, or most of them disagree.
+    Low,
+    Medium,
+    /// Many lines weighed in, and nearly all of them agree.
+    High,
+}
+
+impl DetectionConfidence {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+impl Detection {
+    /// How much to trust the detected indentation, or `None` when none was detected.
+    pub fn confidence(&self) -> Option<DetectionConfidence> {
+        self.indent?;
+        let (supporting, deciding) = (self.supporting_lines, self.deciding_lines);
+        Some(if deciding < 5 || supporting * 2 < deciding {
+            DetectionConfidence::Low
+        } else if deciding >= 20 && supporting * 10 >= deciding * 9 {
+            DetectionConfidence::High
+        } else {
+            DetectionConfidence::Medium
+        })
+    }
+}
+
+/// Infers the indentation of `text` from the most common change in indentation between
+/// consecutive non-blank lines, or tabs when more lines are indented with tabs than spaces.
+///
+/// Nested blocks opened several levels at once make changes of a multiple of the indentation
+/// nearly as common as the indentation itself, so the greatest common divisor of the changes
+/// that are is taken instead, when that's at least two spaces.
+pub fn detect_indentation(text: &Rope, options: &DetectOptions) -> Detection {
+    let mut detector = IndentationDetector::new(options);
+    let mut lines = text.chunks().lines();
+    while let Some(line) = lines.next() {
+        if detector.is_done() {
+            break;
+        }
+        detector.push_line(line);
+    }
+    detector.detection()
+}
+
+/// Infers the indentation of a text fed to it a line at a time, as [`detect_indentation`] does,
+/// so that a long text can be inspected a chunk at a time and left off as soon as the detection
+/// is confident enough.
+#[derive(Clone, Debug)]
+pub struct IndentationDetector {
+    max_scanned_lines: usize,
+    space_indented_lines: usize,
+    tab_indented_lines: usize,
+    exotic_whitespace_lines: usize,
+    space_deltas: HashMap<u32, usize>,
+    previous_spaces: u32,
+}
+
+impl IndentationDetector {
+    pub fn new(options: &DetectOptions) -> Self {
+        Self {
+            max_scanned_lines: options.max_scanned_lines,
+            space_indented_lines: 0,
+            tab_indented_lines: 0,
+            exotic_whitespace_lines: 0,
+            space_deltas: HashMap::default(),
+            previous_spaces: 0,
+        }
+    }
+
+    /// Whether as many indented lines as the options allow have been inspected, so that the
+    /// lines pushed from now on are ignored.
+    pub fn is_done(&self) -> bool {
+        self.space_indented_lines + self.tab_indented_lines >= self.max_scanned_lines
+    }
+
+    /// Inspects the next line of the text, without its newline.
+    pub fn push_line(&mut self, line: &str) {
+        if self.is_done() || line.trim().is_empty() {
+            return;
+        }
+        let (is_tab, len, has_exotic_whitespace) = leading_indent(line);
+        if has_exotic_whitespace {
+            self.exotic_whitespace_lines += 1;
+        }
+        if is_tab {
+            self.tab_indented_lines += 1;
+            return;
+        }
+        if len > 0 {
+            self.space_indented_lines += 1;
+        }
+        let delta = len.abs_diff(self.previous_spaces);
+        if delta > 0 {
+            *self.space_deltas.entry(delta).or_default() += 1;
+        }
+        self.previous_spaces = len;
+    }
+
+    /// What the lines inspected so far show.
+    pub fn detection(&self) -> Detection {
+        let mut detection = Detection {
+            space_indented_lines: self.space_indented_lines,
+            tab_indented_lines: self.tab_indented_lines,
+            exotic_whitespace_lines: self.exotic_whitespace_lines,
+            deciding_lines: self.tab_indented_lines + self.space_deltas.values().sum::<usize>(),
+            ..Detection::default()
+        };
+        let space_deltas = &self.space_deltas;
+        if detection.tab_indented_lines == 0 && detection.space_indented_lines == 0 {
+            detection.indent = None;
+        } else if detection.tab_indented_lines > detection.space_indented_lines {
+            detection.indent = Some(DetectedIndent::Tabs);
+            detection.supporting_lines = detection.tab_indented_lines;
+        } else if let Some((&delta, &count)) = space_deltas
+            .iter()
+            .max_by_key(|(delta, count)| (**count, std::cmp::Reverse(**delta)))
+        {
+            let width = space_deltas
+                .iter()
+                .filter(|(_, other_count)| **other_count * 2 >= count)
+                .fold(delta, |width, (other_delta, _)| gcd(width, *other_delta));
+            let width = if width >= 2 { width } else { delta };
+            detection.indent = Some(DetectedIndent::Spaces(width));
+            detection.supporting_lines = space_deltas
+                .iter()
+                .filter(|(delta, _)| *delta % width == 0)
+                .map(|(_, count)| count)
+                .sum();
+        }
+        detection
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// A way in which a text breaks the indentation it's expected to use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentViolation {
+    /// The text is indented with `detected` rather than `expected`.
+    Mismatch {
+        detected: DetectedIndent,
+        expected: DetectedIndent,
+    },
+    /// Some of the text's lines are indented with [`ExoticWhitespace`].
+    ExoticWhitespace { lines: usize },
+}
+
+impl std::fmt::Display for IndentViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let describe = |indent: &DetectedIndent| match indent {
+            DetectedIndent::Tabs => "tabs".to_string(),
+            DetectedIndent::Spaces(1) => "1 space".to_string(),
+            DetectedIndent::Spaces(len) => format!("{len} spaces"),
+        };
+        match self {
+            Self::Mismatch { detected, expected } => write!(
+                f,
+                "indented with {} instead of {}",
+                describe(detected),
+                describe(expected)
+            ),
+            Self::ExoticWhitespace { lines: 1 } => {
+                write!(f, "1 line is indented with unusual whitespace")
+            }
+            Self::ExoticWhitespace { lines } => {
+                write!(f, "{lines} lines are indented with unusual whitespace")
+            }
+        }
+    }
+}
+
+/// Checks what [`detect_indentation`] found in a text against the indentation it's `expected` to
+/// use, flagging [`ExoticWhitespace`] too when `flag_exotic_whitespace` is set. Texts without any
+/// indentation follow every expectation.
+pub fn indent_violations(
+    detection: &Detection,
+    expected: DetectedIndent,
+    flag_exotic_whitespace: bool,
+) -> Vec<IndentViolation> {
+    let mut violations = Vec::new();
+    if let Some(detected) = detection.indent
+        && detected != expected
+    {
+        violations.push(IndentViolation::Mismatch { detected, expected });
+    }
+    if flag_exotic_whitespace && detection.exotic_whitespace_lines > 0 {
+        violations.push(IndentViolation::ExoticWhitespace {
+            lines: detection.exotic_whitespace_lines,
+        });
+    }
+    violations
+}
+
+/// Returns whether `line` is indented with a tab, judging by its first character, the length
+/// of its leading whitespace, and whether that contains [`ExoticWhitespace`]. Exotic whitespace
+/// counts as one column, like a space.
+fn leading_indent(line: &str) -> (bool, u32, bool) {
+    let mut len = 0;
+    let mut has_exotic_whitespace = false;
+    for c in line.chars().take_while(|c| is_indent_char(*c)) {
+        len += 1;
+        has_exotic_whitespace |= ExoticWhitespace::classify(c).is_some();
+    }
+    (line.starts_with('\t'), len, has_exotic_whitespace)
+}
+
+/// A rule the indentation of each line of a text is checked against by [`lint_indentation`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IndentLint {
+    /// The line is indented with a number of spaces that isn't a multiple of the indent size.
+    NotMultipleOfIndentSize,
+    /// The line is indented with tabs as well as spaces.
+    MixedTabsAndSpaces,
+    /// The line's indentation has a space before a tab, so its depth depends on the tab width.
+    SpacesBeforeTabs,
+}
+
+impl IndentLint {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::NotMultipleOfIndentSize => "not indented by a multiple of the indent size",
+            Self::MixedTabsAndSpaces => "indented with tabs and spaces",
+            Self::SpacesBeforeTabs => "indented with spaces before tabs",
+        }
+    }
+
+    fn is_broken_by(&self, indent: &str, indent_size: u32) -> bool {
+        match self {
+            Self::NotMultipleOfIndentSize => {
+                indent_size > 0 && !indent.contains('\t') && indent.len() as u32 % indent_size != 0
+            }
+            Self::MixedTabsAndSpaces => indent.contains(' ') && indent.contains('\t'),
+            Self::SpacesBeforeTabs => indent
+                .find(' ')
+                .is_some_and(|space| indent[space..].contains('\t')),
+        }
+    }
+}
+
+/// The lines of a text that break an [`IndentLint`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndentLintViolation {
+    pub lint: IndentLint,
+    /// The rows of the lines breaking it, in order.
+    pub rows: Vec<u32>,
+}
+
+impl std::fmt::Display for IndentLintViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.rows.len() {
+            1 => write!(f, "1 line is {}", self.lint.label()),
+            lines => write!(f, "{lines} lines are {}", self.lint.label()),
+        }
+    }
+}
+
+/// Checks the indentation of each non-blank line of `text` against `lints`, taking a level of
+/// indentation to be `indent_size` spaces. Returns the lines breaking each of `lints`, in their
+/// order, leaving out the lints no line breaks.
+pub fn lint_indentation(
+    text: &Rope,
+    indent_size: u32,
+    lints: &[IndentLint],
+) -> Vec<IndentLintViolation> {
+    let mut violations = lints
+        .iter()
+        .map(|lint| IndentLintViolation {
+            lint: *lint,
+            rows: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+    if lints.is_empty() {
+        return violations;
+    }
+
+    let mut lines = text.chunks().lines();
+    let mut row = 0;
+    while let Some(line) = lines.next() {
+        if !line.trim().is_empty() {
+            let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+            let indent = &line[..indent_len];
+            for violation in &mut violations {
+                if !indent.is_empty() && violation.lint.is_broken_by(indent, indent_size) {
+                    violation.rows.push(row);
+                }
+            }
+        }
+        row += 1;
+    }
+    violations.retain(|violation| !violation.rows.is_empty());
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_exotic_whitespace() {
+        assert_eq!(ExoticWhitespace::classify(' '), None);
+        assert_eq!(ExoticWhitespace::classify('\t'), None);
+        assert_eq!(
+            ExoticWhitespace::classify('\u{00A0}'),
+            Some(ExoticWhitespace::NoBreakSpace)
+        );
+        assert_eq!(
+            ExoticWhitespace::classify('\u{3000}'),
+            Some(ExoticWhitespace::IdeographicSpace)
+        );
+        assert_eq!(
+            ExoticWhitespace::classify('\u{000B}'),
+            Some(ExoticWhitespace::VerticalTab)
+        );
+        assert_eq!(
+            ExoticWhitespace::classify('\u{2003}'),
+            Some(ExoticWhitespace::OtherSpace)
+        );
+    }
+
+    #[test]
+    fn test_indent_violations() {
+        let check = |text: &str, expected: DetectedIndent, flag_exotic_whitespace: bool| {
+            let detection = detect_indentation(&Rope::from(text), &DetectOptions::default());
+            indent_violations(&detection, expected, flag_exotic_whitespace)
+        };
+
+        assert_eq!(check("a\nb\n", DetectedIndent::Tabs, true), Vec::new());
+        assert_eq!(
+            check("a:\n  b:\n    c\n", DetectedIndent::Spaces(2), true),
+            Vec::new()
+        );
+        assert_eq!(
+            check("a:\n  b:\n    c\n", DetectedIndent::Tabs, true),
+            vec![IndentViolation::Mismatch {
+                detected: DetectedIndent::Spaces(2),
+                expected: DetectedIndent::Tabs,
+            }]
+        );
+        assert_eq!(
+            check("a:\n\u{00A0} b\n", DetectedIndent::Spaces(2), false),
+            Vec::new()
+        );
+        assert_eq!(
+            check("a:\n\u{00A0} b\n", DetectedIndent::Spaces(2), true),
+            vec![IndentViolation::ExoticWhitespace { lines: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_lint_indentation() {
+        let lint = |text: &str| {
+            lint_indentation(
+                &Rope::from(text),
+                4,
+                &[
+                    IndentLint::NotMultipleOfIndentSize,
+                    IndentLint::MixedTabsAndSpaces,
+                    IndentLint::SpacesBeforeTabs,
+                ],
+            )
+        };
+
+        assert_eq!(lint("fn a() {\n    b();\n\n\tc();\n}\n"), Vec::new());
+        assert_eq!(
+            lint("a\n   b\n\t  c\n  \td\n      \n"),
+            vec![
+                IndentLintViolation {
+                    lint: IndentLint::NotMultipleOfIndentSize,
+                    rows: vec![1],
+                },
+                IndentLintViolation {
+                    lint: IndentLint::MixedTabsAndSpaces,
+                    rows: vec![2, 3],
+                },
+                IndentLintViolation {
+                    lint: IndentLint::SpacesBeforeTabs,
+                    rows: vec![3],
+                },
+            ]
+        );
+        // Only the lints asked for are checked.
+        assert_eq!(
+            lint_indentation(&Rope::from("   a\n"), 4, &[IndentLint::SpacesBeforeTabs]),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn test_detect_indentation() {
+        let detect = |text: &str| detect_indentation(&Rope::from(text), &DetectOptions::default());
+
+        assert_eq!(detect("fn a() {}\n"), Detection::default());
+        assert_eq!(
+            detect("a:\n  b:\n\n    c: 1\n  d: 2\n"),
+            Detection {
+                indent: Some(DetectedIndent::Spaces(2)),
+                space_indented_lines: 3,
+                tab_indented_lines: 0,
+                exotic_whitespace_lines: 0,
+                supporting_lines: 3,
+                deciding_lines: 3,
+            }
+        );
+        assert_eq!(
+            detect("a:\n  b:\n \u{00A0}  c: 1\n\u{3000}\u{3000}d: 2\n"),
+            Detection {
+                indent: Some(DetectedIndent::Spaces(2)),
+                space_indented_lines: 3,
+                tab_indented_lines: 0,
+                exotic_whitespace_lines: 2,
+                supporting_lines: 3,
+                deciding_lines: 3,
+            }
+        );
+        assert_eq!(
+            detect("func a() {\n\tif b {\n\t\treturn\n\t}\n}\n").indent,
+            Some(DetectedIndent::Tabs)
+        );
+        assert_eq!(
+            detect_indentation(
+                &Rope::from("a:\n\tb\n\tc\n    d\n        e\n    f\n"),
+                &DetectOptions {
+                    max_scanned_lines: 2,
+                },
+            )
+            .indent,
+            Some(DetectedIndent::Tabs)
+        );
+    }
+
+    #[test]
+    fn test_detect_indentation_of_nested_blocks() {
+        let detect = |text: &str| detect_indentation(&Rope::from(text), &DetectOptions::default());
+
+        // Opening two blocks at once changes the indentation by 8 as often as by 4.
+        let detection = detect(&"a:\n    b:\n            c: 1\n    d: 2\n".repeat(3));
+        assert_eq!(detection.indent, Some(DetectedIndent::Spaces(4)));
+        assert_eq!(detection.supporting_lines, detection.deciding_lines);
+
+        // A width that's only occasionally a multiple of another is kept.
+        assert_eq!(
+            detect("a:\n    b:\n    c:\n        d: 1\n            e: 2\n  f: 3\n").indent,
+            Some(DetectedIndent::Spaces(4))
+        );
+        // Divisors below two spaces are ignored.
+        assert_eq!(
+            detect(&"a:\n  b:\n     c: 1\n".repeat(3)).indent,
+            Some(DetectedIndent::Spaces(2))
+        );
+    }
+
+    #[test]
+    fn test_detection_confidence() {
+        let confidence = |text: &str| {
+            detect_indentation(&Rope::from(text), &DetectOptions::default()).confidence()
+        };
+
+        assert_eq!(confidence("fn a() {}\n"), None);
+        assert_eq!(
+            confidence("a:\n  b:\n    c: 1\n"),
+            Some(DetectionConfidence::Low)
+        );
+        assert_eq!(
+            confidence(&"a:\n  b: 1\n".repeat(11)),
+            Some(DetectionConfidence::High)
+        );
+        assert_eq!(
+            confidence(&"a:\n  b: 1\n".repeat(3)),
+            Some(DetectionConfidence::Medium)
+        );
+        // Lines changing by a different width count against the detected one.
+        assert_eq!(
+            confidence(&"a:\n  b:\n     c: 1\n".repeat(3)),
+            Some(DetectionConfidence::Low)
+        );
+    }
+
+    #[test]
+    fn test_indentation_detector() {
+        let text = "a:\n  b:\n    c: 1\n\n\td\n".repeat(4);
+        let options = DetectOptions {
+            max_scanned_lines: 10,
+        };
+        let mut detector = IndentationDetector::new(&options);
+        for line in text.lines() {
+            detector.push_line(line);
+        }
+        assert!(detector.is_done());
+        assert_eq!(
+            detector.detection(),
+            detect_indentation(&Rope::from(text.as_str()), &options)
+        );
+        assert_eq!(detector.detection().space_indented_lines, 7);
+    }
+}
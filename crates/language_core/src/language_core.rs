@@ -4,6 +4,7 @@
 pub mod diagnostic;
 pub mod grammar;
 pub mod highlight_map;
+pub mod indent_detection;
 pub mod language_config;
 
 pub use diagnostic::{Diagnostic, DiagnosticSourceKind};
@@ -14,6 +15,11 @@ pub use grammar::{
     RunnableCapture, RunnableConfig, TextObject, TextObjectConfig,
 };
 pub use highlight_map::{HighlightId, HighlightMap};
+pub use indent_detection::{
+    DetectOptions, DetectedIndent, Detection, DetectionConfidence, ExoticWhitespace, IndentLint,
+    IndentLintViolation, IndentViolation, IndentationDetector, detect_indentation,
+    indent_violations, is_indent_char, lint_indentation,
+};
 pub use language_config::{
     BlockCommentConfig, BracketPair, BracketPairConfig, BracketPairContent, DecreaseIndentConfig,
     JsxTagAutoCloseConfig, LanguageConfig, LanguageConfigOverride, LanguageMatcher,
@@ -334,3 +334,9 @@ pub(crate) mod m_2026_04_10 {
 
     pub(crate) use settings::rename_web_search_to_search_web;
 }
+
+pub(crate) mod m_2026_10_14 {
+    mod keymap;
+
+    pub(crate) use keymap::KEYMAP_PATTERNS;
+}
@@ -147,6 +147,10 @@ pub fn migrate_keymap(text: &str) -> Result<Option<String>> {
             migrations::m_2026_03_23::KEYMAP_PATTERNS,
             &KEYMAP_QUERY_2026_03_23,
         ),
+        MigrationType::TreeSitter(
+            migrations::m_2026_10_14::KEYMAP_PATTERNS,
+            &KEYMAP_QUERY_2026_10_14,
+        ),
     ];
     run_migrations(text, migrations)
 }
@@ -392,6 +396,10 @@ define_query!(
     KEYMAP_QUERY_2026_03_23,
     migrations::m_2026_03_23::KEYMAP_PATTERNS
 );
+define_query!(
+    KEYMAP_QUERY_2026_10_14,
+    migrations::m_2026_10_14::KEYMAP_PATTERNS
+);
 
 // custom query
 static EDIT_PREDICTION_SETTINGS_MIGRATION_QUERY: LazyLock<Query> = LazyLock::new(|| {
@@ -4980,4 +4988,30 @@ mod tests {
             ),
         );
     }
+
+    #[test]
+    fn test_migrate_line_ending_selector_toggle() {
+        assert_migrate_keymap(
+            r#"
+            [
+                {
+                    "bindings": {
+                        "cmd-k l": "line_ending_selector::Toggle"
+                    }
+                }
+            ]
+            "#,
+            Some(
+                r#"
+            [
+                {
+                    "bindings": {
+                        "cmd-k l": "indent_size_selector::ToggleEndOfLine"
+                    }
+                }
+            ]
+            "#,
+            ),
+        );
+    }
 }
@@ -4143,6 +4143,38 @@ fn check_multibuffer_edits(
     pretty_assertions::assert_eq!(text, snapshot.text());
 }
 
+#[gpui::test]
+fn test_singleton_indentation_override(cx: &mut App) {
+    cx.set_global(SettingsStore::test(cx));
+    let buffer = cx.new(|cx| Buffer::local("a\n", cx));
+    let multibuffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
+    let tab_size = |cx: &App| {
+        multibuffer
+            .read(cx)
+            .snapshot(cx)
+            .language_settings_at(MultiBufferOffset::default(), cx)
+            .tab_size
+            .get()
+    };
+    assert_eq!(tab_size(cx), 4);
+
+    // Snapshots taken after the override pick it up without an edit.
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_indentation_override(
+            Some(language::language_settings::IndentationOverride {
+                tab_size: std::num::NonZeroU32::new(2).unwrap(),
+                tab_width: None,
+                hard_tabs: false,
+            }),
+            cx,
+        )
+    });
+    assert_eq!(tab_size(cx), 2);
+
+    buffer.update(cx, |buffer, cx| buffer.set_indentation_override(None, cx));
+    assert_eq!(tab_size(cx), 4);
+}
+
 #[gpui::test]
 fn test_history(cx: &mut App) {
     let test_settings = SettingsStore::test(cx);
@@ -18,11 +18,12 @@ use gpui::{App, AsyncApp, Entity, SharedString, Task, prelude::FluentBuilder};
 use language::{
     Anchor, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CharKind, CharScopeContext,
     OffsetRangeExt, PointUtf16, ToOffset, ToPointUtf16, Transaction, Unclipped,
-    language_settings::{InlayHintKind, LanguageSettings},
+    language_settings::{InlayHintKind, LanguageSettings, ResolvedWhitespaceOptions},
     point_from_lsp, point_to_lsp,
     proto::{
-        deserialize_anchor, deserialize_anchor_range, deserialize_version, serialize_anchor,
-        serialize_anchor_range, serialize_version,
+        deserialize_anchor, deserialize_anchor_range, deserialize_indentation_override,
+        deserialize_version, serialize_anchor, serialize_anchor_range,
+        serialize_indentation_override, serialize_version,
     },
     range_from_lsp, range_to_lsp,
 };
@@ -54,12 +55,13 @@ fn code_action_kind_matches(requested: &lsp::CodeActionKind, actual: &lsp::CodeA
 }
 
 pub fn lsp_formatting_options(settings: &LanguageSettings) -> lsp::FormattingOptions {
+    let whitespace = ResolvedWhitespaceOptions::from_settings(settings);
     lsp::FormattingOptions {
-        tab_size: settings.tab_size.into(),
-        insert_spaces: !settings.hard_tabs,
-        trim_trailing_whitespace: Some(settings.remove_trailing_whitespace_on_save),
-        trim_final_newlines: Some(settings.ensure_final_newline_on_save),
-        insert_final_newline: Some(settings.ensure_final_newline_on_save),
+        tab_size: whitespace.indent_size.into(),
+        insert_spaces: !whitespace.hard_tabs,
+        trim_trailing_whitespace: Some(whitespace.trim_trailing_whitespace),
+        trim_final_newlines: Some(whitespace.insert_final_newline),
+        insert_final_newline: Some(whitespace.insert_final_newline),
         ..lsp::FormattingOptions::default()
     }
 }
@@ -2916,6 +2918,9 @@ impl LspCommand for OnTypeFormatting {
             )),
             trigger: self.trigger.clone(),
             version: serialize_version(&buffer.version()),
+            indentation_override: buffer
+                .indentation_override()
+                .map(serialize_indentation_override),
         }
     }
 
@@ -2935,8 +2940,16 @@ impl LspCommand for OnTypeFormatting {
             })
             .await?;
 
+        // The requesting replica's override of the indentation, which this one doesn't have.
+        let indentation_override = message
+            .indentation_override
+            .and_then(deserialize_indentation_override);
         let options = buffer.update(&mut cx, |buffer, cx| {
-            lsp_formatting_options(LanguageSettings::for_buffer(buffer, cx).as_ref())
+            let mut settings = LanguageSettings::for_buffer(buffer, cx);
+            if let Some(indentation_override) = &indentation_override {
+                indentation_override.apply_to(settings.to_mut());
+            }
+            lsp_formatting_options(settings.as_ref())
         });
 
         Ok(Self {
@@ -5029,3 +5042,28 @@ fn process_full_diagnostics_report(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    fn test_lsp_formatting_options(cx: &mut TestAppContext) {
+        cx.update(|cx| cx.set_global(SettingsStore::test(cx)));
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local("", cx);
+            buffer.set_modeline(language::parse_modeline(&["# vim: ts=8 sw=2 et"], &[]));
+            buffer
+        });
+        let options = cx.update(|cx| {
+            let settings = LanguageSettings::for_buffer(buffer.read(cx), cx);
+            assert_eq!(settings.tab_width.map(|width| width.get()), Some(8));
+            lsp_formatting_options(&settings)
+        });
+        // Formatters indent by a level, whatever the width of a tab.
+        assert_eq!(options.tab_size, 2);
+        assert!(options.insert_spaces);
+    }
+}
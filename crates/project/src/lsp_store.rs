@@ -77,12 +77,14 @@ use language::{
     OffsetUtf16, Patch, PointUtf16, TextBufferSnapshot, ToOffset, ToOffsetUtf16, ToPointUtf16,
     Toolchain, Transaction, Unclipped,
     language_settings::{
-        AllLanguageSettings, FormatOnSave, Formatter, LanguageSettings, all_language_settings,
+        AllLanguageSettings, FormatOnSave, Formatter, IndentationOverride, LanguageSettings,
+        ResolvedWhitespaceOptions, all_language_settings,
     },
     modeline, point_to_lsp,
     proto::{
-        deserialize_anchor, deserialize_anchor_range, deserialize_version, serialize_anchor,
-        serialize_anchor_range, serialize_version,
+        deserialize_anchor, deserialize_anchor_range, deserialize_indentation_override,
+        deserialize_version, serialize_anchor, serialize_anchor_range,
+        serialize_indentation_override, serialize_version,
     },
     range_from_lsp, range_to_lsp,
     row_chunk::RowChunk,
@@ -1586,7 +1588,7 @@ impl LocalLspStore {
         logger: zlog::Logger,
         cx: &mut AsyncApp,
     ) -> Result<()> {
-        let (adapters_and_servers, settings, request_timeout) =
+        let (adapters_and_servers, mut settings, request_timeout) =
             lsp_store.update(cx, |lsp_store, cx| {
                 buffer.handle.update(cx, |buffer, cx| {
                     let adapters_and_servers = lsp_store
@@ -1602,9 +1604,13 @@ impl LocalLspStore {
                     (adapters_and_servers, settings, request_timeout)
                 })
             })?;
+        if let Some(indentation_override) = &buffer.indentation_override {
+            indentation_override.apply_to(&mut settings);
+        }
 
         // handle whitespace formatting
-        if settings.remove_trailing_whitespace_on_save {
+        let whitespace = ResolvedWhitespaceOptions::from_settings(&settings);
+        if whitespace.trim_trailing_whitespace {
             zlog::trace!(logger => "removing trailing whitespace");
             let diff = buffer
                 .handle
@@ -1615,7 +1621,7 @@ impl LocalLspStore {
             })?;
         }
 
-        if settings.ensure_final_newline_on_save {
+        if whitespace.insert_final_newline {
             zlog::trace!(logger => "ensuring final newline");
             extend_formatting_transaction(buffer, formatting_transaction_id, cx, |buffer, cx| {
                 buffer.ensure_final_newline(cx);
@@ -3930,6 +3936,9 @@ pub struct FormattableBuffer {
     abs_path: Option<PathBuf>,
     env: Option<HashMap<String, String>>,
     ranges: Option<Vec<Range<Anchor>>>,
+    /// The override of the buffer's indentation on the replica that asked for the formatting,
+    /// when it's another one.
+    indentation_override: Option<IndentationOverride>,
 }
 
 pub struct RemoteLspStore {
@@ -10689,6 +10698,27 @@ impl LspStore {
         push_to_history: bool,
         trigger: FormatTrigger,
         cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<ProjectTransaction>> {
+        self.format_with_indentation_overrides(
+            buffers,
+            target,
+            push_to_history,
+            trigger,
+            HashMap::default(),
+            cx,
+        )
+    }
+
+    /// Formats `buffers` as [`Self::format`] does, indenting those in `indentation_overrides`
+    /// as the replica that asked for the formatting overrides them to.
+    fn format_with_indentation_overrides(
+        &mut self,
+        buffers: HashSet<Entity<Buffer>>,
+        target: LspFormatTarget,
+        push_to_history: bool,
+        trigger: FormatTrigger,
+        indentation_overrides: HashMap<BufferId, IndentationOverride>,
+        cx: &mut Context<Self>,
     ) -> Task<anyhow::Result<ProjectTransaction>> {
         let logger = zlog::scoped!("format");
         if self.as_local().is_some() {
@@ -10727,6 +10757,7 @@ impl LspStore {
                         abs_path,
                         env,
                         ranges,
+                        indentation_override: indentation_overrides.get(&id).copied(),
                     });
                 }
                 zlog::trace!(logger => "Formatting {:?} buffers", formattable_buffers.len());
@@ -10766,6 +10797,19 @@ impl LspStore {
                     .collect(),
             };
 
+            // Overrides only exist on the replica they were set on, so the host learns them here.
+            let indentation_overrides = buffers
+                .iter()
+                .filter_map(|buffer| {
+                    let buffer = buffer.read(cx);
+                    Some(proto::BufferIndentationOverride {
+                        buffer_id: buffer.remote_id().to_proto(),
+                        indentation_override: Some(serialize_indentation_override(
+                            buffer.indentation_override()?,
+                        )),
+                    })
+                })
+                .collect();
             let buffer_store = self.buffer_store();
             cx.spawn(async move |lsp_store, cx| {
                 zlog::trace!(logger => "Sending remote format request");
@@ -10779,6 +10823,7 @@ impl LspStore {
                             .map(|buffer| buffer.read_with(cx, |buffer, _| buffer.remote_id().to_proto()))
                             .collect(),
                         buffer_ranges,
+                        indentation_overrides,
                     })
                     .await
                     .and_then(|result| result.transaction.context("missing transaction"));
@@ -10839,8 +10884,29 @@ impl LspStore {
                 LspFormatTarget::Ranges(ranges_map)
             };
 
+            let mut indentation_overrides = HashMap::default();
+            for buffer_override in &envelope.payload.indentation_overrides {
+                if let Some(indentation_override) = buffer_override
+                    .indentation_override
+                    .clone()
+                    .and_then(deserialize_indentation_override)
+                {
+                    indentation_overrides.insert(
+                        BufferId::new(buffer_override.buffer_id)?,
+                        indentation_override,
+                    );
+                }
+            }
+
             let trigger = FormatTrigger::from_proto(envelope.payload.trigger);
-            anyhow::Ok(this.format(buffers, target, false, trigger, cx))
+            anyhow::Ok(this.format_with_indentation_overrides(
+                buffers,
+                target,
+                false,
+                trigger,
+                indentation_overrides,
+                cx,
+            ))
         })?;
 
         let project_transaction = format.await?;
@@ -5,7 +5,7 @@ use dap::adapters::DebugAdapterName;
 use fs::Fs;
 use futures::StreamExt as _;
 use git::repository::DEFAULT_WORKTREE_DIRECTORY;
-use gpui::{AsyncApp, BorrowAppContext, Context, Entity, EventEmitter, Subscription, Task};
+use gpui::{App, AsyncApp, BorrowAppContext, Context, Entity, EventEmitter, Subscription, Task};
 use lsp::{DEFAULT_LSP_REQUEST_TIMEOUT_SECS, LanguageServerName};
 use paths::{
     EDITORCONFIG_NAME, local_debug_file_relative_path, local_settings_file_relative_path,
@@ -759,6 +759,15 @@ pub struct SettingsObserver {
     _trusted_worktrees_watcher: Option<Subscription>,
     _user_settings_watcher: Option<Subscription>,
     _editorconfig_watcher: Option<Subscription>,
+    /// The server of a project opened over SSH, to which the `.editorconfig` contents set on
+    /// this side are sent.
+    upstream_client: Option<AnyProtoClient>,
+    /// The `.editorconfig` contents that this side and upstream agree on, so that only the ones
+    /// changed on this side are sent there.
+    upstream_editorconfigs: HashMap<(WorktreeId, LocalSettingsPath), String>,
+    /// Like `upstream_editorconfigs`, for the guests of a shared project.
+    downstream_editorconfigs: HashMap<(WorktreeId, LocalSettingsPath), String>,
+    _editorconfig_sync_watcher: Option<Subscription>,
     _global_task_config_watcher: Task<()>,
     _global_debug_config_watcher: Task<()>,
 }
@@ -843,7 +852,10 @@ impl SettingsObserver {
                     path,
                     content,
                     affected_worktree_ids,
-                } = event;
+                } = event
+                else {
+                    return;
+                };
                 for worktree_id in affected_worktree_ids {
                     if let Some(worktree) = this
                         .worktree_store
@@ -874,6 +886,10 @@ impl SettingsObserver {
             pending_local_settings: HashMap::default(),
             _user_settings_watcher: None,
             _editorconfig_watcher: Some(_editorconfig_watcher),
+            upstream_client: None,
+            upstream_editorconfigs: HashMap::default(),
+            downstream_editorconfigs: HashMap::default(),
+            _editorconfig_sync_watcher: Self::watch_local_editorconfigs(cx),
             project_id: REMOTE_SERVER_PROJECT_ID,
             _global_task_config_watcher: if watch_global_configs {
                 Self::subscribe_to_global_task_file_changes(
@@ -906,7 +922,7 @@ impl SettingsObserver {
     ) -> Self {
         let mut user_settings_watcher = None;
         if cx.try_global::<SettingsStore>().is_some() {
-            if let Some(upstream_client) = upstream_client {
+            if let Some(upstream_client) = upstream_client.clone() {
                 let mut user_settings = None;
                 user_settings_watcher = Some(cx.observe_global::<SettingsStore>(move |_, cx| {
                     if let Some(new_settings) = cx.global::<SettingsStore>().raw_user_settings() {
@@ -938,6 +954,10 @@ impl SettingsObserver {
             pending_local_settings: HashMap::default(),
             _user_settings_watcher: user_settings_watcher,
             _editorconfig_watcher: None,
+            upstream_client: upstream_client.filter(|_| !via_collab),
+            upstream_editorconfigs: HashMap::default(),
+            downstream_editorconfigs: HashMap::default(),
+            _editorconfig_sync_watcher: Self::watch_local_editorconfigs(cx),
             _global_task_config_watcher: Self::subscribe_to_global_task_file_changes(
                 fs.clone(),
                 paths::tasks_file().clone(),
@@ -997,10 +1017,91 @@ impl SettingsObserver {
                     .log_err();
             }
         }
+        self.downstream_editorconfigs = self.worktree_editorconfigs(cx);
     }
 
     pub fn unshared(&mut self, _: &mut Context<Self>) {
         self.downstream_client = None;
+        self.downstream_editorconfigs.clear();
+    }
+
+    /// Syncs the `.editorconfig` contents of a worktree whenever they change.
+    fn watch_local_editorconfigs(cx: &mut Context<Self>) -> Option<Subscription> {
+        let editorconfig_store = cx.try_global::<SettingsStore>()?.editorconfig_store.clone();
+        Some(cx.subscribe(
+            &editorconfig_store,
+            |this, _, event: &EditorconfigEvent, cx| {
+                if let EditorconfigEvent::LocalConfigsChanged { worktree_id } = event {
+                    this.sync_editorconfigs(*worktree_id, cx);
+                }
+            },
+        ))
+    }
+
+    /// Sends the `.editorconfig` contents set on this side within the worktree, such as the
+    /// overrides written by the indent size selector, to the SSH server and to the guests, so
+    /// that formatting and auto-indent there use them too.
+    fn sync_editorconfigs(&mut self, worktree_id: WorktreeId, cx: &mut Context<Self>) {
+        if self.upstream_client.is_none() && self.downstream_client.is_none() {
+            return;
+        }
+        // A removed worktree is gone from the other side too.
+        if self
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(worktree_id, cx)
+            .is_none()
+        {
+            self.upstream_editorconfigs
+                .retain(|key, _| key.0 != worktree_id);
+            self.downstream_editorconfigs
+                .retain(|key, _| key.0 != worktree_id);
+            return;
+        }
+        let editorconfigs = self.editorconfigs_of_worktree(worktree_id, cx);
+        if let Some(upstream_client) = &self.upstream_client {
+            send_editorconfig_changes(
+                upstream_client,
+                REMOTE_SERVER_PROJECT_ID,
+                worktree_id,
+                &mut self.upstream_editorconfigs,
+                &editorconfigs,
+            );
+        }
+        if let Some(downstream_client) = &self.downstream_client {
+            send_editorconfig_changes(
+                downstream_client,
+                self.project_id,
+                worktree_id,
+                &mut self.downstream_editorconfigs,
+                &editorconfigs,
+            );
+        }
+    }
+
+    /// The `.editorconfig` contents of the worktrees, other than the ones outside of them,
+    /// which each side discovers by itself.
+    fn worktree_editorconfigs(&self, cx: &App) -> HashMap<(WorktreeId, LocalSettingsPath), String> {
+        self.worktree_store
+            .read(cx)
+            .worktrees()
+            .flat_map(|worktree| self.editorconfigs_of_worktree(worktree.read(cx).id(), cx))
+            .collect()
+    }
+
+    /// Like [`Self::worktree_editorconfigs`], for the worktree with `worktree_id` alone.
+    fn editorconfigs_of_worktree(
+        &self,
+        worktree_id: WorktreeId,
+        cx: &App,
+    ) -> HashMap<(WorktreeId, LocalSettingsPath), String> {
+        cx.global::<SettingsStore>()
+            .editorconfig_store
+            .read(cx)
+            .local_editorconfig_settings(worktree_id)
+            .filter(|(path, _, _)| !path.is_outside_worktree())
+            .map(|(path, content, _)| ((worktree_id, path), content.to_string()))
+            .collect()
     }
 
     async fn handle_update_worktree_settings(
@@ -1033,13 +1134,22 @@ impl SettingsObserver {
                 return;
             };
 
+            let kind = local_settings_kind_from_proto(kind);
+            if matches!(this.mode, SettingsObserverMode::Remote { .. })
+                && kind == LocalSettingsKind::Editorconfig
+            {
+                // What upstream sends is already in sync, and mustn't be sent back.
+                record_editorconfig(
+                    &mut this.upstream_editorconfigs,
+                    worktree_id,
+                    &path,
+                    envelope.payload.content.as_deref(),
+                );
+            }
+
             this.update_settings(
                 worktree,
-                [(
-                    path,
-                    local_settings_kind_from_proto(kind),
-                    envelope.payload.content,
-                )],
+                [(path, kind, envelope.payload.content)],
                 is_via_collab,
                 cx,
             );
@@ -1384,6 +1494,14 @@ impl SettingsObserver {
 
             if applied {
                 if let Some(downstream_client) = &self.downstream_client {
+                    if kind == LocalSettingsKind::Editorconfig {
+                        record_editorconfig(
+                            &mut self.downstream_editorconfigs,
+                            worktree_id,
+                            &directory_path,
+                            file_content.as_deref(),
+                        );
+                    }
                     downstream_client
                         .send(proto::UpdateWorktreeSettings {
                             project_id: self.project_id,
@@ -1545,6 +1663,64 @@ fn apply_local_settings(
     })
 }
 
+/// Sends the `.editorconfig` contents within the worktree that changed since they were last
+/// `synced` with the other side of `client`, then records them as synced.
+fn send_editorconfig_changes(
+    client: &AnyProtoClient,
+    project_id: u64,
+    worktree_id: WorktreeId,
+    synced: &mut HashMap<(WorktreeId, LocalSettingsPath), String>,
+    editorconfigs: &HashMap<(WorktreeId, LocalSettingsPath), String>,
+) {
+    let changed = editorconfigs
+        .iter()
+        .filter(|(key, content)| synced.get(key) != Some(content))
+        .map(|(key, content)| (key, Some(content.clone())));
+    let removed = synced
+        .keys()
+        .filter(|key| key.0 == worktree_id && !editorconfigs.contains_key(key))
+        .map(|key| (key, None));
+    for ((worktree_id, path), content) in changed.chain(removed) {
+        client
+            .send(proto::UpdateWorktreeSettings {
+                project_id,
+                worktree_id: worktree_id.to_proto(),
+                path: path.to_proto(),
+                content,
+                kind: Some(local_settings_kind_to_proto(LocalSettingsKind::Editorconfig).into()),
+                outside_worktree: Some(false),
+            })
+            .log_err();
+    }
+    synced.retain(|key, _| key.0 != worktree_id);
+    synced.extend(
+        editorconfigs
+            .iter()
+            .map(|(key, content)| (key.clone(), content.clone())),
+    );
+}
+
+/// Records that the other side has `content` as the `.editorconfig` at `path`.
+fn record_editorconfig(
+    synced: &mut HashMap<(WorktreeId, LocalSettingsPath), String>,
+    worktree_id: WorktreeId,
+    path: &LocalSettingsPath,
+    content: Option<&str>,
+) {
+    if path.is_outside_worktree() {
+        return;
+    }
+    let key = (worktree_id, path.clone());
+    match content {
+        Some(content) => {
+            synced.insert(key, content.to_string());
+        }
+        None => {
+            synced.remove(&key);
+        }
+    }
+}
+
 pub fn local_settings_kind_from_proto(kind: proto::LocalSettingsKind) -> LocalSettingsKind {
     match kind {
         proto::LocalSettingsKind::Settings => LocalSettingsKind::Settings,
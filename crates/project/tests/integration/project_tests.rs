@@ -336,6 +336,52 @@ async fn test_editorconfig_support(cx: &mut gpui::TestAppContext) {
     assert_eq!(Some(settings_readme.tab_size), NonZeroU32::new(8));
 }
 
+#[gpui::test]
+async fn test_editorconfig_wrap_guide(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let dir = TempTree::new(json!({
+        ".editorconfig": r#"
+        root = true
+        [*.rs]
+            max_line_length = 120
+        "#,
+        "a.rs": "fn a() {}",
+        "b": {
+            ".zed": {
+                "settings.json": r#"{ "editorconfig_wrap_guide": true, "wrap_guides": [80] }"#,
+            },
+            "b.rs": "fn b() {}",
+        },
+    }));
+
+    let path = dir.path();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree_from_real_fs(path, path).await;
+    let project = Project::test(fs, [path], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+    let worktree = project.update(cx, |project, cx| project.worktrees(cx).next().unwrap());
+    cx.executor().run_until_parked();
+
+    let settings_for = async |path: &str, cx: &mut TestAppContext| -> LanguageSettings {
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_buffer((worktree.read(cx).id(), rel_path(path)), cx)
+            })
+            .await
+            .unwrap();
+        cx.update(|cx| LanguageSettings::for_buffer(&buffer.read(cx), cx).into_owned())
+    };
+
+    // The wrap guides are left as they are unless asked to show `max_line_length`.
+    let settings_a = settings_for("a.rs", cx).await;
+    assert_eq!(settings_a.preferred_line_length, 120);
+    assert!(settings_a.wrap_guides.is_empty());
+
+    let settings_b = settings_for("b/b.rs", cx).await;
+    assert_eq!(settings_b.wrap_guides, vec![80, 120]);
+}
+
 #[gpui::test]
 async fn test_external_editorconfig_support(cx: &mut gpui::TestAppContext) {
     init_test(cx);
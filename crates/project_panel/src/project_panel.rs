@@ -373,6 +373,8 @@ actions!(
         ToggleHideHidden,
         /// Starts a new search in the selected directory.
         NewSearchInDirectory,
+        /// Sets the indentation of the selected or marked files and directories.
+        SetIndentation,
         /// Unfolds the selected directory.
         UnfoldDirectory,
         /// Folds the selected directory.
@@ -1154,6 +1156,9 @@ impl ProjectPanel {
                                 menu.separator()
                                     .action("Find in Folder…", Box::new(NewSearchInDirectory))
                             })
+                            .when(!is_collab, |menu| {
+                                menu.action("Set Indentation…", Box::new(SetIndentation))
+                            })
                             .when(is_unfoldable, |menu| {
                                 menu.action("Unfold Directory", Box::new(UnfoldDirectory))
                             })
@@ -3577,6 +3582,28 @@ impl ProjectPanel {
         }
     }
 
+    fn set_indentation(&mut self, _: &SetIndentation, window: &mut Window, cx: &mut Context<Self>) {
+        let project = self.project.read(cx);
+        let targets = self
+            .effective_entries()
+            .into_iter()
+            .filter_map(|selection| {
+                let worktree = project.worktree_for_id(selection.worktree_id, cx)?;
+                let entry = worktree.read(cx).entry_for_id(selection.entry_id)?;
+                Some(indent_size_selector::IndentationTarget {
+                    worktree_id: selection.worktree_id,
+                    path: entry.path.clone(),
+                    is_dir: entry.is_dir(),
+                })
+            })
+            .collect::<Vec<_>>();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                indent_size_selector::toggle_for_entries(workspace, targets, window, cx);
+            })
+            .ok();
+    }
+
     fn move_entry(
         &mut self,
         entry_to_move: ProjectEntryId,
@@ -6634,6 +6661,7 @@ impl Render for ProjectPanel {
                 .on_action(cx.listener(Self::copy_path))
                 .on_action(cx.listener(Self::copy_relative_path))
                 .on_action(cx.listener(Self::new_search_in_directory))
+                .on_action(cx.listener(Self::set_indentation))
                 .on_action(cx.listener(Self::unfold_directory))
                 .on_action(cx.listener(Self::fold_directory))
                 .on_action(cx.listener(Self::remove_from_project))
@@ -15,7 +15,8 @@ use git::repository::Worktree as GitWorktree;
 use gpui::{AppContext as _, Entity, SharedString, TestAppContext};
 use http_client::{BlockedHttpClient, FakeHttpClient};
 use language::{
-    Buffer, FakeLspAdapter, LanguageConfig, LanguageMatcher, LanguageRegistry, LineEnding,
+    AutoindentMode, Buffer, FakeLspAdapter, LanguageConfig, LanguageMatcher, LanguageRegistry,
+    LineEnding, Point,
     language_settings::{AllLanguageSettings, LanguageSettings},
 };
 use lsp::{
@@ -30,7 +31,10 @@ use project::{
 };
 use remote::RemoteClient;
 use serde_json::json;
-use settings::{Settings, SettingsLocation, SettingsStore, initial_server_settings_content};
+use settings::{
+    LocalSettingsKind, LocalSettingsPath, Settings, SettingsLocation, SettingsStore,
+    initial_server_settings_content,
+};
 use smol::stream::StreamExt;
 use std::{
     path::{Path, PathBuf},
@@ -531,6 +535,171 @@ async fn test_remote_settings(cx: &mut TestAppContext, server_cx: &mut TestAppCo
     });
 }
 
+#[gpui::test]
+async fn test_remote_editorconfig_overrides(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, headless) = init_test(&fs, cx, server_cx).await;
+    let worktree_id = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap()
+        .0
+        .read_with(cx, |worktree, _| worktree.id());
+    cx.run_until_parked();
+
+    let server_tab_size = |server_cx: &mut TestAppContext| {
+        server_cx.read(|cx| {
+            let worktree_id = headless
+                .read(cx)
+                .worktree_store
+                .read(cx)
+                .worktrees()
+                .next()
+                .unwrap()
+                .read(cx)
+                .id();
+            let location = SettingsLocation {
+                worktree_id,
+                path: rel_path("src/lib.rs"),
+            };
+            AllLanguageSettings::get_global(cx)
+                .language(Some(location), None, cx)
+                .tab_size
+                .get()
+        })
+    };
+    let default_tab_size = server_tab_size(server_cx);
+
+    cx.update_global(|settings_store: &mut SettingsStore, cx| {
+        settings_store.set_local_settings(
+            worktree_id,
+            LocalSettingsPath::InWorktree(rel_path("src").into()),
+            LocalSettingsKind::Editorconfig,
+            Some("[*.rs]\nindent_size = 7\n"),
+            cx,
+        )
+    })
+    .unwrap();
+    cx.run_until_parked();
+    assert_eq!(
+        server_tab_size(server_cx),
+        7,
+        "Editorconfig overrides set on the client should apply on the server"
+    );
+
+    cx.update_global(|settings_store: &mut SettingsStore, cx| {
+        settings_store.set_local_settings(
+            worktree_id,
+            LocalSettingsPath::InWorktree(rel_path("src").into()),
+            LocalSettingsKind::Editorconfig,
+            None,
+            cx,
+        )
+    })
+    .unwrap();
+    cx.run_until_parked();
+    assert_eq!(
+        server_tab_size(server_cx),
+        default_tab_size,
+        "Removing an editorconfig override on the client should remove it on the server"
+    );
+}
+
+#[gpui::test]
+async fn test_remote_editorconfig_override_autoindents_on_server(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                "src": {
+                    "lib.rs": "fn one() {\n}\n"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, headless) = init_test(&fs, cx, server_cx).await;
+    server_cx.update(|cx| headless.read(cx).languages.add(rust_lang()));
+    let worktree_id = project
+        .update(cx, |project, cx| {
+            project.languages().add(rust_lang());
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap()
+        .0
+        .read_with(cx, |worktree, _| worktree.id());
+    cx.run_until_parked();
+
+    // What the indent size selector writes when applying 2 spaces to the directory.
+    cx.update_global(|settings_store: &mut SettingsStore, cx| {
+        settings_store.set_local_settings(
+            worktree_id,
+            LocalSettingsPath::InWorktree(rel_path("src").into()),
+            LocalSettingsKind::Editorconfig,
+            Some("[*.rs]\nindent_style = space\nindent_size = 2\n"),
+            cx,
+        )
+    })
+    .unwrap();
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    server_cx.run_until_parked();
+
+    let buffer_id = cx.read(|cx| buffer.read(cx).remote_id());
+    let server_buffer = server_cx.read(|cx| {
+        headless
+            .read(cx)
+            .buffer_store
+            .read(cx)
+            .get(buffer_id)
+            .unwrap()
+    });
+    server_cx.update(|cx| {
+        server_buffer.update(cx, |buffer, cx| {
+            let end_of_first_line = Point::new(0, buffer.line_len(0));
+            buffer.edit(
+                [(end_of_first_line..end_of_first_line, "\ntwo();")],
+                Some(AutoindentMode::EachLine),
+                cx,
+            );
+        })
+    });
+    server_cx.run_until_parked();
+    assert_eq!(
+        server_cx.read(|cx| server_buffer.read(cx).text()),
+        "fn one() {\n  two();\n}\n",
+        "Server-side auto-indent should follow editorconfig overrides set on the client"
+    );
+}
+
 #[gpui::test]
 async fn test_remote_lsp(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
@@ -40,6 +40,8 @@ pub enum EditorconfigEvent {
         content: Option<String>,
         affected_worktree_ids: Vec<WorktreeId>,
     },
+    /// The contents of the `.editorconfig` files within a worktree changed.
+    LocalConfigsChanged { worktree_id: WorktreeId },
 }
 
 impl EventEmitter<EditorconfigEvent> for EditorconfigStore {}
@@ -64,11 +66,14 @@ impl EditorconfigStore {
         worktree_id: WorktreeId,
         path: LocalSettingsPath,
         content: Option<&str>,
+        cx: &mut Context<Self>,
     ) -> std::result::Result<(), InvalidSettingsError> {
         match (&path, content) {
             (LocalSettingsPath::InWorktree(rel_path), None) => {
-                if let Some(state) = self.worktree_state.get_mut(&worktree_id) {
-                    state.internal_configs.remove(rel_path);
+                if let Some(state) = self.worktree_state.get_mut(&worktree_id)
+                    && state.internal_configs.remove(rel_path).is_some()
+                {
+                    cx.emit(EditorconfigEvent::LocalConfigsChanged { worktree_id });
                 }
             }
             (LocalSettingsPath::OutsideWorktree(abs_path), None) => {
@@ -91,6 +96,7 @@ impl EditorconfigStore {
                     .get(rel_path)
                     .map_or(true, |entry| entry.0 != content);
                 if should_update {
+                    cx.emit(EditorconfigEvent::LocalConfigsChanged { worktree_id });
                     let parsed = match content.parse::<Editorconfig>() {
                         Ok(parsed) => Some(parsed),
                         Err(e) => {
@@ -139,11 +145,16 @@ impl EditorconfigStore {
         Ok(())
     }
 
-    pub(crate) fn remove_for_worktree(&mut self, root_id: WorktreeId) {
+    pub(crate) fn remove_for_worktree(&mut self, root_id: WorktreeId, cx: &mut Context<Self>) {
         self.local_external_config_discovery_tasks.remove(&root_id);
         let Some(removed) = self.worktree_state.remove(&root_id) else {
             return;
         };
+        if !removed.internal_configs.is_empty() {
+            cx.emit(EditorconfigEvent::LocalConfigsChanged {
+                worktree_id: root_id,
+            });
+        }
         let paths_in_use: HashSet<_> = self
             .worktree_state
             .values()
@@ -1088,8 +1088,8 @@ impl SettingsStore {
                 }
             }
             (directory_path, LocalSettingsKind::Editorconfig, editorconfig_contents) => {
-                self.editorconfig_store.update(cx, |store, _| {
-                    store.set_configs(root_id, directory_path, editorconfig_contents)
+                self.editorconfig_store.update(cx, |store, cx| {
+                    store.set_configs(root_id, directory_path, editorconfig_contents, cx)
                 })?;
             }
             (LocalSettingsPath::OutsideWorktree(path), kind, _) => {
@@ -1131,7 +1131,7 @@ impl SettingsStore {
             .retain(|(worktree_id, _), _| worktree_id != &root_id);
 
         self.editorconfig_store
-            .update(cx, |store, _cx| store.remove_for_worktree(root_id));
+            .update(cx, |store, cx| store.remove_for_worktree(root_id, cx));
 
         for setting_value in self.setting_values.values_mut() {
             setting_value.clear_local_values(root_id);
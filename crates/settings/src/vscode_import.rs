@@ -192,6 +192,7 @@ impl VsCodeSettings {
             }),
             helix_mode: None,
             image_viewer: None,
+            indent_size_selector: None,
             journal: None,
             language_models: None,
             line_indicator_format: None,
@@ -531,6 +532,8 @@ impl VsCodeSettings {
                 }),
                 ..Default::default()
             }),
+            convert_indentation_on_save: None,
+            indentation_lints: None,
             debuggers: None,
             edit_predictions_disabled_in: None,
             enable_language_server: None,
@@ -583,6 +586,7 @@ impl VsCodeSettings {
                 })
             }),
             show_wrap_guides: None,
+            editorconfig_wrap_guide: None,
             soft_wrap: self.read_enum("editor.wordWrap", |s| match s {
                 "on" => Some(SoftWrap::EditorWidth),
                 "wordWrapColumn" => Some(SoftWrap::PreferLine),
@@ -773,6 +777,7 @@ impl VsCodeSettings {
             active_language_button: None,
             cursor_position_button: None,
             line_endings_button: None,
+            indentation_button: None,
             active_encoding_button: None,
         })
     }
@@ -434,6 +434,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: []
     pub wrap_guides: Option<Vec<usize>>,
+    /// Whether to show a wrap guide at the `max_line_length` that an `.editorconfig`
+    /// sets, along with the 'wrap_guides'.
+    ///
+    /// Default: false
+    pub editorconfig_wrap_guide: Option<bool>,
     /// Indent guide related settings.
     pub indent_guides: Option<IndentGuideSettingsContent>,
     /// Whether or not to perform a buffer format before saving.
@@ -450,6 +455,13 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub ensure_final_newline_on_save: Option<bool>,
+    /// Whether or not to re-indent a buffer to its configured indentation when
+    /// saving it, if it's indented differently.
+    ///
+    /// Default: false
+    pub convert_indentation_on_save: Option<bool>,
+    /// How each indentation lint is reported in the status bar's indentation item.
+    pub indentation_lints: Option<IndentationLintsContent>,
     /// How to perform a buffer format.
     ///
     /// Default: auto
@@ -829,6 +841,48 @@ pub enum LspInsertMode {
     ReplaceSuffix,
 }
 
+/// How each indentation lint is reported for a language.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+pub struct IndentationLintsContent {
+    /// Lines indented with a number of spaces that isn't a multiple of the indent size.
+    ///
+    /// Default: off
+    pub not_multiple_of_indent_size: Option<IndentationLintSeverity>,
+    /// Lines indented with tabs as well as spaces.
+    ///
+    /// Default: off
+    pub mixed_tabs_and_spaces: Option<IndentationLintSeverity>,
+    /// Lines whose indentation has a space before a tab.
+    ///
+    /// Default: off
+    pub spaces_before_tabs: Option<IndentationLintSeverity>,
+}
+
+/// How an indentation lint is reported.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IndentationLintSeverity {
+    /// The lint isn't checked.
+    Off,
+    Warning,
+    Error,
+}
+
 /// Controls how document's words are completed.
 #[derive(
     Copy,
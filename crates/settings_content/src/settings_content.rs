@@ -67,6 +67,7 @@ macro_rules! settings_overrides {
 }
 use std::collections::{BTreeMap, BTreeSet};
 use std::hash::Hash;
+use std::num::NonZeroU32;
 use std::sync::Arc;
 pub use util::serde::default_true;
 
@@ -147,6 +148,9 @@ pub struct SettingsContent {
     /// The settings for the image viewer.
     pub image_viewer: Option<ImageViewerSettingsContent>,
 
+    /// Configuration for the indent size selector.
+    pub indent_size_selector: Option<IndentSizeSelectorSettingsContent>,
+
     pub repl: Option<ReplSettingsContent>,
 
     /// Whether or not to enable Helix mode.
@@ -867,6 +871,256 @@ pub enum HourFormat {
     Hour24,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct IndentSizeSelectorSettingsContent {
+    /// The indent sizes offered by the indent size selector, in spaces.
+    ///
+    /// Default: [2, 4, 8]
+    pub sizes: Option<Vec<u32>>,
+    /// Whether the indent size selector offers switching between spaces and hard tabs.
+    ///
+    /// Default: true
+    pub show_hard_tabs: Option<bool>,
+    /// Whether to show the detected indentation of each file in the tab switcher.
+    ///
+    /// Default: false
+    pub annotate_tab_switcher: Option<bool>,
+    /// Whether to mark the gutter of the lines whose leading whitespace doesn't match the
+    /// configured indentation.
+    ///
+    /// Default: false
+    pub mark_inconsistent_lines: Option<bool>,
+    /// Whether to suggest switching a file's indent size after repeatedly re-indenting its
+    /// lines by hand to a different width.
+    ///
+    /// Default: false
+    pub suggest_from_corrections: Option<bool>,
+    /// Whether changing the indentation of a preview tab makes it a permanent tab.
+    ///
+    /// Default: true
+    pub promote_preview_tabs: Option<bool>,
+    /// Where confirming a choice in the indent size selector writes it.
+    /// `indent_size_selector::ConfirmForLanguages` always writes it for the files' languages in
+    /// the user settings.
+    ///
+    /// Default: ask
+    pub confirm_writes_to: Option<IndentSizeSelectorConfirmTarget>,
+    /// Whether saving a file re-indents it to the configured indentation when it uses a
+    /// different one, so a project converges as its files are touched.
+    ///
+    /// Default: false
+    pub fix_on_save: Option<bool>,
+    /// Whether re-indenting a file on save leaves alone the lines that match the version of
+    /// the file git has, converting only those changed since, so that the fix doesn't add
+    /// whitespace-only changes to the file's diff. Files without a version in git are
+    /// re-indented whole.
+    ///
+    /// Default: false
+    pub preserve_unmodified_lines: Option<bool>,
+    /// Whether saving a file whose indentation an `.editorconfig` determines first asks whether
+    /// to fix the lines that don't match it, unless saving already re-indents the file.
+    ///
+    /// Default: false
+    pub check_editorconfig_on_save: Option<bool>,
+    /// Whether fixing a file's indentation on save also replaces unusual whitespace in it, such
+    /// as non-breaking or ideographic spaces, with regular spaces.
+    ///
+    /// Default: false
+    pub normalize_exotic_whitespace: Option<bool>,
+    /// Whether confirming an indentation in the indent size selector also re-indents the
+    /// existing lines of the files it's applied to, in one edit that can be undone, rather than
+    /// only affecting new indentation. Confirming with `menu::SecondaryConfirm` always converts
+    /// them.
+    ///
+    /// Default: false
+    pub convert_existing_indentation: Option<bool>,
+    /// Whether converting a file's indentation follows the "smart tabs" convention of
+    /// indenting with tabs and aligning with spaces, keeping the spaces that align
+    /// continuation lines past the indentation of the line they continue.
+    ///
+    /// Default: false
+    pub align_with_spaces: Option<bool>,
+    /// Whether opening a file whose content is indented differently from what its language's
+    /// defaults or your user settings prescribe switches the file to the indentation it uses,
+    /// until it's closed. Project settings and `.editorconfig` files still take precedence.
+    ///
+    /// Default: false
+    pub auto_detect_indentation: Option<bool>,
+    /// Whether opening a file clearly indented differently from its configured indentation
+    /// shows a notification offering to use the file's indentation, to convert the file, or to
+    /// stop notifying about it. Nothing is shown while `auto_detect_indentation` is enabled.
+    ///
+    /// Default: true
+    pub notify_indentation_mismatch: Option<bool>,
+    /// What to do when a file is opened whose indentation no language, `.editorconfig` or
+    /// settings determine, so it would fall back to Zed's defaults.
+    ///
+    /// Default: off
+    pub when_unresolved: Option<IndentSizeSelectorUnresolvedBehavior>,
+    /// What to do when spaces are chosen in the indent size selector for a file whose
+    /// language requires hard tabs, such as a Makefile or Go. Converting a file's indentation
+    /// to spaces never touches the recipe lines of a Makefile either way.
+    ///
+    /// Default: warn
+    pub when_tabs_required: Option<IndentSizeSelectorTabsRequiredBehavior>,
+    /// The broadest scope that an indentation chosen for a file of each language may be
+    /// applied to, keyed by language name. Languages that aren't listed may be applied to any
+    /// scope.
+    ///
+    /// Default: {"Makefile": "file"}
+    pub max_override_scope: Option<HashMap<String, IndentSizeSelectorOverrideScope>>,
+    /// The label of the indentation status bar item. `{kind}` is replaced with "Space" or
+    /// "Tab", and `{size}` with the indent size. A tab width apart from the indent size is
+    /// shown before it, with `{kind}` replaced with "Indent", as in "Tab: 8 / Indent: 4".
+    ///
+    /// Default: "{kind}: {size}"
+    pub status_item_format: Option<String>,
+    /// Named bundles of an indentation and the settings that go along with it, offered by the
+    /// indent size selector next to the indent sizes, and applied together at the scope
+    /// chosen for them.
+    ///
+    /// Default: []
+    pub profiles: Option<Vec<IndentationProfileContent>>,
+    /// The indentation of new untitled buffers, such as those created with `workspace: new
+    /// file`, which they keep once saved until they're closed. Uses the default settings when
+    /// null.
+    ///
+    /// Default: null
+    pub untitled_indentation: Option<IndentationContent>,
+    /// Whether pasting lines indented differently than the file they're pasted into, such as
+    /// with 2 spaces into a file indented with tabs, re-indents them to the file's indentation,
+    /// keeping how deeply each line is nested.
+    ///
+    /// Default: false
+    pub reindent_on_paste: Option<bool>,
+    /// Whether files formatted by Prettier, clang-format or rustfmt are indented as the nearest
+    /// `.prettierrc`, `.clang-format` or `rustfmt.toml` has the formatter indent them, when only
+    /// the defaults or the user settings would determine their indentation otherwise.
+    ///
+    /// Default: true
+    pub read_formatter_configs: Option<bool>,
+    /// Whether empty files opened in a project, such as those just created, are indented as most
+    /// files of their language in the project are, when only the defaults or the user settings
+    /// would determine their indentation otherwise.
+    ///
+    /// Default: true
+    pub new_files_use_project_convention: Option<bool>,
+    /// Whether typing a digit as the first key after opening the indent size selector applies
+    /// that indent size and dismisses the selector, when the selector offers it. Any other
+    /// first key starts a search, as in "tabs" or "two".
+    ///
+    /// Default: true
+    pub quick_set_keys: Option<bool>,
+}
+
+/// An indentation, as a size and whether it's made up of tabs.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct IndentationContent {
+    /// How many columns a level of indentation takes.
+    pub tab_size: NonZeroU32,
+    /// Whether to indent with tabs rather than spaces.
+    ///
+    /// Default: false
+    pub hard_tabs: Option<bool>,
+}
+
+/// A named indentation, along with the `.editorconfig` properties applied with it.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct IndentationProfileContent {
+    /// The name the indent size selector lists the profile by, such as "web".
+    pub name: String,
+    /// How many columns a level of indentation takes.
+    pub tab_size: NonZeroU32,
+    /// Whether to indent with tabs rather than spaces.
+    ///
+    /// Default: false
+    pub hard_tabs: Option<bool>,
+    /// The column lines should wrap at, like `max_line_length` in an `.editorconfig`.
+    pub max_line_length: Option<u32>,
+    /// Whether to remove trailing whitespace on save, like `trim_trailing_whitespace` in an
+    /// `.editorconfig`.
+    pub trim_trailing_whitespace: Option<bool>,
+    /// Whether to end files with a newline on save, like `insert_final_newline` in an
+    /// `.editorconfig`.
+    pub insert_final_newline: Option<bool>,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IndentSizeSelectorConfirmTarget {
+    /// Ask where to apply the chosen indentation.
+    #[default]
+    Ask,
+    /// Write it as an override for the file's language in the user settings.
+    UserSettings,
+    /// Write it as an override for the file's language in the project's settings, or in the
+    /// user settings for a file outside of any worktree.
+    ProjectSettings,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IndentSizeSelectorUnresolvedBehavior {
+    /// Use Zed's defaults without saying so.
+    #[default]
+    Off,
+    /// Show a notification offering to open the indent size selector.
+    Notify,
+    /// Open the indent size selector.
+    Open,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IndentSizeSelectorTabsRequiredBehavior {
+    /// Use spaces without saying anything.
+    Off,
+    /// Use spaces, warning that the language requires tabs.
+    #[default]
+    Warn,
+    /// Keep the file's indentation, explaining that the language requires tabs.
+    Refuse,
+}
+
+/// How many files an indentation override applies to, from the narrowest to the broadest.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IndentSizeSelectorOverrideScope {
+    /// The open buffer, until it's closed.
+    Buffer,
+    /// The file.
+    File,
+    /// The files in the file's directory.
+    Directory,
+    /// The files of the file's language.
+    Language,
+    /// The files in the file's worktree.
+    Worktree,
+    /// Every file.
+    Global,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
 pub struct OutlinePanelSettingsContent {
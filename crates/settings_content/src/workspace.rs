@@ -484,6 +484,10 @@ pub struct StatusBarSettingsContent {
     ///
     /// Default: false
     pub line_endings_button: Option<bool>,
+    /// Whether to show the active buffer's indentation button in the status bar.
+    ///
+    /// Default: true
+    pub indentation_button: Option<bool>,
     /// Whether to show the active encoding button in the status bar.
     ///
     /// Default: non_utf8
@@ -826,6 +826,14 @@ impl PickerDelegate for TabSwitcherDelegate {
             deemphasized: false,
         };
         let label = tab_match.item.tab_content(params, window, cx);
+        let indentation =
+            indent_size_selector::tab_switcher_annotation(tab_match.item.as_ref(), cx).map(
+                |indentation| {
+                    Label::new(indentation)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                },
+            );
 
         let icon = tab_match.icon(&self.project, selected, window, cx);
 
@@ -868,7 +876,7 @@ impl PickerDelegate for TabSwitcherDelegate {
                 .spacing(ListItemSpacing::Sparse)
                 .inset(true)
                 .toggle_state(selected)
-                .child(h_flex().w_full().child(label))
+                .child(h_flex().w_full().gap_2().child(label).children(indentation))
                 .start_slot::<DecoratedIcon>(icon)
                 .map(|el| {
                     if self.selected_index == ix {
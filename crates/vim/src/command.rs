@@ -11,7 +11,7 @@ use gpui::{
     Action, App, AppContext as _, Context, Global, Keystroke, Task, WeakEntity, Window, actions,
 };
 use itertools::Itertools;
-use language::Point;
+use language::{Point, language_settings::IndentationOverride};
 use multi_buffer::MultiBufferRow;
 use project::ProjectPath;
 use regex::Regex;
@@ -21,6 +21,7 @@ use serde::Deserialize;
 use settings::{Settings, SettingsStore};
 use std::{
     iter::Peekable,
+    num::NonZeroU32,
     ops::{Deref, Range},
     path::{Path, PathBuf},
     process::Stdio,
@@ -90,6 +91,9 @@ pub enum VimOption {
     RelativeNumber(bool),
     IgnoreCase(bool),
     GDefault(bool),
+    TabStop(u32),
+    ShiftWidth(u32),
+    ExpandTab(bool),
 }
 
 impl VimOption {
@@ -105,16 +109,17 @@ impl VimOption {
             }
         }
 
-        Self::possibilities(prefix)
+        // Options taking a value can't be completed, but are offered once it's typed.
+        Self::from(prefix)
+            .filter(|option| option.takes_value())
+            .into_iter()
+            .chain(Self::possibilities(prefix))
             .map(|possible| {
                 let mut options = prefix_of_options.clone();
                 options.push(possible);
 
                 CommandInterceptItem {
-                    string: format!(
-                        ":set {}",
-                        options.iter().map(|opt| opt.to_string()).join(" ")
-                    ),
+                    string: format!(":set {}", options.iter().join(" ")),
                     action: VimSet { options }.boxed_clone(),
                     positions: vec![],
                 }
@@ -140,9 +145,17 @@ impl VimOption {
             (Some("gd"), VimOption::GDefault(true)),
             (None, VimOption::GDefault(false)),
             (Some("nogd"), VimOption::GDefault(false)),
+            (None, VimOption::ExpandTab(true)),
+            (Some("et"), VimOption::ExpandTab(true)),
+            (None, VimOption::ExpandTab(false)),
+            (Some("noet"), VimOption::ExpandTab(false)),
         ]
         .into_iter()
-        .filter(move |(prefix, option)| prefix.unwrap_or(option.to_string()).starts_with(query))
+        .filter(move |(prefix, option)| {
+            prefix
+                .map_or_else(|| option.to_string(), String::from)
+                .starts_with(query)
+        })
         .map(|(_, option)| option)
     }
 
@@ -171,22 +184,45 @@ impl VimOption {
             "nogdefault" => Some(Self::GDefault(false)),
             "nogd" => Some(Self::GDefault(false)),
 
-            _ => None,
+            "expandtab" => Some(Self::ExpandTab(true)),
+            "et" => Some(Self::ExpandTab(true)),
+            "noexpandtab" => Some(Self::ExpandTab(false)),
+            "noet" => Some(Self::ExpandTab(false)),
+
+            _ => {
+                let (name, value) = option.split_once('=')?;
+                let value = value.parse().ok()?;
+                match name {
+                    "tabstop" | "ts" if value > 0 => Some(Self::TabStop(value)),
+                    "shiftwidth" | "sw" => Some(Self::ShiftWidth(value)),
+                    _ => None,
+                }
+            }
         }
     }
 
-    fn to_string(&self) -> &'static str {
+    fn takes_value(&self) -> bool {
+        matches!(self, VimOption::TabStop(_) | VimOption::ShiftWidth(_))
+    }
+}
+
+impl std::fmt::Display for VimOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            VimOption::Wrap(true) => "wrap",
-            VimOption::Wrap(false) => "nowrap",
-            VimOption::Number(true) => "number",
-            VimOption::Number(false) => "nonumber",
-            VimOption::RelativeNumber(true) => "relativenumber",
-            VimOption::RelativeNumber(false) => "norelativenumber",
-            VimOption::IgnoreCase(true) => "ignorecase",
-            VimOption::IgnoreCase(false) => "noignorecase",
-            VimOption::GDefault(true) => "gdefault",
-            VimOption::GDefault(false) => "nogdefault",
+            VimOption::Wrap(true) => write!(f, "wrap"),
+            VimOption::Wrap(false) => write!(f, "nowrap"),
+            VimOption::Number(true) => write!(f, "number"),
+            VimOption::Number(false) => write!(f, "nonumber"),
+            VimOption::RelativeNumber(true) => write!(f, "relativenumber"),
+            VimOption::RelativeNumber(false) => write!(f, "norelativenumber"),
+            VimOption::IgnoreCase(true) => write!(f, "ignorecase"),
+            VimOption::IgnoreCase(false) => write!(f, "noignorecase"),
+            VimOption::GDefault(true) => write!(f, "gdefault"),
+            VimOption::GDefault(false) => write!(f, "nogdefault"),
+            VimOption::TabStop(value) => write!(f, "tabstop={value}"),
+            VimOption::ShiftWidth(value) => write!(f, "shiftwidth={value}"),
+            VimOption::ExpandTab(true) => write!(f, "expandtab"),
+            VimOption::ExpandTab(false) => write!(f, "noexpandtab"),
         }
     }
 }
@@ -315,8 +351,10 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
                         store.override_global(settings);
                     })
                 }
+                VimOption::TabStop(_) | VimOption::ShiftWidth(_) | VimOption::ExpandTab(_) => {}
             });
         }
+        vim.set_indentation(&action.options, cx);
     });
     Vim::action(editor, cx, |vim, _: &VisualCommand, window, cx| {
         let Some(workspace) = vim.workspace(window, cx) else {
@@ -2301,6 +2339,51 @@ pub struct ShellExec {
 }
 
 impl Vim {
+    /// Applies `tabstop`, `shiftwidth` and `expandtab` to the active buffer alone, until it's
+    /// closed. `tabstop` sets how wide a tab is rendered and `shiftwidth` how wide a level of
+    /// indentation is, following `tabstop` when it's 0, as in Vim.
+    fn set_indentation(&mut self, options: &[VimOption], cx: &mut Context<Self>) {
+        let (mut tab_stop, mut shift_width, mut expand_tab) = (None, None, None);
+        for option in options {
+            match option {
+                VimOption::TabStop(value) => tab_stop = NonZeroU32::new(*value),
+                VimOption::ShiftWidth(value) => shift_width = Some(*value),
+                VimOption::ExpandTab(enabled) => expand_tab = Some(*enabled),
+                _ => {}
+            }
+        }
+        if tab_stop.is_none() && shift_width.is_none() && expand_tab.is_none() {
+            return;
+        }
+        self.update_editor(cx, |_, editor, cx| {
+            let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+                return;
+            };
+            let settings = editor
+                .buffer()
+                .read(cx)
+                .language_settings_at(editor.selections.newest_anchor().head(), cx);
+            let tab_width = tab_stop.unwrap_or(settings.tab_width.unwrap_or(settings.tab_size));
+            let tab_size = match shift_width {
+                Some(0) => Some(tab_width),
+                Some(shift_width) => NonZeroU32::new(shift_width),
+                None => Some(settings.tab_size),
+            };
+            let Some(tab_size) = tab_size else {
+                return;
+            };
+            let hard_tabs = expand_tab.map_or(settings.hard_tabs, |expand_tab| !expand_tab);
+            let indentation_override = IndentationOverride {
+                tab_size,
+                tab_width: (tab_width != tab_size).then_some(tab_width),
+                hard_tabs,
+            };
+            buffer.update(cx, |buffer, cx| {
+                buffer.set_indentation_override(Some(indentation_override), cx)
+            });
+        });
+    }
+
     pub fn cancel_running_command(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.running_command.take().is_some() {
             self.update_editor(cx, |_, editor, cx| {
@@ -2646,7 +2729,10 @@ impl ShellExec {
 
 #[cfg(test)]
 mod test {
-    use std::path::{Path, PathBuf};
+    use std::{
+        num::NonZeroU32,
+        path::{Path, PathBuf},
+    };
 
     use crate::{
         VimAddon,
@@ -2656,6 +2742,7 @@ mod test {
     use editor::{Editor, EditorSettings};
     use gpui::{Context, TestAppContext};
     use indoc::indoc;
+    use language::language_settings::{AllLanguageSettings, LanguageSettings};
     use settings::Settings;
     use util::path;
     use workspace::{OpenOptions, Workspace};
@@ -3447,6 +3534,65 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_set_indentation_command(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let whitespace = |cx: &mut VimTestContext| {
+            cx.update_editor(|editor, _, cx| {
+                let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+                let override_ = buffer.read(cx).indentation_override().copied();
+                let settings = LanguageSettings::for_buffer(buffer.read(cx), cx);
+                (
+                    override_.is_some(),
+                    settings.tab_size.get(),
+                    settings.tab_width.map(NonZeroU32::get),
+                    settings.hard_tabs,
+                )
+            })
+        };
+
+        // Tabs render 8 wide while indenting by 4, for the buffer alone.
+        cx.simulate_keystrokes(": set space ts=8 space sw=4 space noet");
+        cx.simulate_keystrokes("enter");
+        assert_eq!(whitespace(&mut cx), (true, 4, Some(8), true));
+        cx.read(|cx| {
+            let defaults = &AllLanguageSettings::get_global(cx).defaults;
+            assert_eq!((defaults.tab_size.get(), defaults.hard_tabs), (4, false));
+        });
+
+        // Only the options given change, keeping the width of a tab.
+        cx.simulate_keystrokes(": set space sw=2 space et");
+        cx.simulate_keystrokes("enter");
+        assert_eq!(whitespace(&mut cx), (true, 2, Some(8), false));
+
+        // A shiftwidth of 0 follows the tabstop.
+        cx.simulate_keystrokes(": set space sw=0");
+        cx.simulate_keystrokes("enter");
+        assert_eq!(whitespace(&mut cx), (true, 8, None, false));
+    }
+
+    #[test]
+    fn test_indentation_options() {
+        let parse = |option| VimOption::from(option).map(|option| option.to_string());
+        assert_eq!(parse("ts=4"), Some("tabstop=4".to_string()));
+        assert_eq!(parse("shiftwidth=0"), Some("shiftwidth=0".to_string()));
+        assert_eq!(parse("et"), Some("expandtab".to_string()));
+        assert_eq!(parse("noet"), Some("noexpandtab".to_string()));
+        assert_eq!(parse("ts=0"), None);
+        assert_eq!(parse("ts=four"), None);
+
+        let commands = VimOption::possible_commands("sw=2 noet")
+            .into_iter()
+            .map(|command| command.string)
+            .collect::<Vec<_>>();
+        assert_eq!(commands, vec![":set shiftwidth=2 noexpandtab".to_string()]);
+        let commands = VimOption::possible_commands("et sw=2")
+            .into_iter()
+            .map(|command| command.string)
+            .collect::<Vec<_>>();
+        assert_eq!(commands, vec![":set expandtab shiftwidth=2".to_string()]);
+    }
+
     #[gpui::test]
     async fn test_sort_commands(cx: &mut TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
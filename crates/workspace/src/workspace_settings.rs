@@ -159,6 +159,7 @@ pub struct StatusBarSettings {
     pub active_language_button: bool,
     pub cursor_position_button: bool,
     pub line_endings_button: bool,
+    pub indentation_button: bool,
     pub active_encoding_button: EncodingDisplayOptions,
 }
 
@@ -171,6 +172,7 @@ impl Settings for StatusBarSettings {
             active_language_button: status_bar.active_language_button.unwrap(),
             cursor_position_button: status_bar.cursor_position_button.unwrap(),
             line_endings_button: status_bar.line_endings_button.unwrap(),
+            indentation_button: status_bar.indentation_button.unwrap(),
             active_encoding_button: status_bar.active_encoding_button.unwrap(),
         }
     }
@@ -253,6 +253,12 @@ fn main() {
         return;
     }
 
+    // `zed --check-indentation` Checks indentation headlessly, for use in CI
+    if args.check_indentation {
+        check_indentation(&args.paths_or_urls);
+        return;
+    }
+
     // Set custom data directory.
     if let Some(dir) = &args.user_data_dir {
         paths::set_custom_data_dir(dir);
@@ -717,8 +723,8 @@ fn main() {
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
         encoding_selector::init(cx);
+        indent_size_selector::init(cx);
         language_selector::init(cx);
-        line_ending_selector::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
         settings_profile_selector::init(cx);
@@ -1633,6 +1639,15 @@ struct Args {
     #[arg(long)]
     dev_server_token: Option<String>,
 
+    /// Checks the indentation of the given paths, or the current directory, against the
+    /// project's settings and `.editorconfig` files without opening a window. Exits with 1 when
+    /// any file violates them and with 2 when a file can't be read.
+    ///
+    /// Uses the same checker as the editor's indentation consistency report, so CI can enforce
+    /// its rules.
+    #[arg(long)]
+    check_indentation: bool,
+
     /// Prints system specs.
     ///
     /// Useful for submitting issues on GitHub when encountering a bug that
@@ -1860,6 +1875,75 @@ fn watch_languages(fs: Arc<dyn fs::Fs>, languages: Arc<LanguageRegistry>, cx: &m
     .detach();
 }
 
+/// Checks the indentation of `paths`, or of the current directory when there are none, the way
+/// the editor's consistency report does, and exits with the check's status: 1 when files
+/// violate their configured indentation and 2 when the check fails.
+fn check_indentation(paths: &[String]) {
+    let mut paths = paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+    if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+    let app_version = AppVersion::load(env!("CARGO_PKG_VERSION"), None, None);
+
+    gpui_platform::headless().run(move |cx| {
+        release_channel::init(app_version, cx);
+        settings::init(cx);
+        let client = Client::production(cx);
+        let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
+        let fs = Arc::new(RealFs::new(None, cx.background_executor().clone()));
+        let languages = Arc::new(LanguageRegistry::new(cx.background_executor().clone()));
+        languages::init(
+            languages.clone(),
+            fs.clone(),
+            NodeRuntime::unavailable(),
+            cx,
+        );
+        let project = project::Project::local(
+            client,
+            NodeRuntime::unavailable(),
+            user_store,
+            languages,
+            fs,
+            None,
+            project::LocalProjectFlags {
+                init_worktree_trust: false,
+                ..Default::default()
+            },
+            cx,
+        );
+
+        cx.spawn(async move |cx| {
+            let check = async {
+                for path in paths {
+                    project
+                        .update(cx, |project, cx| {
+                            project.find_or_create_worktree(path, true, cx)
+                        })
+                        .await?;
+                }
+                project
+                    .read_with(cx, |project, cx| project.wait_for_initial_scan(cx))
+                    .await;
+                cx.update(|cx| indent_size_selector::check_indentation(&project, cx))
+                    .await
+            };
+            let status = match check.await {
+                Ok(check) => {
+                    print!("{}", check.output);
+                    eprintln!("{}", check.summary);
+                    check.exit_code()
+                }
+                Err(err) => {
+                    eprintln!("Error: {err:#}");
+                    2
+                }
+            };
+            process::exit(status);
+        })
+        .detach();
+    });
+}
+
 fn dump_all_gpui_actions() {
     #[derive(Debug, serde::Serialize)]
     struct ActionDef {